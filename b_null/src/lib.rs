@@ -0,0 +1,371 @@
+//! A [`Backend`] without a window or GPU device, for tests and CI.
+//!
+//! It keeps texture sizes and records every draw call instead of drawing, so
+//! tests can check what a frame would have rendered:
+//!
+//! ```ignore
+//! let mut r = Renderer::from_backend(NullBackend::new(640, 480));
+//! let tex = r.create_texture_from_rgba(1, 1, &[255; 4])?;
+//! r.begin_frame();
+//! r.bind_camera(&Camera::screen_space());
+//! r.draw_sprites(&SpriteBatch::new(tex, vec![SpriteInstance::default(); 3]));
+//! r.end_frame();
+//! assert_eq!(r.backend().draw_calls(), [DrawCall::Sprites { tex, instances: 3 }]);
+//! ```
+use glam::Vec2;
+use jester_core::{
+    AdapterInfo, Backend, Camera, Color, MaterialId, MeshBatch, MeshVertex, PanelInstance,
+    SpriteBatch, TextureFormat, TextureId, MAX_MESH_INDICES, MAX_MESH_VERTICES, MAX_PANELS,
+    MAX_SPRITES, MAX_TEXTURES,
+};
+use winit::{dpi::PhysicalSize, window::Window};
+
+#[derive(Debug, thiserror::Error)]
+pub enum NullError {
+    #[error("texture limit reached ({MAX_TEXTURES})")]
+    TooManyTextures,
+    #[error("no texture in slot {0}")]
+    UnknownTexture(usize),
+    #[error("{width}x{height} {format:?} texture needs {expected} bytes, got {got}")]
+    TexelCount {
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        expected: usize,
+        got: usize,
+    },
+    #[error("material bytes are not SPIR-V")]
+    InvalidSpirv,
+}
+
+/// What one `draw_*` call would have rendered, after the per-frame limits the
+/// Vulkan backend applies; calls left with nothing to draw are not recorded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrawCall {
+    Sprites {
+        tex: TextureId,
+        instances: usize,
+    },
+    /// Same as `Sprites`, colored through the palette texture in slot `palette`.
+    PalettedSprites {
+        tex: TextureId,
+        palette: usize,
+        instances: usize,
+    },
+    Mesh {
+        tex: TextureId,
+        vertices: usize,
+        indices: usize,
+    },
+    Panels {
+        count: usize,
+    },
+    /// A line list sharing the mesh vertex budget.
+    Lines {
+        vertices: usize,
+    },
+    /// Later calls draw into the render target in texture slot `slot`, or the frame when `None`.
+    Target {
+        slot: Option<usize>,
+    },
+}
+
+/// Backend that records draw calls rather than drawing, see the crate docs.
+///
+/// Frames are tracked like on a GPU: the calls between `begin_frame` and
+/// `end_frame` become [`NullBackend::draw_calls`] once the frame ends.
+#[derive(Debug, Clone)]
+pub struct NullBackend {
+    size: PhysicalSize<u32>,
+    /// `None` for slots freed with `destroy_texture`, reused first.
+    textures: Vec<Option<(u32, u32, TextureFormat)>>,
+    recording: Vec<DrawCall>,
+    last_frame: Vec<DrawCall>,
+    camera: Option<Camera>,
+    /// Slot of the render target drawn into, `None` for the frame.
+    target: Option<usize>,
+    /// `Camera::view_constants` of every `bind_camera` this frame, as the
+    /// Vulkan backend pushes them.
+    views: Vec<[f32; 6]>,
+    last_views: Vec<[f32; 6]>,
+    frames: u64,
+    sprites: usize,
+    panels: usize,
+    mesh_vertices: usize,
+    mesh_indices: usize,
+    tint: Color,
+    materials: u32,
+}
+
+impl NullBackend {
+    /// A backend pretending to draw to a `width` by `height` window.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            size: PhysicalSize::new(width, height),
+            textures: Vec::new(),
+            recording: Vec::new(),
+            last_frame: Vec::new(),
+            camera: None,
+            target: None,
+            views: Vec::new(),
+            last_views: Vec::new(),
+            frames: 0,
+            sprites: 0,
+            panels: 0,
+            mesh_vertices: 0,
+            mesh_indices: 0,
+            tint: Color::WHITE,
+            materials: MaterialId::BUILTIN.len() as u32,
+        }
+    }
+
+    /// Draw calls of the last finished frame, in order.
+    pub fn draw_calls(&self) -> &[DrawCall] {
+        &self.last_frame
+    }
+
+    /// Sprite instances the last frame drew with `tex`.
+    pub fn instances_of(&self, tex: TextureId) -> usize {
+        self.last_frame
+            .iter()
+            .map(|c| match *c {
+                DrawCall::Sprites { tex: t, instances }
+                | DrawCall::PalettedSprites {
+                    tex: t, instances, ..
+                } if t == tex => instances,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Frames ended so far.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn size(&self) -> PhysicalSize<u32> {
+        self.size
+    }
+
+    /// The camera bound last, `None` before the first `bind_camera`.
+    pub fn camera(&self) -> Option<&Camera> {
+        self.camera.as_ref()
+    }
+
+    /// The view constants every camera bound in the last finished frame
+    /// was drawn with, in order; see `Camera::view_constants`.
+    pub fn view_constants(&self) -> &[[f32; 6]] {
+        &self.last_views
+    }
+
+    pub fn tint(&self) -> Color {
+        self.tint
+    }
+
+    /// Width, height and format of the texture in slot `idx`.
+    pub fn texture(&self, idx: usize) -> Option<(u32, u32, TextureFormat)> {
+        self.textures.get(idx).copied().flatten()
+    }
+
+    fn check_texels(
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        pixels: &[u8],
+    ) -> Result<(), NullError> {
+        let expected = width as usize * height as usize * format.bytes_per_texel();
+        if pixels.len() < expected {
+            return Err(NullError::TexelCount {
+                width,
+                height,
+                format,
+                expected,
+                got: pixels.len(),
+            });
+        }
+        Ok(())
+    }
+
+    // instances of `batch` that fit in what is left of the frame's sprite buffer
+    fn take_sprites(&mut self, batch: &SpriteBatch) -> usize {
+        let n = batch.instances.len().min(MAX_SPRITES - self.sprites);
+        self.sprites += n;
+        n
+    }
+}
+
+impl Backend for NullBackend {
+    type Error = NullError;
+
+    fn init(_app_name: &str, window: &Window) -> Result<Self, Self::Error> {
+        let size = window.inner_size();
+        Ok(Self::new(size.width, size.height))
+    }
+
+    fn begin_frame(&mut self) {
+        self.recording.clear();
+        self.views.clear();
+        self.target = None;
+        self.sprites = 0;
+        self.panels = 0;
+        self.mesh_vertices = 0;
+        self.mesh_indices = 0;
+    }
+
+    fn draw_sprites(&mut self, _tex_idx: usize, batch: &SpriteBatch) {
+        let instances = self.take_sprites(batch);
+        if instances > 0 {
+            self.recording.push(DrawCall::Sprites {
+                tex: batch.tex,
+                instances,
+            });
+        }
+    }
+
+    fn draw_sprites_paletted(&mut self, _tex_idx: usize, palette_idx: usize, batch: &SpriteBatch) {
+        let instances = self.take_sprites(batch);
+        if instances > 0 {
+            self.recording.push(DrawCall::PalettedSprites {
+                tex: batch.tex,
+                palette: palette_idx,
+                instances,
+            });
+        }
+    }
+
+    fn end_frame(&mut self) {
+        self.last_frame = std::mem::take(&mut self.recording);
+        self.last_views = std::mem::take(&mut self.views);
+        self.frames += 1;
+    }
+
+    fn draw_panels(&mut self, panels: &[PanelInstance]) {
+        let count = panels.len().min(MAX_PANELS - self.panels);
+        if count > 0 {
+            self.panels += count;
+            self.recording.push(DrawCall::Panels { count });
+        }
+    }
+
+    fn draw_mesh(&mut self, _tex_idx: usize, batch: &MeshBatch) {
+        let (vertices, indices) = (batch.mesh.vertices.len(), batch.mesh.indices.len());
+        // like the Vulkan backend, a mesh that does not fit is skipped whole
+        if self.mesh_vertices + vertices > MAX_MESH_VERTICES
+            || self.mesh_indices + indices > MAX_MESH_INDICES
+        {
+            return;
+        }
+        self.mesh_vertices += vertices;
+        self.mesh_indices += indices;
+        self.recording.push(DrawCall::Mesh {
+            tex: batch.tex,
+            vertices,
+            indices,
+        });
+    }
+
+    fn set_render_target(&mut self, idx: Option<usize>) {
+        self.target = idx;
+        self.recording.push(DrawCall::Target { slot: idx });
+    }
+
+    fn draw_lines(&mut self, _tex_idx: usize, vertices: &[MeshVertex]) {
+        let vertices = vertices.len().min(MAX_MESH_VERTICES - self.mesh_vertices) / 2 * 2;
+        if vertices == 0 {
+            return;
+        }
+        self.mesh_vertices += vertices;
+        self.recording.push(DrawCall::Lines { vertices });
+    }
+
+    fn handle_resize(&mut self, size: PhysicalSize<u32>) {
+        if size.width > 0 && size.height > 0 {
+            self.size = size;
+        }
+    }
+
+    fn bind_camera(&mut self, camera: &Camera) {
+        let target = self.target.and_then(|i| self.texture(i));
+        let screen = match target {
+            Some((w, h, _)) => Vec2::new(w as f32, h as f32),
+            None => Vec2::new(self.size.width as f32, self.size.height as f32),
+        };
+        self.views.push(camera.view_constants(screen));
+        self.camera = Some(*camera);
+    }
+
+    fn set_tint(&mut self, tint: Color) {
+        self.tint = tint;
+    }
+
+    fn adapter_info(&self) -> AdapterInfo {
+        AdapterInfo {
+            backend: "null",
+            name: String::from("null device"),
+            driver_version: 0,
+            api_version: String::new(),
+        }
+    }
+
+    fn create_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+    ) -> Result<usize, Self::Error> {
+        self.create_texture_with_format(width, height, TextureFormat::Rgba8, pixels)
+    }
+
+    fn create_texture_with_format(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        pixels: &[u8],
+    ) -> Result<usize, Self::Error> {
+        Self::check_texels(width, height, format, pixels)?;
+        if let Some(free) = self.textures.iter().position(Option::is_none) {
+            self.textures[free] = Some((width, height, format));
+            return Ok(free);
+        }
+        if self.textures.len() >= MAX_TEXTURES {
+            return Err(NullError::TooManyTextures);
+        }
+        self.textures.push(Some((width, height, format)));
+        Ok(self.textures.len() - 1)
+    }
+
+    fn replace_texture(
+        &mut self,
+        idx: usize,
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        pixels: &[u8],
+    ) -> Result<(), Self::Error> {
+        Self::check_texels(width, height, format, pixels)?;
+        let slot = self
+            .textures
+            .get_mut(idx)
+            .and_then(Option::as_mut)
+            .ok_or(NullError::UnknownTexture(idx))?;
+        *slot = (width, height, format);
+        Ok(())
+    }
+
+    fn destroy_texture(&mut self, idx: usize) {
+        if let Some(slot) = self.textures.get_mut(idx) {
+            *slot = None;
+        }
+    }
+
+    fn create_material(&mut self, spirv: &[u8]) -> Result<MaterialId, Self::Error> {
+        // failures take an id too, as on the GPU
+        let id = MaterialId(self.materials);
+        self.materials += 1;
+        match spirv.len().is_multiple_of(4) && spirv.starts_with(&0x0723_0203u32.to_le_bytes()) {
+            true => Ok(id),
+            false => Err(NullError::InvalidSpirv),
+        }
+    }
+}
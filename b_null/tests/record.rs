@@ -0,0 +1,95 @@
+use b_null::{DrawCall, NullBackend};
+use glam::Vec2;
+use jester_core::{
+    Camera, Renderer, SpriteBatch, SpriteInstance, TextureId, Viewport, MAX_SPRITES,
+};
+
+fn renderer() -> Renderer<NullBackend> {
+    Renderer::from_backend(NullBackend::new(640, 480))
+}
+
+fn batch(tex: TextureId, instances: usize) -> SpriteBatch {
+    SpriteBatch::new(tex, vec![SpriteInstance::default(); instances])
+}
+
+#[test]
+fn records_the_draws_of_a_frame() {
+    let path = std::env::temp_dir().join(format!("b_null_record_{}.png", std::process::id()));
+    image::RgbaImage::from_pixel(4, 2, image::Rgba([255, 0, 0, 255]))
+        .save(&path)
+        .unwrap();
+    let mut r = renderer();
+    let file = TextureId::from_path(&path);
+    r.load_texture_sync(file, &path).unwrap();
+    std::fs::remove_file(&path).ok();
+    let made = r.create_texture_from_rgba(1, 1, &[255; 4]).unwrap();
+    assert_eq!(r.texture_meta(file).map(|m| (m.w, m.h)), Some((4, 2)));
+
+    r.begin_frame();
+    r.bind_camera(&Camera::screen_space());
+    r.draw_sprites(&batch(file, 3));
+    r.draw_sprites(&batch(made, 0));
+    r.draw_sprites(&batch(TextureId(42), 5));
+    r.draw_sprites(&batch(made, 2));
+    // nothing shows before the frame ends
+    assert!(r.backend().draw_calls().is_empty());
+    r.end_frame();
+
+    assert_eq!(
+        r.backend().draw_calls(),
+        [
+            DrawCall::Sprites {
+                tex: file,
+                instances: 3
+            },
+            DrawCall::Sprites {
+                tex: made,
+                instances: 2
+            },
+        ]
+    );
+    assert_eq!(r.backend().instances_of(file), 3);
+    assert_eq!(r.backend().frames(), 1);
+    assert_eq!(r.batch_stats().instances, 5);
+}
+
+#[test]
+fn drops_sprites_past_the_frame_limit() {
+    let mut r = renderer();
+    let tex = r.create_texture_from_rgba(1, 1, &[255; 4]).unwrap();
+    r.begin_frame();
+    r.draw_sprites(&batch(tex, MAX_SPRITES - 1));
+    r.draw_sprites(&batch(tex, 5));
+    r.draw_sprites(&batch(tex, 5));
+    r.end_frame();
+    assert_eq!(r.backend().draw_calls().len(), 2);
+    assert_eq!(r.backend().instances_of(tex), MAX_SPRITES);
+
+    // the limit is per frame
+    r.begin_frame();
+    r.draw_sprites(&batch(tex, 5));
+    r.end_frame();
+    assert_eq!(r.backend().instances_of(tex), 5);
+}
+
+#[test]
+fn records_the_bound_cameras_view() {
+    let mut r = renderer();
+    let tex = r.create_texture_from_rgba(1, 1, &[255; 4]).unwrap();
+    let mut camera = Camera::screen_space();
+    camera.center = Vec2::new(10.0, 20.0);
+    camera.set_zoom(2.0);
+    r.begin_frame();
+    r.bind_camera(&camera);
+    r.draw_sprites(&batch(tex, 1));
+    let left_half = camera.with_viewport(Viewport::new(0.0, 0.0, 0.5, 1.0));
+    r.bind_camera(&left_half);
+    r.end_frame();
+    assert_eq!(
+        r.backend().view_constants(),
+        [
+            [640.0, 480.0, 10.0, 20.0, 2.0, 2.0],
+            [320.0, 480.0, 10.0, 20.0, 2.0, 2.0],
+        ]
+    );
+}
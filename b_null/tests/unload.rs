@@ -0,0 +1,35 @@
+use b_null::NullBackend;
+use jester_core::{Renderer, TextureFormat, TextureId};
+
+#[test]
+fn updating_a_shared_texture_leaves_the_slot_to_the_others() {
+    let dir = std::env::temp_dir().join(format!("b_null_unload_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut r = Renderer::from_backend(NullBackend::new(64, 64));
+    let [a, b, c] = ["a.png", "b.png", "c.png"].map(|name| {
+        let path = dir.join(name);
+        image::RgbaImage::from_pixel(2, 2, image::Rgba([0, 255, 0, 255]))
+            .save(&path)
+            .unwrap();
+        let tex = TextureId::from_path(&path);
+        r.load_texture_sync(tex, &path).unwrap();
+        tex
+    });
+    std::fs::remove_dir_all(&dir).ok();
+    // identical files share the first one's slot
+    assert_eq!(r.backend().texture(0), Some((2, 2, TextureFormat::Rgba8)));
+    assert_eq!(r.backend().texture(1), None);
+
+    r.update_texture(a, 3, 3, &[255; 36]).unwrap();
+    assert_eq!(r.texture_meta(a).map(|m| (m.w, m.h)), Some((3, 3)));
+    assert_eq!(r.texture_meta(b).map(|m| (m.w, m.h)), Some((2, 2)));
+    assert_eq!(r.backend().texture(1).map(|t| (t.0, t.1)), Some((3, 3)));
+
+    // the old slot is freed with the last of the files still sharing it
+    assert!(r.unload_texture(b));
+    assert!(r.backend().texture(0).is_some());
+    assert!(r.unload_texture(c));
+    assert_eq!(r.backend().texture(0), None);
+    assert!(r.unload_texture(a));
+    assert_eq!(r.backend().texture(1), None);
+}
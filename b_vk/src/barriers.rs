@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use ash::{vk, Device};
+
+/// How a copy uses an image, which fixes the layout the image must be in
+/// and what a barrier before the copy waits for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Usage {
+    /// Copied from.
+    TransferSrc,
+    /// Copied into.
+    TransferDst,
+    /// Read by fragment shaders.
+    Sampled,
+    /// Handed to the presentation engine.
+    Present,
+}
+
+impl Usage {
+    fn layout(self) -> vk::ImageLayout {
+        match self {
+            Self::TransferSrc => vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            Self::TransferDst => vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            Self::Sampled => vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            Self::Present => vk::ImageLayout::PRESENT_SRC_KHR,
+        }
+    }
+
+    fn stage(self) -> vk::PipelineStageFlags {
+        match self {
+            Self::TransferSrc | Self::TransferDst => vk::PipelineStageFlags::TRANSFER,
+            Self::Sampled => vk::PipelineStageFlags::FRAGMENT_SHADER,
+            Self::Present => vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+        }
+    }
+
+    fn access(self) -> vk::AccessFlags {
+        match self {
+            Self::TransferSrc => vk::AccessFlags::TRANSFER_READ,
+            Self::TransferDst => vk::AccessFlags::TRANSFER_WRITE,
+            Self::Sampled => vk::AccessFlags::SHADER_READ,
+            Self::Present => vk::AccessFlags::empty(),
+        }
+    }
+
+    fn writes(self) -> bool {
+        self == Self::TransferDst
+    }
+}
+
+/// Where an image was left: its layout and the last stage touching it,
+/// with the accesses still to be made visible if that stage wrote it.
+#[derive(Clone, Copy, Debug)]
+struct State {
+    layout: vk::ImageLayout,
+    stage: vk::PipelineStageFlags,
+    writes: vk::AccessFlags,
+}
+
+impl From<Usage> for State {
+    fn from(usage: Usage) -> Self {
+        Self {
+            layout: usage.layout(),
+            stage: usage.stage(),
+            writes: match usage.writes() {
+                true => usage.access(),
+                false => vk::AccessFlags::empty(),
+            },
+        }
+    }
+}
+
+/// The images a copy reads and writes and how, declared before it records.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Uses {
+    uses: Vec<(vk::Image, vk::ImageAspectFlags, Usage)>,
+}
+
+impl Uses {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares the copy uses the color aspect of `image` as `usage`.
+    pub fn color(mut self, image: vk::Image, usage: Usage) -> Self {
+        self.uses.push((image, vk::ImageAspectFlags::COLOR, usage));
+        self
+    }
+}
+
+/// Layout transitions of the images copied outside render passes, i.e.
+/// texture uploads and screenshot readbacks, with the barriers between the
+/// copies of one command buffer derived from what each declares.
+///
+/// Every image starts undefined, so its first copy discards the contents,
+/// unless [`ImageBarriers::rendered`] says a render pass left it in a layout.
+/// Render passes are not tracked here: their attachments synchronize
+/// through the layouts and subpass dependencies of each `vk::RenderPass`.
+///
+/// ```ignore
+/// let mut barriers = ImageBarriers::default();
+/// barriers.record(d, c, &Uses::new().color(image, Usage::TransferDst), |d, c| copy(d, c));
+/// barriers.transition(d, c, &Uses::new().color(image, Usage::Sampled));
+/// ```
+#[derive(Debug, Default)]
+pub(crate) struct ImageBarriers {
+    images: HashMap<vk::Image, State>,
+}
+
+impl ImageBarriers {
+    /// `image` was last drawn into by a render pass leaving it in `layout`.
+    pub fn rendered(&mut self, image: vk::Image, layout: vk::ImageLayout) {
+        self.images.insert(
+            image,
+            State {
+                layout,
+                stage: vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                writes: vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+            },
+        );
+    }
+
+    /// Records the barriers `uses` needs, then the copy itself.
+    pub unsafe fn record(
+        &mut self,
+        device: &Device,
+        cmd: vk::CommandBuffer,
+        uses: &Uses,
+        body: impl FnOnce(&Device, vk::CommandBuffer),
+    ) {
+        unsafe { self.transition(device, cmd, uses) };
+        body(device, cmd);
+    }
+
+    /// Records the barriers bringing every image of `uses` into its usage,
+    /// e.g. back to [`Usage::Present`] after the last copy.
+    pub unsafe fn transition(&mut self, device: &Device, cmd: vk::CommandBuffer, uses: &Uses) {
+        let mut barriers = Vec::new();
+        let mut src_stage = vk::PipelineStageFlags::empty();
+        let mut dst_stage = vk::PipelineStageFlags::empty();
+        for &(image, aspect, usage) in &uses.uses {
+            let from = self.images.get(&image).copied().unwrap_or(State {
+                layout: vk::ImageLayout::UNDEFINED,
+                stage: vk::PipelineStageFlags::TOP_OF_PIPE,
+                writes: vk::AccessFlags::empty(),
+            });
+            self.images.insert(image, usage.into());
+            // reads after reads in the same layout need no barrier
+            if from.layout == usage.layout() && from.writes.is_empty() && !usage.writes() {
+                continue;
+            }
+            src_stage |= from.stage;
+            dst_stage |= usage.stage();
+            barriers.push(
+                vk::ImageMemoryBarrier::default()
+                    .image(image)
+                    .src_access_mask(from.writes)
+                    .dst_access_mask(usage.access())
+                    .old_layout(from.layout)
+                    .new_layout(usage.layout())
+                    .subresource_range(
+                        vk::ImageSubresourceRange::default()
+                            .aspect_mask(aspect)
+                            .level_count(1)
+                            .layer_count(1),
+                    ),
+            );
+        }
+        if barriers.is_empty() {
+            return;
+        }
+        unsafe {
+            device.cmd_pipeline_barrier(
+                cmd,
+                src_stage,
+                dst_stage,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[],
+                &barriers,
+            );
+        }
+    }
+}
@@ -1,15 +1,31 @@
-use self::utils::{create_surface, enumerate_required_extensions, record_submit_commandbuffer};
+use self::{
+    barriers::{ImageBarriers, Usage, Uses},
+    post::PostTargets,
+    timing::PresentTiming,
+    transient::Transients,
+    utils::{create_surface, enumerate_required_extensions, record_submit_commandbuffer},
+};
 #[cfg(feature = "debug")]
 use ash::ext::debug_utils;
 use ash::{
+    google::display_timing,
     khr::{surface, swapchain},
     vk::{self, API_VERSION_1_3},
     Device, Entry, Instance,
 };
 use jester_core::{
-    Backend, Camera, SpriteBatch, SpriteInstance, MAX_SPRITES, MAX_TEXTURES, VERTEX_COUNT,
+    rgba8_from, AdapterInfo, AddressMode, Backend, BlendMode, Camera, Color, DisplayCalibration,
+    MaterialId, MemoryStats, MeshBatch, MeshVertex, PanelInstance, PresentMode, PresentStats,
+    RetainedBatch, SamplerDesc, ScreenFilter, Screenshot, SpriteBatch, SpriteInstance,
+    TextureFilter, TextureFormat, MAX_MESH_INDICES, MAX_MESH_VERTICES, MAX_PANELS, MAX_SPRITES,
+    MAX_TEXTURES, VERTEX_COUNT,
+};
+use std::{
+    collections::HashMap,
+    ffi,
+    ops::Range,
+    time::{Duration, Instant},
 };
-use std::ffi;
 use winit::{
     raw_window_handle::{HasDisplayHandle, HasWindowHandle},
     window::Window,
@@ -41,8 +57,81 @@ const QUAD_VERTS: [QuadVertex; 4] = [
     },
 ];
 
+mod barriers;
+mod post;
+mod timing;
+mod transient;
 mod utils;
 
+/// The closest of `supported` to `mode`, see [`PresentMode`] for the fallbacks.
+fn present_mode(mode: PresentMode, supported: &[vk::PresentModeKHR]) -> vk::PresentModeKHR {
+    let order: &[vk::PresentModeKHR] = match mode {
+        PresentMode::Fifo => &[],
+        PresentMode::Mailbox => &[vk::PresentModeKHR::MAILBOX, vk::PresentModeKHR::IMMEDIATE],
+        PresentMode::Immediate => &[vk::PresentModeKHR::IMMEDIATE, vk::PresentModeKHR::MAILBOX],
+    };
+    // FIFO is always supported
+    order
+        .iter()
+        .copied()
+        .find(|m| supported.contains(m))
+        .unwrap_or(vk::PresentModeKHR::FIFO)
+}
+
+/// Clear values of `render_pass`'s color and depth attachments.
+fn target_clear(color: [f32; 4]) -> [vk::ClearValue; 2] {
+    [
+        vk::ClearValue {
+            color: vk::ClearColorValue { float32: color },
+        },
+        vk::ClearValue {
+            depth_stencil: vk::ClearDepthStencilValue {
+                depth: 1.0,
+                stencil: 0,
+            },
+        },
+    ]
+}
+
+/// Device-local copy of a baked batch's instances, see [`SpriteBatch::baked`].
+pub struct BakedInstances {
+    pub buffer: vk::Buffer,
+    pub memory: vk::DeviceMemory,
+    pub bytes: vk::DeviceSize,
+    /// `frame_count` of the last frame drawing it.
+    pub last_used: u64,
+}
+
+/// Host-visible copies of a retained batch's instances, one per frame in
+/// flight, see [`SpriteBatch::retained`].
+pub struct RetainedInstances {
+    pub slots: Vec<RetainedSlot>,
+    /// [`RetainedBatch::generation`] of the last batch seen.
+    pub generation: u64,
+    /// `frame_count` of the last frame drawing it.
+    pub last_used: u64,
+}
+
+/// The framebuffer of a texture slot made with `create_render_target`;
+/// the color image is the slot's own, the depth buffer is shared through
+/// [`Transients`] with every target of the same size.
+pub struct RenderTarget {
+    pub framebuffer: vk::Framebuffer,
+    pub depth: (vk::Image, vk::DeviceMemory, vk::ImageView),
+    pub extent: vk::Extent2D,
+}
+
+pub struct RetainedSlot {
+    pub buffer: vk::Buffer,
+    pub memory: vk::DeviceMemory,
+    /// In instances.
+    pub capacity: usize,
+    /// Instances changed since the slot was last written.
+    pub pending: Vec<Range<usize>>,
+    /// Whether everything must be written, e.g. after the slot was created.
+    pub stale: bool,
+}
+
 pub struct VkBackend {
     pub entry: Entry,
     pub instance: Instance,
@@ -62,6 +151,7 @@ pub struct VkBackend {
     pub surface: vk::SurfaceKHR,
     pub surface_format: vk::SurfaceFormatKHR,
     pub surface_resolution: vk::Extent2D,
+    pub depth_format: vk::Format,
 
     pub swapchain: vk::SwapchainKHR,
     pub present_images: Vec<vk::Image>,
@@ -71,6 +161,8 @@ pub struct VkBackend {
     pub cmds: Vec<vk::CommandBuffer>,
 
     pub render_pass: vk::RenderPass,
+    /// `render_pass` keeping what was drawn, to go on with the frame after a render target.
+    pub resume_pass: vk::RenderPass,
     pub framebuffers: Vec<vk::Framebuffer>,
     pub current_img: usize,
     pub image_available: [vk::Semaphore; Self::MAX_FRAMES_IN_FLIGHT],
@@ -81,33 +173,173 @@ pub struct VkBackend {
 
     // misc
     pub swapchain_rebuild: bool,
+    pub pending_extent: Option<vk::Extent2D>,
+    pub last_rebuild: Instant,
 
     // pipeline
     pub pipeline_layout: vk::PipelineLayout,
-    pub pipeline: vk::Pipeline,
+    /// `pipeline_layout` with a second descriptor set for the palette texture.
+    pub palette_layout: vk::PipelineLayout,
+    pub sprite_vert: vk::ShaderModule,
+    /// Fragment shader of each material, indexed by `MaterialId`.
+    pub material_frags: Vec<vk::ShaderModule>,
+    /// Sprite pipelines built so far, see `sprite_pipeline`.
+    pub sprite_pipelines: HashMap<(MaterialId, BlendMode), vk::Pipeline>,
+    pub mesh_vert: vk::ShaderModule,
+    pub mesh_frag: vk::ShaderModule,
+    /// Mesh pipelines built so far, see `mesh_pipeline`.
+    pub mesh_pipelines: HashMap<BlendMode, vk::Pipeline>,
+    /// The mesh shaders drawing line lists, built on first use; null if that failed.
+    pub line_pipeline: Option<vk::Pipeline>,
+    pub pipeline_cache: vk::PipelineCache,
+    pub panel_pipeline: vk::Pipeline,
+    pub bound_pipeline: vk::Pipeline,
+
+    // post-processing, the scene is drawn offscreen and `post_pass` composites it
+    pub post_pass: vk::RenderPass,
+    pub post_layout: vk::PipelineLayout,
+    pub post_pipeline: vk::Pipeline,
+    /// Draws the previous frame's target under a camera with [`ScreenFilter::trail`].
+    pub feedback_pipeline: vk::Pipeline,
+    /// Whether the previous frame's target holds a finished frame, false after a rebuild.
+    pub post_history: bool,
+    pub post_sampler: vk::Sampler,
+    pub post_targets: PostTargets,
+    /// Camera regions with a [`ScreenFilter`] this frame: pixel min, size and filter.
+    pub filter_regions: Vec<([f32; 2], [f32; 2], ScreenFilter)>,
+
+    /// Render targets by texture slot, see [`Backend::create_render_target`].
+    pub render_targets: HashMap<usize, RenderTarget>,
+    /// Depth buffers of the render targets, one per size.
+    pub(crate) transients: Transients,
+    /// Slot of the render target drawn into, `None` for the frame.
+    pub current_target: Option<usize>,
 
     pub quad_vbo: vk::Buffer,
     pub quad_vbo_mem: vk::DeviceMemory,
 
     pub instance_vbo: vk::Buffer,
     pub instance_vbo_mem: vk::DeviceMemory,
+    /// Instances of baked batches by [`SpriteBatch::baked`] id.
+    pub baked: HashMap<u64, BakedInstances>,
+    /// Instances of retained batches by [`RetainedBatch::id`].
+    pub retained: HashMap<u64, RetainedInstances>,
+    /// Frames begun so far, to tell when a baked buffer is no longer in flight.
+    pub frame_count: u64,
+    /// Alignment of flushed ranges of host-visible memory.
+    pub non_coherent_atom_size: vk::DeviceSize,
+
+    pub panel_vbo: vk::Buffer,
+    pub panel_vbo_mem: vk::DeviceMemory,
+    pub panel_cursor: vk::DeviceSize,
+
+    pub mesh_vbo: vk::Buffer,
+    pub mesh_vbo_mem: vk::DeviceMemory,
+    pub mesh_vertex_cursor: vk::DeviceSize,
+    pub mesh_ibo: vk::Buffer,
+    pub mesh_ibo_mem: vk::DeviceMemory,
+    pub mesh_index_cursor: vk::DeviceSize,
 
     // textures
     pub images: Vec<vk::Image>,
     pub image_mem: Vec<vk::DeviceMemory>,
     pub image_views: Vec<vk::ImageView>,
     pub samplers: Vec<vk::Sampler>,
+    /// What each of `samplers` was created from, see [`Backend::set_sampler`].
+    pub sampler_descs: Vec<SamplerDesc>,
+    pub image_bytes: Vec<vk::DeviceSize>,
     pub descriptor_sets: Vec<vk::DescriptorSet>, // ← one per texture
+    /// Slots emptied by `destroy_texture`, their descriptor sets kept for the next texture.
+    pub free_slots: Vec<usize>,
 
     // common objects
     pub desc_set_layout: vk::DescriptorSetLayout,
     pub desc_pool: vk::DescriptorPool,
 
     pub instance_cursor: vk::DeviceSize,
+
+    // readback
+    pub screenshot_requested: bool,
+    pub screenshot: Option<Screenshot>,
+
+    pub present_timing: PresentTiming,
+    /// Allocations other than `post_targets`, which track their own size.
+    pub memory: MemoryStats,
+    pub time: f32,
+    pub calibration: DisplayCalibration,
+    /// Multiplies the final image, see [`Backend::set_tint`].
+    pub tint: Color,
+    /// Requested mode, the swapchain has the closest supported one, see [`Backend::set_present_mode`].
+    pub present_mode: PresentMode,
+    /// Filter of every sampler in `samplers`, see [`Backend::set_texture_filter`].
+    pub texture_filter: TextureFilter,
 }
 
 impl VkBackend {
     const MAX_FRAMES_IN_FLIGHT: usize = 2;
+    /// Minimum time between swapchain rebuilds while a resize is in progress.
+    const REBUILD_INTERVAL: Duration = Duration::from_millis(50);
+
+    fn rebuild_swapchain(&mut self) {
+        unsafe { self.device.device_wait_idle() }.unwrap();
+        let extent = self
+            .pending_extent
+            .take()
+            .unwrap_or(self.surface_resolution);
+        let _ = self.create_swapchain(extent.width, extent.height);
+        self.swapchain_rebuild = false;
+        self.last_rebuild = Instant::now();
+    }
+
+    /// A sampler for a texture with `desc`, filtered by the current
+    /// [`VkBackend::texture_filter`] unless `desc` has its own.
+    fn texture_sampler(&self, desc: SamplerDesc) -> Result<vk::Sampler, vk::Result> {
+        let filter = match desc.filter.unwrap_or(self.texture_filter) {
+            TextureFilter::Nearest => vk::Filter::NEAREST,
+            TextureFilter::Linear => vk::Filter::LINEAR,
+        };
+        let address = match desc.address_mode {
+            AddressMode::ClampToEdge => vk::SamplerAddressMode::CLAMP_TO_EDGE,
+            AddressMode::Repeat => vk::SamplerAddressMode::REPEAT,
+            AddressMode::MirroredRepeat => vk::SamplerAddressMode::MIRRORED_REPEAT,
+        };
+        unsafe {
+            self.device.create_sampler(
+                &vk::SamplerCreateInfo::default()
+                    .min_filter(filter)
+                    .mag_filter(filter)
+                    .mipmap_mode(vk::SamplerMipmapMode::NEAREST)
+                    .address_mode_u(address)
+                    .address_mode_v(address)
+                    .max_lod(0.0),
+                None,
+            )
+        }
+    }
+
+    /// Recreates the sampler of slot `idx` from its desc and points the slot's descriptor at it.
+    ///
+    /// # Safety
+    /// The slot must no longer be in use by the GPU.
+    unsafe fn rebuild_sampler(&mut self, idx: usize) -> Result<(), vk::Result> {
+        let sampler = self.texture_sampler(self.sampler_descs[idx])?;
+        unsafe {
+            self.device.destroy_sampler(self.samplers[idx], None);
+            self.samplers[idx] = sampler;
+            let img_info = vk::DescriptorImageInfo::default()
+                .sampler(sampler)
+                .image_view(self.image_views[idx])
+                .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+            let write = vk::WriteDescriptorSet::default()
+                .dst_set(self.descriptor_sets[idx])
+                .dst_binding(0)
+                .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+                .image_info(std::slice::from_ref(&img_info));
+            self.device
+                .update_descriptor_sets(std::slice::from_ref(&write), &[]);
+        }
+        Ok(())
+    }
 
     fn create_swapchain(
         &mut self,
@@ -127,11 +359,7 @@ impl VkBackend {
             let present_modes = self
                 .surface_loader
                 .get_physical_device_surface_present_modes(self.pdevice, self.surface)?;
-            let present_mode = present_modes
-                .iter()
-                .cloned()
-                .find(|m| *m == vk::PresentModeKHR::MAILBOX)
-                .unwrap_or(vk::PresentModeKHR::IMMEDIATE);
+            let present_mode = present_mode(self.present_mode, &present_modes);
 
             let desired_image_count =
                 (caps.min_image_count + 1).min(caps.max_image_count.max(caps.min_image_count + 1));
@@ -164,7 +392,10 @@ impl VkBackend {
                 .image_color_space(self.surface_format.color_space)
                 .image_format(self.surface_format.format)
                 .image_extent(self.surface_resolution)
-                .image_usage(vk::ImageUsageFlags::COLOR_ATTACHMENT)
+                .image_usage(
+                    vk::ImageUsageFlags::COLOR_ATTACHMENT
+                        | (caps.supported_usage_flags & vk::ImageUsageFlags::TRANSFER_SRC),
+                )
                 .image_sharing_mode(vk::SharingMode::EXCLUSIVE)
                 .pre_transform(
                     if caps
@@ -182,6 +413,7 @@ impl VkBackend {
                 .image_array_layers(1);
 
             self.swapchain = self.swapchain_loader.create_swapchain(&swap_info, None)?;
+            self.present_timing.reset(self.swapchain);
 
             self.present_images = self.swapchain_loader.get_swapchain_images(self.swapchain)?;
             self.present_image_views = self
@@ -214,7 +446,7 @@ impl VkBackend {
                 .iter()
                 .map(|&view| {
                     let fb_info = vk::FramebufferCreateInfo::default()
-                        .render_pass(self.render_pass)
+                        .render_pass(self.post_pass)
                         .attachments(std::slice::from_ref(&view))
                         .width(self.surface_resolution.width)
                         .height(self.surface_resolution.height)
@@ -223,57 +455,698 @@ impl VkBackend {
                 })
                 .collect::<Result<_, _>>()?;
 
+            self.post_targets.destroy(&self.device);
+            self.post_targets = PostTargets::new(
+                &self.device,
+                &self.device_memory_properties,
+                self.render_pass,
+                self.desc_set_layout,
+                self.post_sampler,
+                self.surface_format.format,
+                self.depth_format,
+                self.surface_resolution,
+                Self::MAX_FRAMES_IN_FLIGHT,
+            )?;
+            self.post_history = false;
+
             Ok(())
         }
     }
-}
 
-impl Backend for VkBackend {
-    type Error = vk::Result;
+    /// Blends the previous frame's target into the current one over a camera region.
+    fn draw_feedback(
+        &mut self,
+        cmd: vk::CommandBuffer,
+        min: [f32; 2],
+        size: [f32; 2],
+        filter: &ScreenFilter,
+    ) {
+        let extent = self.surface_resolution;
+        let screen = [extent.width as f32, extent.height as f32];
+        let prev = (self.frame_idx + Self::MAX_FRAMES_IN_FLIGHT - 1) % Self::MAX_FRAMES_IN_FLIGHT;
+        let pc = post::push_constants(
+            min,
+            size,
+            screen,
+            self.time,
+            filter,
+            self.calibration,
+            self.tint,
+        );
+        unsafe {
+            self.device.cmd_bind_pipeline(
+                cmd,
+                vk::PipelineBindPoint::GRAPHICS,
+                self.feedback_pipeline,
+            );
+            self.device.cmd_bind_descriptor_sets(
+                cmd,
+                vk::PipelineBindPoint::GRAPHICS,
+                self.post_layout,
+                0,
+                std::slice::from_ref(&self.post_targets.sets[prev]),
+                &[],
+            );
+            self.device.cmd_push_constants(
+                cmd,
+                self.post_layout,
+                vk::ShaderStageFlags::FRAGMENT,
+                0,
+                bytemuck::cast_slice(&pc),
+            );
+            self.device.cmd_draw(cmd, 3, 1, 0, 0);
+        }
+        self.bound_pipeline = self.feedback_pipeline;
+    }
 
-    fn handle_resize(&mut self, size: winit::dpi::PhysicalSize<u32>) {
-        if size.width == self.surface_resolution.width
-            && size.height == self.surface_resolution.height
+    /// Copies the scene onto swapchain image `img`, filtering the regions of cameras that asked for it.
+    fn record_post(&mut self, cmd: vk::CommandBuffer, img: usize) {
+        let extent = self.surface_resolution;
+        let screen = [extent.width as f32, extent.height as f32];
+        let clear = vk::ClearValue {
+            color: vk::ClearColorValue {
+                float32: [0.0, 0.0, 0.0, 1.0],
+            },
+        };
+        let full = ([0.0, 0.0], screen, ScreenFilter::NONE);
+        unsafe {
+            self.device.cmd_begin_render_pass(
+                cmd,
+                &vk::RenderPassBeginInfo::default()
+                    .render_pass(self.post_pass)
+                    .framebuffer(self.framebuffers[img])
+                    .render_area(vk::Rect2D {
+                        offset: vk::Offset2D { x: 0, y: 0 },
+                        extent,
+                    })
+                    .clear_values(std::slice::from_ref(&clear)),
+                vk::SubpassContents::INLINE,
+            );
+            self.device
+                .cmd_bind_pipeline(cmd, vk::PipelineBindPoint::GRAPHICS, self.post_pipeline);
+            self.device.cmd_bind_descriptor_sets(
+                cmd,
+                vk::PipelineBindPoint::GRAPHICS,
+                self.post_layout,
+                0,
+                std::slice::from_ref(&self.post_targets.sets[self.frame_idx]),
+                &[],
+            );
+            // an unfiltered copy of the whole screen first, filtered regions on top
+            for (min, size, filter) in std::iter::once(full).chain(self.filter_regions.drain(..)) {
+                let vp = vk::Viewport::default()
+                    .x(min[0])
+                    .y(min[1])
+                    .width(size[0])
+                    .height(size[1])
+                    .min_depth(0.0)
+                    .max_depth(1.0);
+                let sc = vk::Rect2D {
+                    offset: vk::Offset2D {
+                        x: min[0] as i32,
+                        y: min[1] as i32,
+                    },
+                    extent: vk::Extent2D {
+                        width: size[0] as u32,
+                        height: size[1] as u32,
+                    },
+                };
+                let pc = post::push_constants(
+                    min,
+                    size,
+                    screen,
+                    self.time,
+                    &filter,
+                    self.calibration,
+                    self.tint,
+                );
+                self.device
+                    .cmd_set_viewport(cmd, 0, std::slice::from_ref(&vp));
+                self.device
+                    .cmd_set_scissor(cmd, 0, std::slice::from_ref(&sc));
+                self.device.cmd_push_constants(
+                    cmd,
+                    self.post_layout,
+                    vk::ShaderStageFlags::FRAGMENT,
+                    0,
+                    bytemuck::cast_slice(&pc),
+                );
+                self.device.cmd_draw(cmd, 3, 1, 0, 0);
+            }
+            self.device.cmd_end_render_pass(cmd);
+        }
+    }
+
+    /// Records a copy of present image `img` into a host-visible buffer.
+    ///
+    /// Must be called after the render pass ended, the image is back in
+    /// `PRESENT_SRC_KHR` once the copy is done.
+    fn record_readback(
+        &self,
+        cmd: vk::CommandBuffer,
+        img: usize,
+    ) -> Option<(vk::Buffer, vk::DeviceMemory)> {
+        let caps = unsafe {
+            self.surface_loader
+                .get_physical_device_surface_capabilities(self.pdevice, self.surface)
+        }
+        .ok()?;
+        let readable_format = matches!(
+            self.surface_format.format,
+            vk::Format::B8G8R8A8_UNORM
+                | vk::Format::B8G8R8A8_SRGB
+                | vk::Format::R8G8B8A8_UNORM
+                | vk::Format::R8G8B8A8_SRGB
+        );
+        if !caps
+            .supported_usage_flags
+            .contains(vk::ImageUsageFlags::TRANSFER_SRC)
+            || !readable_format
         {
-            return;
+            tracing::warn!("screenshots are not supported by this surface");
+            return None;
         }
-        self.swapchain_rebuild = true;
+
+        let extent = self.surface_resolution;
+        let (buf, mem) = shaders::create_buffer(
+            &self.device,
+            &self.device_memory_properties,
+            (extent.width * extent.height * 4) as vk::DeviceSize,
+            vk::BufferUsageFlags::TRANSFER_DST,
+            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+        );
+        let image = self.present_images[img];
+        let mut barriers = ImageBarriers::default();
+        barriers.rendered(image, vk::ImageLayout::PRESENT_SRC_KHR);
+        unsafe {
+            barriers.transition(
+                &self.device,
+                cmd,
+                &Uses::new().color(image, Usage::TransferSrc),
+            );
+
+            let region = vk::BufferImageCopy::default()
+                .image_subresource(
+                    vk::ImageSubresourceLayers::default()
+                        .aspect_mask(vk::ImageAspectFlags::COLOR)
+                        .layer_count(1),
+                )
+                .image_extent(vk::Extent3D {
+                    width: extent.width,
+                    height: extent.height,
+                    depth: 1,
+                });
+            self.device.cmd_copy_image_to_buffer(
+                cmd,
+                image,
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                buf,
+                std::slice::from_ref(&region),
+            );
+
+            barriers.transition(&self.device, cmd, &Uses::new().color(image, Usage::Present));
+        }
+        Some((buf, mem))
     }
 
-    fn bind_camera(&mut self, camera: &Camera) {
-        let pc = [
-            self.surface_resolution.width as f32,
-            self.surface_resolution.height as f32,
-            camera.center.x,
-            camera.center.y,
-            camera.zoom,
-        ];
+    /// Pipeline drawing `material` with `blend`, built on first use.
+    fn sprite_pipeline(&mut self, material: MaterialId, blend: BlendMode) -> Option<vk::Pipeline> {
+        if let Some(&p) = self.sprite_pipelines.get(&(material, blend)) {
+            return (p != vk::Pipeline::null()).then_some(p);
+        }
+        let frag = *self
+            .material_frags
+            .get(material.0 as usize)
+            .filter(|f| **f != vk::ShaderModule::null())?;
+        let layout = if material == MaterialId::PALETTE {
+            self.palette_layout
+        } else {
+            self.pipeline_layout
+        };
+        let (bindings, attributes) = shaders::sprite_vertex_input();
+        let vertex_state = vk::PipelineVertexInputStateCreateInfo::default()
+            .vertex_binding_descriptions(&bindings)
+            .vertex_attribute_descriptions(&attributes);
+        let started = Instant::now();
+        let pipeline = shaders::create_pipeline(
+            &self.device,
+            self.pipeline_cache,
+            self.render_pass,
+            layout,
+            self.sprite_vert,
+            frag,
+            &vertex_state,
+            vk::PrimitiveTopology::TRIANGLE_STRIP,
+            blend,
+            material == MaterialId::CUTOUT,
+        );
+        // failures are remembered as null so they are reported once, not every frame
+        let pipeline = match pipeline {
+            Ok(p) => {
+                tracing::debug!(
+                    "Built {material:?} pipeline for {blend:?} in {:?}",
+                    started.elapsed()
+                );
+                p
+            }
+            Err(e) => {
+                tracing::error!("Failed to build {material:?} pipeline for {blend:?}: {e}");
+                vk::Pipeline::null()
+            }
+        };
+        self.sprite_pipelines.insert((material, blend), pipeline);
+        (pipeline != vk::Pipeline::null()).then_some(pipeline)
+    }
+
+    fn mesh_pipeline(&mut self, blend: BlendMode) -> Option<vk::Pipeline> {
+        if let Some(&p) = self.mesh_pipelines.get(&blend) {
+            return (p != vk::Pipeline::null()).then_some(p);
+        }
+        let (bindings, attributes) = shaders::mesh_vertex_input();
+        let vertex_state = vk::PipelineVertexInputStateCreateInfo::default()
+            .vertex_binding_descriptions(&bindings)
+            .vertex_attribute_descriptions(&attributes);
+        let pipeline = shaders::create_pipeline(
+            &self.device,
+            self.pipeline_cache,
+            self.render_pass,
+            self.pipeline_layout,
+            self.mesh_vert,
+            self.mesh_frag,
+            &vertex_state,
+            vk::PrimitiveTopology::TRIANGLE_LIST,
+            blend,
+            false,
+        );
+        // failures are remembered as null so they are reported once, not every frame
+        let pipeline = pipeline.unwrap_or_else(|e| {
+            tracing::error!("Failed to build mesh pipeline for {blend:?}: {e}");
+            vk::Pipeline::null()
+        });
+        self.mesh_pipelines.insert(blend, pipeline);
+        (pipeline != vk::Pipeline::null()).then_some(pipeline)
+    }
+
+    fn line_pipeline(&mut self) -> Option<vk::Pipeline> {
+        if let Some(p) = self.line_pipeline {
+            return (p != vk::Pipeline::null()).then_some(p);
+        }
+        let (bindings, attributes) = shaders::mesh_vertex_input();
+        let vertex_state = vk::PipelineVertexInputStateCreateInfo::default()
+            .vertex_binding_descriptions(&bindings)
+            .vertex_attribute_descriptions(&attributes);
+        let pipeline = shaders::create_pipeline(
+            &self.device,
+            self.pipeline_cache,
+            self.render_pass,
+            self.pipeline_layout,
+            self.mesh_vert,
+            self.mesh_frag,
+            &vertex_state,
+            vk::PrimitiveTopology::LINE_LIST,
+            BlendMode::Alpha,
+            false,
+        );
+        // failures are remembered as null so they are reported once, not every frame
+        let pipeline = pipeline.unwrap_or_else(|e| {
+            tracing::error!("Failed to build line pipeline: {e}");
+            vk::Pipeline::null()
+        });
+        self.line_pipeline = Some(pipeline);
+        (pipeline != vk::Pipeline::null()).then_some(pipeline)
+    }
+
+    /// Device-local buffer holding the instances of baked batch `id`, uploaded on first use.
+    fn baked_instances(&mut self, id: u64, instances: &[SpriteInstance]) -> Option<vk::Buffer> {
+        if let Some(b) = self.baked.get_mut(&id) {
+            b.last_used = self.frame_count;
+            return Some(b.buffer);
+        }
+        let bytes = std::mem::size_of_val(instances) as vk::DeviceSize;
+        let (buffer, memory) = shaders::create_buffer(
+            &self.device,
+            &self.device_memory_properties,
+            bytes,
+            vk::BufferUsageFlags::VERTEX_BUFFER | vk::BufferUsageFlags::TRANSFER_DST,
+            vk::MemoryPropertyFlags::DEVICE_LOCAL,
+        );
+        let (stage_buf, stage_mem) = shaders::create_buffer(
+            &self.device,
+            &self.device_memory_properties,
+            bytes,
+            vk::BufferUsageFlags::TRANSFER_SRC,
+            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+        );
+        let uploaded = unsafe {
+            let ptr = self
+                .device
+                .map_memory(stage_mem, 0, bytes, vk::MemoryMapFlags::empty())
+                .ok()? as *mut SpriteInstance;
+            ptr.copy_from_nonoverlapping(instances.as_ptr(), instances.len());
+            self.device.unmap_memory(stage_mem);
+            self.copy_buffer(stage_buf, buffer, bytes)
+        };
         unsafe {
-            self.device.cmd_push_constants(
-                self.cmds[self.frame_idx],
-                self.pipeline_layout,
-                vk::ShaderStageFlags::VERTEX,
+            self.device.destroy_buffer(stage_buf, None);
+            self.device.free_memory(stage_mem, None);
+        }
+        if let Err(e) = uploaded {
+            tracing::error!("Failed to upload baked sprites: {e}");
+            unsafe {
+                self.device.destroy_buffer(buffer, None);
+                self.device.free_memory(memory, None);
+            }
+            return None;
+        }
+        self.memory.buffers += bytes;
+        self.baked.insert(
+            id,
+            BakedInstances {
+                buffer,
+                memory,
+                bytes,
+                last_used: self.frame_count,
+            },
+        );
+        Some(buffer)
+    }
+
+    /// This frame's buffer holding the instances of retained batch `retained`,
+    /// writing only the ranges changed since this buffer was last drawn.
+    fn retained_instances(
+        &mut self,
+        retained: &RetainedBatch,
+        instances: &[SpriteInstance],
+    ) -> Option<vk::Buffer> {
+        let entry = self
+            .retained
+            .entry(retained.id)
+            .or_insert_with(|| RetainedInstances {
+                slots: Vec::new(),
+                generation: retained.generation,
+                last_used: self.frame_count,
+            });
+        // other cameras drawing it this frame find it written already
+        if entry.last_used != self.frame_count || entry.slots.is_empty() {
+            let missed = entry.generation + 1 != retained.generation;
+            for slot in &mut entry.slots {
+                slot.stale |= missed;
+                slot.pending.extend(retained.dirty.iter().cloned());
+            }
+            entry.generation = retained.generation;
+            entry.last_used = self.frame_count;
+        }
+        while entry.slots.len() < Self::MAX_FRAMES_IN_FLIGHT {
+            entry.slots.push(RetainedSlot {
+                buffer: vk::Buffer::null(),
+                memory: vk::DeviceMemory::null(),
+                capacity: 0,
+                pending: Vec::new(),
+                stale: true,
+            });
+        }
+
+        let inst_size = std::mem::size_of::<SpriteInstance>() as vk::DeviceSize;
+        let slot = &mut entry.slots[self.frame_idx];
+        if slot.capacity < instances.len() {
+            if slot.buffer != vk::Buffer::null() {
+                // the frame that last drew from it has finished, begin_frame waited on its fence
+                unsafe {
+                    self.device.destroy_buffer(slot.buffer, None);
+                    self.device.free_memory(slot.memory, None);
+                }
+                self.memory.buffers -= slot.capacity as vk::DeviceSize * inst_size;
+            }
+            // room to grow, so adding a few sprites does not reallocate every frame
+            let capacity = instances.len().next_power_of_two();
+            let (buffer, memory) = shaders::create_buffer(
+                &self.device,
+                &self.device_memory_properties,
+                capacity as vk::DeviceSize * inst_size,
+                vk::BufferUsageFlags::VERTEX_BUFFER,
+                vk::MemoryPropertyFlags::HOST_VISIBLE,
+            );
+            self.memory.buffers += capacity as vk::DeviceSize * inst_size;
+            *slot = RetainedSlot {
+                buffer,
+                memory,
+                capacity,
+                pending: Vec::new(),
+                stale: true,
+            };
+        }
+
+        let ranges = match std::mem::take(&mut slot.stale) {
+            true => std::iter::once(0..instances.len()).collect(),
+            false => std::mem::take(&mut slot.pending),
+        };
+        slot.pending.clear();
+        let atom = self.non_coherent_atom_size.max(1);
+        let size = slot.capacity as vk::DeviceSize * inst_size;
+        let mut flushes = Vec::new();
+        unsafe {
+            let ptr = self
+                .device
+                .map_memory(slot.memory, 0, vk::WHOLE_SIZE, vk::MemoryMapFlags::empty())
+                .ok()? as *mut SpriteInstance;
+            for r in ranges {
+                let r = r.start.min(instances.len())..r.end.min(instances.len());
+                if r.is_empty() {
+                    continue;
+                }
+                ptr.add(r.start)
+                    .copy_from_nonoverlapping(instances[r.clone()].as_ptr(), r.len());
+                let start = r.start as vk::DeviceSize * inst_size / atom * atom;
+                let end = (r.end as vk::DeviceSize * inst_size).next_multiple_of(atom);
+                flushes.push(
+                    vk::MappedMemoryRange::default()
+                        .memory(slot.memory)
+                        .offset(start)
+                        .size(if end >= size {
+                            vk::WHOLE_SIZE
+                        } else {
+                            end - start
+                        }),
+                );
+            }
+            if !flushes.is_empty()
+                && let Err(e) = self.device.flush_mapped_memory_ranges(&flushes)
+            {
+                tracing::error!("Failed to flush retained sprites: {e}");
+            }
+            self.device.unmap_memory(slot.memory);
+        }
+        Some(slot.buffer)
+    }
+
+    /// Copies `bytes` from `src` to `dst` and waits for it.
+    fn copy_buffer(
+        &self,
+        src: vk::Buffer,
+        dst: vk::Buffer,
+        bytes: vk::DeviceSize,
+    ) -> Result<(), vk::Result> {
+        unsafe {
+            let tmp_cmd = self.device.allocate_command_buffers(
+                &vk::CommandBufferAllocateInfo::default()
+                    .command_pool(self.pool)
+                    .level(vk::CommandBufferLevel::PRIMARY)
+                    .command_buffer_count(1),
+            )?[0];
+            let tmp_fence = self.device.create_fence(
+                &vk::FenceCreateInfo::default().flags(vk::FenceCreateFlags::SIGNALED),
+                None,
+            )?;
+            let region = vk::BufferCopy::default().size(bytes);
+            record_submit_commandbuffer(
+                &self.device,
+                tmp_cmd,
+                tmp_fence,
+                self.present_queue,
+                &[],
+                &[],
+                &[],
+                |d, c| {
+                    d.cmd_copy_buffer(c, src, dst, std::slice::from_ref(&region));
+                },
+            );
+            let waited = self.device.wait_for_fences(&[tmp_fence], true, u64::MAX);
+            self.device.destroy_fence(tmp_fence, None);
+            self.device.free_command_buffers(self.pool, &[tmp_cmd]);
+            waited
+        }
+    }
+
+    /// Frees baked and retained buffers no frame in flight has drawn from.
+    fn retire_baked(&mut self) {
+        let frame = self.frame_count;
+        let (device, memory) = (&self.device, &mut self.memory);
+        let inst_size = std::mem::size_of::<SpriteInstance>() as vk::DeviceSize;
+        self.retained.retain(|_, r| {
+            let live = frame - r.last_used <= Self::MAX_FRAMES_IN_FLIGHT as u64;
+            if !live {
+                for s in &r.slots {
+                    unsafe {
+                        device.destroy_buffer(s.buffer, None);
+                        device.free_memory(s.memory, None);
+                    }
+                    memory.buffers -= s.capacity as vk::DeviceSize * inst_size;
+                }
+            }
+            live
+        });
+        self.baked.retain(|_, b| {
+            let live = frame - b.last_used <= Self::MAX_FRAMES_IN_FLIGHT as u64;
+            if !live {
+                unsafe {
+                    device.destroy_buffer(b.buffer, None);
+                    device.free_memory(b.memory, None);
+                }
+                memory.buffers -= b.bytes;
+            }
+            live
+        });
+    }
+
+    /// Uploads the batch into the instance buffer, unless it is baked and
+    /// already on the GPU or retained, and draws it with `pipeline`.
+    fn draw_instances(
+        &mut self,
+        pipeline: vk::Pipeline,
+        layout: vk::PipelineLayout,
+        sets: &[vk::DescriptorSet],
+        batch: &SpriteBatch,
+    ) {
+        let baked = match &batch.retained {
+            Some(r) => self.retained_instances(r, &batch.instances),
+            None => batch
+                .baked
+                .and_then(|id| self.baked_instances(id, &batch.instances)),
+        };
+        let (buffer, offset, count) = match baked {
+            Some(buffer) => (buffer, 0, batch.instances.len()),
+            None => {
+                let Some((offset, count)) = self.upload_instances(&batch.instances) else {
+                    return;
+                };
+                (self.instance_vbo, offset, count)
+            }
+        };
+
+        let cmd = self.cmds[self.frame_idx];
+
+        unsafe {
+            if self.bound_pipeline != pipeline {
+                self.bound_pipeline = pipeline;
+                self.device
+                    .cmd_bind_pipeline(cmd, vk::PipelineBindPoint::GRAPHICS, pipeline);
+            }
+
+            self.device.cmd_bind_descriptor_sets(
+                cmd,
+                vk::PipelineBindPoint::GRAPHICS,
+                layout,
                 0,
-                bytemuck::cast_slice(&pc),
+                sets,
+                &[],
             );
+
+            let buffers = [self.quad_vbo, buffer];
+            let offsets = [0, offset];
+            self.device
+                .cmd_bind_vertex_buffers(cmd, 0, &buffers, &offsets);
+
+            self.device
+                .cmd_draw(cmd, VERTEX_COUNT as u32, count as u32, 0, 0);
         }
     }
 
-    fn create_texture(
+    /// Copies `instances` into this frame's part of the instance buffer,
+    /// returning where they start and how many fit.
+    fn upload_instances(
+        &mut self,
+        instances: &[SpriteInstance],
+    ) -> Option<(vk::DeviceSize, usize)> {
+        // the instance buffer holds MAX_SPRITES per frame across all batches and cameras
+        let inst_size = std::mem::size_of::<SpriteInstance>() as vk::DeviceSize;
+        let capacity = MAX_SPRITES - (self.instance_cursor / inst_size) as usize;
+        let instances = &instances[..instances.len().min(capacity)];
+        if instances.is_empty() {
+            return None;
+        }
+        let offset = self.instance_cursor;
+        let byte_count = instances.len() as vk::DeviceSize * inst_size;
+        unsafe {
+            let ptr = self
+                .device
+                .map_memory(
+                    self.instance_vbo_mem,
+                    offset,
+                    byte_count,
+                    vk::MemoryMapFlags::empty(),
+                )
+                .unwrap() as *mut SpriteInstance;
+            ptr.copy_from_nonoverlapping(instances.as_ptr(), instances.len());
+            self.device.unmap_memory(self.instance_vbo_mem);
+        }
+        self.instance_cursor += byte_count;
+        Some((offset, instances.len()))
+    }
+
+    /// Waits for the frame that recorded the readback and converts it to RGBA-8.
+    fn finish_readback(&mut self, fence: vk::Fence, buf: vk::Buffer, mem: vk::DeviceMemory) {
+        let extent = self.surface_resolution;
+        let len = (extent.width * extent.height * 4) as usize;
+        unsafe {
+            self.device
+                .wait_for_fences(&[fence], true, u64::MAX)
+                .expect("Wait for fence failed.");
+            let ptr = self
+                .device
+                .map_memory(mem, 0, len as vk::DeviceSize, vk::MemoryMapFlags::empty())
+                .unwrap() as *const u8;
+            let mut pixels = std::slice::from_raw_parts(ptr, len).to_vec();
+            self.device.unmap_memory(mem);
+            self.device.destroy_buffer(buf, None);
+            self.device.free_memory(mem, None);
+
+            if matches!(
+                self.surface_format.format,
+                vk::Format::B8G8R8A8_UNORM | vk::Format::B8G8R8A8_SRGB
+            ) {
+                for px in pixels.chunks_exact_mut(4) {
+                    px.swap(0, 2);
+                }
+            }
+            self.screenshot = Some(Screenshot {
+                width: extent.width,
+                height: extent.height,
+                pixels,
+            });
+        }
+    }
+
+    /// Uploads texels into a new sampled image, returning it with its memory, view and size.
+    fn upload_image(
         &mut self,
         width: u32,
         height: u32,
+        format: TextureFormat,
         pixels: &[u8],
-    ) -> Result<usize, vk::Result> {
+    ) -> Result<(vk::Image, vk::DeviceMemory, vk::ImageView, vk::DeviceSize), vk::Result> {
         assert_eq!(
             pixels.len(),
-            (width * height * 4) as usize,
-            "pixels buffer must be RGBA-8 per texel"
+            width as usize * height as usize * format.bytes_per_texel(),
+            "pixels buffer must match {format:?}"
         );
-        if self.images.len() >= MAX_TEXTURES {
-            panic!("texture limit reached ({MAX_TEXTURES})");
-        }
-
+        let converted;
+        let (vk_format, pixels) = match texture_format(format) {
+            (f, _) if self.supports_sampling(f) => (f, pixels),
+            _ => {
+                converted = rgba8_from(format, width, height, pixels);
+                (vk::Format::R8G8B8A8_UNORM, &converted[..])
+            }
+        };
         let img_size = pixels.len() as vk::DeviceSize;
         let (stage_buf, stage_mem) = shaders::create_buffer(
             &self.device,
@@ -294,7 +1167,7 @@ impl Backend for VkBackend {
 
         let img_info = vk::ImageCreateInfo::default()
             .image_type(vk::ImageType::TYPE_2D)
-            .format(vk::Format::R8G8B8A8_UNORM)
+            .format(vk_format)
             .extent(vk::Extent3D {
                 width,
                 height,
@@ -311,6 +1184,7 @@ impl Backend for VkBackend {
         let image = unsafe { self.device.create_image(&img_info, None)? };
 
         let req = unsafe { self.device.get_image_memory_requirements(image) };
+        let bytes = req.size;
         let mem_index = utils::find_memorytype_index(
             &req,
             &self.device_memory_properties,
@@ -362,57 +1236,22 @@ impl Backend for VkBackend {
             &[],
             &[],
             |d, c| unsafe {
-                let to_transfer = vk::ImageMemoryBarrier::default()
-                    .image(image)
-                    .src_access_mask(vk::AccessFlags::empty())
-                    .dst_access_mask(vk::AccessFlags::TRANSFER_WRITE)
-                    .old_layout(vk::ImageLayout::UNDEFINED)
-                    .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
-                    .subresource_range(
-                        vk::ImageSubresourceRange::default()
-                            .aspect_mask(vk::ImageAspectFlags::COLOR)
-                            .level_count(1)
-                            .layer_count(1),
-                    );
-                d.cmd_pipeline_barrier(
-                    c,
-                    vk::PipelineStageFlags::TOP_OF_PIPE,
-                    vk::PipelineStageFlags::TRANSFER,
-                    vk::DependencyFlags::empty(),
-                    &[],
-                    &[],
-                    &[to_transfer],
-                );
-
-                d.cmd_copy_buffer_to_image(
-                    c,
-                    stage_buf,
-                    image,
-                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
-                    std::slice::from_ref(&region),
-                );
-
-                let to_shader = vk::ImageMemoryBarrier::default()
-                    .image(image)
-                    .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
-                    .dst_access_mask(vk::AccessFlags::SHADER_READ)
-                    .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
-                    .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
-                    .subresource_range(
-                        vk::ImageSubresourceRange::default()
-                            .aspect_mask(vk::ImageAspectFlags::COLOR)
-                            .level_count(1)
-                            .layer_count(1),
-                    );
-                d.cmd_pipeline_barrier(
+                let mut barriers = ImageBarriers::default();
+                barriers.record(
+                    d,
                     c,
-                    vk::PipelineStageFlags::TRANSFER,
-                    vk::PipelineStageFlags::FRAGMENT_SHADER,
-                    vk::DependencyFlags::empty(),
-                    &[],
-                    &[],
-                    &[to_shader],
+                    &Uses::new().color(image, Usage::TransferDst),
+                    |d, c| {
+                        d.cmd_copy_buffer_to_image(
+                            c,
+                            stage_buf,
+                            image,
+                            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                            std::slice::from_ref(&region),
+                        );
+                    },
                 );
+                barriers.transition(d, c, &Uses::new().color(image, Usage::Sampled));
             },
         );
 
@@ -429,7 +1268,8 @@ impl Backend for VkBackend {
                 &vk::ImageViewCreateInfo::default()
                     .image(image)
                     .view_type(vk::ImageViewType::TYPE_2D)
-                    .format(vk::Format::R8G8B8A8_UNORM)
+                    .format(vk_format)
+                    .components(texture_format(format).1)
                     .subresource_range(
                         vk::ImageSubresourceRange::default()
                             .aspect_mask(vk::ImageAspectFlags::COLOR)
@@ -439,41 +1279,42 @@ impl Backend for VkBackend {
                 None,
             )?
         };
+        Ok((image, image_mem, view, bytes))
+    }
 
-        // LINEAR SAMPLING
-        // let sampler = unsafe {
-        //     self.device.create_sampler(
-        //         &vk::SamplerCreateInfo::default()
-        //             .min_filter(vk::Filter::LINEAR)
-        //             .mag_filter(vk::Filter::LINEAR)
-        //             .mipmap_mode(vk::SamplerMipmapMode::LINEAR)
-        //             .address_mode_u(vk::SamplerAddressMode::REPEAT)
-        //             .address_mode_v(vk::SamplerAddressMode::REPEAT)
-        //             .max_lod(0.0),
-        //         None,
-        //     )?
-        // };
-
-        // NEAREST SAMPLING
-        let sampler = unsafe {
-            self.device.create_sampler(
-                &vk::SamplerCreateInfo::default()
-                    .min_filter(vk::Filter::NEAREST)
-                    .mag_filter(vk::Filter::NEAREST)
-                    .mipmap_mode(vk::SamplerMipmapMode::NEAREST)
-                    .address_mode_u(vk::SamplerAddressMode::CLAMP_TO_EDGE)
-                    .address_mode_v(vk::SamplerAddressMode::CLAMP_TO_EDGE)
-                    .max_lod(0.0),
-                None,
-            )?
-        };
+    /// Frees what `t` adds to its texture slot.
+    ///
+    /// # Safety
+    /// The target must no longer be in use by the GPU.
+    unsafe fn destroy_render_target(&mut self, t: &RenderTarget) {
+        unsafe {
+            self.device.destroy_framebuffer(t.framebuffer, None);
+            self.transients.release(&self.device, t.depth.0);
+        }
+    }
 
-        let desc_set = unsafe {
-            self.device.allocate_descriptor_sets(
-                &vk::DescriptorSetAllocateInfo::default()
-                    .descriptor_pool(self.desc_pool)
-                    .set_layouts(std::slice::from_ref(&self.desc_set_layout)),
-            )?[0]
+    /// Adds a texture slot sampling `view`, counting `bytes` as texture memory.
+    fn add_slot(
+        &mut self,
+        image: vk::Image,
+        image_mem: vk::DeviceMemory,
+        view: vk::ImageView,
+        bytes: vk::DeviceSize,
+    ) -> Result<usize, vk::Result> {
+        self.memory.textures += bytes;
+
+        let sampler = self.texture_sampler(SamplerDesc::default())?;
+
+        let free = self.free_slots.pop();
+        let desc_set = match free {
+            Some(idx) => self.descriptor_sets[idx],
+            None => unsafe {
+                self.device.allocate_descriptor_sets(
+                    &vk::DescriptorSetAllocateInfo::default()
+                        .descriptor_pool(self.desc_pool)
+                        .set_layouts(std::slice::from_ref(&self.desc_set_layout)),
+                )?[0]
+            },
         };
 
         let img_info = vk::DescriptorImageInfo::default()
@@ -491,25 +1332,520 @@ impl Backend for VkBackend {
             self.device
                 .update_descriptor_sets(std::slice::from_ref(&write), &[]);
         }
-        let idx = self.descriptor_sets.len();
+        if let Some(idx) = free {
+            self.images[idx] = image;
+            self.image_mem[idx] = image_mem;
+            self.image_views[idx] = view;
+            self.samplers[idx] = sampler;
+            self.sampler_descs[idx] = SamplerDesc::default();
+            self.image_bytes[idx] = bytes;
+            return Ok(idx);
+        }
+        let idx = self.descriptor_sets.len();
+
+        self.images.push(image);
+        self.image_mem.push(image_mem);
+        self.image_views.push(view);
+        self.samplers.push(sampler);
+        self.sampler_descs.push(SamplerDesc::default());
+        self.image_bytes.push(bytes);
+        self.descriptor_sets.push(desc_set);
+
+        Ok(idx)
+    }
+
+    /// Whether images of `format` can be sampled with optimal tiling on this device.
+    fn supports_sampling(&self, format: vk::Format) -> bool {
+        let props = unsafe {
+            self.instance
+                .get_physical_device_format_properties(self.pdevice, format)
+        };
+        props
+            .optimal_tiling_features
+            .contains(vk::FormatFeatureFlags::SAMPLED_IMAGE)
+    }
+}
+
+/// Vulkan format of a [`TextureFormat`] and the swizzle that makes it read like RGBA.
+fn texture_format(format: TextureFormat) -> (vk::Format, vk::ComponentMapping) {
+    match format {
+        TextureFormat::Rgba8 => (vk::Format::R8G8B8A8_UNORM, vk::ComponentMapping::default()),
+        TextureFormat::Rgba16 => (
+            vk::Format::R16G16B16A16_UNORM,
+            vk::ComponentMapping::default(),
+        ),
+        TextureFormat::R8 => (
+            vk::Format::R8_UNORM,
+            vk::ComponentMapping {
+                r: vk::ComponentSwizzle::R,
+                g: vk::ComponentSwizzle::R,
+                b: vk::ComponentSwizzle::R,
+                a: vk::ComponentSwizzle::ONE,
+            },
+        ),
+    }
+}
+
+impl Backend for VkBackend {
+    type Error = vk::Result;
+
+    fn handle_resize(&mut self, size: winit::dpi::PhysicalSize<u32>) {
+        if size.width == 0 || size.height == 0 {
+            return;
+        }
+        if self.pending_extent.is_none()
+            && size.width == self.surface_resolution.width
+            && size.height == self.surface_resolution.height
+        {
+            return;
+        }
+        self.pending_extent = Some(vk::Extent2D {
+            width: size.width,
+            height: size.height,
+        });
+        self.swapchain_rebuild = true;
+    }
+
+    fn bind_camera(&mut self, camera: &Camera) {
+        let target = self
+            .current_target
+            .and_then(|i| self.render_targets.get(&i))
+            .map(|t| t.extent);
+        let extent = target.unwrap_or(self.surface_resolution);
+        let screen = glam::Vec2::new(extent.width as f32, extent.height as f32);
+        let (min, size) = camera.output_viewport().to_pixels(screen);
+        let vp = vk::Viewport::default()
+            .x(min.x)
+            .y(min.y)
+            .width(size.x)
+            .height(size.y)
+            .min_depth(0.0)
+            .max_depth(1.0);
+        let sc = vk::Rect2D {
+            offset: vk::Offset2D {
+                x: min.x as i32,
+                y: min.y as i32,
+            },
+            extent: vk::Extent2D {
+                width: size.x as u32,
+                height: size.y as u32,
+            },
+        };
+        let [w, h, cx, cy, sx, sy] = camera.view_constants(screen);
+        let pc = [w, h, cx, cy, sx, sy, self.time];
+        // filters apply when the frame is shown, render targets go without
+        if !camera.filter.is_none() && target.is_none() {
+            self.filter_regions
+                .push((min.to_array(), size.to_array(), camera.filter));
+        }
+        let cmd = self.cmds[self.frame_idx];
+        unsafe {
+            self.device
+                .cmd_set_viewport(cmd, 0, std::slice::from_ref(&vp));
+            self.device
+                .cmd_set_scissor(cmd, 0, std::slice::from_ref(&sc));
+            // depth is per camera, a minimap must not be hidden by the sprites under it
+            let depth_clear = vk::ClearAttachment {
+                aspect_mask: vk::ImageAspectFlags::DEPTH,
+                color_attachment: 0,
+                clear_value: vk::ClearValue {
+                    depth_stencil: vk::ClearDepthStencilValue {
+                        depth: 1.0,
+                        stencil: 0,
+                    },
+                },
+            };
+            let x0 = sc.offset.x.clamp(0, extent.width as i32);
+            let y0 = sc.offset.y.clamp(0, extent.height as i32);
+            let x1 = (min.x + size.x).clamp(0.0, screen.x) as i32;
+            let y1 = (min.y + size.y).clamp(0.0, screen.y) as i32;
+            if x1 > x0 && y1 > y0 {
+                let depth_rect = vk::ClearRect {
+                    rect: vk::Rect2D {
+                        offset: vk::Offset2D { x: x0, y: y0 },
+                        extent: vk::Extent2D {
+                            width: (x1 - x0) as u32,
+                            height: (y1 - y0) as u32,
+                        },
+                    },
+                    base_array_layer: 0,
+                    layer_count: 1,
+                };
+                self.device
+                    .cmd_clear_attachments(cmd, &[depth_clear], &[depth_rect]);
+            }
+            if camera.filter.trail > 0.0 && self.post_history && target.is_none() {
+                self.draw_feedback(cmd, min.to_array(), size.to_array(), &camera.filter);
+            }
+            self.device.cmd_push_constants(
+                cmd,
+                self.pipeline_layout,
+                vk::ShaderStageFlags::VERTEX,
+                0,
+                bytemuck::cast_slice(&pc),
+            );
+        }
+    }
+
+    fn set_time(&mut self, seconds: f32) {
+        self.time = seconds;
+    }
+
+    fn set_calibration(&mut self, calibration: DisplayCalibration) {
+        self.calibration = calibration;
+    }
+
+    fn set_tint(&mut self, tint: Color) {
+        self.tint = tint;
+    }
+
+    fn set_texture_filter(&mut self, filter: TextureFilter) {
+        if self.texture_filter == filter {
+            return;
+        }
+        self.texture_filter = filter;
+        unsafe {
+            // frames in flight may still sample through the old samplers
+            if let Err(e) = self.device.device_wait_idle() {
+                tracing::warn!("set_texture_filter: {e}");
+                return;
+            }
+            // textures with their own filter keep it
+            for idx in 0..self.samplers.len() {
+                if self.sampler_descs[idx].filter.is_none()
+                    && !self.free_slots.contains(&idx)
+                    && let Err(e) = self.rebuild_sampler(idx)
+                {
+                    tracing::warn!("set_texture_filter: {e}");
+                    return;
+                }
+            }
+        }
+    }
+
+    fn set_sampler(&mut self, tex_idx: usize, desc: SamplerDesc) {
+        if self.sampler_descs.get(tex_idx).is_none_or(|d| *d == desc)
+            || self.free_slots.contains(&tex_idx)
+        {
+            return;
+        }
+        self.sampler_descs[tex_idx] = desc;
+        unsafe {
+            // frames in flight may still sample through the old sampler
+            if let Err(e) = self.device.device_wait_idle() {
+                tracing::warn!("set_sampler: {e}");
+                return;
+            }
+            if let Err(e) = self.rebuild_sampler(tex_idx) {
+                tracing::warn!("set_sampler: {e}");
+            }
+        }
+    }
+
+    fn set_present_mode(&mut self, mode: PresentMode) {
+        if self.present_mode != mode {
+            self.present_mode = mode;
+            self.swapchain_rebuild = true;
+        }
+    }
+
+    fn supports_depth(&self) -> bool {
+        true
+    }
+
+    fn precompile(&mut self, materials: &[MaterialId], blend_modes: &[BlendMode]) {
+        for &material in materials {
+            for &blend in blend_modes {
+                self.sprite_pipeline(material, blend);
+            }
+        }
+    }
+
+    fn max_texture_size(&self) -> u32 {
+        let props = unsafe { self.instance.get_physical_device_properties(self.pdevice) };
+        props.limits.max_image_dimension2_d
+    }
+
+    fn adapter_info(&self) -> AdapterInfo {
+        let props = unsafe { self.instance.get_physical_device_properties(self.pdevice) };
+        let name = props
+            .device_name_as_c_str()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        AdapterInfo {
+            backend: "vulkan",
+            name,
+            driver_version: props.driver_version,
+            api_version: format!(
+                "{}.{}.{}",
+                vk::api_version_major(props.api_version),
+                vk::api_version_minor(props.api_version),
+                vk::api_version_patch(props.api_version)
+            ),
+        }
+    }
+
+    fn present_stats(&self) -> Option<PresentStats> {
+        Some(self.present_timing.stats())
+    }
+
+    fn memory_stats(&self) -> MemoryStats {
+        MemoryStats {
+            render_targets: self.post_targets.bytes + self.transients.bytes,
+            ..self.memory
+        }
+    }
+
+    fn request_screenshot(&mut self) {
+        self.screenshot_requested = true;
+    }
+
+    fn take_screenshot(&mut self) -> Option<Screenshot> {
+        self.screenshot.take()
+    }
+
+    fn create_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+    ) -> Result<usize, vk::Result> {
+        self.create_texture_with_format(width, height, TextureFormat::Rgba8, pixels)
+    }
+
+    fn create_texture_with_format(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        pixels: &[u8],
+    ) -> Result<usize, vk::Result> {
+        if self.images.len() >= MAX_TEXTURES && self.free_slots.is_empty() {
+            panic!("texture limit reached ({MAX_TEXTURES})");
+        }
+
+        let (image, image_mem, view, bytes) = self.upload_image(width, height, format, pixels)?;
+        self.add_slot(image, image_mem, view, bytes)
+    }
+
+    fn create_render_target(&mut self, width: u32, height: u32) -> Result<usize, vk::Result> {
+        if self.images.len() >= MAX_TEXTURES && self.free_slots.is_empty() {
+            panic!("texture limit reached ({MAX_TEXTURES})");
+        }
+        let extent = vk::Extent2D {
+            width: width.max(1),
+            height: height.max(1),
+        };
+        let (image, image_mem, view) = unsafe {
+            post::create_attachment(
+                &self.device,
+                &self.device_memory_properties,
+                self.surface_format.format,
+                extent,
+                vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::SAMPLED,
+                vk::ImageAspectFlags::COLOR,
+            )?
+        };
+        let depth = unsafe {
+            self.transients.acquire(
+                &self.device,
+                &self.device_memory_properties,
+                self.depth_format,
+                extent,
+                vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+                vk::ImageAspectFlags::DEPTH,
+            )?
+        };
+        let framebuffer = unsafe {
+            self.device.create_framebuffer(
+                &vk::FramebufferCreateInfo::default()
+                    .render_pass(self.render_pass)
+                    .attachments(&[view, depth.2])
+                    .width(extent.width)
+                    .height(extent.height)
+                    .layers(1),
+                None,
+            )?
+        };
+
+        // an empty pass clears the image and leaves it ready to sample
+        let tmp_cmd = unsafe {
+            self.device.allocate_command_buffers(
+                &vk::CommandBufferAllocateInfo::default()
+                    .command_pool(self.pool)
+                    .level(vk::CommandBufferLevel::PRIMARY)
+                    .command_buffer_count(1),
+            )?[0]
+        };
+        let tmp_fence = unsafe {
+            self.device.create_fence(
+                &vk::FenceCreateInfo::default().flags(vk::FenceCreateFlags::SIGNALED),
+                None,
+            )?
+        };
+        let render_pass = self.render_pass;
+        record_submit_commandbuffer(
+            &self.device,
+            tmp_cmd,
+            tmp_fence,
+            self.present_queue,
+            &[],
+            &[],
+            &[],
+            |d, c| unsafe {
+                d.cmd_begin_render_pass(
+                    c,
+                    &vk::RenderPassBeginInfo::default()
+                        .render_pass(render_pass)
+                        .framebuffer(framebuffer)
+                        .render_area(extent.into())
+                        .clear_values(&target_clear([0.0; 4])),
+                    vk::SubpassContents::INLINE,
+                );
+                d.cmd_end_render_pass(c);
+            },
+        );
+        let bytes = unsafe {
+            self.device.wait_for_fences(&[tmp_fence], true, u64::MAX)?;
+            self.device.destroy_fence(tmp_fence, None);
+            self.device.free_command_buffers(self.pool, &[tmp_cmd]);
+            self.device.get_image_memory_requirements(image).size
+        };
+        let idx = self.add_slot(image, image_mem, view, bytes)?;
+        self.render_targets.insert(
+            idx,
+            RenderTarget {
+                framebuffer,
+                depth,
+                extent,
+            },
+        );
+        Ok(idx)
+    }
+
+    fn set_render_target(&mut self, idx: Option<usize>) {
+        if idx == self.current_target || idx.is_some_and(|i| !self.render_targets.contains_key(&i))
+        {
+            return;
+        }
+        let cmd = self.cmds[self.frame_idx];
+        // targets clear to transparent, the frame's target is loaded back
+        let (pass, framebuffer, extent) = match idx.and_then(|i| self.render_targets.get(&i)) {
+            Some(t) => (self.render_pass, t.framebuffer, t.extent),
+            None => (
+                self.resume_pass,
+                self.post_targets.framebuffers[self.frame_idx],
+                self.surface_resolution,
+            ),
+        };
+        unsafe {
+            self.device.cmd_end_render_pass(cmd);
+            self.device.cmd_begin_render_pass(
+                cmd,
+                &vk::RenderPassBeginInfo::default()
+                    .render_pass(pass)
+                    .framebuffer(framebuffer)
+                    .render_area(extent.into())
+                    .clear_values(&target_clear([0.0; 4])),
+                vk::SubpassContents::INLINE,
+            );
+            let vp = vk::Viewport::default()
+                .width(extent.width as f32)
+                .height(extent.height as f32)
+                .min_depth(0.0)
+                .max_depth(1.0);
+            self.device
+                .cmd_set_viewport(cmd, 0, std::slice::from_ref(&vp));
+            self.device
+                .cmd_set_scissor(cmd, 0, std::slice::from_ref(&extent.into()));
+        }
+        self.current_target = idx;
+        self.bound_pipeline = vk::Pipeline::null();
+    }
+
+    fn replace_texture(
+        &mut self,
+        idx: usize,
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        pixels: &[u8],
+    ) -> Result<(), vk::Result> {
+        if idx >= self.images.len() || self.free_slots.contains(&idx) {
+            return Err(vk::Result::ERROR_UNKNOWN);
+        }
+        let (image, image_mem, view, bytes) = self.upload_image(width, height, format, pixels)?;
+        unsafe {
+            // frames in flight may still sample the old image through this slot's set
+            self.device.device_wait_idle()?;
+            // a render target given texels becomes a plain texture
+            if let Some(t) = self.render_targets.remove(&idx) {
+                self.destroy_render_target(&t);
+            }
+            self.device.destroy_image_view(self.image_views[idx], None);
+            self.device.destroy_image(self.images[idx], None);
+            self.device.free_memory(self.image_mem[idx], None);
+
+            let img_info = vk::DescriptorImageInfo::default()
+                .sampler(self.samplers[idx])
+                .image_view(view)
+                .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+            let write = vk::WriteDescriptorSet::default()
+                .dst_set(self.descriptor_sets[idx])
+                .dst_binding(0)
+                .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+                .image_info(std::slice::from_ref(&img_info));
+            self.device
+                .update_descriptor_sets(std::slice::from_ref(&write), &[]);
+        }
+        self.memory.textures = self.memory.textures - self.image_bytes[idx] + bytes;
+        self.images[idx] = image;
+        self.image_mem[idx] = image_mem;
+        self.image_views[idx] = view;
+        self.image_bytes[idx] = bytes;
+        Ok(())
+    }
 
-        self.images.push(image);
-        self.image_mem.push(image_mem);
-        self.image_views.push(view);
-        self.samplers.push(sampler);
-        self.descriptor_sets.push(desc_set);
+    fn destroy_texture(&mut self, idx: usize) {
+        if idx >= self.images.len() || self.free_slots.contains(&idx) {
+            return;
+        }
+        unsafe {
+            // frames in flight may still sample the image through this slot's set
+            if let Err(e) = self.device.device_wait_idle() {
+                tracing::warn!("destroy_texture: {e}");
+                return;
+            }
+            if let Some(t) = self.render_targets.remove(&idx) {
+                self.destroy_render_target(&t);
+            }
+            self.device.destroy_sampler(self.samplers[idx], None);
+            self.device.destroy_image_view(self.image_views[idx], None);
+            self.device.destroy_image(self.images[idx], None);
+            self.device.free_memory(self.image_mem[idx], None);
+        }
+        // destroying null handles does nothing, so dropping the backend skips these
+        self.samplers[idx] = vk::Sampler::null();
+        self.image_views[idx] = vk::ImageView::null();
+        self.images[idx] = vk::Image::null();
+        self.image_mem[idx] = vk::DeviceMemory::null();
+        self.memory.textures -= self.image_bytes[idx];
+        self.image_bytes[idx] = 0;
+        self.free_slots.push(idx);
+    }
 
-        Ok(idx)
+    fn create_material(&mut self, spirv: &[u8]) -> Result<MaterialId, vk::Result> {
+        // a failed shader keeps its slot as a null module, which sprite_pipeline skips
+        let module = shaders::try_create_shader(&self.device, spirv);
+        self.material_frags
+            .push(*module.as_ref().unwrap_or(&vk::ShaderModule::null()));
+        module.map(|_| MaterialId(self.material_frags.len() as u32 - 1))
     }
 
     fn begin_frame(&mut self) {
-        if self.swapchain_rebuild {
-            unsafe { self.device.device_wait_idle() }.unwrap();
-            let _ = self.create_swapchain(
-                self.surface_resolution.width,
-                self.surface_resolution.height,
-            );
-            self.swapchain_rebuild = false;
+        // rebuilds are rate limited so dragging a window edge does not rebuild every event
+        if self.swapchain_rebuild && self.last_rebuild.elapsed() >= Self::REBUILD_INTERVAL {
+            self.rebuild_swapchain();
         }
         let fi = self.frame_idx;
         let cmd = self.cmds[fi];
@@ -522,15 +1858,22 @@ impl Backend for VkBackend {
                 .expect("Reset fences failed.");
         }
 
-        let (img_index, _) = unsafe {
-            self.swapchain_loader.acquire_next_image(
-                self.swapchain,
+        let acquire = |b: &Self| unsafe {
+            b.swapchain_loader.acquire_next_image(
+                b.swapchain,
                 u64::MAX,
-                self.image_available[fi],
+                b.image_available[fi],
                 vk::Fence::null(),
             )
-        }
-        .unwrap();
+        };
+        let (img_index, suboptimal) = match acquire(self) {
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
+                self.rebuild_swapchain();
+                acquire(self).unwrap()
+            }
+            res => res.unwrap(),
+        };
+        self.swapchain_rebuild |= suboptimal;
         self.current_img = img_index as usize;
 
         unsafe {
@@ -552,25 +1895,28 @@ impl Backend for VkBackend {
             self.device
                 .cmd_set_scissor(cmd, 0, std::slice::from_ref(&sc));
 
-            let clear = vk::ClearValue {
-                color: vk::ClearColorValue {
-                    float32: [0.05, 0.05, 0.09, 1.0],
-                },
-            };
+            let clear = target_clear([0.05, 0.05, 0.09, 1.0]);
             self.device.cmd_begin_render_pass(
                 cmd,
                 &vk::RenderPassBeginInfo::default()
                     .render_pass(self.render_pass)
-                    .framebuffer(self.framebuffers[self.current_img])
+                    .framebuffer(self.post_targets.framebuffers[fi])
                     .render_area(vk::Rect2D {
                         offset: vk::Offset2D { x: 0, y: 0 },
                         extent: self.surface_resolution,
                     })
-                    .clear_values(std::slice::from_ref(&clear)),
+                    .clear_values(&clear),
                 vk::SubpassContents::INLINE,
             );
         }
+        self.frame_count += 1;
+        self.retire_baked();
         self.instance_cursor = 0;
+        self.panel_cursor = 0;
+        self.mesh_vertex_cursor = 0;
+        self.mesh_index_cursor = 0;
+        self.bound_pipeline = vk::Pipeline::null();
+        self.filter_regions.clear();
     }
 
     fn end_frame(&mut self) {
@@ -579,8 +1925,16 @@ impl Backend for VkBackend {
         let cmd = self.cmds[fi];
         let rf_sema = self.render_finished[img];
 
+        self.set_render_target(None);
+        unsafe { self.device.cmd_end_render_pass(cmd) };
+        self.record_post(cmd, img);
+        let readback = if std::mem::take(&mut self.screenshot_requested) {
+            self.record_readback(cmd, img)
+        } else {
+            None
+        };
+
         unsafe {
-            self.device.cmd_end_render_pass(cmd);
             self.device.end_command_buffer(cmd).unwrap();
 
             let submit = vk::SubmitInfo::default()
@@ -598,65 +1952,223 @@ impl Backend for VkBackend {
                 .unwrap();
 
             let img_u32 = img as u32;
-            let present = vk::PresentInfoKHR::default()
+            let present_time = self.present_timing.next_present_time();
+            let mut times = vk::PresentTimesInfoGOOGLE::default().times(present_time.as_slice());
+            let mut present = vk::PresentInfoKHR::default()
                 .wait_semaphores(std::slice::from_ref(&rf_sema))
                 .swapchains(std::slice::from_ref(&self.swapchain))
                 .image_indices(std::slice::from_ref(&img_u32));
+            if present_time.is_some() {
+                present = present.push_next(&mut times);
+            }
 
-            self.swapchain_loader
+            match self
+                .swapchain_loader
                 .queue_present(self.present_queue, &present)
-                .unwrap();
+            {
+                Ok(false) => {}
+                Ok(true) | Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => self.swapchain_rebuild = true,
+                Err(e) => panic!("queue_present failed: {e}"),
+            }
+        }
+        self.present_timing.after_present(self.swapchain);
+
+        if let Some((buf, mem)) = readback {
+            self.finish_readback(self.in_flight_fence[fi], buf, mem);
         }
 
         self.frame_idx = (fi + 1) % Self::MAX_FRAMES_IN_FLIGHT;
+        self.post_history = true;
     }
 
     fn draw_sprites(&mut self, idx: usize, batch: &SpriteBatch) {
-        if batch.instances.is_empty() {
+        // paletted batches only get here when their palette is unknown
+        let material = match batch.effective_material() {
+            MaterialId::PALETTE => MaterialId::DEFAULT,
+            m => m,
+        };
+        let Some(pipeline) = self.sprite_pipeline(material, batch.blend) else {
+            return;
+        };
+        let sets = [self.descriptor_sets[idx]];
+        self.draw_instances(pipeline, self.pipeline_layout, &sets, batch);
+    }
+
+    fn draw_sprites_paletted(&mut self, idx: usize, palette_idx: usize, batch: &SpriteBatch) {
+        let Some(pipeline) = self.sprite_pipeline(MaterialId::PALETTE, batch.blend) else {
+            return;
+        };
+        let sets = [self.descriptor_sets[idx], self.descriptor_sets[palette_idx]];
+        self.draw_instances(pipeline, self.palette_layout, &sets, batch);
+    }
+
+    fn draw_panels(&mut self, panels: &[PanelInstance]) {
+        let inst_size = std::mem::size_of::<PanelInstance>() as vk::DeviceSize;
+        let capacity = MAX_PANELS - (self.panel_cursor / inst_size) as usize;
+        let panels = &panels[..panels.len().min(capacity)];
+        if panels.is_empty() {
             return;
         }
-        assert!(batch.instances.len() <= MAX_SPRITES);
-        let inst_size = std::mem::size_of::<SpriteInstance>() as vk::DeviceSize;
-        let byte_count = batch.instances.len() as vk::DeviceSize * inst_size;
+        let byte_count = panels.len() as vk::DeviceSize * inst_size;
         unsafe {
             let ptr = self
                 .device
                 .map_memory(
-                    self.instance_vbo_mem,
-                    self.instance_cursor,
+                    self.panel_vbo_mem,
+                    self.panel_cursor,
                     byte_count,
                     vk::MemoryMapFlags::empty(),
                 )
-                .unwrap() as *mut SpriteInstance;
-            ptr.copy_from_nonoverlapping(batch.instances.as_ptr(), batch.instances.len());
-            self.device.unmap_memory(self.instance_vbo_mem);
+                .unwrap() as *mut PanelInstance;
+            ptr.copy_from_nonoverlapping(panels.as_ptr(), panels.len());
+            self.device.unmap_memory(self.panel_vbo_mem);
         }
 
         let cmd = self.cmds[self.frame_idx];
-        let set = self.descriptor_sets[idx];
-
         unsafe {
+            self.bound_pipeline = self.panel_pipeline;
+            self.device.cmd_bind_pipeline(
+                cmd,
+                vk::PipelineBindPoint::GRAPHICS,
+                self.panel_pipeline,
+            );
+
+            let buffers = [self.quad_vbo, self.panel_vbo];
+            let offsets = [0, self.panel_cursor];
+            self.device
+                .cmd_bind_vertex_buffers(cmd, 0, &buffers, &offsets);
+
             self.device
-                .cmd_bind_pipeline(cmd, vk::PipelineBindPoint::GRAPHICS, self.pipeline);
+                .cmd_draw(cmd, VERTEX_COUNT as u32, panels.len() as u32, 0, 0);
+        }
+        self.panel_cursor += byte_count;
+    }
+
+    fn draw_mesh(&mut self, idx: usize, batch: &MeshBatch) {
+        let Some(pipeline) = self.mesh_pipeline(batch.blend) else {
+            return;
+        };
+        let mesh = &batch.mesh;
+        let vert_size = std::mem::size_of::<MeshVertex>() as vk::DeviceSize;
+        let index_size = std::mem::size_of::<u32>() as vk::DeviceSize;
+        let indices = &mesh.indices[..mesh.indices.len() / 3 * 3];
+        // meshes are drawn whole or not at all, a cut off one would show holes
+        if mesh.vertices.len() > MAX_MESH_VERTICES - (self.mesh_vertex_cursor / vert_size) as usize
+            || indices.len() > MAX_MESH_INDICES - (self.mesh_index_cursor / index_size) as usize
+        {
+            return;
+        }
+        let vertex_bytes = mesh.vertices.len() as vk::DeviceSize * vert_size;
+        let index_bytes = indices.len() as vk::DeviceSize * index_size;
+        unsafe {
+            let ptr = self
+                .device
+                .map_memory(
+                    self.mesh_vbo_mem,
+                    self.mesh_vertex_cursor,
+                    vertex_bytes,
+                    vk::MemoryMapFlags::empty(),
+                )
+                .unwrap() as *mut MeshVertex;
+            ptr.copy_from_nonoverlapping(mesh.vertices.as_ptr(), mesh.vertices.len());
+            self.device.unmap_memory(self.mesh_vbo_mem);
+            let ptr = self
+                .device
+                .map_memory(
+                    self.mesh_ibo_mem,
+                    self.mesh_index_cursor,
+                    index_bytes,
+                    vk::MemoryMapFlags::empty(),
+                )
+                .unwrap() as *mut u32;
+            ptr.copy_from_nonoverlapping(indices.as_ptr(), indices.len());
+            self.device.unmap_memory(self.mesh_ibo_mem);
+        }
 
+        let cmd = self.cmds[self.frame_idx];
+        unsafe {
+            if self.bound_pipeline != pipeline {
+                self.bound_pipeline = pipeline;
+                self.device
+                    .cmd_bind_pipeline(cmd, vk::PipelineBindPoint::GRAPHICS, pipeline);
+            }
             self.device.cmd_bind_descriptor_sets(
                 cmd,
                 vk::PipelineBindPoint::GRAPHICS,
                 self.pipeline_layout,
                 0,
-                std::slice::from_ref(&set),
+                &[self.descriptor_sets[idx]],
                 &[],
             );
-
-            let buffers = [self.quad_vbo, self.instance_vbo];
-            let offsets = [0, self.instance_cursor];
+            self.device.cmd_bind_vertex_buffers(
+                cmd,
+                0,
+                &[self.mesh_vbo],
+                &[self.mesh_vertex_cursor],
+            );
+            self.device.cmd_bind_index_buffer(
+                cmd,
+                self.mesh_ibo,
+                self.mesh_index_cursor,
+                vk::IndexType::UINT32,
+            );
             self.device
-                .cmd_bind_vertex_buffers(cmd, 0, &buffers, &offsets);
+                .cmd_draw_indexed(cmd, indices.len() as u32, 1, 0, 0, 0);
+        }
+        self.mesh_vertex_cursor += vertex_bytes;
+        self.mesh_index_cursor += index_bytes;
+    }
 
-            self.device
-                .cmd_draw(cmd, VERTEX_COUNT as u32, batch.instances.len() as u32, 0, 0);
+    fn draw_lines(&mut self, idx: usize, vertices: &[MeshVertex]) {
+        let Some(pipeline) = self.line_pipeline() else {
+            return;
+        };
+        let vert_size = std::mem::size_of::<MeshVertex>() as vk::DeviceSize;
+        // lines past the mesh vertex buffer's end are dropped, whole segments only
+        let capacity = MAX_MESH_VERTICES - (self.mesh_vertex_cursor / vert_size) as usize;
+        let vertices = &vertices[..vertices.len().min(capacity) / 2 * 2];
+        if vertices.is_empty() {
+            return;
         }
-        self.instance_cursor += byte_count;
+        let vertex_bytes = vertices.len() as vk::DeviceSize * vert_size;
+        unsafe {
+            let ptr = self
+                .device
+                .map_memory(
+                    self.mesh_vbo_mem,
+                    self.mesh_vertex_cursor,
+                    vertex_bytes,
+                    vk::MemoryMapFlags::empty(),
+                )
+                .unwrap() as *mut MeshVertex;
+            ptr.copy_from_nonoverlapping(vertices.as_ptr(), vertices.len());
+            self.device.unmap_memory(self.mesh_vbo_mem);
+        }
+
+        let cmd = self.cmds[self.frame_idx];
+        unsafe {
+            if self.bound_pipeline != pipeline {
+                self.bound_pipeline = pipeline;
+                self.device
+                    .cmd_bind_pipeline(cmd, vk::PipelineBindPoint::GRAPHICS, pipeline);
+            }
+            self.device.cmd_bind_descriptor_sets(
+                cmd,
+                vk::PipelineBindPoint::GRAPHICS,
+                self.pipeline_layout,
+                0,
+                &[self.descriptor_sets[idx]],
+                &[],
+            );
+            self.device.cmd_bind_vertex_buffers(
+                cmd,
+                0,
+                &[self.mesh_vbo],
+                &[self.mesh_vertex_cursor],
+            );
+            self.device.cmd_draw(cmd, vertices.len() as u32, 1, 0, 0);
+        }
+        self.mesh_vertex_cursor += vertex_bytes;
     }
 
     fn init(app_name: &str, window: &Window) -> Result<Self, Self::Error> {
@@ -784,11 +2296,19 @@ impl Backend for VkBackend {
                 })
                 .expect("Couldn't find suitable device.");
             let queue_family_index = queue_family_index as u32;
-            let device_extension_names_raw = [
+            let has_display_timing = instance
+                .enumerate_device_extension_properties(pdevice)
+                .unwrap_or_default()
+                .iter()
+                .any(|e| e.extension_name_as_c_str() == Ok(display_timing::NAME));
+            let mut device_extension_names_raw = vec![
                 swapchain::NAME.as_ptr(),
                 #[cfg(any(target_os = "macos", target_os = "ios"))]
                 ash::khr::portability_subset::NAME.as_ptr(),
             ];
+            if has_display_timing {
+                device_extension_names_raw.push(display_timing::NAME.as_ptr());
+            }
             let features = vk::PhysicalDeviceFeatures {
                 shader_clip_distance: 1,
                 ..Default::default()
@@ -809,6 +2329,9 @@ impl Backend for VkBackend {
                 .unwrap();
 
             let present_queue = device.get_device_queue(queue_family_index, 0);
+            let mut present_timing = PresentTiming::new(
+                has_display_timing.then(|| display_timing::Device::new(&instance, &device)),
+            );
 
             let surface_format = surface_loader
                 .get_physical_device_surface_formats(pdevice, surface)
@@ -820,7 +2343,7 @@ impl Backend for VkBackend {
                 .load_op(vk::AttachmentLoadOp::CLEAR)
                 .store_op(vk::AttachmentStoreOp::STORE)
                 .initial_layout(vk::ImageLayout::UNDEFINED)
-                .final_layout(vk::ImageLayout::PRESENT_SRC_KHR);
+                .final_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
 
             let color_ref = vk::AttachmentReference {
                 attachment: 0,
@@ -831,12 +2354,102 @@ impl Backend for VkBackend {
                 .pipeline_bind_point(vk::PipelineBindPoint::GRAPHICS)
                 .color_attachments(std::slice::from_ref(&color_ref));
 
+            let depth_format = [vk::Format::D32_SFLOAT, vk::Format::D16_UNORM]
+                .into_iter()
+                .find(|&f| {
+                    instance
+                        .get_physical_device_format_properties(pdevice, f)
+                        .optimal_tiling_features
+                        .contains(vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT)
+                })
+                .unwrap_or(vk::Format::D16_UNORM);
+            let depth_attach = vk::AttachmentDescription::default()
+                .format(depth_format)
+                .samples(vk::SampleCountFlags::TYPE_1)
+                .load_op(vk::AttachmentLoadOp::CLEAR)
+                .store_op(vk::AttachmentStoreOp::DONT_CARE)
+                .initial_layout(vk::ImageLayout::UNDEFINED)
+                .final_layout(vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL);
+            let depth_ref = vk::AttachmentReference {
+                attachment: 1,
+                layout: vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+            };
+            let scene_subpass = subpass.depth_stencil_attachment(&depth_ref);
+
+            // the scene target is read by the post pass, which may still run for the previous frame
+            let scene_deps = [
+                vk::SubpassDependency::default()
+                    .src_subpass(vk::SUBPASS_EXTERNAL)
+                    .dst_subpass(0)
+                    .src_stage_mask(
+                        vk::PipelineStageFlags::FRAGMENT_SHADER
+                            | vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT
+                            | vk::PipelineStageFlags::LATE_FRAGMENT_TESTS,
+                    )
+                    .dst_stage_mask(
+                        vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT
+                            | vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS,
+                    )
+                    .src_access_mask(vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE)
+                    .dst_access_mask(
+                        vk::AccessFlags::COLOR_ATTACHMENT_WRITE
+                            | vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                    ),
+                vk::SubpassDependency::default()
+                    .src_subpass(0)
+                    .dst_subpass(vk::SUBPASS_EXTERNAL)
+                    .src_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+                    .dst_stage_mask(vk::PipelineStageFlags::FRAGMENT_SHADER)
+                    .src_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+                    .dst_access_mask(vk::AccessFlags::SHADER_READ),
+            ];
+            let scene_attachments = [color_attach, depth_attach];
             let rp_info = vk::RenderPassCreateInfo::default()
-                .attachments(std::slice::from_ref(&color_attach))
-                .subpasses(std::slice::from_ref(&subpass));
+                .attachments(&scene_attachments)
+                .subpasses(std::slice::from_ref(&scene_subpass))
+                .dependencies(&scene_deps);
 
             let render_pass = device.create_render_pass(&rp_info, None)?;
 
+            // after a render target the frame's target is loaded back; depth is cleared per camera anyway
+            let resume_color = color_attach
+                .load_op(vk::AttachmentLoadOp::LOAD)
+                .initial_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+            let resume_attachments = [resume_color, depth_attach];
+            let mut resume_deps = scene_deps;
+            resume_deps[0] = resume_deps[0]
+                .src_access_mask(
+                    vk::AccessFlags::COLOR_ATTACHMENT_WRITE
+                        | vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                )
+                .dst_access_mask(
+                    vk::AccessFlags::COLOR_ATTACHMENT_READ
+                        | vk::AccessFlags::COLOR_ATTACHMENT_WRITE
+                        | vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                );
+            let resume_pass = device.create_render_pass(
+                &vk::RenderPassCreateInfo::default()
+                    .attachments(&resume_attachments)
+                    .subpasses(std::slice::from_ref(&scene_subpass))
+                    .dependencies(&resume_deps),
+                None,
+            )?;
+
+            let present_attach = color_attach.final_layout(vk::ImageLayout::PRESENT_SRC_KHR);
+            let present_dep = vk::SubpassDependency::default()
+                .src_subpass(vk::SUBPASS_EXTERNAL)
+                .dst_subpass(0)
+                .src_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+                .dst_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+                .dst_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE);
+            let post_pass = device.create_render_pass(
+                &vk::RenderPassCreateInfo::default()
+                    .attachments(std::slice::from_ref(&present_attach))
+                    .subpasses(std::slice::from_ref(&subpass))
+                    .dependencies(std::slice::from_ref(&present_dep)),
+                None,
+            )?;
+
             let surface_capabilities = surface_loader
                 .get_physical_device_surface_capabilities(pdevice, surface)
                 .unwrap();
@@ -864,11 +2477,7 @@ impl Backend for VkBackend {
             let present_modes = surface_loader
                 .get_physical_device_surface_present_modes(pdevice, surface)
                 .unwrap();
-            let present_mode = present_modes
-                .iter()
-                .cloned()
-                .find(|&mode| mode == vk::PresentModeKHR::MAILBOX)
-                .unwrap_or(vk::PresentModeKHR::IMMEDIATE);
+            let present_mode = present_mode(PresentMode::default(), &present_modes);
             let swapchain_loader = swapchain::Device::new(&instance, &device);
 
             let swapchain_create_info = vk::SwapchainCreateInfoKHR::default()
@@ -877,7 +2486,11 @@ impl Backend for VkBackend {
                 .image_color_space(surface_format.color_space)
                 .image_format(surface_format.format)
                 .image_extent(surface_resolution)
-                .image_usage(vk::ImageUsageFlags::COLOR_ATTACHMENT)
+                .image_usage(
+                    vk::ImageUsageFlags::COLOR_ATTACHMENT
+                        | (surface_capabilities.supported_usage_flags
+                            & vk::ImageUsageFlags::TRANSFER_SRC),
+                )
                 .image_sharing_mode(vk::SharingMode::EXCLUSIVE)
                 .pre_transform(pre_transform)
                 .composite_alpha(vk::CompositeAlphaFlagsKHR::OPAQUE)
@@ -888,6 +2501,7 @@ impl Backend for VkBackend {
             let swapchain = swapchain_loader
                 .create_swapchain(&swapchain_create_info, None)
                 .unwrap();
+            present_timing.reset(swapchain);
 
             let pool_create_info = vk::CommandPoolCreateInfo::default()
                 .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
@@ -928,6 +2542,10 @@ impl Backend for VkBackend {
                 })
                 .collect();
             let device_memory_properties = instance.get_physical_device_memory_properties(pdevice);
+            let non_coherent_atom_size = instance
+                .get_physical_device_properties(pdevice)
+                .limits
+                .non_coherent_atom_size;
 
             let semaphore_create_info = vk::SemaphoreCreateInfo::default();
 
@@ -935,7 +2553,7 @@ impl Backend for VkBackend {
                 .iter()
                 .map(|&view| {
                     let fb_info = vk::FramebufferCreateInfo::default()
-                        .render_pass(render_pass)
+                        .render_pass(post_pass)
                         .attachments(std::slice::from_ref(&view))
                         .width(surface_resolution.width)
                         .height(surface_resolution.height)
@@ -1041,13 +2659,21 @@ impl Backend for VkBackend {
             let pc_range = vk::PushConstantRange::default()
                 .stage_flags(vk::ShaderStageFlags::VERTEX)
                 .offset(0)
-                .size(std::mem::size_of::<[f32; 5]>() as u32);
+                .size(std::mem::size_of::<[f32; 7]>() as u32);
 
             let pipeline_layout_info = vk::PipelineLayoutCreateInfo::default()
                 .set_layouts(std::slice::from_ref(&desc_set_layout))
                 .push_constant_ranges(std::slice::from_ref(&pc_range));
             let pipeline_layout = device.create_pipeline_layout(&pipeline_layout_info, None)?;
 
+            let palette_set_layouts = [desc_set_layout; 2];
+            let palette_layout = device.create_pipeline_layout(
+                &vk::PipelineLayoutCreateInfo::default()
+                    .set_layouts(&palette_set_layouts)
+                    .push_constant_ranges(std::slice::from_ref(&pc_range)),
+                None,
+            )?;
+
             let desc_pool_size = vk::DescriptorPoolSize::default()
                 .ty(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
                 .descriptor_count((MAX_TEXTURES * MAX_TEXTURES) as u32);
@@ -1058,30 +2684,35 @@ impl Backend for VkBackend {
                 None,
             )?;
 
-            let binding_descriptions = [
-                vk::VertexInputBindingDescription::default() // binding 0: quad verts
-                    .binding(0)
-                    .stride(std::mem::size_of::<QuadVertex>() as u32)
-                    .input_rate(vk::VertexInputRate::VERTEX),
-                vk::VertexInputBindingDescription::default() // binding 1: per instance
+            let (binding_descriptions, attribute_descriptions) = shaders::sprite_vertex_input();
+            let material_frags = vec![
+                frag_mod,
+                shaders::create_shader(&device, include_bytes!("shaders/palette.frag.spv")),
+                shaders::create_shader(&device, include_bytes!("shaders/cutout.frag.spv")),
+                shaders::create_shader(&device, include_bytes!("shaders/msdf.frag.spv")),
+            ];
+            let mesh_vert =
+                shaders::create_shader(&device, include_bytes!("shaders/mesh.vert.spv"));
+            let mesh_frag =
+                shaders::create_shader(&device, include_bytes!("shaders/mesh.frag.spv"));
+            let pipeline_cache =
+                device.create_pipeline_cache(&vk::PipelineCacheCreateInfo::default(), None)?;
+
+            let panel_vert =
+                shaders::create_shader(&device, include_bytes!("shaders/panel.vert.spv"));
+            let panel_frag =
+                shaders::create_shader(&device, include_bytes!("shaders/panel.frag.spv"));
+            let panel_bindings = [
+                binding_descriptions[0],
+                vk::VertexInputBindingDescription::default()
                     .binding(1)
-                    .stride(std::mem::size_of::<SpriteInstance>() as u32)
+                    .stride(std::mem::size_of::<PanelInstance>() as u32)
                     .input_rate(vk::VertexInputRate::INSTANCE),
             ];
-
-            let attribute_descriptions = [
-                // binding 0
-                vk::VertexInputAttributeDescription::default()
-                    .binding(0)
-                    .location(0)
-                    .format(vk::Format::R32G32_SFLOAT)
-                    .offset(0),
-                vk::VertexInputAttributeDescription::default()
-                    .binding(0)
-                    .location(1)
-                    .format(vk::Format::R32G32_SFLOAT)
-                    .offset(8),
-                // binding 1
+            let panel_attributes = [
+                attribute_descriptions[0],
+                attribute_descriptions[1],
+                // binding 1: pos_size, color_a, color_b, border_color, params
                 vk::VertexInputAttributeDescription::default()
                     .binding(1)
                     .location(2)
@@ -1092,83 +2723,133 @@ impl Backend for VkBackend {
                     .location(3)
                     .format(vk::Format::R32G32B32A32_SFLOAT)
                     .offset(16),
+                vk::VertexInputAttributeDescription::default()
+                    .binding(1)
+                    .location(4)
+                    .format(vk::Format::R32G32B32A32_SFLOAT)
+                    .offset(32),
+                vk::VertexInputAttributeDescription::default()
+                    .binding(1)
+                    .location(5)
+                    .format(vk::Format::R32G32B32A32_SFLOAT)
+                    .offset(48),
+                vk::VertexInputAttributeDescription::default()
+                    .binding(1)
+                    .location(6)
+                    .format(vk::Format::R32G32B32A32_SFLOAT)
+                    .offset(64),
             ];
+            let panel_pipeline = shaders::create_pipeline(
+                &device,
+                pipeline_cache,
+                render_pass,
+                pipeline_layout,
+                panel_vert,
+                panel_frag,
+                &vk::PipelineVertexInputStateCreateInfo::default()
+                    .vertex_binding_descriptions(&panel_bindings)
+                    .vertex_attribute_descriptions(&panel_attributes),
+                vk::PrimitiveTopology::TRIANGLE_STRIP,
+                BlendMode::Alpha,
+                false,
+            )?;
+            device.destroy_shader_module(panel_vert, None);
+            device.destroy_shader_module(panel_frag, None);
+
+            let post_layout = device.create_pipeline_layout(
+                &vk::PipelineLayoutCreateInfo::default()
+                    .set_layouts(std::slice::from_ref(&desc_set_layout))
+                    .push_constant_ranges(std::slice::from_ref(
+                        &vk::PushConstantRange::default()
+                            .stage_flags(vk::ShaderStageFlags::FRAGMENT)
+                            .size(std::mem::size_of::<[f32; 20]>() as u32),
+                    )),
+                None,
+            )?;
+            let post_vert =
+                shaders::create_shader(&device, include_bytes!("shaders/post.vert.spv"));
+            let post_frag =
+                shaders::create_shader(&device, include_bytes!("shaders/post.frag.spv"));
+            let post_pipeline = shaders::create_pipeline(
+                &device,
+                pipeline_cache,
+                post_pass,
+                post_layout,
+                post_vert,
+                post_frag,
+                &vk::PipelineVertexInputStateCreateInfo::default(),
+                vk::PrimitiveTopology::TRIANGLE_STRIP,
+                BlendMode::Alpha,
+                false,
+            )?;
+            let feedback_frag =
+                shaders::create_shader(&device, include_bytes!("shaders/feedback.frag.spv"));
+            let feedback_pipeline = shaders::create_pipeline(
+                &device,
+                pipeline_cache,
+                render_pass,
+                post_layout,
+                post_vert,
+                feedback_frag,
+                &vk::PipelineVertexInputStateCreateInfo::default(),
+                vk::PrimitiveTopology::TRIANGLE_STRIP,
+                BlendMode::Alpha,
+                false,
+            )?;
+            device.destroy_shader_module(post_vert, None);
+            device.destroy_shader_module(post_frag, None);
+            device.destroy_shader_module(feedback_frag, None);
 
-            let vertex_state = vk::PipelineVertexInputStateCreateInfo::default()
-                .vertex_binding_descriptions(&binding_descriptions)
-                .vertex_attribute_descriptions(&attribute_descriptions);
-
-            let input_assembly = vk::PipelineInputAssemblyStateCreateInfo::default()
-                .topology(vk::PrimitiveTopology::TRIANGLE_STRIP)
-                .primitive_restart_enable(false);
-
-            let viewport_state = vk::PipelineViewportStateCreateInfo::default()
-                .viewport_count(1)
-                .scissor_count(1);
-
-            let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
-            let dynamic_state =
-                vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dynamic_states);
-
-            let raster = vk::PipelineRasterizationStateCreateInfo::default()
-                .polygon_mode(vk::PolygonMode::FILL)
-                .cull_mode(vk::CullModeFlags::NONE)
-                .front_face(vk::FrontFace::COUNTER_CLOCKWISE)
-                .line_width(1.0);
-            let multisample = vk::PipelineMultisampleStateCreateInfo::default()
-                .rasterization_samples(vk::SampleCountFlags::TYPE_1);
-            let colour_blend_attachment = vk::PipelineColorBlendAttachmentState::default()
-                .blend_enable(true)
-                .src_color_blend_factor(vk::BlendFactor::SRC_ALPHA)
-                .dst_color_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
-                .color_blend_op(vk::BlendOp::ADD)
-                .src_alpha_blend_factor(vk::BlendFactor::ONE)
-                .dst_alpha_blend_factor(vk::BlendFactor::ZERO)
-                .alpha_blend_op(vk::BlendOp::ADD)
-                .color_write_mask(
-                    vk::ColorComponentFlags::R
-                        | vk::ColorComponentFlags::G
-                        | vk::ColorComponentFlags::B
-                        | vk::ColorComponentFlags::A,
-                );
-            let colour_blend = vk::PipelineColorBlendStateCreateInfo::default()
-                .attachments(std::slice::from_ref(&colour_blend_attachment));
-
-            let shader_entry = std::ffi::CString::new("main").unwrap();
-            let stages = [
-                vk::PipelineShaderStageCreateInfo::default()
-                    .module(vert_mod)
-                    .name(&shader_entry)
-                    .stage(vk::ShaderStageFlags::VERTEX),
-                vk::PipelineShaderStageCreateInfo::default()
-                    .module(frag_mod)
-                    .name(&shader_entry)
-                    .stage(vk::ShaderStageFlags::FRAGMENT),
-            ];
-
-            let pipeline_info = vk::GraphicsPipelineCreateInfo::default()
-                .stages(&stages)
-                .vertex_input_state(&vertex_state)
-                .input_assembly_state(&input_assembly)
-                .viewport_state(&viewport_state)
-                .dynamic_state(&dynamic_state)
-                .rasterization_state(&raster)
-                .multisample_state(&multisample)
-                .color_blend_state(&colour_blend)
-                .layout(pipeline_layout)
-                .render_pass(render_pass)
-                .subpass(0);
-
-            let pipeline = device
-                .create_graphics_pipelines(
-                    vk::PipelineCache::null(),
-                    std::slice::from_ref(&pipeline_info),
-                    None,
-                )
-                .map_err(|(_, e)| e)?[0];
+            let post_sampler = device.create_sampler(
+                &vk::SamplerCreateInfo::default()
+                    .min_filter(vk::Filter::LINEAR)
+                    .mag_filter(vk::Filter::LINEAR)
+                    .mipmap_mode(vk::SamplerMipmapMode::NEAREST)
+                    .address_mode_u(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+                    .address_mode_v(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+                    .max_lod(0.0),
+                None,
+            )?;
+            let post_targets = PostTargets::new(
+                &device,
+                &device_memory_properties,
+                render_pass,
+                desc_set_layout,
+                post_sampler,
+                surface_format.format,
+                depth_format,
+                surface_resolution,
+                VkBackend::MAX_FRAMES_IN_FLIGHT,
+            )?;
 
-            device.destroy_shader_module(vert_mod, None);
-            device.destroy_shader_module(frag_mod, None);
+            let (panel_vbo, panel_vbo_mem) = shaders::create_buffer(
+                &device,
+                &device_memory_properties,
+                (std::mem::size_of::<PanelInstance>() * MAX_PANELS) as vk::DeviceSize,
+                vk::BufferUsageFlags::VERTEX_BUFFER,
+                vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+            );
+            let (mesh_vbo, mesh_vbo_mem) = shaders::create_buffer(
+                &device,
+                &device_memory_properties,
+                (std::mem::size_of::<MeshVertex>() * MAX_MESH_VERTICES) as vk::DeviceSize,
+                vk::BufferUsageFlags::VERTEX_BUFFER,
+                vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+            );
+            let (mesh_ibo, mesh_ibo_mem) = shaders::create_buffer(
+                &device,
+                &device_memory_properties,
+                (std::mem::size_of::<u32>() * MAX_MESH_INDICES) as vk::DeviceSize,
+                vk::BufferUsageFlags::INDEX_BUFFER,
+                vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+            );
+            let memory = MemoryStats {
+                buffers: [quad_vbo, instance_vbo, panel_vbo, mesh_vbo, mesh_ibo]
+                    .iter()
+                    .map(|&b| device.get_buffer_memory_requirements(b).size)
+                    .sum(),
+                ..Default::default()
+            };
 
             Ok(Self {
                 entry,
@@ -1181,6 +2862,7 @@ impl Backend for VkBackend {
                 surface_format,
                 present_queue,
                 surface_resolution,
+                depth_format,
                 swapchain_loader,
                 swapchain,
                 present_images,
@@ -1192,6 +2874,7 @@ impl Backend for VkBackend {
                 #[cfg(feature = "debug")]
                 debug_utils_loader,
                 render_pass,
+                resume_pass,
                 framebuffers,
                 current_img: 0,
                 image_available,
@@ -1200,8 +2883,40 @@ impl Backend for VkBackend {
                 frame_idx: 0,
                 cmds: cmd,
                 swapchain_rebuild: false,
-                pipeline,
+                pending_extent: None,
+                last_rebuild: Instant::now(),
                 pipeline_layout,
+                palette_layout,
+                sprite_vert: vert_mod,
+                mesh_vert,
+                mesh_frag,
+                mesh_pipelines: HashMap::new(),
+                line_pipeline: None,
+                material_frags,
+                sprite_pipelines: HashMap::new(),
+                pipeline_cache,
+                panel_pipeline,
+                bound_pipeline: vk::Pipeline::null(),
+                post_pass,
+                post_layout,
+                post_pipeline,
+                feedback_pipeline,
+                post_history: false,
+                post_sampler,
+                post_targets,
+                filter_regions: Vec::new(),
+                render_targets: HashMap::new(),
+                transients: Transients::default(),
+                current_target: None,
+                panel_vbo,
+                panel_vbo_mem,
+                mesh_vbo,
+                mesh_vbo_mem,
+                mesh_vertex_cursor: 0,
+                mesh_ibo,
+                mesh_ibo_mem,
+                mesh_index_cursor: 0,
+                panel_cursor: 0,
                 quad_vbo,
                 quad_vbo_mem,
                 instance_vbo,
@@ -1209,12 +2924,28 @@ impl Backend for VkBackend {
                 desc_set_layout,
                 desc_pool,
                 descriptor_sets: Vec::new(),
+                free_slots: Vec::new(),
 
                 images: Vec::new(),
                 image_mem: Vec::new(),
                 image_views: Vec::new(),
                 samplers: Vec::new(),
+                sampler_descs: Vec::new(),
+                image_bytes: Vec::new(),
                 instance_cursor: 0,
+                baked: HashMap::new(),
+                retained: HashMap::new(),
+                frame_count: 0,
+                non_coherent_atom_size,
+                screenshot_requested: false,
+                screenshot: None,
+                present_timing,
+                memory,
+                time: 0.0,
+                calibration: DisplayCalibration::NEUTRAL,
+                tint: Color::WHITE,
+                present_mode: PresentMode::default(),
+                texture_filter: TextureFilter::Nearest,
             })
         }
     }
@@ -1245,15 +2976,58 @@ impl Drop for VkBackend {
             self.device.free_memory(self.quad_vbo_mem, None);
             self.device.destroy_buffer(self.instance_vbo, None);
             self.device.free_memory(self.instance_vbo_mem, None);
-
-            self.device.destroy_pipeline(self.pipeline, None);
+            for b in self.baked.values() {
+                self.device.destroy_buffer(b.buffer, None);
+                self.device.free_memory(b.memory, None);
+            }
+            for s in self.retained.values().flat_map(|r| &r.slots) {
+                self.device.destroy_buffer(s.buffer, None);
+                self.device.free_memory(s.memory, None);
+            }
+            self.device.destroy_buffer(self.panel_vbo, None);
+            self.device.free_memory(self.panel_vbo_mem, None);
+            self.device.destroy_buffer(self.mesh_vbo, None);
+            self.device.free_memory(self.mesh_vbo_mem, None);
+            self.device.destroy_buffer(self.mesh_ibo, None);
+            self.device.free_memory(self.mesh_ibo_mem, None);
+
+            for &p in self
+                .sprite_pipelines
+                .values()
+                .chain(self.mesh_pipelines.values())
+                .chain(self.line_pipeline.iter())
+            {
+                self.device.destroy_pipeline(p, None);
+            }
+            self.device.destroy_shader_module(self.mesh_vert, None);
+            self.device.destroy_shader_module(self.mesh_frag, None);
+            self.device.destroy_shader_module(self.sprite_vert, None);
+            for &m in &self.material_frags {
+                self.device.destroy_shader_module(m, None);
+            }
+            self.device
+                .destroy_pipeline_cache(self.pipeline_cache, None);
+            self.device.destroy_pipeline(self.panel_pipeline, None);
+            self.device.destroy_pipeline(self.post_pipeline, None);
+            self.device.destroy_pipeline(self.feedback_pipeline, None);
+            self.device.destroy_pipeline_layout(self.post_layout, None);
+            self.post_targets.destroy(&self.device);
+            self.device.destroy_sampler(self.post_sampler, None);
             self.device
                 .destroy_pipeline_layout(self.pipeline_layout, None);
+            self.device
+                .destroy_pipeline_layout(self.palette_layout, None);
 
             for &fb in &self.framebuffers {
                 self.device.destroy_framebuffer(fb, None);
             }
+            for (_, t) in std::mem::take(&mut self.render_targets) {
+                self.destroy_render_target(&t);
+            }
+            self.transients.clear(&self.device);
             self.device.destroy_render_pass(self.render_pass, None);
+            self.device.destroy_render_pass(self.resume_pass, None);
+            self.device.destroy_render_pass(self.post_pass, None);
             for &view in &self.present_image_views {
                 self.device.destroy_image_view(view, None);
             }
@@ -1287,6 +3061,9 @@ impl Drop for VkBackend {
 mod shaders {
     use crate::utils::find_memorytype_index;
     use ash::{vk, Device};
+    use jester_core::{BlendMode, MeshVertex, SpriteInstance};
+
+    use super::QuadVertex;
 
     pub fn create_buffer(
         device: &Device,
@@ -1312,10 +3089,217 @@ mod shaders {
 
         (buffer, memory)
     }
+    /// Blended pipeline with dynamic viewport/scissor.
+    ///
+    /// Depth is tested against what earlier cutout sprites wrote and only written with `depth_write`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pipeline(
+        device: &Device,
+        cache: vk::PipelineCache,
+        render_pass: vk::RenderPass,
+        layout: vk::PipelineLayout,
+        vert_mod: vk::ShaderModule,
+        frag_mod: vk::ShaderModule,
+        vertex_state: &vk::PipelineVertexInputStateCreateInfo,
+        topology: vk::PrimitiveTopology,
+        blend: BlendMode,
+        depth_write: bool,
+    ) -> Result<vk::Pipeline, vk::Result> {
+        let (src, dst) = match blend {
+            BlendMode::Alpha => (
+                vk::BlendFactor::SRC_ALPHA,
+                vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+            ),
+            BlendMode::Additive => (vk::BlendFactor::SRC_ALPHA, vk::BlendFactor::ONE),
+            BlendMode::Multiply => (vk::BlendFactor::DST_COLOR, vk::BlendFactor::ZERO),
+        };
+        let input_assembly = vk::PipelineInputAssemblyStateCreateInfo::default()
+            .topology(topology)
+            .primitive_restart_enable(false);
+
+        let viewport_state = vk::PipelineViewportStateCreateInfo::default()
+            .viewport_count(1)
+            .scissor_count(1);
+
+        let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+        let dynamic_state =
+            vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dynamic_states);
+
+        let raster = vk::PipelineRasterizationStateCreateInfo::default()
+            .polygon_mode(vk::PolygonMode::FILL)
+            .cull_mode(vk::CullModeFlags::NONE)
+            .front_face(vk::FrontFace::COUNTER_CLOCKWISE)
+            .line_width(1.0);
+        let multisample = vk::PipelineMultisampleStateCreateInfo::default()
+            .rasterization_samples(vk::SampleCountFlags::TYPE_1);
+        let colour_blend_attachment = vk::PipelineColorBlendAttachmentState::default()
+            .blend_enable(true)
+            .src_color_blend_factor(src)
+            .dst_color_blend_factor(dst)
+            .color_blend_op(vk::BlendOp::ADD)
+            .src_alpha_blend_factor(vk::BlendFactor::ONE)
+            .dst_alpha_blend_factor(vk::BlendFactor::ZERO)
+            .alpha_blend_op(vk::BlendOp::ADD)
+            .color_write_mask(
+                vk::ColorComponentFlags::R
+                    | vk::ColorComponentFlags::G
+                    | vk::ColorComponentFlags::B
+                    | vk::ColorComponentFlags::A,
+            );
+        let depth = vk::PipelineDepthStencilStateCreateInfo::default()
+            .depth_test_enable(true)
+            .depth_write_enable(depth_write)
+            .depth_compare_op(vk::CompareOp::LESS_OR_EQUAL);
+        let colour_blend = vk::PipelineColorBlendStateCreateInfo::default()
+            .attachments(std::slice::from_ref(&colour_blend_attachment));
+
+        let shader_entry = c"main";
+        let stages = [
+            vk::PipelineShaderStageCreateInfo::default()
+                .module(vert_mod)
+                .name(shader_entry)
+                .stage(vk::ShaderStageFlags::VERTEX),
+            vk::PipelineShaderStageCreateInfo::default()
+                .module(frag_mod)
+                .name(shader_entry)
+                .stage(vk::ShaderStageFlags::FRAGMENT),
+        ];
+
+        let pipeline_info = vk::GraphicsPipelineCreateInfo::default()
+            .stages(&stages)
+            .vertex_input_state(vertex_state)
+            .input_assembly_state(&input_assembly)
+            .viewport_state(&viewport_state)
+            .dynamic_state(&dynamic_state)
+            .rasterization_state(&raster)
+            .multisample_state(&multisample)
+            .depth_stencil_state(&depth)
+            .color_blend_state(&colour_blend)
+            .layout(layout)
+            .render_pass(render_pass)
+            .subpass(0);
+
+        unsafe {
+            device
+                .create_graphics_pipelines(cache, std::slice::from_ref(&pipeline_info), None)
+                .map_err(|(_, e)| e)
+                .map(|p| p[0])
+        }
+    }
+
+    /// Vertex buffer layout of the sprite pipelines: quad corners and [`SpriteInstance`]s.
+    pub fn mesh_vertex_input() -> (
+        [vk::VertexInputBindingDescription; 1],
+        [vk::VertexInputAttributeDescription; 3],
+    ) {
+        let binding_descriptions = [vk::VertexInputBindingDescription::default()
+            .binding(0)
+            .stride(std::mem::size_of::<MeshVertex>() as u32)
+            .input_rate(vk::VertexInputRate::VERTEX)];
+        // pos, uv, color
+        let attribute_descriptions = [
+            vk::VertexInputAttributeDescription::default()
+                .binding(0)
+                .location(0)
+                .format(vk::Format::R32G32_SFLOAT)
+                .offset(0),
+            vk::VertexInputAttributeDescription::default()
+                .binding(0)
+                .location(1)
+                .format(vk::Format::R32G32_SFLOAT)
+                .offset(8),
+            vk::VertexInputAttributeDescription::default()
+                .binding(0)
+                .location(2)
+                .format(vk::Format::R32G32B32A32_SFLOAT)
+                .offset(16),
+        ];
+        (binding_descriptions, attribute_descriptions)
+    }
+
+    pub fn sprite_vertex_input() -> (
+        [vk::VertexInputBindingDescription; 2],
+        [vk::VertexInputAttributeDescription; 8],
+    ) {
+        let binding_descriptions = [
+            vk::VertexInputBindingDescription::default() // binding 0: quad verts
+                .binding(0)
+                .stride(std::mem::size_of::<QuadVertex>() as u32)
+                .input_rate(vk::VertexInputRate::VERTEX),
+            vk::VertexInputBindingDescription::default() // binding 1: per instance
+                .binding(1)
+                .stride(std::mem::size_of::<SpriteInstance>() as u32)
+                .input_rate(vk::VertexInputRate::INSTANCE),
+        ];
+
+        let attribute_descriptions = [
+            // binding 0
+            vk::VertexInputAttributeDescription::default()
+                .binding(0)
+                .location(0)
+                .format(vk::Format::R32G32_SFLOAT)
+                .offset(0),
+            vk::VertexInputAttributeDescription::default()
+                .binding(0)
+                .location(1)
+                .format(vk::Format::R32G32_SFLOAT)
+                .offset(8),
+            // binding 1
+            vk::VertexInputAttributeDescription::default()
+                .binding(1)
+                .location(2)
+                .format(vk::Format::R32G32B32A32_SFLOAT)
+                .offset(0),
+            vk::VertexInputAttributeDescription::default()
+                .binding(1)
+                .location(3)
+                .format(vk::Format::R32G32B32A32_SFLOAT)
+                .offset(16),
+            vk::VertexInputAttributeDescription::default()
+                .binding(1)
+                .location(4)
+                .format(vk::Format::R32G32B32A32_SFLOAT)
+                .offset(32),
+            vk::VertexInputAttributeDescription::default()
+                .binding(1)
+                .location(5)
+                .format(vk::Format::R32G32B32A32_SFLOAT)
+                .offset(48),
+            vk::VertexInputAttributeDescription::default()
+                .binding(1)
+                .location(6)
+                .format(vk::Format::R32_SFLOAT)
+                .offset(64),
+            vk::VertexInputAttributeDescription::default()
+                .binding(1)
+                .location(7)
+                .format(vk::Format::R32_SFLOAT)
+                .offset(68),
+        ];
+        (binding_descriptions, attribute_descriptions)
+    }
+
     pub fn create_shader(device: &Device, bytes: &[u8]) -> vk::ShaderModule {
         let (prefix, code, _) = unsafe { bytes.align_to::<u32>() };
         assert!(prefix.is_empty(), "SPIR-V must be 4-byte aligned");
         let info = vk::ShaderModuleCreateInfo::default().code(code);
         unsafe { device.create_shader_module(&info, None).unwrap() }
     }
+
+    /// Like `create_shader` for user bytes, which may be unaligned or not SPIR-V at all.
+    pub fn try_create_shader(
+        device: &Device,
+        bytes: &[u8],
+    ) -> Result<vk::ShaderModule, vk::Result> {
+        const MAGIC: u32 = 0x0723_0203;
+        let code: Vec<u32> = bytes
+            .chunks_exact(4)
+            .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]]))
+            .collect();
+        if !bytes.len().is_multiple_of(4) || code.len() < 5 || code[0] != MAGIC {
+            return Err(vk::Result::ERROR_INVALID_SHADER_NV);
+        }
+        let info = vk::ShaderModuleCreateInfo::default().code(&code);
+        unsafe { device.create_shader_module(&info, None) }
+    }
 }
@@ -0,0 +1,270 @@
+use ash::{vk, Device};
+use jester_core::{Color, ColorVision, DisplayCalibration, ScreenFilter};
+
+use crate::utils::find_memorytype_index;
+
+/// Offscreen color targets the scene is drawn into, one per frame in flight.
+///
+/// The post pass then copies the current one onto the swapchain image,
+/// applying each camera's [`ScreenFilter`] to its region on the way. Targets
+/// alternate every frame, so the previous frame stays readable for
+/// [`ScreenFilter::trail`].
+pub struct PostTargets {
+    pub images: Vec<vk::Image>,
+    pub memory: Vec<vk::DeviceMemory>,
+    pub views: Vec<vk::ImageView>,
+    pub framebuffers: Vec<vk::Framebuffer>,
+    pub sets: Vec<vk::DescriptorSet>,
+    /// Depth buffer of each target for [`AlphaSorting::TwoPass`](jester_core::AlphaSorting::TwoPass).
+    pub depth: Vec<(vk::Image, vk::DeviceMemory, vk::ImageView)>,
+    /// Device memory of all color and depth images.
+    pub bytes: u64,
+    pool: vk::DescriptorPool,
+}
+
+impl PostTargets {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        device: &Device,
+        mem_props: &vk::PhysicalDeviceMemoryProperties,
+        render_pass: vk::RenderPass,
+        set_layout: vk::DescriptorSetLayout,
+        sampler: vk::Sampler,
+        format: vk::Format,
+        depth_format: vk::Format,
+        extent: vk::Extent2D,
+        count: usize,
+    ) -> Result<Self, vk::Result> {
+        let pool_size = vk::DescriptorPoolSize::default()
+            .ty(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .descriptor_count(count as u32);
+        let pool = unsafe {
+            device.create_descriptor_pool(
+                &vk::DescriptorPoolCreateInfo::default()
+                    .max_sets(count as u32)
+                    .pool_sizes(std::slice::from_ref(&pool_size)),
+                None,
+            )?
+        };
+        let mut targets = Self {
+            images: Vec::with_capacity(count),
+            memory: Vec::with_capacity(count),
+            views: Vec::with_capacity(count),
+            framebuffers: Vec::with_capacity(count),
+            sets: Vec::with_capacity(count),
+            depth: Vec::with_capacity(count),
+            bytes: 0,
+            pool,
+        };
+        for _ in 0..count {
+            targets.push(
+                device,
+                mem_props,
+                render_pass,
+                set_layout,
+                sampler,
+                format,
+                depth_format,
+                extent,
+            )?;
+        }
+        Ok(targets)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push(
+        &mut self,
+        device: &Device,
+        mem_props: &vk::PhysicalDeviceMemoryProperties,
+        render_pass: vk::RenderPass,
+        set_layout: vk::DescriptorSetLayout,
+        sampler: vk::Sampler,
+        format: vk::Format,
+        depth_format: vk::Format,
+        extent: vk::Extent2D,
+    ) -> Result<(), vk::Result> {
+        unsafe {
+            let (image, mem, view) = create_attachment(
+                device,
+                mem_props,
+                format,
+                extent,
+                vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::SAMPLED,
+                vk::ImageAspectFlags::COLOR,
+            )?;
+            self.images.push(image);
+            self.memory.push(mem);
+            self.views.push(view);
+            let depth = create_attachment(
+                device,
+                mem_props,
+                depth_format,
+                extent,
+                vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+                vk::ImageAspectFlags::DEPTH,
+            )?;
+            self.depth.push(depth);
+            self.bytes += device.get_image_memory_requirements(image).size
+                + device.get_image_memory_requirements(depth.0).size;
+
+            let fb = device.create_framebuffer(
+                &vk::FramebufferCreateInfo::default()
+                    .render_pass(render_pass)
+                    .attachments(&[view, depth.2])
+                    .width(extent.width)
+                    .height(extent.height)
+                    .layers(1),
+                None,
+            )?;
+            self.framebuffers.push(fb);
+
+            let set = device.allocate_descriptor_sets(
+                &vk::DescriptorSetAllocateInfo::default()
+                    .descriptor_pool(self.pool)
+                    .set_layouts(std::slice::from_ref(&set_layout)),
+            )?[0];
+            let img_info = vk::DescriptorImageInfo::default()
+                .sampler(sampler)
+                .image_view(view)
+                .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+            let write = vk::WriteDescriptorSet::default()
+                .dst_set(set)
+                .dst_binding(0)
+                .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+                .image_info(std::slice::from_ref(&img_info));
+            device.update_descriptor_sets(std::slice::from_ref(&write), &[]);
+            self.sets.push(set);
+        }
+        Ok(())
+    }
+
+    /// # Safety
+    /// The targets must no longer be in use by the GPU.
+    pub unsafe fn destroy(&mut self, device: &Device) {
+        unsafe {
+            for &fb in &self.framebuffers {
+                device.destroy_framebuffer(fb, None);
+            }
+            for &view in &self.views {
+                device.destroy_image_view(view, None);
+            }
+            for &img in &self.images {
+                device.destroy_image(img, None);
+            }
+            for &mem in &self.memory {
+                device.free_memory(mem, None);
+            }
+            for &(img, mem, view) in &self.depth {
+                device.destroy_image_view(view, None);
+                device.destroy_image(img, None);
+                device.free_memory(mem, None);
+            }
+            device.destroy_descriptor_pool(self.pool, None);
+        }
+        self.pool = vk::DescriptorPool::null();
+        self.framebuffers.clear();
+        self.views.clear();
+        self.images.clear();
+        self.memory.clear();
+        self.sets.clear();
+        self.depth.clear();
+        self.bytes = 0;
+    }
+}
+
+/// Device-local 2D image with a view, for use as a framebuffer attachment.
+pub(crate) unsafe fn create_attachment(
+    device: &Device,
+    mem_props: &vk::PhysicalDeviceMemoryProperties,
+    format: vk::Format,
+    extent: vk::Extent2D,
+    usage: vk::ImageUsageFlags,
+    aspect: vk::ImageAspectFlags,
+) -> Result<(vk::Image, vk::DeviceMemory, vk::ImageView), vk::Result> {
+    unsafe {
+        let image = device.create_image(
+            &vk::ImageCreateInfo::default()
+                .image_type(vk::ImageType::TYPE_2D)
+                .format(format)
+                .extent(vk::Extent3D {
+                    width: extent.width,
+                    height: extent.height,
+                    depth: 1,
+                })
+                .mip_levels(1)
+                .array_layers(1)
+                .samples(vk::SampleCountFlags::TYPE_1)
+                .tiling(vk::ImageTiling::OPTIMAL)
+                .usage(usage)
+                .sharing_mode(vk::SharingMode::EXCLUSIVE)
+                .initial_layout(vk::ImageLayout::UNDEFINED),
+            None,
+        )?;
+
+        let req = device.get_image_memory_requirements(image);
+        let mem_index =
+            find_memorytype_index(&req, mem_props, vk::MemoryPropertyFlags::DEVICE_LOCAL)
+                .expect("no device-local memory for post target");
+        let mem = device.allocate_memory(
+            &vk::MemoryAllocateInfo::default()
+                .allocation_size(req.size)
+                .memory_type_index(mem_index),
+            None,
+        )?;
+        device.bind_image_memory(image, mem, 0)?;
+
+        let view = device.create_image_view(
+            &vk::ImageViewCreateInfo::default()
+                .image(image)
+                .view_type(vk::ImageViewType::TYPE_2D)
+                .format(format)
+                .subresource_range(
+                    vk::ImageSubresourceRange::default()
+                        .aspect_mask(aspect)
+                        .level_count(1)
+                        .layer_count(1),
+                ),
+            None,
+        )?;
+        Ok((image, mem, view))
+    }
+}
+
+/// Push constants of `post.frag` for one region of the screen.
+pub fn push_constants(
+    min: [f32; 2],
+    size: [f32; 2],
+    screen: [f32; 2],
+    time: f32,
+    f: &ScreenFilter,
+    calibration: DisplayCalibration,
+    tint: Color,
+) -> [f32; 20] {
+    let (cvd_mode, cvd_kind) = match f.color_vision {
+        ColorVision::Normal => (0.0, 0.0),
+        ColorVision::Simulate(kind) => (1.0, kind as u8 as f32),
+        ColorVision::Daltonize(kind) => (2.0, kind as u8 as f32),
+    };
+    [
+        min[0],
+        min[1],
+        size[0],
+        size[1],
+        screen[0],
+        screen[1],
+        time,
+        f.curvature,
+        f.scanlines,
+        f.chromatic_aberration,
+        f.grain,
+        f.vignette,
+        f.trail,
+        cvd_mode,
+        cvd_kind,
+        calibration.gamma.max(0.01),
+        calibration.brightness.max(0.0),
+        tint.r,
+        tint.g,
+        tint.b,
+    ]
+}
@@ -0,0 +1,23 @@
+#version 450
+
+// drawn under a camera's sprites: the previous frame, faded by `trail`
+layout(set = 0, binding = 0) uniform sampler2D u_prev;
+
+layout(push_constant) uniform PC {
+    vec4 rect;
+    vec2 screen;
+    float time;
+    float curvature;
+    float scanlines;
+    float aberration;
+    float grain;
+    float vignette;
+    float trail;
+} pc;
+
+layout(location = 0) out vec4 out_color;
+
+void main()
+{
+    out_color = vec4(texture(u_prev, gl_FragCoord.xy / pc.screen).rgb, pc.trail);
+}
@@ -0,0 +1,12 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform sampler2D u_tex;
+
+layout(location = 0) in vec2 v_uv;
+layout(location = 1) in vec4 v_color;
+layout(location = 0) out vec4 out_color;
+
+void main()
+{
+    out_color = texture(u_tex, vec2(v_uv.x, 1.0 - v_uv.y)) * v_color;
+}
@@ -0,0 +1,25 @@
+#version 450
+layout(location = 0) in vec2 in_pos;     // pixels, world space
+layout(location = 1) in vec2 in_uv;
+layout(location = 2) in vec4 in_color;
+
+layout(location = 0) out vec2 v_uv;
+layout(location = 1) out vec4 v_color;
+
+layout(push_constant) uniform PC {
+    vec2 screen;
+    vec2 camCenter;
+    vec2 camZoom;
+    float time;
+} pc;
+
+void main() {
+    vec2 pixel = (in_pos - pc.camCenter) * pc.camZoom;
+
+    vec2 ndc = pixel / pc.screen * 2.0 - 1.0;
+    ndc.y = -ndc.y;
+    gl_Position = vec4(ndc, 0.0, 1.0);
+
+    v_uv = in_uv;
+    v_color = in_color;
+}
@@ -0,0 +1,41 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform sampler2D u_tex;
+
+layout(location = 0) in vec2 vLocal;
+layout(location = 1) flat in vec4 vRect;
+layout(location = 2) flat in float vWrap;
+layout(location = 3) flat in vec4 vCustom; // x: distance range in texels
+layout(location = 0) out vec4 out_color;
+
+float median(vec3 v)
+{
+    return max(min(v.r, v.g), min(max(v.r, v.g), v.b));
+}
+
+// sprite textures are sampled nearest, distance fields need filtering between texels
+vec3 bilinear(vec2 uv, vec2 size)
+{
+    vec2 p = uv * size - 0.5;
+    ivec2 i = ivec2(floor(p));
+    vec2 f = fract(p);
+    ivec2 hi = ivec2(size) - 1;
+    vec3 a = texelFetch(u_tex, clamp(i, ivec2(0), hi), 0).rgb;
+    vec3 b = texelFetch(u_tex, clamp(i + ivec2(1, 0), ivec2(0), hi), 0).rgb;
+    vec3 c = texelFetch(u_tex, clamp(i + ivec2(0, 1), ivec2(0), hi), 0).rgb;
+    vec3 d = texelFetch(u_tex, clamp(i + ivec2(1, 1), ivec2(0), hi), 0).rgb;
+    return mix(mix(a, b, f.x), mix(c, d, f.x), f.y);
+}
+
+void main()
+{
+    vec2 local = mix(vLocal, fract(vLocal), vWrap);
+    vec2 uv = mix(vRect.xy, vRect.zw, local);
+    uv.y = 1.0 - uv.y;
+    vec2 size = vec2(textureSize(u_tex, 0));
+    float sd = median(bilinear(uv, size)) - 0.5;
+    // the distance range as seen on screen, so edges stay one pixel wide at any zoom
+    vec2 screen_texels = vec2(1.0) / fwidth(uv * size);
+    float screen_range = max(0.5 * dot(vec2(vCustom.x), screen_texels), 1.0);
+    out_color = vec4(1.0, 1.0, 1.0, clamp(sd * screen_range + 0.5, 0.0, 1.0));
+}
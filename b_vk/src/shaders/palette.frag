@@ -0,0 +1,24 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform sampler2D u_tex;
+layout(set = 1, binding = 0) uniform sampler2D u_palette;
+
+layout(location = 0) in vec2 vLocal;
+layout(location = 1) flat in vec4 vRect;
+layout(location = 2) flat in float vWrap;
+layout(location = 4) flat in float vPaletteRow;
+layout(location = 0) out vec4 out_color;
+
+void main()
+{
+    vec2 local = mix(vLocal, fract(vLocal), vWrap);
+    vec2 uv = mix(vRect.xy, vRect.zw, local);
+    uv.y = 1.0 - uv.y;
+    // red holds the palette index, alpha the index texture's coverage; fetched
+    // so a linear texture filter does not blend indices
+    ivec2 size = textureSize(u_tex, 0);
+    vec4 idx = texelFetch(u_tex, clamp(ivec2(uv * vec2(size)), ivec2(0), size - 1), 0);
+    ivec2 texel = ivec2(int(idx.r * 255.0 + 0.5), int(vPaletteRow));
+    vec4 color = texelFetch(u_palette, texel, 0);
+    out_color = vec4(color.rgb, color.a * idx.a);
+}
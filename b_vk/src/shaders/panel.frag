@@ -0,0 +1,45 @@
+#version 450
+
+layout(location = 0) in vec2 v_local;
+layout(location = 1) in vec2 v_half;
+layout(location = 2) in vec2 v_uv;
+layout(location = 3) in vec4 v_color_a;
+layout(location = 4) in vec4 v_color_b;
+layout(location = 5) in vec4 v_border_color;
+layout(location = 6) flat in vec4 v_params;
+
+layout(location = 0) out vec4 out_color;
+
+float sd_round_box(vec2 p, vec2 b, float r)
+{
+    vec2 q = abs(p) - b + r;
+    return length(max(q, 0.0)) + min(max(q.x, q.y), 0.0) - r;
+}
+
+void main()
+{
+    float radius = min(v_params.x, min(v_half.x, v_half.y));
+    float border = v_params.y;
+    int kind = int(v_params.z + 0.5);
+    float angle = v_params.w;
+
+    float d = sd_round_box(v_local, v_half, radius);
+    float aa = max(fwidth(d), 1e-4);
+
+    float t = 0.0;
+    if (kind == 1) {
+        vec2 dir = vec2(cos(angle), sin(angle));
+        t = clamp(dot(v_uv - 0.5, dir) + 0.5, 0.0, 1.0);
+    } else if (kind == 2) {
+        t = clamp(length(v_uv - 0.5) * 2.0, 0.0, 1.0);
+    }
+    vec4 col = mix(v_color_a, v_color_b, t);
+
+    if (border > 0.0) {
+        float edge = smoothstep(-border - aa, -border + aa, d);
+        col = mix(col, v_border_color, edge);
+    }
+
+    float coverage = 1.0 - smoothstep(-aa, aa, d);
+    out_color = vec4(col.rgb, col.a * coverage);
+}
@@ -0,0 +1,40 @@
+#version 450
+layout(location = 0) in vec2 in_pos;
+layout(location = 1) in vec2 in_uv;
+
+layout(location = 2) in vec4 inst_pos_size;     // x, y, w, h  (pixels)
+layout(location = 3) in vec4 inst_color_a;      // fill / gradient start
+layout(location = 4) in vec4 inst_color_b;      // gradient end
+layout(location = 5) in vec4 inst_border_color;
+layout(location = 6) in vec4 inst_params;       // radius, border, kind, angle
+
+layout(location = 0) out vec2 v_local;
+layout(location = 1) out vec2 v_half;
+layout(location = 2) out vec2 v_uv;
+layout(location = 3) out vec4 v_color_a;
+layout(location = 4) out vec4 v_color_b;
+layout(location = 5) out vec4 v_border_color;
+layout(location = 6) flat out vec4 v_params;
+
+layout(push_constant) uniform PC {
+    vec2 screen;
+    vec2 camCenter;
+    vec2 camZoom;
+} pc;
+
+void main() {
+    vec2 pixel = (inst_pos_size.xy - pc.camCenter) * pc.camZoom
+                 + in_pos * inst_pos_size.zw * pc.camZoom;
+
+    vec2 ndc = pixel / pc.screen * 2.0 - 1.0;
+    ndc.y = -ndc.y;
+    gl_Position = vec4(ndc, 0.0, 1.0);
+
+    v_local = in_pos * inst_pos_size.zw;
+    v_half = inst_pos_size.zw * 0.5;
+    v_uv = in_uv;
+    v_color_a = inst_color_a;
+    v_color_b = inst_color_b;
+    v_border_color = inst_border_color;
+    v_params = inst_params;
+}
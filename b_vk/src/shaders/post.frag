@@ -0,0 +1,99 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform sampler2D u_scene;
+
+layout(push_constant) uniform PC {
+    vec4 rect;      // camera region in pixels: x, y, w, h
+    vec2 screen;
+    float time;
+    float curvature;
+    float scanlines;
+    float aberration;
+    float grain;
+    float vignette;
+    float trail;
+    float cvd_mode; // 0 off, 1 simulate, 2 daltonize
+    float cvd_kind; // 0 protanopia, 1 deuteranopia, 2 tritanopia
+    float gamma;
+    float brightness;
+    // time of day, multiplies every region; floats since a vec3 would be aligned to 16 bytes
+    float tint_r;
+    float tint_g;
+    float tint_b;
+} pc;
+
+layout(location = 0) out vec4 out_color;
+
+vec3 sample_scene(vec2 px) {
+    // stay inside the region so neighbouring cameras do not bleed in
+    px = clamp(px, pc.rect.xy + 0.5, pc.rect.xy + pc.rect.zw - 0.5);
+    return texture(u_scene, px / pc.screen).rgb;
+}
+
+// rows of the linear RGB to LMS cone response matrix and back (Vienot, Brettel and Mollon)
+const mat3 RGB_TO_LMS = mat3(
+    vec3(17.8824, 43.5161, 4.11935),
+    vec3(3.45565, 27.1554, 3.86714),
+    vec3(0.0299566, 0.184309, 1.46709)
+);
+const mat3 LMS_TO_RGB = mat3(
+    vec3(0.0809444479, -0.130504409, 0.116721066),
+    vec3(-0.0102485335, 0.0540193266, -0.113614708),
+    vec3(-0.000365296938, -0.00412161469, 0.693511405)
+);
+
+// what a viewer missing one cone type sees
+vec3 simulate_cvd(vec3 rgb, float kind) {
+    vec3 lms = rgb * RGB_TO_LMS;
+    if (kind < 0.5) {
+        lms.x = 2.02344 * lms.y - 2.52581 * lms.z;
+    } else if (kind < 1.5) {
+        lms.y = 0.494207 * lms.x + 1.24827 * lms.z;
+    } else {
+        lms.z = -0.395913 * lms.x + 0.801109 * lms.y;
+    }
+    return lms * LMS_TO_RGB;
+}
+
+void main()
+{
+    vec2 c = (gl_FragCoord.xy - pc.rect.xy) / pc.rect.zw * 2.0 - 1.0;
+    c += c * (c.yx * c.yx) * pc.curvature;
+    if (any(greaterThan(abs(c), vec2(1.0)))) {
+        out_color = vec4(0.0, 0.0, 0.0, 1.0);
+        return;
+    }
+    vec2 px = pc.rect.xy + (c * 0.5 + 0.5) * pc.rect.zw;
+
+    vec2 shift = vec2(pc.aberration, 0.0);
+    vec3 col = vec3(
+        sample_scene(px + shift).r,
+        sample_scene(px).g,
+        sample_scene(px - shift).b
+    );
+
+    col *= 1.0 - pc.scanlines * mod(floor(gl_FragCoord.y), 2.0);
+    col *= 1.0 - pc.vignette * dot(c, c) * 0.5;
+
+    float n = fract(sin(dot(gl_FragCoord.xy + pc.time * 61.0, vec2(12.9898, 78.233))) * 43758.5453);
+    col += (n - 0.5) * pc.grain;
+
+    if (pc.cvd_mode > 0.5) {
+        col = clamp(col, 0.0, 1.0);
+        vec3 seen = simulate_cvd(col, pc.cvd_kind);
+        if (pc.cvd_mode < 1.5) {
+            col = seen;
+        } else {
+            // shift the lost contrast into channels the viewer still tells apart
+            vec3 err = col - seen;
+            col += vec3(0.0, 0.7 * err.r + err.g, 0.7 * err.r + err.b);
+        }
+    }
+
+    col *= vec3(pc.tint_r, pc.tint_g, pc.tint_b);
+
+    // display calibration, the same for every region
+    col = pow(max(col, vec3(0.0)), vec3(1.0 / pc.gamma)) * pc.brightness;
+
+    out_color = vec4(col, 1.0);
+}
@@ -2,10 +2,15 @@
 
 layout(set = 0, binding = 0) uniform sampler2D u_tex;
 
-layout(location = 0) in  vec2 v_uv;
+layout(location = 0) in vec2 vLocal;
+layout(location = 1) flat in vec4 vRect;
+layout(location = 2) flat in float vWrap;
 layout(location = 0) out vec4 out_color;
 
 void main()
 {
-    out_color = texture(u_tex, v_uv);
+    vec2 local = mix(vLocal, fract(vLocal), vWrap);
+    vec2 uv = mix(vRect.xy, vRect.zw, local);
+    uv.y = 1.0 - uv.y;
+    out_color = texture(u_tex, uv);
 }
@@ -4,13 +4,22 @@ layout(location = 1) in vec2 in_uv;
 
 layout(location = 2) in vec4 inst_pos_size;   // x, y, w, h  (pixels)
 layout(location = 3) in vec4 inst_uv;         // u0,v0,u1,v1
+layout(location = 4) in vec4 inst_uv_anim;    // offset.xy, scroll speed.xy
+layout(location = 5) in vec4 inst_custom;     // user data for custom fragment shaders
+layout(location = 6) in float inst_palette_row;
+layout(location = 7) in float inst_depth;
 
-layout(location = 0) out vec2 vUV;
+layout(location = 0) out vec2 vLocal;
+layout(location = 1) flat out vec4 vRect;
+layout(location = 2) flat out float vWrap;
+layout(location = 3) flat out vec4 vCustom;
+layout(location = 4) flat out float vPaletteRow;
 
 layout(push_constant) uniform PC {
     vec2 screen;
     vec2 camCenter;
-    float camZoom;
+    vec2 camZoom;
+    float time;
 } pc;
 
 void main() {
@@ -19,7 +28,13 @@ void main() {
 
     vec2 ndc = pixel / pc.screen * 2.0 - 1.0;
     ndc.y = -ndc.y;
-    gl_Position = vec4(ndc, 0.0, 1.0);
-    vUV = mix(inst_uv.xy, inst_uv.zw, in_uv);
-    vUV.y = 1.0 - vUV.y;
+    gl_Position = vec4(ndc, inst_depth, 1.0);
+
+    // wrapping happens per fragment so scrolled sprites stay inside their atlas rect
+    vec2 scroll = fract(inst_uv_anim.xy + inst_uv_anim.zw * pc.time);
+    vLocal = in_uv + scroll;
+    vRect = inst_uv;
+    vWrap = any(notEqual(scroll, vec2(0.0))) ? 1.0 : 0.0;
+    vCustom = inst_custom;
+    vPaletteRow = inst_palette_row;
 }
@@ -0,0 +1,109 @@
+use ash::{google::display_timing, vk};
+use jester_core::{PresentStats, PresentTimingSource};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+const WINDOW: usize = 120;
+
+/// Measures present-to-present intervals, from `VK_GOOGLE_display_timing`
+/// when the device has it, from CPU timestamps around `queue_present` otherwise.
+pub struct PresentTiming {
+    loader: Option<display_timing::Device>,
+    refresh: Option<Duration>,
+    next_id: u32,
+    last_actual: Option<u64>,
+    last_cpu: Option<Instant>,
+    intervals: VecDeque<Duration>,
+    missed: u64,
+    presented: u64,
+}
+
+impl PresentTiming {
+    pub fn new(loader: Option<display_timing::Device>) -> Self {
+        Self {
+            loader,
+            refresh: None,
+            next_id: 1,
+            last_actual: None,
+            last_cpu: None,
+            intervals: VecDeque::with_capacity(WINDOW),
+            missed: 0,
+            presented: 0,
+        }
+    }
+
+    /// Swapchain changed: refresh rate may differ and old ids are gone.
+    pub fn reset(&mut self, swapchain: vk::SwapchainKHR) {
+        self.last_actual = None;
+        self.last_cpu = None;
+        self.refresh = self.loader.as_ref().and_then(|l| {
+            unsafe { l.get_refresh_cycle_duration(swapchain) }
+                .ok()
+                .map(|r| Duration::from_nanos(r.refresh_duration))
+        });
+    }
+
+    /// Id to attach to the next present, `None` without display timing.
+    pub fn next_present_time(&mut self) -> Option<vk::PresentTimeGOOGLE> {
+        self.loader.as_ref()?;
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        Some(vk::PresentTimeGOOGLE {
+            present_id: id,
+            desired_present_time: 0,
+        })
+    }
+
+    pub fn after_present(&mut self, swapchain: vk::SwapchainKHR) {
+        match &self.loader {
+            Some(l) => {
+                let past = unsafe { l.get_past_presentation_timing(swapchain) }.unwrap_or_default();
+                for t in past {
+                    if let Some(prev) = self.last_actual
+                        && t.actual_present_time > prev
+                    {
+                        self.record(Duration::from_nanos(t.actual_present_time - prev));
+                    }
+                    self.last_actual = Some(t.actual_present_time);
+                }
+            }
+            None => {
+                let now = Instant::now();
+                if let Some(prev) = self.last_cpu {
+                    self.record(now - prev);
+                }
+                self.last_cpu = Some(now);
+            }
+        }
+    }
+
+    fn record(&mut self, interval: Duration) {
+        self.presented += 1;
+        if let Some(refresh) = self.refresh.filter(|r| !r.is_zero()) {
+            let cycles = (interval.as_secs_f64() / refresh.as_secs_f64()).round() as u64;
+            self.missed += cycles.saturating_sub(1);
+        }
+        if self.intervals.len() == WINDOW {
+            self.intervals.pop_front();
+        }
+        self.intervals.push_back(interval);
+    }
+
+    pub fn stats(&self) -> PresentStats {
+        let n = self.intervals.len().max(1) as u32;
+        PresentStats {
+            source: match self.loader {
+                Some(_) => PresentTimingSource::DisplayTiming,
+                None => PresentTimingSource::CpuEstimate,
+            },
+            refresh_interval: self.refresh,
+            last_interval: self.intervals.back().copied().unwrap_or_default(),
+            average_interval: self.intervals.iter().sum::<Duration>() / n,
+            max_interval: self.intervals.iter().max().copied().unwrap_or_default(),
+            missed_vsyncs: self.missed,
+            presented: self.presented,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use ash::{vk, Device};
+
+/// Attachments whose contents never outlive a pass, shared by every holder
+/// of the same format, size and usage, e.g. the depth buffers of render
+/// targets, which each pass clears before drawing.
+#[derive(Debug, Default)]
+pub(crate) struct Transients {
+    // attachment by key, with how many hold it
+    pool: HashMap<TransientKey, ((vk::Image, vk::DeviceMemory, vk::ImageView), usize)>,
+    /// Device memory of the attachments alive.
+    pub bytes: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct TransientKey {
+    format: vk::Format,
+    width: u32,
+    height: u32,
+    usage: vk::ImageUsageFlags,
+    aspect: vk::ImageAspectFlags,
+}
+
+impl Transients {
+    /// An attachment of this kind, created if none is held yet; give it back
+    /// with [`Transients::release`].
+    pub unsafe fn acquire(
+        &mut self,
+        device: &Device,
+        mem_props: &vk::PhysicalDeviceMemoryProperties,
+        format: vk::Format,
+        extent: vk::Extent2D,
+        usage: vk::ImageUsageFlags,
+        aspect: vk::ImageAspectFlags,
+    ) -> Result<(vk::Image, vk::DeviceMemory, vk::ImageView), vk::Result> {
+        let key = TransientKey {
+            format,
+            width: extent.width,
+            height: extent.height,
+            usage,
+            aspect,
+        };
+        if let Some((attachment, holders)) = self.pool.get_mut(&key) {
+            *holders += 1;
+            return Ok(*attachment);
+        }
+        let attachment = unsafe {
+            crate::post::create_attachment(device, mem_props, format, extent, usage, aspect)?
+        };
+        self.bytes += unsafe { device.get_image_memory_requirements(attachment.0).size };
+        self.pool.insert(key, (attachment, 1));
+        Ok(attachment)
+    }
+
+    /// Drops a hold on the attachment of `image`, destroying it with the last.
+    pub unsafe fn release(&mut self, device: &Device, image: vk::Image) {
+        let Some((&key, (_, holders))) = self.pool.iter_mut().find(|(_, (a, _))| a.0 == image)
+        else {
+            return;
+        };
+        *holders -= 1;
+        if *holders == 0
+            && let Some((attachment, _)) = self.pool.remove(&key)
+        {
+            unsafe { self.destroy(device, attachment) };
+        }
+    }
+
+    /// Destroys every attachment, held or not.
+    pub unsafe fn clear(&mut self, device: &Device) {
+        for (_, (attachment, _)) in std::mem::take(&mut self.pool) {
+            unsafe { self.destroy(device, attachment) };
+        }
+    }
+
+    unsafe fn destroy(
+        &mut self,
+        device: &Device,
+        (image, mem, view): (vk::Image, vk::DeviceMemory, vk::ImageView),
+    ) {
+        unsafe {
+            self.bytes -= device.get_image_memory_requirements(image).size;
+            device.destroy_image_view(view, None);
+            device.destroy_image(image, None);
+            device.free_memory(mem, None);
+        }
+    }
+}
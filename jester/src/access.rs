@@ -0,0 +1,157 @@
+//! Exposes the UI set with `Ctx::set_ui` to screen readers through AccessKit.
+//!
+//! Behind the opt-in `accesskit` feature, it pulls in a platform adapter per OS.
+use std::sync::{Arc, Mutex};
+
+use accesskit::{
+    Action, ActionHandler, ActionRequest, ActivationHandler, DeactivationHandler, Live, Node,
+    NodeId, Rect, Role, Tree, TreeUpdate,
+};
+use accesskit_winit::Adapter;
+use jester_core::UiLayout;
+use winit::{event::WindowEvent, event_loop::ActiveEventLoop, window::Window};
+
+const ROOT: NodeId = NodeId(0);
+/// Live region the announcements are written to.
+const ANNOUNCER: NodeId = NodeId(1);
+
+/// Node id of the UI node at `index` in [`UiLayout::rects`].
+fn node_id(index: usize) -> NodeId {
+    NodeId(index as u64 + 2)
+}
+
+/// What assistive technology asked the UI to do.
+pub(crate) enum Request {
+    Focus(String),
+    Click(String),
+}
+
+struct Requests(Arc<Mutex<Vec<ActionRequest>>>);
+
+impl ActionHandler for Requests {
+    fn do_action(&mut self, request: ActionRequest) {
+        self.0.lock().unwrap().push(request);
+    }
+}
+
+/// The tree is sent by the next frame instead, there is no cheap way to build it from another thread.
+struct NoInitialTree;
+
+impl ActivationHandler for NoInitialTree {
+    fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+        None
+    }
+}
+
+struct Deactivate;
+
+impl DeactivationHandler for Deactivate {
+    fn deactivate_accessibility(&mut self) {}
+}
+
+pub(crate) struct ScreenReader {
+    adapter: Adapter,
+    requests: Arc<Mutex<Vec<ActionRequest>>>,
+    announcement: String,
+}
+
+impl ScreenReader {
+    /// Must be created before `window` is first shown.
+    pub fn new(event_loop: &ActiveEventLoop, window: &Window) -> Self {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let adapter = Adapter::with_direct_handlers(
+            event_loop,
+            window,
+            NoInitialTree,
+            Requests(requests.clone()),
+            Deactivate,
+        );
+        Self {
+            adapter,
+            requests,
+            announcement: String::new(),
+        }
+    }
+
+    pub fn process_event(&mut self, window: &Window, event: &WindowEvent) {
+        self.adapter.process_event(window, event);
+    }
+
+    /// Focus and click requests received since the last call, by node name.
+    pub fn take_requests(&mut self, layout: &UiLayout) -> Vec<Request> {
+        let requests = std::mem::take(&mut *self.requests.lock().unwrap());
+        requests
+            .into_iter()
+            .filter_map(|r| {
+                let (_, _, _, name) = layout.accessible().find(|n| node_id(n.0) == r.target)?;
+                let name = name?.to_owned();
+                match r.action {
+                    Action::Focus => Some(Request::Focus(name)),
+                    Action::Click => Some(Request::Click(name)),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Sends the current UI, focus and `announcements` if a screen reader is listening.
+    pub fn update(
+        &mut self,
+        title: &str,
+        layout: Option<&UiLayout>,
+        focused: Option<&str>,
+        announcements: &[String],
+    ) {
+        if !announcements.is_empty() {
+            self.announcement = announcements.join(". ");
+        }
+        let announcement = &self.announcement;
+        self.adapter.update_if_active(|| {
+            let mut root = Node::new(Role::Window);
+            root.set_label(title);
+            let mut announcer = Node::new(Role::Label);
+            announcer.set_value(announcement.as_str());
+            announcer.set_live(Live::Polite);
+            root.push_child(ANNOUNCER);
+
+            let mut nodes = Vec::new();
+            let mut focus = ROOT;
+            for (index, label, rect, name) in layout.into_iter().flat_map(UiLayout::accessible) {
+                let id = node_id(index);
+                let mut node = match name {
+                    Some(name) => {
+                        if focused == Some(name) {
+                            focus = id;
+                        }
+                        let mut node = Node::new(Role::Button);
+                        node.set_label(label);
+                        node.add_action(Action::Focus);
+                        node.add_action(Action::Click);
+                        node
+                    }
+                    None => {
+                        let mut node = Node::new(Role::Label);
+                        node.set_value(label);
+                        node
+                    }
+                };
+                let max = rect.max();
+                node.set_bounds(Rect::new(
+                    rect.min.x as f64,
+                    rect.min.y as f64,
+                    max.x as f64,
+                    max.y as f64,
+                ));
+                root.push_child(id);
+                nodes.push((id, node));
+            }
+            nodes.push((ROOT, root));
+            nodes.push((ANNOUNCER, announcer));
+            TreeUpdate {
+                nodes,
+                tree: Some(Tree::new(ROOT)),
+                focus,
+            }
+        });
+    }
+}
@@ -0,0 +1,222 @@
+use std::any::TypeId;
+
+use glam::Vec2;
+use hashbrown::HashMap;
+use jester_core::{
+    Backend, BlendMode, Color, Ctx, Fill, Font, GlyphAtlas, MaterialId, Panel, PanelInstance,
+    Renderer, SpriteBatch, Transform,
+};
+use tracing::{info, warn};
+use winit::keyboard::{Key, KeyCode, NamedKey};
+
+use crate::fps::FpsStats;
+
+pub type ConsoleCommand = Box<dyn FnMut(&[&str], &mut Ctx<'_>) -> String + Send>;
+
+/// Drop-down debug console, toggled with [`Console::toggle_key`].
+///
+/// Games register their own commands; `help`, `clear`, `entities`, `fps`,
+/// `scenes` and `scene <name>` are built in.
+pub struct Console {
+    pub toggle_key: KeyCode,
+    pub max_lines: usize,
+    pub background: Color,
+    open: bool,
+    input: String,
+    lines: Vec<String>,
+    submitted: Vec<String>,
+    commands: HashMap<String, ConsoleCommand>,
+    font: Option<(Font, f32)>,
+    atlas: Option<GlyphAtlas>,
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self {
+            toggle_key: KeyCode::Backquote,
+            max_lines: 12,
+            background: Color::rgba(0.02, 0.02, 0.05, 0.85),
+            open: false,
+            input: String::new(),
+            lines: Vec::new(),
+            submitted: Vec::new(),
+            commands: HashMap::new(),
+            font: None,
+            atlas: None,
+        }
+    }
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Font used to draw the console; without one output only goes to the log.
+    pub fn with_font(mut self, font: Font, size: f32) -> Self {
+        self.font = Some((font, size));
+        self
+    }
+
+    pub fn register<F>(&mut self, name: &str, f: F)
+    where
+        F: FnMut(&[&str], &mut Ctx<'_>) -> String + Send + 'static,
+    {
+        if self.commands.insert(name.to_owned(), Box::new(f)).is_some() {
+            warn!("console command `{name}` registered twice, keeping the latest");
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn print(&mut self, line: impl Into<String>) {
+        let line = line.into();
+        info!("[console] {line}");
+        self.lines.push(line);
+        let excess = self.lines.len().saturating_sub(self.max_lines.max(1) * 4);
+        self.lines.drain(..excess);
+    }
+
+    /// Feeds a key press. Returns `true` when the console consumed it.
+    pub fn handle_key(&mut self, code: Option<KeyCode>, key: &Key, text: Option<&str>) -> bool {
+        if code == Some(self.toggle_key) {
+            self.open = !self.open;
+            return true;
+        }
+        if !self.open {
+            return false;
+        }
+        match key {
+            Key::Named(NamedKey::Enter) => {
+                let line = std::mem::take(&mut self.input);
+                if !line.trim().is_empty() {
+                    self.submitted.push(line);
+                }
+            }
+            Key::Named(NamedKey::Backspace) => {
+                self.input.pop();
+            }
+            Key::Named(NamedKey::Escape) => self.open = false,
+            _ => {
+                if let Some(t) = text {
+                    self.input.extend(t.chars().filter(|c| !c.is_control()));
+                }
+            }
+        }
+        true
+    }
+
+    /// Runs every line submitted since the last call.
+    pub fn execute_pending(&mut self, ctx: &mut Ctx<'_>, scenes: &[(&'static str, TypeId)]) {
+        for line in std::mem::take(&mut self.submitted) {
+            self.print(format!("> {line}"));
+            let args: Vec<&str> = line.split_whitespace().collect();
+            let (name, args) = (args[0], &args[1..]);
+            let out = match name {
+                "help" => {
+                    let mut names: Vec<&str> = self.commands.keys().map(String::as_str).collect();
+                    names.sort_unstable();
+                    format!(
+                        "built-ins: help clear entities fps scenes scene | commands: {}",
+                        names.join(" ")
+                    )
+                }
+                "clear" => {
+                    self.lines.clear();
+                    String::new()
+                }
+                "entities" => format!(
+                    "{} sprites, {} panels",
+                    ctx.pool.entities.len(),
+                    ctx.pool.panels.len()
+                ),
+                "fps" => match ctx.resources.get::<FpsStats>() {
+                    Some(s) => format!("{:.1} fps ({:.2} ms)", s.fps, s.frame_ms),
+                    None => "FpsStats resource not registered".to_owned(),
+                },
+                "scenes" => scenes.iter().map(|(n, _)| *n).collect::<Vec<_>>().join(" "),
+                "scene" => match args.first() {
+                    Some(target) => match scenes.iter().find(|(n, _)| n == target) {
+                        Some((_, ty)) => {
+                            ctx.commands.scene_switch = Some(*ty);
+                            format!("switching to {target}")
+                        }
+                        None => format!("unknown scene `{target}`"),
+                    },
+                    None => "usage: scene <name>".to_owned(),
+                },
+                _ => match self.commands.get_mut(name) {
+                    Some(cmd) => cmd(args, ctx),
+                    None => format!("unknown command `{name}`, try `help`"),
+                },
+            };
+            for l in out.lines() {
+                self.print(l.to_owned());
+            }
+        }
+    }
+
+    /// Adds the glyphs of the visible text to the atlas and uploads it when it changed.
+    ///
+    /// Must run before the frame begins, re-uploading waits for the GPU.
+    pub fn prepare<B: Backend>(&mut self, renderer: &mut Renderer<B>) {
+        if self.atlas.is_none() {
+            let Some((font, size)) = &self.font else {
+                return;
+            };
+            self.atlas = Some(GlyphAtlas::new(font, *size));
+        }
+        let body = self.open.then(|| self.body());
+        let atlas = self.atlas.as_mut().expect("created above");
+        if let Some(body) = body {
+            atlas.prepare(&body);
+        }
+        if !atlas.finish_frame() {
+            return;
+        }
+        let (tex, w, h) = (atlas.tex, atlas.width, atlas.height);
+        if let Err(e) = renderer.update_texture(tex, w, h, atlas.pixels()) {
+            warn!("console: failed to upload glyph atlas: {e}");
+            self.font = None;
+            self.atlas = None;
+        }
+    }
+
+    /// The last [`Console::max_lines`] of output followed by the prompt.
+    fn body(&self) -> String {
+        let start = self.lines.len().saturating_sub(self.max_lines);
+        let mut body = self.lines[start..].join("\n");
+        body.push_str(&format!("\n> {}_", self.input));
+        body
+    }
+
+    /// Background panel and text, in screen-space pixels with y up.
+    pub fn draw_data(&self, screen: Vec2) -> (PanelInstance, Option<SpriteBatch>) {
+        let line_h = self.atlas.as_ref().map_or(16.0, |a| a.line_height());
+        let height = (self.max_lines as f32 + 1.5) * line_h;
+        let bg = Panel {
+            transform: Transform::from_xy(screen.x * 0.5, screen.y - height * 0.5),
+            size: Vec2::new(screen.x, height),
+            fill: Fill::Solid(self.background),
+            ..Default::default()
+        };
+
+        let text = self.atlas.as_ref().map(|atlas| {
+            let body = self.body();
+            let pad = 6.0;
+            SpriteBatch {
+                tex: atlas.tex,
+                blend: BlendMode::Alpha,
+                palette: None,
+                cutout: false,
+                material: MaterialId::DEFAULT,
+                baked: None,
+                retained: None,
+                instances: atlas.layout(&body, Vec2::new(pad, screen.y - pad)),
+            }
+        });
+        (bg.instance(), text)
+    }
+}
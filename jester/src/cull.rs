@@ -0,0 +1,129 @@
+use glam::Vec2;
+use jester_core::{Aabb, Bounds, Camera, Color, DebugDraw, EntityPool, InputState, SceneDraw};
+use winit::keyboard::KeyCode;
+
+/// Sprites and scene batch instances inside and outside the cameras' cull
+/// rects, counted while the [`CullOverlay`] is shown; insert it as a
+/// resource to have the App fill it in.
+///
+/// Every sprite counted was submitted for drawing: the App does not cull
+/// pool sprites, so off-screen ones still cost their share of the batches.
+/// Scenes cull what they render themselves, e.g. `Tilemap::batches` leaves
+/// out chunks outside the view, which then never reach the counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CullStats {
+    /// Pool sprites overlapping at least one camera's rect.
+    pub on_screen: usize,
+    /// Pool sprites outside every camera's rect.
+    pub off_screen: usize,
+    /// Instances of the batches from `Scene::render`, e.g. tilemap chunks,
+    /// overlapping at least one camera's rect.
+    pub batch_on_screen: usize,
+    pub batch_off_screen: usize,
+}
+
+impl CullStats {
+    pub fn total(&self) -> usize {
+        self.on_screen + self.off_screen + self.batch_on_screen + self.batch_off_screen
+    }
+}
+
+/// Debug view of what the cameras see, to check culling and chunk
+/// streaming, e.g. that `Tilemap::batches` or a `ChunkStreamer` leaves out
+/// what is far off screen.
+///
+/// While shown, every camera's cull rect is outlined and every sprite in
+/// the pool is outlined in `on_screen_color` or `off_screen_color`, whether
+/// its world bounds overlap any camera's rect or not. Rects are the camera
+/// views shrunk by `inset`, so what falls outside them stays on screen to
+/// be seen. Counts go to the [`CullStats`] resource; pool sprites are drawn
+/// either way, see there.
+#[derive(Debug)]
+pub struct CullOverlay {
+    pub enabled: bool,
+    pub toggle_key: KeyCode,
+    /// Fraction of each view's width and height cut off every side.
+    pub inset: f32,
+    pub rect_color: Color,
+    pub on_screen_color: Color,
+    pub off_screen_color: Color,
+}
+
+impl Default for CullOverlay {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            toggle_key: KeyCode::F3,
+            inset: 0.2,
+            rect_color: Color::rgb(1.0, 0.8, 0.1),
+            on_screen_color: Color::rgb(0.2, 1.0, 0.3),
+            off_screen_color: Color::rgb(1.0, 0.2, 0.2),
+        }
+    }
+}
+
+impl CullOverlay {
+    /// The world area `camera` keeps in a `screen` sized window.
+    pub fn cull_rect(&self, camera: &Camera, screen: Vec2) -> Aabb {
+        let view = camera.view_aabb(screen);
+        let cut = view.size() * self.inset.clamp(0.0, 0.49);
+        Aabb::new(view.min + cut, view.max - cut)
+    }
+
+    /// Toggles the overlay and, while shown, outlines the rects and sprites
+    /// into `out` and returns the counts.
+    pub fn update(
+        &mut self,
+        input: &InputState,
+        pool: &EntityPool,
+        cameras: &[Camera],
+        screen: Vec2,
+        draws: &[SceneDraw],
+        out: &mut DebugDraw,
+    ) -> Option<CullStats> {
+        if input.just_pressed(self.toggle_key) {
+            self.enabled = !self.enabled;
+        }
+        if !self.enabled {
+            return None;
+        }
+        let rects: Vec<Aabb> = cameras.iter().map(|c| self.cull_rect(c, screen)).collect();
+        for r in &rects {
+            out.rect(*r, self.rect_color);
+        }
+        let seen = |b: &Aabb| rects.iter().any(|r| r.intersects(b));
+
+        let mut stats = CullStats::default();
+        for s in pool.entities.values() {
+            let bounds = s.world_bounds();
+            let on_screen = seen(&bounds.aabb());
+            let color = match on_screen {
+                true => self.on_screen_color,
+                false => self.off_screen_color,
+            };
+            match bounds {
+                Bounds::Aabb(b) => out.rect(b, color),
+                Bounds::Obb(b) => out.polygon(&b.corners(), color),
+            };
+            match on_screen {
+                true => stats.on_screen += 1,
+                false => stats.off_screen += 1,
+            }
+        }
+        // too many to outline, tilemaps alone hold thousands
+        for d in draws {
+            let SceneDraw::Sprites(b) = d else { continue };
+            for i in &b.instances {
+                let [x, y, w, h] = i.pos_size;
+                match seen(&Aabb::from_center_size(
+                    Vec2::new(x, y),
+                    Vec2::new(w, h).abs(),
+                )) {
+                    true => stats.batch_on_screen += 1,
+                    false => stats.batch_off_screen += 1,
+                }
+            }
+        }
+        Some(stats)
+    }
+}
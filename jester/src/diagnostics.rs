@@ -0,0 +1,218 @@
+use std::{
+    collections::VecDeque,
+    fmt::Write as _,
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use jester_core::{AdapterInfo, Screenshot};
+use tracing::{
+    field::{Field, Visit},
+    Event, Subscriber,
+};
+use tracing_subscriber::layer::{Context, Layer};
+
+type ReportCallback = Box<dyn Fn(&Path) + Send + Sync>;
+
+/// Writes a bug-report bundle when the game panics.
+///
+/// A bundle is a directory holding `report.txt` (engine version, adapter,
+/// frame stats, recent log lines, the failure reason) and, when one was
+/// captured, `screenshot.png`. Add [`Diagnostics::log_layer`] to your
+/// `tracing` subscriber so reports include recent log lines.
+#[derive(Clone)]
+pub struct Diagnostics {
+    state: Arc<Mutex<DiagnosticsState>>,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct FrameInfo {
+    pub frame: u64,
+    pub dt: f32,
+    pub fps: f32,
+    pub entities: usize,
+    pub scene: &'static str,
+}
+
+struct DiagnosticsState {
+    dir: PathBuf,
+    max_log_lines: usize,
+    screenshot_interval: Option<Duration>,
+    logs: VecDeque<String>,
+    adapter: Option<AdapterInfo>,
+    frame: FrameInfo,
+    screenshot: Option<Screenshot>,
+    on_report: Option<ReportCallback>,
+}
+
+impl Diagnostics {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(DiagnosticsState {
+                dir: dir.into(),
+                max_log_lines: 200,
+                screenshot_interval: None,
+                logs: VecDeque::new(),
+                adapter: None,
+                frame: FrameInfo::default(),
+                screenshot: None,
+                on_report: None,
+            })),
+        }
+    }
+
+    pub fn with_max_log_lines(self, n: usize) -> Self {
+        self.lock().max_log_lines = n;
+        self
+    }
+
+    /// Periodically grab a frame so reports can include what was on screen.
+    pub fn with_screenshot_interval(self, every: Duration) -> Self {
+        self.lock().screenshot_interval = Some(every);
+        self
+    }
+
+    /// Called with the bundle directory after every report written.
+    pub fn on_report<F: Fn(&Path) + Send + Sync + 'static>(self, f: F) -> Self {
+        self.lock().on_report = Some(Box::new(f));
+        self
+    }
+
+    /// A `tracing` layer feeding the "last N log lines" section of reports.
+    pub fn log_layer(&self) -> LogCapture {
+        LogCapture {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Chains a panic hook that writes a report before the previous hook runs.
+    pub fn install_panic_hook(&self) {
+        let state = self.state.clone();
+        let prev = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            // try_lock: the panic may have happened while the state was held
+            if let Ok(mut s) = state.try_lock() {
+                let _ = s.write_report(&format!("panic: {info}"));
+            }
+            prev(info);
+        }));
+    }
+
+    /// Writes a report right now, e.g. after a recoverable device error.
+    pub fn write_report(&self, reason: &str) -> io::Result<PathBuf> {
+        self.lock().write_report(reason)
+    }
+
+    pub fn screenshot_interval(&self) -> Option<Duration> {
+        self.lock().screenshot_interval
+    }
+
+    pub fn set_adapter(&self, info: AdapterInfo) {
+        self.lock().adapter = Some(info);
+    }
+
+    pub fn set_frame(&self, frame: FrameInfo) {
+        self.lock().frame = frame;
+    }
+
+    pub fn set_screenshot(&self, shot: Screenshot) {
+        self.lock().screenshot = Some(shot);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DiagnosticsState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl DiagnosticsState {
+    fn write_report(&mut self, reason: &str) -> io::Result<PathBuf> {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let dir = self.dir.join(format!("report-{stamp}"));
+        std::fs::create_dir_all(&dir)?;
+
+        let mut out = String::new();
+        let _ = writeln!(out, "jester {}", env!("CARGO_PKG_VERSION"));
+        let _ = writeln!(
+            out,
+            "os: {} {}",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+        match &self.adapter {
+            Some(a) => {
+                let _ = writeln!(
+                    out,
+                    "adapter: {} ({} {}, driver {:#x})",
+                    a.name, a.backend, a.api_version, a.driver_version
+                );
+            }
+            None => out.push_str("adapter: <not initialized>\n"),
+        }
+        let f = &self.frame;
+        let _ = writeln!(
+            out,
+            "frame: {} dt: {:.2} ms fps: {:.1} entities: {} scene: {}",
+            f.frame,
+            f.dt * 1000.0,
+            f.fps,
+            f.entities,
+            f.scene
+        );
+        let _ = writeln!(
+            out,
+            "\nreason:\n{reason}\n\nlast {} log lines:",
+            self.logs.len()
+        );
+        for l in &self.logs {
+            out.push_str(l);
+            out.push('\n');
+        }
+        std::fs::write(dir.join("report.txt"), out)?;
+
+        if let Some(shot) = &self.screenshot {
+            let _ = shot.save_png(dir.join("screenshot.png"));
+        }
+        if let Some(cb) = &self.on_report {
+            cb(&dir);
+        }
+        Ok(dir)
+    }
+}
+
+/// See [`Diagnostics::log_layer`].
+pub struct LogCapture {
+    state: Arc<Mutex<DiagnosticsState>>,
+}
+
+struct MessageVisitor<'a>(&'a mut String);
+
+impl Visit for MessageVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, " {value:?}");
+        } else {
+            let _ = write!(self.0, " {}={value:?}", field.name());
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogCapture {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let meta = event.metadata();
+        let mut line = format!("{} {}:", meta.level(), meta.target());
+        event.record(&mut MessageVisitor(&mut line));
+
+        let Ok(mut s) = self.state.try_lock() else {
+            return;
+        };
+        s.logs.push_back(line);
+        while s.logs.len() > s.max_log_lines {
+            s.logs.pop_front();
+        }
+    }
+}
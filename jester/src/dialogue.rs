@@ -0,0 +1,200 @@
+use glam::Vec2;
+use jester_core::{
+    Backend, BlendMode, Color, DialogueRunner, DialogueStep, Events, Fill, Font, GamepadButton,
+    GlyphAtlas, InputState, MaterialId, Panel, PanelInstance, Renderer, SpriteBatch, Transform,
+};
+use tracing::warn;
+use winit::{event::MouseButton, keyboard::KeyCode};
+
+const DIGITS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// Shows the app's running dialogue in a box along the bottom of the window.
+///
+/// Enter, space, a click or the south gamepad button continue past a line or
+/// pick the selected choice; up and down (or the d-pad) move the selection and
+/// the digit keys pick a choice directly. Without a font the box stays hidden
+/// and the dialogue is left to the scenes.
+pub struct DialogueBox {
+    pub background: Color,
+    pub border_color: Color,
+    /// Lines of text the box has room for.
+    pub lines: usize,
+    /// Space around the box and between its border and the text, in pixels.
+    pub margin: f32,
+    font: Option<(Font, f32)>,
+    atlas: Option<GlyphAtlas>,
+    selected: usize,
+}
+
+impl Default for DialogueBox {
+    fn default() -> Self {
+        Self {
+            background: Color::rgba(0.02, 0.02, 0.05, 0.85),
+            border_color: Color::WHITE,
+            lines: 4,
+            margin: 16.0,
+            font: None,
+            atlas: None,
+            selected: 0,
+        }
+    }
+}
+
+impl DialogueBox {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_font(mut self, font: Font, size: f32) -> Self {
+        self.font = Some((font, size));
+        self
+    }
+
+    /// Feeds this frame's input to `runner`.
+    pub fn update(&mut self, runner: &mut DialogueRunner, input: &InputState, events: &mut Events) {
+        let confirm = input.just_pressed(KeyCode::Enter)
+            || input.just_pressed(KeyCode::NumpadEnter)
+            || input.just_pressed(KeyCode::Space)
+            || input.mouse_just_pressed(MouseButton::Left)
+            || input.pad_just_pressed(GamepadButton::South);
+        match runner.current() {
+            Some(DialogueStep::Line(_)) if confirm => runner.advance(events),
+            Some(DialogueStep::Choices(choices)) => {
+                let count = choices.len();
+                if input.just_pressed(KeyCode::ArrowUp)
+                    || input.pad_just_pressed(GamepadButton::DPadUp)
+                {
+                    self.selected = (self.selected + count - 1) % count;
+                }
+                if input.just_pressed(KeyCode::ArrowDown)
+                    || input.pad_just_pressed(GamepadButton::DPadDown)
+                {
+                    self.selected = (self.selected + 1) % count;
+                }
+                let picked = DIGITS[..count.min(DIGITS.len())]
+                    .iter()
+                    .position(|&k| input.just_pressed(k))
+                    .or(confirm.then_some(self.selected));
+                if let Some(i) = picked {
+                    self.selected = 0;
+                    runner.choose(i, events);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Adds the glyphs of the shown text to the atlas and uploads it when it changed.
+    ///
+    /// Must run before the frame begins, re-uploading waits for the GPU.
+    pub fn prepare<B: Backend>(
+        &mut self,
+        renderer: &mut Renderer<B>,
+        runner: &DialogueRunner,
+        screen: Vec2,
+    ) {
+        if self.atlas.is_none() {
+            let Some((font, size)) = &self.font else {
+                return;
+            };
+            self.atlas = Some(GlyphAtlas::new(font, *size));
+        }
+        let body = self.body(runner, screen);
+        let atlas = self.atlas.as_mut().expect("created above");
+        if let Some(body) = body {
+            atlas.prepare(&body);
+        }
+        if !atlas.finish_frame() {
+            return;
+        }
+        let (tex, w, h) = (atlas.tex, atlas.width, atlas.height);
+        if let Err(e) = renderer.update_texture(tex, w, h, atlas.pixels()) {
+            warn!("dialogue box: failed to upload glyph atlas: {e}");
+            self.font = None;
+            self.atlas = None;
+        }
+    }
+
+    /// Background panel and text, in screen-space pixels with y up; `None` when nothing is shown.
+    pub fn draw_data(
+        &self,
+        runner: &DialogueRunner,
+        screen: Vec2,
+    ) -> Option<(PanelInstance, SpriteBatch)> {
+        let atlas = self.atlas.as_ref()?;
+        let body = self.body(runner, screen)?;
+        let height = self.lines as f32 * atlas.line_height() + self.margin * 2.0;
+        let bg = Panel {
+            transform: Transform::from_xy(screen.x * 0.5, self.margin + height * 0.5),
+            size: Vec2::new(screen.x - self.margin * 2.0, height),
+            fill: Fill::Solid(self.background),
+            corner_radius: 6.0,
+            border_width: 2.0,
+            border_color: self.border_color,
+            layer: 0,
+        };
+        let top_left = Vec2::new(self.margin * 2.0, self.margin + height - self.margin);
+        let text = SpriteBatch {
+            tex: atlas.tex,
+            blend: BlendMode::Alpha,
+            palette: None,
+            cutout: false,
+            material: MaterialId::DEFAULT,
+            baked: None,
+            retained: None,
+            instances: atlas.layout(&body, top_left),
+        };
+        Some((bg.instance(), text))
+    }
+
+    /// The text shown for the current step, wrapped to the box.
+    fn body(&self, runner: &DialogueRunner, screen: Vec2) -> Option<String> {
+        let atlas = self.atlas.as_ref()?;
+        let width = screen.x - self.margin * 4.0;
+        let lines = match runner.current()? {
+            DialogueStep::Line(line) => {
+                let mut lines: Vec<String> = line.speaker.iter().cloned().collect();
+                lines.extend(wrap(atlas, &line.text, width));
+                lines
+            }
+            DialogueStep::Choices(choices) => choices
+                .iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    let marker = if i == self.selected { '>' } else { ' ' };
+                    format!("{marker} {}. {c}", i + 1)
+                })
+                .collect(),
+        };
+        Some(lines.join("\n"))
+    }
+}
+
+/// Breaks `text` at spaces so no line is wider than `width`.
+fn wrap(atlas: &GlyphAtlas, text: &str, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split(' ') {
+        let candidate = match line.is_empty() {
+            true => word.to_owned(),
+            false => format!("{line} {word}"),
+        };
+        if !line.is_empty() && atlas.measure(&candidate).x > width {
+            lines.push(std::mem::replace(&mut line, word.to_owned()));
+        } else {
+            line = candidate;
+        }
+    }
+    lines.push(line);
+    lines
+}
@@ -0,0 +1,173 @@
+use glam::Vec2;
+use jester_core::{
+    Aabb, Camera, Color, Commands, EntityId, EntityPool, Fill, InputState, Panel, PanelInstance,
+    Transform, MAX_PANELS,
+};
+use winit::{event::MouseButton, keyboard::KeyCode};
+
+/// In-engine editing overlay: world grid, click to select, drag to move.
+///
+/// Edits are emitted as [`Commands::moves`] so they go through the same apply
+/// phase as scene-issued changes.
+#[derive(Debug)]
+pub struct Editor {
+    pub enabled: bool,
+    pub toggle_key: KeyCode,
+    pub grid_size: f32,
+    pub snap: bool,
+    pub grid_color: Color,
+    pub selection_color: Color,
+    selected: Option<EntityId>,
+    drag_offset: Option<Vec2>,
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            toggle_key: KeyCode::F1,
+            grid_size: 32.0,
+            snap: true,
+            grid_color: Color::rgba(1.0, 1.0, 1.0, 0.15),
+            selection_color: Color::rgb(1.0, 0.8, 0.1),
+            selected: None,
+            drag_offset: None,
+        }
+    }
+}
+
+impl Editor {
+    pub fn selected(&self) -> Option<EntityId> {
+        self.selected
+    }
+
+    pub fn snap_to_grid(&self, p: Vec2) -> Vec2 {
+        if !self.snap || self.grid_size <= 0.0 {
+            return p;
+        }
+        (p / self.grid_size).round() * self.grid_size
+    }
+
+    pub fn update(
+        &mut self,
+        input: &InputState,
+        pool: &EntityPool,
+        camera: &Camera,
+        screen: Vec2,
+        cmds: &mut Commands,
+    ) {
+        if input.just_pressed(self.toggle_key) {
+            self.enabled = !self.enabled;
+            self.drag_offset = None;
+        }
+        if !self.enabled {
+            return;
+        }
+
+        let cursor = camera.window_to_world(input.mouse_pos(), screen);
+
+        if input.mouse_just_pressed(MouseButton::Left) {
+            self.selected = pick(pool, cursor);
+            self.drag_offset = self
+                .selected
+                .and_then(|id| entity_bounds(pool, id))
+                .map(|(center, _)| center - cursor);
+        }
+        if input.mouse_just_released(MouseButton::Left) {
+            self.drag_offset = None;
+        }
+
+        if let (Some(id), Some(offset)) = (self.selected, self.drag_offset) {
+            let target = self.snap_to_grid(cursor + offset);
+            if entity_bounds(pool, id).is_some_and(|(center, _)| center != target) {
+                cmds.moves.push((id, target));
+            }
+        }
+    }
+
+    /// Grid lines and selection outline for the current frame.
+    pub fn overlay(&self, pool: &EntityPool, camera: &Camera, screen: Vec2) -> Vec<PanelInstance> {
+        let mut out = Vec::new();
+        if !self.enabled {
+            return out;
+        }
+
+        let a = camera.window_to_world(Vec2::new(0.0, screen.y), screen);
+        let b = camera.window_to_world(Vec2::new(screen.x, 0.0), screen);
+        let (min, max) = (a.min(b), a.max(b));
+        let thickness = 1.0 / camera.scale().x;
+
+        let step = self.grid_size;
+        let lines = ((max - min) / step).ceil();
+        if step > 0.0 && (lines.x + lines.y) as usize <= MAX_PANELS / 2 {
+            let line = |pos: Vec2, size: Vec2| {
+                Panel {
+                    transform: Transform::from_xy(pos.x, pos.y),
+                    size,
+                    fill: Fill::Solid(self.grid_color),
+                    ..Default::default()
+                }
+                .instance()
+            };
+            let mid = (min + max) * 0.5;
+            let mut x = (min.x / step).floor() * step;
+            while x <= max.x {
+                out.push(line(
+                    Vec2::new(x, mid.y),
+                    Vec2::new(thickness, max.y - min.y),
+                ));
+                x += step;
+            }
+            let mut y = (min.y / step).floor() * step;
+            while y <= max.y {
+                out.push(line(
+                    Vec2::new(mid.x, y),
+                    Vec2::new(max.x - min.x, thickness),
+                ));
+                y += step;
+            }
+        }
+
+        if let Some((center, size)) = self.selected.and_then(|id| entity_bounds(pool, id)) {
+            let outline = Panel {
+                transform: Transform::from_xy(center.x, center.y),
+                size: size + Vec2::splat(4.0 * thickness),
+                fill: Fill::Solid(Color::TRANSPARENT),
+                border_width: 2.0 * thickness,
+                border_color: self.selection_color,
+                ..Default::default()
+            };
+            out.push(outline.instance());
+        }
+        out
+    }
+}
+
+/// Center and full extents of a sprite or panel.
+fn entity_bounds(pool: &EntityPool, id: EntityId) -> Option<(Vec2, Vec2)> {
+    if let Some(s) = pool.entities.get(&id) {
+        let size = s.size.unwrap_or(Vec2::ONE) * s.transform.scale;
+        return Some((s.transform.translation, size));
+    }
+    pool.panels
+        .get(&id)
+        .map(|p| (p.transform.translation, p.size * p.transform.scale))
+}
+
+/// Smallest entity under `cursor`, so small props win over big backgrounds.
+fn pick(pool: &EntityPool, cursor: Vec2) -> Option<EntityId> {
+    pool.entities
+        .keys()
+        .chain(pool.panels.keys())
+        .filter_map(|&id| {
+            let (center, size) = entity_bounds(pool, id)?;
+            let hit = match pool.entities.get(&id) {
+                // rotated sprites are hit inside their turned box only
+                Some(s) => s.world_bounds().contains(cursor),
+                None => Aabb::from_center_size(center, size.abs()).contains(cursor),
+            };
+            hit.then_some((id, (size.x * size.y).abs()))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(id, _)| id)
+}
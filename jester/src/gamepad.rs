@@ -0,0 +1,161 @@
+//! Gamepad input through gilrs, merged into the `InputState` every scene sees
+//! and kept per pad in `InputDevices`.
+//!
+//! On Linux gilrs needs `libudev`, hence the opt-in `gamepad` feature.
+use std::time::{Duration, Instant};
+
+use gilrs::ff::{BaseEffect, BaseEffectType, Effect, EffectBuilder, Repeat, Replay, Ticks};
+use gilrs::{Axis, Button, EventType, GamepadId, Gilrs};
+use jester_core::{GamepadAxis, GamepadButton};
+
+use crate::replay::InputEvent;
+use tracing::{info, warn};
+
+pub(crate) struct Gamepads {
+    gilrs: Gilrs,
+    // dropping an effect stops it, so each is kept with its pads until it ends
+    rumbles: Vec<(Effect, Vec<GamepadId>, Instant)>,
+}
+
+impl Gamepads {
+    /// `None` when the platform backend fails, games then run keyboard and mouse only.
+    pub fn new() -> Option<Self> {
+        match Gilrs::new() {
+            Ok(gilrs) => Some(Self {
+                gilrs,
+                rumbles: Vec::new(),
+            }),
+            Err(e) => {
+                warn!("Gamepad support unavailable: {e}");
+                None
+            }
+        }
+    }
+
+    /// Collects the events queued since the last frame, for the app to apply.
+    pub fn poll(&mut self, events: &mut Vec<InputEvent>) {
+        let now = Instant::now();
+        self.rumbles.retain(|(_, _, end)| *end > now);
+        while let Some(ev) = self.gilrs.next_event() {
+            let d = pad_index(ev.id);
+            let event = match ev.event {
+                EventType::ButtonPressed(b, _) => {
+                    button(b).map(|b| InputEvent::PadButton(d, b, true))
+                }
+                EventType::ButtonReleased(b, _) => {
+                    button(b).map(|b| InputEvent::PadButton(d, b, false))
+                }
+                // analog triggers report through button values
+                EventType::ButtonChanged(Button::LeftTrigger2, v, _) => {
+                    Some(InputEvent::PadAxis(d, GamepadAxis::LeftTrigger, v))
+                }
+                EventType::ButtonChanged(Button::RightTrigger2, v, _) => {
+                    Some(InputEvent::PadAxis(d, GamepadAxis::RightTrigger, v))
+                }
+                EventType::AxisChanged(a, v, _) => axis(a).map(|a| InputEvent::PadAxis(d, a, v)),
+                EventType::Connected => {
+                    let pad = self.gilrs.gamepad(ev.id);
+                    info!("Gamepad connected: {}", pad.name());
+                    Some(InputEvent::PadConnected(d))
+                }
+                EventType::Disconnected => {
+                    info!("Gamepad {} disconnected", ev.id);
+                    Some(InputEvent::PadDisconnected(d))
+                }
+                _ => None,
+            };
+            events.extend(event);
+        }
+    }
+
+    /// Rumbles the pads `pads`, or every pad when empty, at `strength` from
+    /// 0 to 1 for `duration` seconds. Strength 0 stops those pads instead.
+    pub fn rumble(&mut self, pads: &[u32], strength: f32, duration: f32) {
+        let ids: Vec<GamepadId> = self
+            .gilrs
+            .gamepads()
+            .filter(|(id, pad)| {
+                pad.is_ff_supported() && (pads.is_empty() || pads.contains(&pad_index(*id)))
+            })
+            .map(|(id, _)| id)
+            .collect();
+        if ids.is_empty() {
+            return;
+        }
+        // a new effect replaces what those pads were playing
+        self.rumbles
+            .retain(|(_, playing, _)| !playing.iter().any(|id| ids.contains(id)));
+        if strength <= 0.0 || duration <= 0.0 {
+            return;
+        }
+        let magnitude = (strength.min(1.0) * u16::MAX as f32) as u16;
+        let ticks = Ticks::from_ms((duration * 1000.0) as u32);
+        let scheduling = Replay {
+            play_for: ticks,
+            ..Default::default()
+        };
+        let effect = EffectBuilder::new()
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Strong { magnitude },
+                scheduling,
+                ..Default::default()
+            })
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Weak { magnitude },
+                scheduling,
+                ..Default::default()
+            })
+            .gamepads(&ids)
+            .repeat(Repeat::For(ticks))
+            .finish(&mut self.gilrs)
+            .and_then(|effect| effect.play().map(|_| effect));
+        match effect {
+            Ok(effect) => {
+                let end = Instant::now() + Duration::from_secs_f32(duration);
+                self.rumbles.push((effect, ids, end));
+            }
+            Err(e) => warn!("Failed to rumble gamepad: {e}"),
+        }
+    }
+
+    /// Stops every rumble, e.g. when the window loses focus.
+    pub fn stop_rumble(&mut self) {
+        self.rumbles.clear();
+    }
+}
+
+fn pad_index(id: GamepadId) -> u32 {
+    usize::from(id) as u32
+}
+
+fn button(b: Button) -> Option<GamepadButton> {
+    Some(match b {
+        Button::South => GamepadButton::South,
+        Button::East => GamepadButton::East,
+        Button::West => GamepadButton::West,
+        Button::North => GamepadButton::North,
+        Button::DPadUp => GamepadButton::DPadUp,
+        Button::DPadDown => GamepadButton::DPadDown,
+        Button::DPadLeft => GamepadButton::DPadLeft,
+        Button::DPadRight => GamepadButton::DPadRight,
+        Button::LeftTrigger => GamepadButton::LeftShoulder,
+        Button::RightTrigger => GamepadButton::RightShoulder,
+        Button::LeftTrigger2 => GamepadButton::LeftTrigger,
+        Button::RightTrigger2 => GamepadButton::RightTrigger,
+        Button::LeftThumb => GamepadButton::LeftThumb,
+        Button::RightThumb => GamepadButton::RightThumb,
+        Button::Select => GamepadButton::Select,
+        Button::Start => GamepadButton::Start,
+        _ => return None,
+    })
+}
+
+fn axis(a: Axis) -> Option<GamepadAxis> {
+    Some(match a {
+        Axis::LeftStickX => GamepadAxis::LeftStickX,
+        Axis::LeftStickY => GamepadAxis::LeftStickY,
+        Axis::RightStickX => GamepadAxis::RightStickX,
+        Axis::RightStickY => GamepadAxis::RightStickY,
+        _ => return None,
+    })
+}
@@ -0,0 +1,209 @@
+//! Hot-reloadable scene logic for development builds.
+//!
+//! Game logic lives in a `dylib` crate built with the same toolchain as the game
+//! and exports plain Rust functions:
+//!
+//! ```text
+//! #[unsafe(no_mangle)] pub fn jester_hot_start(ctx: &mut Ctx<'_>)     // optional
+//! #[unsafe(no_mangle)] pub fn jester_hot_update(ctx: &mut Ctx<'_>)
+//! #[unsafe(no_mangle)] pub fn jester_hot_reloaded(ctx: &mut Ctx<'_>)  // optional
+//! ```
+//!
+//! [`HotScene`] reloads the library whenever the file changes while the App,
+//! renderer and `EntityPool` keep running. Keep state in the pool or in
+//! resources of jester's own types: anything whose code lives in the old
+//! library (closures, trait objects, its own types) is invalid after a reload.
+use std::{
+    path::{Path, PathBuf},
+    process::{Child, Command},
+    time::SystemTime,
+};
+
+use jester_core::{Ctx, Scene};
+use libloading::Library;
+use tracing::{info, warn};
+
+type HotFn = fn(&mut Ctx<'_>);
+
+/// Seconds between checks of the watched source tree.
+const POLL_INTERVAL: f32 = 0.5;
+
+struct Rebuild {
+    src: PathBuf,
+    args: Vec<String>,
+    last_change: Option<SystemTime>,
+    child: Option<Child>,
+    since_poll: f32,
+}
+
+pub struct HotScene {
+    path: PathBuf,
+    lib: Option<Library>,
+    // the copy `lib` was loaded from, and older ones the OS kept locked
+    copy: Option<PathBuf>,
+    stale: Vec<PathBuf>,
+    loaded_mtime: Option<SystemTime>,
+    generation: u32,
+    rebuild: Option<Rebuild>,
+}
+
+impl HotScene {
+    /// Runs the logic in the dynamic library at `path`, e.g. `target/debug/libgame.so`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lib: None,
+            copy: None,
+            stale: Vec::new(),
+            loaded_mtime: None,
+            generation: 0,
+            rebuild: None,
+        }
+    }
+
+    /// Runs `cargo <args>` whenever a file under `src` changes, e.g.
+    /// `("game/src", ["build", "-p", "game"])`, so saving a file is enough to reload.
+    pub fn with_rebuild<I, S>(mut self, src: impl Into<PathBuf>, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let src = src.into();
+        self.rebuild = Some(Rebuild {
+            last_change: newest_mtime(&src),
+            src,
+            args: args.into_iter().map(Into::into).collect(),
+            child: None,
+            since_poll: 0.0,
+        });
+        self
+    }
+
+    /// How many times the library has been (re)loaded.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    fn reload(&mut self) -> bool {
+        let Some(mtime) = mtime(&self.path) else {
+            return false;
+        };
+        if self.loaded_mtime == Some(mtime) {
+            return false;
+        }
+        // load a copy so the build can overwrite the original, and the OS does not
+        // hand back the already mapped library for the same path
+        let ext = self.path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let copy = std::env::temp_dir().join(format!(
+            "jester-hot-{}-{}.{ext}",
+            std::process::id(),
+            self.generation
+        ));
+        if let Err(e) = std::fs::copy(&self.path, &copy) {
+            // usually the linker is still writing, try again next frame
+            warn!("Failed to copy {}: {e}", self.path.display());
+            return false;
+        }
+        match unsafe { Library::new(&copy) } {
+            Ok(lib) => {
+                // unloads the previous library, so its copy can go
+                self.lib = Some(lib);
+                self.stale.extend(self.copy.replace(copy));
+                self.stale.retain(|old| std::fs::remove_file(old).is_err());
+                self.loaded_mtime = Some(mtime);
+                self.generation += 1;
+                info!(
+                    "Loaded {} (generation {})",
+                    self.path.display(),
+                    self.generation
+                );
+                true
+            }
+            Err(e) => {
+                warn!("Failed to load {}: {e}", self.path.display());
+                std::fs::remove_file(&copy).ok();
+                false
+            }
+        }
+    }
+
+    fn call(&self, symbol: &[u8], ctx: &mut Ctx<'_>) {
+        let Some(lib) = &self.lib else {
+            return;
+        };
+        if let Ok(f) = unsafe { lib.get::<HotFn>(symbol) } {
+            f(ctx);
+        }
+    }
+
+    fn poll_rebuild(&mut self, dt: f32) {
+        let Some(r) = &mut self.rebuild else {
+            return;
+        };
+        if let Some(child) = &mut r.child {
+            match child.try_wait() {
+                Ok(Some(status)) if !status.success() => warn!("cargo {} failed", r.args.join(" ")),
+                Ok(None) => return,
+                _ => {}
+            }
+            r.child = None;
+        }
+        r.since_poll += dt;
+        if r.since_poll < POLL_INTERVAL {
+            return;
+        }
+        r.since_poll = 0.0;
+        let newest = newest_mtime(&r.src);
+        if newest > r.last_change {
+            r.last_change = newest;
+            info!("Sources changed, running cargo {}", r.args.join(" "));
+            match Command::new("cargo").args(&r.args).spawn() {
+                Ok(child) => r.child = Some(child),
+                Err(e) => warn!("Failed to run cargo: {e}"),
+            }
+        }
+    }
+}
+
+impl Drop for HotScene {
+    fn drop(&mut self) {
+        self.lib = None;
+        for copy in self.copy.take().into_iter().chain(self.stale.drain(..)) {
+            std::fs::remove_file(copy).ok();
+        }
+    }
+}
+
+impl Scene for HotScene {
+    fn start(&mut self, ctx: &mut Ctx<'_>) {
+        self.reload();
+        self.call(b"jester_hot_start\0", ctx);
+    }
+
+    fn update(&mut self, ctx: &mut Ctx<'_>) {
+        self.poll_rebuild(ctx.dt);
+        let building = self.rebuild.as_ref().is_some_and(|r| r.child.is_some());
+        if !building && self.reload() {
+            self.call(b"jester_hot_reloaded\0", ctx);
+        }
+        self.call(b"jester_hot_update\0", ctx);
+    }
+}
+
+fn mtime(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn newest_mtime(dir: &Path) -> Option<SystemTime> {
+    let mut newest = mtime(dir);
+    for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let t = if path.is_dir() {
+            newest_mtime(&path)
+        } else {
+            mtime(&path)
+        };
+        newest = newest.max(t);
+    }
+    newest
+}
@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use jester_core::Error;
+
+/// Overrides given on the command line, applied with `App::apply_launch_options`.
+///
+/// Flags it does not know are kept in [`LaunchOptions::rest`] for the game
+/// to read its own.
+///
+/// ```ignore
+/// let opts = LaunchOptions::from_env().unwrap_or_else(|e| {
+///     eprintln!("{e}\n{}", LaunchOptions::USAGE);
+///     std::process::exit(2)
+/// });
+/// let mut app = App::new("game".into());
+/// app.add_scene(Menu::default());
+/// app.add_scene(Level::default());
+/// app.apply_launch_options(&opts)?;
+/// app.run()
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LaunchOptions {
+    /// `--fullscreen` or `--windowed`.
+    pub fullscreen: Option<bool>,
+    /// `--width W`.
+    pub width: Option<u32>,
+    /// `--height H`.
+    pub height: Option<u32>,
+    /// `--vsync on|off`.
+    pub vsync: Option<bool>,
+    /// `--scene Name`: the scene to start in, by type name.
+    pub scene: Option<String>,
+    /// `--record-input FILE`: writes every frame's input, see `App::record_input`.
+    pub record_input: Option<PathBuf>,
+    /// `--replay-input FILE`: plays a recording back instead of live input.
+    pub replay_input: Option<PathBuf>,
+    /// Arguments not recognized above, in order.
+    pub rest: Vec<String>,
+}
+
+impl LaunchOptions {
+    pub const USAGE: &str = "options: [--windowed | --fullscreen] [--width W] [--height H] \
+         [--vsync on|off] [--scene NAME] [--record-input FILE] [--replay-input FILE]";
+
+    /// Parses the arguments the process was started with.
+    pub fn from_env() -> Result<Self, Error> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    /// Parses `args`, without the program name.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, Error> {
+        let mut opts = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| Error::Launch(format!("{arg} needs a value")))
+            };
+            match arg.as_str() {
+                "--windowed" => opts.fullscreen = Some(false),
+                "--fullscreen" => opts.fullscreen = Some(true),
+                "--width" => opts.width = Some(number(&arg, &value()?)?),
+                "--height" => opts.height = Some(number(&arg, &value()?)?),
+                "--vsync" => opts.vsync = Some(switch(&arg, &value()?)?),
+                "--scene" => opts.scene = Some(value()?),
+                "--record-input" => opts.record_input = Some(PathBuf::from(value()?)),
+                "--replay-input" => opts.replay_input = Some(PathBuf::from(value()?)),
+                _ => opts.rest.push(arg),
+            }
+        }
+        Ok(opts)
+    }
+}
+
+fn number(flag: &str, v: &str) -> Result<u32, Error> {
+    v.parse()
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or_else(|| Error::Launch(format!("{flag}: `{v}` is not a positive number")))
+}
+
+fn switch(flag: &str, v: &str) -> Result<bool, Error> {
+    match v {
+        "on" | "true" | "1" => Ok(true),
+        "off" | "false" | "0" => Ok(false),
+        _ => Err(Error::Launch(format!(
+            "{flag}: expected on or off, got `{v}`"
+        ))),
+    }
+}
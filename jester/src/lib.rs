@@ -1,46 +1,167 @@
+#[cfg(all(feature = "null", not(feature = "vulkan")))]
+pub use b_null::NullBackend as DefaultBackend;
 #[cfg(feature = "vulkan")]
 pub use b_vk::VkBackend as DefaultBackend;
 use glam::Vec2;
 use hashbrown::HashMap;
 use jester_core::{
-    Camera, Commands, Ctx, EntityPool, Error, InputState, Renderer, Resources, Scene, SceneKey,
-    SpriteBatch, SpriteInstance,
+    Accessibility, AlphaSorting, AnimationFinished, AssetStates, AxisCurves, BarFill, BatchStats,
+    Camera, CameraDirector, Color, ColorVision, Commands, Ctx, DayNight, DebugDraw, DialogueRunner,
+    DisplayCalibration, EntityId, EntityPool, Error, Events, Fill, FrameValidator, ImportCache,
+    InputDevice, InputDevices, InputState, MaterialId, Minimap, Monitors, Panel, PanelInstance,
+    PresentMode, PresentStats, Progress, QualitySettings, RenderCtx, RenderStats, Renderer,
+    Resources, RetainedBatch, ScalePolicy, Scene, SceneDraw, SceneKey, ScreenFilter, SortMode,
+    SplitScreen, Sprite, SpriteBatch, SpriteGroup, SpriteInstance, TextureId, Transform,
+    TriggerTracker, UiFocus, UiLayout, UiNode, WorldBounds,
+};
+use std::{
+    any::TypeId,
+    path::Path,
+    time::{Duration, Instant},
 };
-use std::{any::TypeId, time::Instant};
 use tracing::{info, warn};
 use winit::{
     application::ApplicationHandler,
-    event::{ElementState, WindowEvent},
+    event::{ElementState, MouseScrollDelta, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
     keyboard::PhysicalKey,
-    window::Window,
 };
 
-use self::fps::FpsStats;
+use self::{
+    console::Console,
+    cull::{CullOverlay, CullStats},
+    diagnostics::{Diagnostics, FrameInfo},
+    dialogue::DialogueBox,
+    editor::Editor,
+    fps::FpsStats,
+    launch::LaunchOptions,
+    photo::PhotoMode,
+    quality::AdaptiveQuality,
+    record::FrameRecorder,
+    replay::{InputEvent, InputRecorder, InputReplay},
+    settings::Settings,
+    text::TextAtlases,
+    window::WindowConfig,
+};
 
+#[cfg(feature = "accesskit")]
+mod access;
+mod console;
+mod cull;
+mod diagnostics;
+mod dialogue;
+mod editor;
 mod fps;
+#[cfg(feature = "gamepad")]
+mod gamepad;
+#[cfg(feature = "hot-reload")]
+mod hot;
+mod launch;
+mod photo;
+#[cfg(feature = "plugins")]
+mod plugin;
+mod quality;
+mod record;
+mod replay;
+mod settings;
+#[cfg(feature = "steam")]
+mod steam;
+mod streaming;
+mod text;
 mod timer;
+mod window;
 
 pub mod prelude {
     pub use super::App;
+    #[cfg(feature = "hot-reload")]
+    pub use crate::hot::HotScene;
+    #[cfg(feature = "plugins")]
+    pub use crate::plugin::{HostApi, PluginCtx, PluginSystem, Plugins, PLUGIN_ABI_VERSION};
+    #[cfg(feature = "steam")]
+    pub use crate::steam::Steam;
     pub use crate::{
+        console::Console,
+        cull::{CullOverlay, CullStats},
+        diagnostics::Diagnostics,
+        dialogue::DialogueBox,
+        editor::Editor,
         fps::FpsStats,
+        launch::LaunchOptions,
+        photo::PhotoMode,
+        quality::AdaptiveQuality,
+        record::{FrameRecorder, RecordOutput},
+        settings::{config_dir, Migration, Settings},
+        streaming::{Chunk, ChunkSource, ChunkSprite, ChunkStreamer},
         timer::{Timer, TimerMode},
+        window::WindowConfig,
     };
     pub use glam::Vec2;
     pub use jester_core::{
-        Backend, Camera, Commands, Ctx, EntityId, Renderer, Scene, Sprite, SpriteBatch, Transform,
+        Aabb, Accessibility, Achievement, AddressMode, Align, AlphaSorting, Anchor, Animation,
+        AnimationFinished, AsepriteSheet, AssetError, AssetState, AssetStates, AxisCurve,
+        AxisCurves, Backend, BarFill, BatchStats, BlendMode, Bounds, ButtonState, Camera,
+        CameraDirector, CharacterController, Chord, Collider, ColliderKind, Colliders, Color,
+        ColorBlindness, ColorVision, Commands, Controls, Cooldowns, Ctx, DayNight, DayNightEvent,
+        DebugDraw, Dialogue, DialogueEvent, DialogueRunner, DialogueStep, Direction,
+        DisplayCalibration, Ease, EdgeMode, Edges, EntityId, Events, Fill, Flock, Font,
+        FrameValidator, GamepadAxis, GamepadButton, GlobalTransform, Hierarchy, ImportCache,
+        InputDevice, InputDevices, InputTimings, Inventory, ItemStack, Justify, KeySet, Length,
+        LineCap, LineJoin, LoadPriority, LocalTransform, LoopMode, MapMarker, MaterialId,
+        MemoryStats, Mesh, MeshBatch, MeshVertex, Minimap, Modifier, ModifierKind, Modifiers,
+        MonitorInfo, MonitorSelect, Monitors, MsdfAtlas, Obb, OnActivate, Palette, Panel,
+        PlayerInput, Polyline, PresentMode, PresentStats, Progress, ProgressEvent, ProgressState,
+        QualityPreset, QualitySettings, RenderCtx, RenderStats, Renderer, ResidencyStats,
+        RetainedBatch, Retry, Rope, RopeEnd, SamplerDesc, ScalePolicy, Scene, SceneDraw,
+        ScreenFilter, SegmentHit, ShortcutConflict, Shortcuts, SortMode, SplitLayout, SplitPlayer,
+        SplitScreen, Sprite, SpriteBatch, SpriteGroup, SpriteStore, StackRules, Stats, Steering,
+        Text, TextStyle, TextureAtlas, TextureFilter, TextureFormat, TextureId, TileLayer, Tilemap,
+        Transform, Trigger, TriggerEvent, UiEvent, UiFocus, UiLayout, UiNode, UiRect,
+        UnlockCondition, ValidationIssue, Velocity, Viewport, Wander, WindowPlacement, WorldBounds,
     };
     pub use winit::keyboard::KeyCode;
 }
 
 type Result<T> = std::result::Result<T, Error>;
 
+/// How long the window size must stay unchanged before scenes see [`Scene::resized`].
+const RESIZE_SETTLE: Duration = Duration::from_millis(150);
+/// Window size when launch options give only one of width and height and no size is configured.
+const DEFAULT_WINDOW_SIZE: (u32, u32) = (1280, 720);
+/// Smaller batches are cheaper to upload whole than to keep a buffer for.
+const RETAIN_MIN_SPRITES: usize = 64;
+
 pub struct App {
     app_name: String,
     win: Option<winit::window::Window>,
+    /// Canvas size of an app made with [`App::headless`], which has no window.
+    headless: Option<Vec2>,
+    window_config: WindowConfig,
     renderer: Option<Renderer<DefaultBackend>>,
     batches: Vec<SpriteBatch>,
+    /// Submitted by the active scene's `Scene::render` this frame.
+    scene_draws: Vec<SceneDraw>,
+    /// Batches of `RenderCtx::draw_in_layer`, sorted in with the pool's sprites.
+    layered_draws: Vec<(i32, SpriteBatch)>,
+    /// Render targets the world is drawn into every frame, see `Ctx::render_to_texture`.
+    render_views: Vec<(TextureId, Camera)>,
+    text: TextAtlases,
+    /// Shapes from `Ctx::debug_draw`, kept while paused.
+    debug_draw: DebugDraw,
+    /// The white texel debug lines sample, created when first needed.
+    debug_white: Option<TextureId>,
+    validator: Option<FrameValidator>,
+    panels: Vec<PanelInstance>,
+    ui: Option<UiNode>,
+    /// Layout of `ui` for the window size it was computed at, `None` once stale.
+    ui_layout: Option<UiLayout>,
+    ui_panels: Vec<PanelInstance>,
+    ui_focus: UiFocus,
+    events: Events,
+    triggers: TriggerTracker,
+    dialogue: DialogueRunner,
+    dialogue_box: Option<DialogueBox>,
+    /// The renderer's asset states as of the last frame, for `ctx.assets`.
+    assets: AssetStates,
     pending: Vec<Job>,
     cameras: Vec<Camera>,
 
@@ -51,7 +172,43 @@ pub struct App {
     scenes: Vec<SceneSlot>,
     resources: Resources,
     input_state: InputState,
+    devices: InputDevices,
+    // winit ids of the keyboards seen so far, indexed by `InputDevice::Keyboard`
+    keyboards: Vec<winit::event::DeviceId>,
     pool: EntityPool,
+    editor: Option<Editor>,
+    cull_overlay: Option<CullOverlay>,
+    console: Option<Console>,
+    photo_mode: Option<PhotoMode>,
+    /// Quality last handed to the renderer, to apply `QualitySettings` only when it changes.
+    applied_quality: Option<QualitySettings>,
+    recorder: Option<FrameRecorder>,
+    input_recorder: Option<InputRecorder>,
+    input_replay: Option<InputReplay>,
+    present_mode: PresentMode,
+    /// Materials added with [`App::add_material`].
+    materials: u32,
+    /// Start scene by name from the launch options, looked up once `run` is called.
+    launch_scene: Option<String>,
+    scene_names: Vec<(&'static str, TypeId)>,
+    diagnostics: Option<Diagnostics>,
+    frame: u64,
+    since_screenshot: f32,
+    layer_sort: HashMap<i32, SortMode>,
+    alpha_sorting: AlphaSorting,
+    memory_budget: Option<u64>,
+    over_budget: bool,
+    texture_budget: Option<u64>,
+    import_cache: Option<ImportCache>,
+    resize_settle: Option<(Vec2, Instant)>,
+    quit: bool,
+    elapsed: f32,
+    #[cfg(feature = "plugins")]
+    plugins: Option<plugin::Plugins>,
+    #[cfg(feature = "gamepad")]
+    gamepads: Option<gamepad::Gamepads>,
+    #[cfg(feature = "accesskit")]
+    screen_reader: Option<access::ScreenReader>,
 }
 
 impl App {
@@ -59,8 +216,27 @@ impl App {
         Self {
             app_name,
             win: None,
+            headless: None,
+            window_config: WindowConfig::default(),
             renderer: None,
             batches: Vec::new(),
+            scene_draws: Vec::new(),
+            layered_draws: Vec::new(),
+            render_views: Vec::new(),
+            text: TextAtlases::default(),
+            debug_draw: DebugDraw::default(),
+            debug_white: None,
+            validator: cfg!(debug_assertions).then(FrameValidator::new),
+            panels: Vec::new(),
+            ui: None,
+            ui_layout: None,
+            ui_panels: Vec::new(),
+            ui_focus: UiFocus::default(),
+            events: Events::default(),
+            triggers: TriggerTracker::default(),
+            dialogue: DialogueRunner::default(),
+            dialogue_box: None,
+            assets: AssetStates::default(),
             pending: Vec::new(),
             cameras: Vec::new(),
             active_scene: SceneKey::new(usize::MAX),
@@ -71,7 +247,276 @@ impl App {
             pool: EntityPool::default(),
             scene_lookup: HashMap::new(),
             input_state: InputState::default(),
+            devices: InputDevices::default(),
+            keyboards: Vec::new(),
+            editor: None,
+            cull_overlay: None,
+            console: None,
+            photo_mode: None,
+            applied_quality: None,
+            recorder: None,
+            input_recorder: None,
+            input_replay: None,
+            present_mode: PresentMode::default(),
+            materials: 0,
+            launch_scene: None,
+            scene_names: Vec::new(),
+            diagnostics: None,
+            frame: 0,
+            since_screenshot: 0.0,
+            layer_sort: HashMap::new(),
+            alpha_sorting: AlphaSorting::Painter,
+            memory_budget: None,
+            over_budget: false,
+            texture_budget: None,
+            import_cache: None,
+            resize_settle: None,
+            quit: false,
+            elapsed: 0.0,
+            #[cfg(feature = "plugins")]
+            plugins: None,
+            #[cfg(feature = "gamepad")]
+            gamepads: gamepad::Gamepads::new(),
+            #[cfg(feature = "accesskit")]
+            screen_reader: None,
+        }
+    }
+
+    /// An app drawing with `backend` rather than into a window, e.g. to run
+    /// scenes in tests or on CI machines without a GPU. Nothing happens until
+    /// [`App::step`], which replaces [`App::run`].
+    ///
+    /// ```ignore
+    /// let mut app = App::headless("test".into(), NullBackend::new(640, 480));
+    /// app.add_scene(Level::default());
+    /// app.set_start_scene::<Level>();
+    /// while app.step(1.0 / 60.0) && app.frame_count() < 600 {}
+    /// ```
+    #[cfg(all(feature = "null", not(feature = "vulkan")))]
+    pub fn headless(app_name: String, backend: DefaultBackend) -> Self {
+        let size = backend.size();
+        let mut app = Self::new(app_name);
+        app.headless = Some(Vec2::new(size.width as f32, size.height as f32));
+        app.renderer = Some(Renderer::from_backend(backend));
+        app
+    }
+
+    /// Runs one frame of `dt` seconds of an app made with [`App::headless`]:
+    /// the active scene's start and update, its commands, batching and
+    /// drawing. False once a scene quit or without an active scene.
+    pub fn step(&mut self, dt: f32) -> bool {
+        let Some(screen) = self.headless else {
+            warn!("App::step needs an app made with App::headless");
+            return false;
+        };
+        self.pick_launch_scene();
+        self.frame(screen, Some(dt)) && !self.quit
+    }
+
+    /// Frames drawn so far.
+    pub fn frame_count(&self) -> u64 {
+        self.frame
+    }
+
+    /// The renderer, `None` before the window exists.
+    pub fn renderer(&self) -> Option<&Renderer<DefaultBackend>> {
+        self.renderer.as_ref()
+    }
+
+    /// Window options used when the window is created.
+    pub fn set_window_config(&mut self, config: WindowConfig) {
+        self.window_config = config;
+    }
+
+    /// Chooses how sprites on `layer` are ordered, [`SortMode::Batched`] by default.
+    pub fn set_layer_sort(&mut self, layer: i32, mode: SortMode) {
+        self.layer_sort.insert(layer, mode);
+    }
+
+    /// Checks every frame for broken sprites and batches, see
+    /// [`FrameValidator`]; on by default in debug builds.
+    pub fn set_validation(&mut self, enabled: bool) {
+        self.validator = enabled.then(FrameValidator::new);
+    }
+
+    /// Chooses how cutout and blended sprites are ordered, [`AlphaSorting::Painter`] by default.
+    pub fn set_alpha_sorting(&mut self, sorting: AlphaSorting) {
+        self.alpha_sorting = sorting;
+    }
+
+    /// Soft limit on GPU memory in bytes; crossing it calls [`Scene::memory_pressure`].
+    pub fn set_memory_budget(&mut self, bytes: Option<u64>) {
+        self.memory_budget = bytes;
+    }
+
+    /// Streams textures loaded from files within `bytes`, see [`Renderer::set_texture_budget`].
+    pub fn set_texture_budget(&mut self, bytes: Option<u64>) {
+        self.texture_budget = bytes;
+        if let Some(r) = &mut self.renderer {
+            r.set_texture_budget(bytes);
+        }
+    }
+
+    /// Loads image files through `cache` rather than decoding them every
+    /// launch, see [`ImportCache`].
+    pub fn set_import_cache(&mut self, cache: Option<ImportCache>) {
+        self.import_cache = cache.clone();
+        if let Some(r) = &mut self.renderer {
+            r.set_import_cache(cache);
+        }
+    }
+
+    /// Runs the systems registered by dynamically loaded plugins every frame.
+    #[cfg(feature = "plugins")]
+    pub fn enable_plugins(&mut self, plugins: plugin::Plugins) {
+        self.plugins = Some(plugins);
+    }
+
+    /// Makes a Steamworks session available as a resource and pumps its callbacks every frame.
+    #[cfg(feature = "steam")]
+    pub fn enable_steam(&mut self, steam: steam::Steam) {
+        self.resources.insert(steam);
+    }
+
+    /// Writes a diagnostics bundle on panic, see [`Diagnostics`].
+    pub fn enable_diagnostics(&mut self, diagnostics: Diagnostics) {
+        diagnostics.install_panic_hook();
+        if let Some(r) = &self.renderer {
+            diagnostics.set_adapter(r.adapter_info());
         }
+        self.diagnostics = Some(diagnostics);
+    }
+
+    /// Enables the drop-down debug console (toggled with [`Console::toggle_key`]).
+    pub fn enable_console(&mut self, console: Console) {
+        self.console = Some(console);
+    }
+
+    /// Shows the running dialogue and lets the player step through it, see [`DialogueBox`].
+    pub fn enable_dialogue_box(&mut self, dialogue_box: DialogueBox) {
+        self.dialogue_box = Some(dialogue_box);
+    }
+
+    /// Enables the editor overlay (toggled in-game with [`Editor::toggle_key`]).
+    pub fn enable_editor(&mut self, editor: Editor) {
+        self.editor = Some(editor);
+    }
+
+    /// Enables the culling overlay (toggled with [`CullOverlay::toggle_key`]).
+    pub fn enable_cull_overlay(&mut self, overlay: CullOverlay) {
+        self.cull_overlay = Some(overlay);
+    }
+
+    /// Enables the free camera for screenshots (toggled with [`PhotoMode::toggle_key`]).
+    pub fn enable_photo_mode(&mut self, photo_mode: PhotoMode) {
+        self.photo_mode = Some(photo_mode);
+    }
+
+    /// Renders offline at the recorder's fixed rate, writing every frame out, see [`FrameRecorder`].
+    pub fn record_frames(&mut self, recorder: FrameRecorder) {
+        self.recorder = Some(recorder);
+    }
+
+    /// Writes every frame's dt and input to `path` for [`App::replay_input`], e.g. for bug repros.
+    pub fn record_input(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        self.input_recorder = Some(InputRecorder::create(path.as_ref())?);
+        Ok(())
+    }
+
+    /// Runs on the dt and input recorded with [`App::record_input`] instead of
+    /// live input until the recording ends; per-device input replays too.
+    pub fn replay_input(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        self.input_replay = Some(InputReplay::load(path.as_ref())?);
+        Ok(())
+    }
+
+    /// Caps frames at the display's refresh rate when on; off by default.
+    pub fn set_vsync(&mut self, vsync: bool) {
+        self.set_present_mode(match vsync {
+            true => PresentMode::Fifo,
+            false => PresentMode::Mailbox,
+        });
+    }
+
+    /// How frames are presented, [`PresentMode::Mailbox`] by default;
+    /// scenes change it with `Ctx::set_present_mode`.
+    pub fn set_present_mode(&mut self, mode: PresentMode) {
+        self.present_mode = mode;
+        if let Some(r) = &mut self.renderer {
+            r.set_present_mode(mode);
+        }
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        self.present_mode
+    }
+
+    /// Adds a sprite fragment shader from SPIR-V, see [`Backend::create_material`](jester_core::Backend::create_material)
+    /// for what it gets; sprites and batches with the returned id as `material` draw with it.
+    ///
+    /// ```ignore
+    /// let dissolve = app.add_material(include_bytes!("dissolve.frag.spv").to_vec());
+    /// // in a scene, with the threshold in `custom[0]`
+    /// ctx.sprite_mut(id).unwrap().material = dissolve;
+    /// ```
+    pub fn add_material(&mut self, spirv: impl Into<Vec<u8>>) -> MaterialId {
+        // backends number materials in the order they are created, failed ones included
+        let id = MaterialId(MaterialId::BUILTIN.len() as u32 + self.materials);
+        self.materials += 1;
+        let spirv = spirv.into();
+        let create = move |app: &mut App| {
+            let r = app.renderer.as_mut().unwrap();
+            match r.create_material(&spirv) {
+                Ok(got) if got != id => warn!("Material {id:?} was created as {got:?}"),
+                Ok(_) => {}
+                Err(e) => warn!("Failed to create material {id:?}: {e}"),
+            }
+        };
+        match self.renderer {
+            Some(_) => create(self),
+            None => self.pending.push(Box::new(create)),
+        }
+        id
+    }
+
+    /// Applies command-line overrides, call it after adding scenes and setting the window config.
+    pub fn apply_launch_options(&mut self, opts: &LaunchOptions) -> Result<()> {
+        if let Some(fullscreen) = opts.fullscreen {
+            self.window_config.fullscreen = fullscreen;
+        }
+        if opts.width.is_some() || opts.height.is_some() {
+            let (w, h) = self.window_config.size.unwrap_or(DEFAULT_WINDOW_SIZE);
+            self.window_config.size = Some((opts.width.unwrap_or(w), opts.height.unwrap_or(h)));
+        }
+        if let Some(vsync) = opts.vsync {
+            self.set_vsync(vsync);
+        }
+        if let Some(scene) = &opts.scene {
+            self.launch_scene = Some(scene.clone());
+        }
+        if let Some(path) = &opts.record_input {
+            self.record_input(path)?;
+        }
+        if let Some(path) = &opts.replay_input {
+            self.replay_input(path)?;
+        }
+        Ok(())
+    }
+
+    /// Applies saved options: the window size, fullscreen and present mode, and the
+    /// quality as the [`QualitySettings`] resource. The settings become a
+    /// resource too, for the game to read volumes and keybinds and to save
+    /// them from its options menu.
+    ///
+    /// Call it before [`App::apply_launch_options`] so the command line wins.
+    pub fn apply_settings(&mut self, settings: Settings) {
+        if settings.window_size.is_some() {
+            self.window_config.size = settings.window_size;
+        }
+        self.window_config.fullscreen = settings.fullscreen;
+        self.set_present_mode(settings.present_mode);
+        self.resources.insert(settings.quality);
+        self.resources.insert(settings);
     }
 
     /// Explicitly mark which scene type should start first.
@@ -99,6 +544,9 @@ impl App {
         let key = SceneKey::new(self.scenes.len());
 
         self.scene_lookup.insert(TypeId::of::<S>(), key);
+        let name = std::any::type_name::<S>();
+        let name = name.rsplit("::").next().unwrap_or(name);
+        self.scene_names.push((name, TypeId::of::<S>()));
 
         self.scenes.push(SceneSlot {
             scene: Box::new(scene),
@@ -110,14 +558,99 @@ impl App {
         }
     }
 
+    fn finish_recording(&mut self) {
+        if let Some(mut rec) = self.recorder.take()
+            && let Err(e) = rec.finish()
+        {
+            warn!("Recording failed: {e}");
+        }
+    }
+
+    /// Applies one input change to the merged and per-device state, recording it if asked to.
+    fn apply_input(&mut self, ev: InputEvent) {
+        if let Some(rec) = &mut self.input_recorder {
+            rec.push(ev);
+        }
+        match ev {
+            InputEvent::Key(d, k, down) => {
+                self.input_state.set_key_down(k, down);
+                self.devices.set_key_down(InputDevice::Keyboard(d), k, down);
+            }
+            InputEvent::Mouse(b, down) => self.input_state.set_mouse_btn(b, down),
+            InputEvent::Cursor(pos) => self.input_state.set_mouse_pos(pos),
+            InputEvent::Scroll(lines) => self.input_state.add_scroll(lines),
+            InputEvent::PadButton(d, b, down) => {
+                self.input_state.set_pad_button(b, down);
+                self.devices
+                    .set_pad_button(InputDevice::Gamepad(d), b, down);
+            }
+            InputEvent::PadAxis(d, a, v) => {
+                self.input_state.set_pad_axis(a, v);
+                self.devices.set_pad_axis(InputDevice::Gamepad(d), a, v);
+            }
+            InputEvent::PadConnected(d) => self.devices.connect(InputDevice::Gamepad(d)),
+            InputEvent::PadDisconnected(d) => self.devices.remove(InputDevice::Gamepad(d)),
+        }
+    }
+
     fn apply_commands(&mut self, mut cmds: Commands) {
-        for (tex_id, p) in cmds.assets_to_load.drain(..) {
+        for tex_id in cmds.assets_to_unload.drain(..) {
+            self.render_views.retain(|(t, _)| *t != tex_id);
             if let Some(r) = &mut self.renderer {
-                let _ = r.load_texture_sync(tex_id, &p);
+                r.unload_texture(tex_id);
+            }
+        }
+        if let Some(r) = &mut self.renderer {
+            for (tex_id, desc) in cmds.samplers.drain(..) {
+                r.set_sampler(tex_id, desc);
+            }
+        }
+        for (tex_id, p) in cmds.assets_to_load.drain(..) {
+            if let Some(r) = &mut self.renderer
+                && let Err(e) = r.load_texture_sync(tex_id, &p)
+            {
+                warn!("{e}");
+                self.events.send(e);
+            }
+        }
+        for (tex_id, name, bytes) in cmds.assets_from_bytes.drain(..) {
+            if let Some(r) = &mut self.renderer
+                && let Err(e) = r.load_texture_from_bytes(tex_id, bytes)
+            {
+                warn!("Failed to load embedded {name}: {e}");
+            }
+        }
+        if let Some(r) = &mut self.renderer {
+            for (tex_id, p, priority) in cmds.assets_to_prefetch.drain(..) {
+                r.prefetch_texture(tex_id, p, priority);
+            }
+        }
+        for (tex_id, w, h, pixels) in cmds.textures_to_create.drain(..) {
+            let Some(r) = &mut self.renderer else {
+                continue;
+            };
+            if let Err(e) = r.upload_texture(tex_id, w, h, &pixels) {
+                warn!("Failed to create texture {w}x{h}: {e}");
+            }
+        }
+        for (tex_id, w, h) in cmds.render_targets_to_create.drain(..) {
+            let Some(r) = &mut self.renderer else {
+                continue;
+            };
+            if let Err(e) = r.add_render_target(tex_id, w, h) {
+                warn!("Failed to create render target {w}x{h}: {e}");
+            }
+        }
+        for (tex_id, camera) in cmds.render_views.drain(..) {
+            self.render_views.retain(|(t, _)| *t != tex_id);
+            if let Some(camera) = camera {
+                self.render_views.push((tex_id, camera));
             }
         }
         for (id, mut s) in cmds.sprites_to_spawn.drain(..) {
-            if let Some(renderer) = &mut self.renderer {
+            if let Some(renderer) = &mut self.renderer
+                && s.size.is_none()
+            {
                 let meta = renderer.texture_meta(s.tex);
                 if let Some(meta) = meta {
                     s.size = Some(Vec2::new(meta.w as f32, meta.h as f32));
@@ -125,14 +658,129 @@ impl App {
             }
             self.pool.entities.insert(id, s);
         }
+        for (id, p) in cmds.panels_to_spawn.drain(..) {
+            self.pool.panels.insert(id, p);
+        }
+        for (id, t) in cmds.texts_to_spawn.drain(..) {
+            self.pool.texts.insert(id, t);
+        }
 
-        for c in cmds.cameras_to_spawn.drain(..) {
+        for (id, t) in cmds.triggers_to_spawn.drain(..) {
+            self.pool.triggers.insert(id, t);
+        }
+        self.pool
+            .trigger_watchers
+            .extend(cmds.trigger_watchers.drain(..));
+
+        for id in cmds.despawn.drain(..) {
+            self.pool.entities.remove(&id);
+            self.pool.panels.remove(&id);
+            self.pool.texts.remove(&id);
+            self.pool.triggers.remove(&id);
+            self.pool.trigger_watchers.remove(&id);
+            self.pool.velocities.remove(&id);
+            self.pool.animations.remove(&id);
+            self.pool.hierarchy.remove(id);
+        }
+
+        for (id, pos) in cmds.moves.drain(..) {
+            if let Some(s) = self.pool.entities.get_mut(&id) {
+                s.transform.translation = pos;
+            } else if let Some(p) = self.pool.panels.get_mut(&id) {
+                p.transform.translation = pos;
+            } else if let Some(t) = self.pool.texts.get_mut(&id) {
+                t.top_left = pos;
+            }
+        }
+
+        for mut c in cmds.cameras_to_spawn.drain(..) {
+            if c.policy != ScalePolicy::PixelPerfect
+                && let Some(win) = &self.win
+            {
+                let size = win.inner_size();
+                c.resize(Vec2::new(size.width as f32, size.height as f32));
+            }
             self.cameras.push(c);
         }
+        if let Some(r) = &mut self.renderer {
+            for (materials, blend_modes) in cmds.precompile.drain(..) {
+                r.precompile(&materials, &blend_modes);
+            }
+        }
+        for (device, player) in cmds.device_assignments.drain(..) {
+            self.devices.assign(device, player);
+        }
+        #[cfg(feature = "gamepad")]
+        if let Some(gamepads) = &mut self.gamepads {
+            for (player, strength, duration) in cmds.rumbles.drain(..) {
+                let pads: Vec<u32> = self
+                    .devices
+                    .devices_of(player)
+                    .filter_map(|d| match d {
+                        InputDevice::Gamepad(p) => Some(p),
+                        InputDevice::Keyboard(_) => None,
+                    })
+                    .collect();
+                gamepads.rumble(&pads, strength, duration);
+            }
+        }
+        cmds.rumbles.clear();
+        self.debug_draw.append(&mut cmds.debug_draw);
+        for (idx, filter) in cmds.camera_filters.drain(..) {
+            match self.cameras.get_mut(idx) {
+                Some(c) => c.filter = filter,
+                None => warn!("set_camera_filter({idx}): no such camera"),
+            }
+        }
+        let screen = match &self.win {
+            Some(w) => Vec2::new(w.inner_size().width as f32, w.inner_size().height as f32),
+            None => self.headless.unwrap_or_default(),
+        };
+        for (idx, zoom, duration, anchor) in cmds.camera_zooms.drain(..) {
+            match (self.cameras.get_mut(idx), anchor) {
+                (Some(c), Some(pt)) => c.zoom_to_about(zoom, duration, pt, screen),
+                (Some(c), None) => c.zoom_to(zoom, duration),
+                (None, _) => warn!("zoom_camera({idx}): no such camera"),
+            }
+        }
+
+        if let Some(ui) = cmds.ui.take() {
+            self.ui = ui;
+            self.ui_layout = None;
+        }
+        self.events.append(&mut cmds.events);
+        for cmd in cmds.dialogue.drain(..) {
+            self.dialogue.apply(cmd, &mut self.events);
+        }
+        if let Some(name) = cmds.ui_focus.take() {
+            self.ui_focus.set_focus(Some(name));
+        }
+
+        self.quit |= cmds.quit;
+        if let Some(mode) = cmds.present_mode.take() {
+            self.set_present_mode(mode);
+        }
+
+        if let (Some(p), Some(win)) = (cmds.window_placement.take(), &self.win) {
+            p.apply(win);
+            self.resources.insert(Monitors::query(win));
+        }
 
         if let Some(target_type) = cmds.scene_switch.take() {
             if let Some(&key) = self.scene_lookup.get(&target_type) {
                 self.pool.entities.clear();
+                self.pool.panels.clear();
+                self.pool.texts.clear();
+                self.pool.triggers.clear();
+                self.pool.trigger_watchers.clear();
+                self.pool.velocities.clear();
+                self.pool.animations.clear();
+                self.pool.hierarchy.clear();
+                self.render_views.clear();
+                self.triggers.clear();
+                self.dialogue.stop(&mut self.events);
+                self.ui = None;
+                self.ui_layout = None;
                 self.scenes[*key].must_start = true;
                 self.active_scene = key;
             } else {
@@ -140,40 +788,885 @@ impl App {
             }
         }
     }
+    /// Runs one frame for a window or canvas of size `screen`: input, the
+    /// active scene's start and update, commands, batching and drawing.
+    /// `dt` overrides the measured frame time. False without an active scene.
+    fn frame(&mut self, screen: Vec2, dt: Option<f32>) -> bool {
+        let now = Instant::now();
+        let replayed = self.input_replay.as_mut().and_then(InputReplay::next_frame);
+        if replayed.is_none() && self.input_replay.take().is_some() {
+            info!("Input replay finished");
+        }
+        self.dt = match (dt, &replayed, &self.recorder) {
+            (Some(dt), _, _) => dt,
+            (None, Some((dt, _)), _) => *dt,
+            (None, None, Some(rec)) => rec.dt(),
+            (None, None, None) => (now - self.prev).as_secs_f32(),
+        };
+        self.prev = now;
+        self.elapsed += self.dt;
+
+        if let Some(s) = self.resources.get_mut::<FpsStats>() {
+            s.tick(self.dt);
+        }
+        #[cfg(feature = "steam")]
+        if let Some(s) = self.resources.get_mut::<steam::Steam>() {
+            s.run_callbacks();
+        }
+        #[cfg(feature = "gamepad")]
+        if let Some(pads) = &mut self.gamepads {
+            let mut events = Vec::new();
+            pads.poll(&mut events);
+            if self.input_replay.is_none() {
+                for ev in events {
+                    self.apply_input(ev);
+                }
+            }
+        }
+        for ev in replayed.map(|(_, events)| events).unwrap_or_default() {
+            self.apply_input(ev);
+        }
+        if let Some(rec) = &mut self.input_recorder
+            && let Err(e) = rec.frame(self.dt)
+        {
+            warn!("Input recording failed: {e}");
+            self.input_recorder = None;
+        }
+        if let Some(a) = self.resources.get::<Accessibility>() {
+            self.input_state.timings = a.input;
+        }
+        if let Some(c) = self.resources.get::<AxisCurves>() {
+            self.input_state.axis_curves = *c;
+        }
+        self.input_state.tick(self.dt);
+        let s = &self.input_state;
+        self.devices.tick(s.timings, s.axis_curves, self.dt);
+        #[cfg(feature = "accesskit")]
+        self.apply_screen_reader_requests();
+        let focused = self.ui_focus.focused().map(str::to_owned);
+        if let Some(layout) = &self.ui_layout {
+            self.ui_focus
+                .update(layout, &self.input_state, &mut self.events);
+        }
+        if let Some(a) = self.resources.get_mut::<Accessibility>()
+            && a.announce_focus
+            && let (Some(name), Some(layout)) = (self.ui_focus.focused(), &self.ui_layout)
+            && focused.as_deref() != Some(name)
+        {
+            a.announce(layout.label(name).unwrap_or(name));
+        }
+        let photo_capture = self
+            .photo_mode
+            .as_mut()
+            .is_some_and(|p| p.update(&self.input_state, &self.cameras, screen, self.dt));
+        // photo mode pauses the simulation
+        let paused = self.photo_mode.as_ref().is_some_and(PhotoMode::is_active);
+        if let Some(b) = &mut self.dialogue_box
+            && !paused
+        {
+            b.update(&mut self.dialogue, &self.input_state, &mut self.events);
+        }
+        self.events.begin_frame();
+        if let Some(p) = self.resources.get_mut::<Progress>() {
+            p.update(&mut self.events);
+        }
+        if !paused {
+            for c in &mut self.cameras {
+                c.tick(self.dt);
+            }
+        }
+        if let Some(split) = self.resources.get_mut::<SplitScreen>()
+            && !paused
+        {
+            split.update(&self.input_state, &self.devices, &self.pool, screen);
+            self.cameras.clear();
+            self.cameras.extend(split.cameras());
+        }
+        if let Some(d) = self.resources.get_mut::<CameraDirector>()
+            && !paused
+            && let Some(cam) = d.update(self.dt, screen)
+        {
+            match self.cameras.first_mut() {
+                Some(c) => *c = cam,
+                None => self.cameras.push(cam),
+            }
+        }
+        if let Some(d) = self.resources.get_mut::<DayNight>()
+            && !paused
+        {
+            d.update(self.dt, &mut self.events);
+        }
+        #[cfg(feature = "steam")]
+        if let Some(ids) = self
+            .resources
+            .get::<Progress>()
+            .map(|p| p.just_unlocked().to_vec())
+            && let Some(s) = self.resources.get_mut::<steam::Steam>()
+        {
+            for id in &ids {
+                s.unlock_achievement(id);
+            }
+        }
+        if let Some(q) = self.resources.get_mut::<AdaptiveQuality>()
+            && q.tick(self.dt)
+        {
+            info!(
+                "Adaptive quality level {:.2} ({:.2} ms avg)",
+                q.level(),
+                q.smoothed_frame_ms()
+            );
+        }
+        if let (Some(s), Some(r)) = (self.resources.get_mut::<BatchStats>(), &self.renderer) {
+            *s = r.batch_stats();
+        }
+        let memory = self
+            .renderer
+            .as_ref()
+            .map(|r| r.memory_stats())
+            .unwrap_or_default();
+        if let (Some(s), Some(r)) = (self.resources.get_mut::<RenderStats>(), &self.renderer) {
+            *s = RenderStats {
+                batches: r.batch_stats(),
+                memory,
+                memory_budget: self.memory_budget,
+            };
+        }
+        // reported once per crossing, scenes that cannot free enough are not nagged every frame
+        let over_budget = self.memory_budget.is_some_and(|b| memory.total() > b);
+        let memory_pressure = over_budget && !self.over_budget;
+        self.over_budget = over_budget;
+        if let (Some(s), Some(r)) = (self.resources.get_mut::<PresentStats>(), &self.renderer)
+            && let Some(stats) = r.present_stats()
+        {
+            *s = stats;
+        }
+        if let Some(r) = &self.renderer {
+            self.assets.clone_from(r.asset_states());
+        }
+
+        if *self.active_scene == usize::MAX {
+            warn!("No active scene");
+            if let Some(r) = &mut self.renderer {
+                r.begin_frame();
+                r.end_frame();
+            }
+            return false;
+        }
+        if !paused {
+            let slot = &mut self.scenes[*self.active_scene];
+            if slot.must_start {
+                let mut startup_cmds = Commands::default();
+                let mut ctx = Ctx {
+                    dt: 0.0,
+                    resources: &mut self.resources,
+                    commands: &mut startup_cmds,
+                    pool: &mut self.pool,
+                    input: &self.input_state,
+                    devices: &self.devices,
+                    cameras: &self.cameras,
+                    ui: &self.ui_focus,
+                    events: &self.events,
+                    dialogue: &self.dialogue,
+                    assets: &self.assets,
+                    screen_pos: screen,
+                };
+                slot.scene.start(&mut ctx);
+                slot.must_start = false;
+                self.apply_commands(startup_cmds);
+            }
+        }
+
+        let mut cmds = Commands::default();
+        if !paused {
+            let slot = &mut self.scenes[*self.active_scene];
+            let mut ctx = Ctx {
+                screen_pos: screen,
+                dt: self.dt,
+                resources: &mut self.resources,
+                commands: &mut cmds,
+                pool: &mut self.pool,
+                input: &self.input_state,
+                devices: &self.devices,
+                cameras: &self.cameras,
+                ui: &self.ui_focus,
+                events: &self.events,
+                dialogue: &self.dialogue,
+                assets: &self.assets,
+            };
+            if let Some((size, at)) = self.resize_settle
+                && at.elapsed() >= RESIZE_SETTLE
+            {
+                self.resize_settle = None;
+                slot.scene.resized(&mut ctx, size);
+            }
+            if memory_pressure {
+                slot.scene.memory_pressure(&mut ctx, &memory);
+            }
+            slot.scene.update(&mut ctx);
+        }
+        #[cfg(feature = "plugins")]
+        if let Some(plugins) = &mut self.plugins
+            && !paused
+        {
+            let mut ctx = Ctx {
+                screen_pos: screen,
+                dt: self.dt,
+                resources: &mut self.resources,
+                commands: &mut cmds,
+                pool: &mut self.pool,
+                input: &self.input_state,
+                devices: &self.devices,
+                cameras: &self.cameras,
+                ui: &self.ui_focus,
+                events: &self.events,
+                dialogue: &self.dialogue,
+                assets: &self.assets,
+            };
+            plugins.update(&mut ctx);
+        }
+        if let Some(console) = &mut self.console {
+            let mut ctx = Ctx {
+                screen_pos: screen,
+                dt: self.dt,
+                resources: &mut self.resources,
+                commands: &mut cmds,
+                pool: &mut self.pool,
+                input: &self.input_state,
+                devices: &self.devices,
+                cameras: &self.cameras,
+                ui: &self.ui_focus,
+                events: &self.events,
+                dialogue: &self.dialogue,
+                assets: &self.assets,
+            };
+            console.execute_pending(&mut ctx, &self.scene_names);
+        }
+        if let (Some(ed), Some(cam)) = (&mut self.editor, self.cameras.first())
+            && !paused
+        {
+            ed.update(&self.input_state, &self.pool, cam, screen, &mut cmds);
+        }
+        self.apply_commands(cmds);
+        if !paused {
+            for (id, v) in &self.pool.velocities {
+                if let Some(s) = self.pool.entities.get_mut(id) {
+                    s.transform.translation += v.0 * self.dt;
+                }
+            }
+            for (id, a) in &mut self.pool.animations {
+                if a.tick(self.dt) {
+                    self.events.send(AnimationFinished(*id));
+                }
+                // only touched on a new frame, so the sprite keeps its cached batch
+                if let Some(uv) = a.uv()
+                    && self.pool.entities.get(id).is_some_and(|s| s.uv != uv)
+                    && let Some(s) = self.pool.entities.get_mut(id)
+                {
+                    s.uv = uv;
+                }
+            }
+        }
+        // also while paused, so children follow parents dragged in the editor
+        self.pool.hierarchy.propagate(&mut self.pool.entities);
+        if !paused {
+            if let Some(b) = self.resources.get_mut::<WorldBounds>() {
+                b.confine_entities(&mut self.pool);
+            }
+            self.triggers.update(&self.pool, &mut self.events);
+        }
+
+        if let Some(b) = self.resources.get::<WorldBounds>() {
+            for c in &mut self.cameras {
+                b.confine_camera(c, screen);
+            }
+        }
+        self.scene_draws.clear();
+        self.layered_draws.clear();
+        let mut ctx = RenderCtx {
+            dt: self.dt,
+            resources: &self.resources,
+            pool: &self.pool,
+            screen_pos: screen,
+            cameras: &self.cameras,
+            draws: &mut self.scene_draws,
+            layered: &mut self.layered_draws,
+        };
+        self.scenes[*self.active_scene].scene.render(&mut ctx);
+        // after rendering, batches sort in the layered draws
+        self.rebuild_batches();
+        self.layout_ui(screen);
+        if let (Some(ed), Some(cam)) = (&self.editor, self.cameras.first())
+            && !paused
+        {
+            self.panels.extend(ed.overlay(&self.pool, cam, screen));
+        }
+        // debug shapes stay while paused, drawing again would double them
+        if let Some(o) = &mut self.cull_overlay
+            && !paused
+        {
+            let stats = o.update(
+                &self.input_state,
+                &self.pool,
+                &self.cameras,
+                screen,
+                &self.scene_draws,
+                &mut self.debug_draw,
+            );
+            if let (Some(s), Some(stats)) = (self.resources.get_mut::<CullStats>(), stats) {
+                *s = stats;
+            }
+        }
+
+        let r = self.renderer.as_mut().expect("renderer is live");
+
+        if let Some(b) = &mut self.dialogue_box {
+            b.prepare(r, &self.dialogue, screen);
+        }
+        if let Some(console) = &mut self.console {
+            console.prepare(r);
+        }
+        // glyphs are only laid out once prepared
+        self.text.prepare(r, &self.pool);
+        self.batches.extend(self.text.batches(&self.pool));
+        if let Some(v) = &mut self.validator {
+            let scene_batches = self.scene_draws.iter().filter_map(|d| match d {
+                SceneDraw::Sprites(b) => Some(b),
+                SceneDraw::Mesh(_) => None,
+            });
+            let batches = self.batches.iter().chain(scene_batches);
+            for issue in v.check(r, &self.pool, batches, self.cameras.len()) {
+                warn!("{issue}");
+                self.events.send(issue);
+            }
+        }
+        if !self.debug_draw.is_empty() && self.debug_white.is_none() {
+            match r.create_texture_from_rgba(1, 1, &[255; 4]) {
+                Ok(tex) => self.debug_white = Some(tex),
+                Err(e) => warn!("failed to create the debug draw texture: {e}"),
+            }
+        }
+        // before the frame starts, changing the filter rewrites descriptors
+        let quality = self.resources.get::<QualitySettings>().copied();
+        if let Some(q) = quality
+            && self.applied_quality != quality
+        {
+            let prev = self.applied_quality.replace(q);
+            if prev.is_none_or(|p| p.texture_filter != q.texture_filter) {
+                r.set_texture_filter(q.texture_filter);
+            }
+        }
+        r.begin_frame();
+        r.set_time(self.elapsed);
+        r.set_calibration(
+            self.resources
+                .get::<DisplayCalibration>()
+                .copied()
+                .unwrap_or_default(),
+        );
+        r.set_tint(
+            self.resources
+                .get::<DayNight>()
+                .map_or(Color::WHITE, DayNight::tint),
+        );
+
+        for (target, cam) in &self.render_views {
+            r.set_render_target(Some(*target));
+            r.bind_camera(cam);
+            // a target cannot be sampled while drawn into
+            for batch in self.batches.iter().filter(|b| b.tex != *target) {
+                r.draw_sprites(batch);
+            }
+            for draw in &self.scene_draws {
+                match draw {
+                    SceneDraw::Sprites(b) if b.tex == *target => {}
+                    d => draw_scene(r, std::slice::from_ref(d)),
+                }
+            }
+        }
+        if let Some(map) = self.resources.get::<Minimap>()
+            && let Some(tex) = map.texture()
+            && r.is_render_target(tex)
+            && map.visible
+            && !paused
+        {
+            r.set_render_target(Some(tex));
+            draw_minimap(r, map, &self.pool, map.canvas(screen));
+        }
+        r.set_render_target(None);
+
+        if let Some(cam) = self.photo_mode.as_ref().and_then(PhotoMode::camera) {
+            r.bind_camera(cam);
+            for batch in &self.batches {
+                r.draw_sprites(batch);
+            }
+            draw_scene(r, &self.scene_draws);
+            r.draw_panels(&self.panels);
+        } else if self.cameras.is_empty() {
+        } else {
+            let color_vision = self
+                .resources
+                .get::<Accessibility>()
+                .map_or(ColorVision::Normal, |a| a.color_vision);
+            for cam in &self.cameras {
+                let mut cam = *cam;
+                if quality.is_some_and(|q| !q.post_effects) {
+                    cam.filter = ScreenFilter {
+                        color_vision: cam.filter.color_vision,
+                        ..ScreenFilter::NONE
+                    };
+                }
+                if cam.filter.color_vision == ColorVision::Normal {
+                    cam.filter.color_vision = color_vision;
+                }
+                r.bind_camera(&cam);
+                for batch in &self.batches {
+                    r.draw_sprites(batch);
+                }
+                draw_scene(r, &self.scene_draws);
+                r.draw_panels(&self.panels);
+                if let Some(white) = self.debug_white {
+                    r.draw_lines(white, self.debug_draw.vertices());
+                }
+            }
+            draw_letterbox_bars(r, &self.cameras, screen);
+        }
+        if let Some(map) = self.resources.get::<Minimap>()
+            && map.texture().is_none()
+            && map.visible
+            && !paused
+        {
+            draw_minimap(r, map, &self.pool, screen);
+        }
+        if !self.ui_panels.is_empty() && !paused {
+            r.bind_camera(&Camera::screen_space());
+            r.draw_panels(&self.ui_panels);
+        }
+
+        if let Some((bg, text)) = self
+            .dialogue_box
+            .as_ref()
+            .and_then(|b| b.draw_data(&self.dialogue, screen))
+            && !paused
+        {
+            r.bind_camera(&Camera::screen_space());
+            r.draw_panels(&[bg]);
+            r.draw_sprites(&text);
+        }
+
+        if let Some(console) = &self.console
+            && console.is_open()
+        {
+            let (bg, text) = console.draw_data(screen);
+            r.bind_camera(&Camera::screen_space());
+            r.draw_panels(&[bg]);
+            if let Some(text) = text {
+                r.draw_sprites(&text);
+            }
+        }
+
+        if photo_capture || self.recorder.is_some() {
+            r.request_screenshot();
+        }
+        r.end_frame();
+        if !paused {
+            self.debug_draw.clear();
+        }
+        for e in r.take_asset_errors() {
+            warn!("Background load failed: {e}");
+            self.events.send(e);
+        }
+
+        self.frame += 1;
+        let shot = r.take_screenshot();
+        if let (Some(p), Some(shot)) = (&mut self.photo_mode, &shot) {
+            p.screenshot_taken(shot);
+        }
+        let mut stop_recording = false;
+        if let (Some(rec), Some(shot)) = (&mut self.recorder, &shot) {
+            if let Err(e) = rec.write(shot) {
+                warn!("Recording stopped: {e}");
+                stop_recording = true;
+            } else if rec.is_done() {
+                stop_recording = true;
+                self.quit = true;
+            }
+        }
+        if let Some(d) = &self.diagnostics {
+            if let Some(shot) = shot {
+                d.set_screenshot(shot);
+            }
+            if let Some(every) = d.screenshot_interval() {
+                self.since_screenshot += self.dt;
+                if self.since_screenshot >= every.as_secs_f32() {
+                    self.since_screenshot = 0.0;
+                    r.request_screenshot();
+                }
+            }
+            d.set_frame(FrameInfo {
+                frame: self.frame,
+                dt: self.dt,
+                fps: self.resources.get::<FpsStats>().map_or(0.0, |s| s.fps),
+                entities: self.pool.entities.len() + self.pool.panels.len(),
+                scene: self
+                    .scene_names
+                    .get(*self.active_scene)
+                    .map_or("<none>", |(n, _)| n),
+            });
+        }
+
+        if stop_recording {
+            self.finish_recording();
+        }
+        self.input_state.begin_frame();
+        self.devices.begin_frame();
+        true
+    }
     pub fn run(&mut self) -> Result<()> {
+        self.pick_launch_scene();
         let eloop = EventLoop::new()?;
         eloop.set_control_flow(ControlFlow::Poll);
 
         eloop.run_app(self)?;
         Ok(())
     }
+    fn pick_launch_scene(&mut self) {
+        if let Some(name) = self.launch_scene.take() {
+            match self
+                .scene_names
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(&name))
+            {
+                Some((_, ty)) => self.active_scene = self.scene_lookup[ty],
+                None => {
+                    let names: Vec<&str> = self.scene_names.iter().map(|(n, _)| *n).collect();
+                    warn!("No scene named {name}, scenes are {}", names.join(", "));
+                }
+            }
+        }
+    }
+    /// Lays the UI out again after it changed or the window was resized, and
+    /// rebuilds its panels for the current focus.
+    fn layout_ui(&mut self, screen: Vec2) {
+        if self.ui_layout.as_ref().is_none_or(|l| l.screen != screen) {
+            let layout = self
+                .ui
+                .as_ref()
+                .map_or_else(UiLayout::default, |ui| ui.layout(screen));
+            if let Some(l) = self.resources.get_mut::<UiLayout>() {
+                *l = layout.clone();
+            }
+            self.ui_layout = Some(layout);
+        }
+        let high_contrast = self
+            .resources
+            .get::<Accessibility>()
+            .is_some_and(|a| a.high_contrast);
+        let layout = self.ui_layout.as_ref().expect("laid out above");
+        self.ui_panels = layout.panels(self.ui_focus.focused());
+        let mut ring = self.ui_focus.highlight_instance(layout);
+        if high_contrast {
+            for p in &mut self.ui_panels {
+                *p = p.high_contrast();
+            }
+            if let Some(ring) = &mut ring {
+                ring.border_color = [1.0, 1.0, 0.0, 1.0];
+                ring.params[1] = ring.params[1].max(4.0);
+            }
+        }
+        self.ui_panels.extend(ring);
+
+        let announcements = self
+            .resources
+            .get_mut::<Accessibility>()
+            .map(Accessibility::take_announcements)
+            .unwrap_or_default();
+        #[cfg(feature = "accesskit")]
+        if let Some(sr) = &mut self.screen_reader {
+            let focused = self.ui_focus.focused();
+            sr.update(
+                &self.app_name,
+                self.ui_layout.as_ref(),
+                focused,
+                &announcements,
+            );
+        }
+        for text in announcements {
+            info!("[announce] {text}");
+        }
+    }
+
+    /// Applies focus changes and clicks requested by assistive technology.
+    #[cfg(feature = "accesskit")]
+    fn apply_screen_reader_requests(&mut self) {
+        let (Some(sr), Some(layout)) = (&mut self.screen_reader, &self.ui_layout) else {
+            return;
+        };
+        for request in sr.take_requests(layout) {
+            match request {
+                access::Request::Focus(name) => self.ui_focus.set_focus(Some(name)),
+                access::Request::Click(name) => {
+                    self.ui_focus.set_focus(Some(name.clone()));
+                    layout.activate(&name, &mut self.events);
+                    self.events.send(jester_core::UiEvent::ButtonClicked(name));
+                }
+            }
+        }
+    }
+
     fn rebuild_batches(&mut self) {
         self.batches.clear();
-        for s in self.pool.entities.values() {
-            let sz = s
-                .size
-                .map(|size| size * s.transform.scale)
-                .unwrap_or(Vec2::ONE);
-
-            let instance = SpriteInstance {
-                pos_size: [
-                    s.transform.translation.x,
-                    s.transform.translation.y,
-                    sz.x,
-                    sz.y,
-                ],
-                uv: s.uv,
+        self.pool.entities.refresh();
+
+        let two_pass = self.alpha_sorting == AlphaSorting::TwoPass
+            && self.renderer.as_ref().is_some_and(|r| r.supports_depth());
+        let layered = std::mem::take(&mut self.layered_draws);
+        let n = self.pool.entities.len()
+            + layered
+                .iter()
+                .map(|(_, b)| b.instances.len())
+                .sum::<usize>();
+        let n = n as f32;
+        let generation = self.pool.entities.generation();
+        let mut drawn = 0;
+        let mut opaque = Vec::new();
+        let groups: Vec<&SpriteGroup> = self.pool.entities.groups().collect();
+        let mut layers: Vec<i32> = groups
+            .iter()
+            .map(|g| g.first().layer)
+            .chain(layered.iter().map(|(l, _)| *l))
+            .collect();
+        layers.sort_unstable();
+        layers.dedup();
+        let mut add = |like: SpriteBatch,
+                       cutout: bool,
+                       baked: Option<u64>,
+                       retained: Option<RetainedBatch>,
+                       instances: &[SpriteInstance]| {
+            let target = match two_pass && cutout {
+                true => &mut opaque,
+                false => &mut self.batches,
             };
-            match self.batches.iter_mut().find(|b| b.tex == s.tex) {
-                Some(b) => b.instances.push(instance),
-                None => self.batches.push(SpriteBatch {
-                    tex: s.tex,
-                    instances: vec![instance],
-                }),
+            // depths follow the draw order of all sprites, so nothing stays baked or retained
+            let baked = baked.filter(|_| !two_pass);
+            let retained = retained.filter(|_| !two_pass);
+            let batch = batch_for(target, like, two_pass && cutout, baked, retained);
+            let from = batch.len();
+            batch.extend_from_slice(instances);
+            if two_pass {
+                // later in draw order is nearer
+                for instance in &mut batch[from..] {
+                    drawn += 1;
+                    instance.depth = 1.0 - drawn as f32 / (n + 1.0);
+                }
+            }
+        };
+        // keyed sprites of every layer, drawn after the rest: key, spawn id, like, cutout, instance
+        let mut keyed: Vec<(u64, u32, SpriteBatch, bool, &SpriteInstance)> = Vec::new();
+        // groups come in layer order
+        let mut rest = groups.as_slice();
+        for layer_id in layers {
+            let (layer, tail) = rest.split_at(
+                rest.iter()
+                    .take_while(|g| g.first().layer == layer_id)
+                    .count(),
+            );
+            rest = tail;
+            let emitted: Vec<&SpriteBatch> = layered
+                .iter()
+                .filter(|(l, _)| *l == layer_id)
+                .map(|(_, b)| b)
+                .collect();
+            let mode = self.layer_sort.get(&layer_id).copied().unwrap_or_default();
+            let has_keyed = layer
+                .iter()
+                .any(|g| g.sprites().iter().any(|s| s.sort_key.is_some()));
+            match mode {
+                SortMode::Batched if !has_keyed => {
+                    for g in layer {
+                        let retained = (g.baked().is_none()
+                            && g.instances().len() >= RETAIN_MIN_SPRITES)
+                            .then(|| RetainedBatch {
+                                id: g.id(),
+                                generation,
+                                dirty: g.dirty_ranges(),
+                            });
+                        add(
+                            batch_like(g.first()),
+                            g.cutout(),
+                            g.baked(),
+                            retained,
+                            g.instances(),
+                        );
+                    }
+                    for b in &emitted {
+                        add(empty_like(b), b.cutout, None, None, &b.instances);
+                    }
+                }
+                mode => {
+                    // drawn like, cutout, instance and y
+                    let mut items: Vec<(SpriteBatch, bool, &SpriteInstance, f32)> = Vec::new();
+                    for g in layer {
+                        let cutout = g.cutout();
+                        let sprites = g.ids().iter().zip(g.sprites()).zip(g.instances());
+                        for ((id, s), i) in sprites {
+                            let like = batch_like(s);
+                            match s.sort_key {
+                                Some(key) => keyed.push((key, id.to_raw(), like, cutout, i)),
+                                None => items.push((like, cutout, i, s.transform.translation.y)),
+                            }
+                        }
+                    }
+                    items.extend(emitted.iter().flat_map(|b| {
+                        b.instances
+                            .iter()
+                            .map(|i| (empty_like(b), b.cutout, i, i.pos_size[1]))
+                    }));
+                    if mode == SortMode::YSort {
+                        items.sort_by(|a, b| b.3.total_cmp(&a.3));
+                    }
+                    for (like, cutout, instance, _) in items {
+                        add(like, cutout, None, None, std::slice::from_ref(instance));
+                    }
+                }
+            }
+        }
+        // entity ids count up with every spawn
+        keyed.sort_by_key(|(key, id, ..)| (*key, *id));
+        for (_, _, like, cutout, instance) in keyed {
+            add(like, cutout, None, None, std::slice::from_ref(instance));
+        }
+        self.pool.entities.clear_dirty();
+        // front to back so the depth test rejects hidden texels early
+        let blended = std::mem::replace(&mut self.batches, Vec::with_capacity(opaque.len()));
+        for mut b in opaque.into_iter().rev() {
+            b.instances.reverse();
+            match self.batches.last_mut() {
+                Some(last) if same_batch(last, &b) => last.instances.append(&mut b.instances),
+                _ => self.batches.push(b),
             }
         }
+        self.batches.extend(blended);
+
+        self.panels.clear();
+        // the pool is a hash map, ids count up with every spawn
+        let mut panels: Vec<(&EntityId, &Panel)> = self.pool.panels.iter().collect();
+        panels.sort_by_key(|(id, p)| (p.layer, id.to_raw()));
+        self.panels
+            .extend(panels.into_iter().map(|(_, p)| p.instance()));
     }
 }
+/// Paints the letterbox bars of `cameras` over whatever the window held there.
+fn draw_letterbox_bars(r: &mut Renderer<DefaultBackend>, cameras: &[Camera], screen: Vec2) {
+    let mut panels = Vec::new();
+    let mut sprites = Vec::new();
+    for cam in cameras {
+        for (min, size) in cam.letterbox_bars(screen) {
+            // bars are in window pixels with y down, screen space has y up
+            let center = Vec2::new(min.x + size.x * 0.5, screen.y - min.y - size.y * 0.5);
+            match cam.bars {
+                BarFill::Color(color) => panels.push(
+                    Panel {
+                        transform: Transform::from_xy(center.x, center.y),
+                        size,
+                        fill: Fill::Solid(color),
+                        ..Default::default()
+                    }
+                    .instance(),
+                ),
+                BarFill::Texture(tex) => sprites.push(SpriteBatch::new(
+                    tex,
+                    vec![SpriteInstance {
+                        pos_size: [center.x, center.y, size.x, size.y],
+                        ..Default::default()
+                    }],
+                )),
+            }
+        }
+    }
+    if panels.is_empty() && sprites.is_empty() {
+        return;
+    }
+    r.bind_camera(&Camera::screen_space());
+    for batch in &sprites {
+        r.draw_sprites(batch);
+    }
+    r.draw_panels(&panels);
+}
+
+/// Instances of the last batch if instances drawn `like` can join it, of a new one otherwise.
+///
+/// Consecutive sprites sharing texture, blend mode, palette, material and pass share a batch,
+/// draw order is batch order.
+fn batch_for(
+    batches: &mut Vec<SpriteBatch>,
+    like: SpriteBatch,
+    cutout: bool,
+    baked: Option<u64>,
+    retained: Option<RetainedBatch>,
+) -> &mut Vec<SpriteInstance> {
+    let batch = SpriteBatch {
+        cutout,
+        baked,
+        retained,
+        ..like
+    };
+    if !batches.last().is_some_and(|b| same_batch(b, &batch)) {
+        batches.push(batch);
+    }
+    &mut batches.last_mut().expect("pushed above").instances
+}
+
+/// An empty batch drawn the way `s` is.
+fn batch_like(s: &Sprite) -> SpriteBatch {
+    SpriteBatch {
+        palette: s.palette.map(|p| p.tex),
+        material: s.material,
+        blend: s.blend,
+        ..SpriteBatch::new(s.tex, Vec::new())
+    }
+}
+
+/// An empty batch drawn the way `b` is.
+fn empty_like(b: &SpriteBatch) -> SpriteBatch {
+    SpriteBatch {
+        baked: None,
+        retained: None,
+        instances: Vec::new(),
+        ..*b
+    }
+}
+
+fn draw_scene(r: &mut Renderer<DefaultBackend>, draws: &[SceneDraw]) {
+    for draw in draws {
+        match draw {
+            SceneDraw::Sprites(batch) => r.draw_sprites(batch),
+            SceneDraw::Mesh(mesh) => r.draw_mesh(mesh),
+        }
+    }
+}
+
+/// Draws `map` into whatever is bound, window or render target, of size `screen`.
+fn draw_minimap(r: &mut Renderer<DefaultBackend>, map: &Minimap, pool: &EntityPool, screen: Vec2) {
+    r.bind_camera(&Camera::screen_space());
+    r.draw_panels(&[map.frame(screen)]);
+    r.bind_camera(&map.camera(pool, screen));
+    for batch in map.batches(pool) {
+        r.draw_sprites(&batch);
+    }
+    r.bind_camera(&Camera::screen_space());
+    r.draw_panels(&map.marker_panels(pool, screen));
+}
+
+fn same_batch(a: &SpriteBatch, b: &SpriteBatch) -> bool {
+    a.tex == b.tex
+        && a.blend == b.blend
+        && a.palette == b.palette
+        && a.cutout == b.cutout
+        && a.material == b.material
+        && a.baked == b.baked
+        && a.retained.as_ref().map(|r| r.id) == b.retained.as_ref().map(|r| r.id)
+}
+
 struct SceneSlot {
     scene: Box<dyn Scene>,
     must_start: bool,
@@ -182,13 +1675,32 @@ struct SceneSlot {
 type Job = Box<dyn FnOnce(&mut App) + Send + 'static>;
 
 impl ApplicationHandler for App {
+    fn exiting(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
+        self.finish_recording();
+    }
+
     fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
-        let win = event_loop
-            .create_window(Window::default_attributes().with_title(&self.app_name))
-            .unwrap();
-        let rend = Renderer::<DefaultBackend>::new(&self.app_name, &win)
+        let attrs = self.window_config.attributes(&self.app_name);
+        // the screen reader adapter must exist before the window is first shown
+        #[cfg(feature = "accesskit")]
+        let attrs = attrs.with_visible(false);
+        let win = event_loop.create_window(attrs).unwrap();
+        #[cfg(feature = "accesskit")]
+        {
+            self.screen_reader = Some(access::ScreenReader::new(event_loop, &win));
+            win.set_visible(true);
+        }
+        self.window_config.apply(&win);
+        self.resources.insert(Monitors::query(&win));
+        let mut rend = Renderer::<DefaultBackend>::new(&self.app_name, &win)
             .expect("Failed to create renderer");
+        rend.set_texture_budget(self.texture_budget);
+        rend.set_import_cache(self.import_cache.clone());
+        rend.set_present_mode(self.present_mode);
 
+        if let Some(d) = &self.diagnostics {
+            d.set_adapter(rend.adapter_info());
+        }
         self.win = Some(win);
         self.renderer = Some(rend);
         let queued: Vec<Job> = std::mem::take(&mut self.pending);
@@ -206,104 +1718,172 @@ impl ApplicationHandler for App {
         event: winit::event::WindowEvent,
     ) {
         let win_size = self.win.as_ref().unwrap().inner_size();
+        #[cfg(feature = "accesskit")]
+        if let (Some(sr), Some(win)) = (&mut self.screen_reader, &self.win) {
+            sr.process_event(win, &event);
+        }
         match event {
             WindowEvent::CloseRequested => {
                 info!("The close button was pressed; stopping");
                 event_loop.exit();
             }
-            WindowEvent::KeyboardInput { event, .. } => {
-                if let PhysicalKey::Code(key) = event.physical_key {
-                    self.input_state
-                        .set_key_down(key, event.state == ElementState::Pressed);
+            #[cfg(feature = "gamepad")]
+            WindowEvent::Focused(false) => {
+                if let Some(pads) = &mut self.gamepads {
+                    pads.stop_rumble();
+                }
+            }
+            WindowEvent::KeyboardInput {
+                device_id, event, ..
+            } => {
+                let code = match event.physical_key {
+                    PhysicalKey::Code(key) => Some(key),
+                    _ => None,
+                };
+                if event.state == ElementState::Pressed
+                    && let Some(console) = &mut self.console
+                    && console.handle_key(code, &event.logical_key, event.text.as_deref())
+                {
+                    return;
+                }
+                if let Some(key) = code
+                    && self.input_replay.is_none()
+                {
+                    let idx = match self.keyboards.iter().position(|d| *d == device_id) {
+                        Some(i) => i,
+                        None => {
+                            self.keyboards.push(device_id);
+                            self.keyboards.len() - 1
+                        }
+                    };
+                    let down = event.state == ElementState::Pressed;
+                    self.apply_input(InputEvent::Key(idx as u32, key, down));
                 }
             }
-            WindowEvent::MouseInput { button, state, .. } => {
-                self.input_state
-                    .set_mouse_btn(button, state == ElementState::Pressed);
+            WindowEvent::MouseInput { button, state, .. } if self.input_replay.is_none() => {
+                self.apply_input(InputEvent::Mouse(button, state == ElementState::Pressed));
+            }
+            WindowEvent::MouseWheel { delta, .. } if self.input_replay.is_none() => {
+                let lines = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => y,
+                    // roughly one line per notch on common mice
+                    MouseScrollDelta::PixelDelta(p) => p.y as f32 / 40.0,
+                };
+                self.apply_input(InputEvent::Scroll(lines));
             }
-            WindowEvent::CursorMoved { position, .. } => {
+            WindowEvent::CursorMoved { position, .. } if self.input_replay.is_none() => {
                 let pos = glam::Vec2::new(position.x as f32, position.y as f32);
-                self.input_state.set_mouse_pos(pos);
+                self.apply_input(InputEvent::Cursor(pos));
             }
             WindowEvent::RedrawRequested => {
-                let now = Instant::now();
-                self.dt = (now - self.prev).as_secs_f32();
-                self.prev = now;
-
-                if let Some(s) = self.resources.get_mut::<FpsStats>() {
-                    s.tick(self.dt);
-                }
-
-                if *self.active_scene == usize::MAX {
-                    warn!("No active scene");
-                    if let Some(r) = &mut self.renderer {
-                        r.begin_frame();
-                        r.end_frame();
-                    }
+                let screen = Vec2::new(win_size.width as f32, win_size.height as f32);
+                if !self.frame(screen, None) {
                     return;
                 }
-                {
-                    let slot = &mut self.scenes[*self.active_scene];
-                    if slot.must_start {
-                        let mut startup_cmds = Commands::default();
-                        let mut ctx = Ctx {
-                            dt: 0.0,
-                            resources: &mut self.resources,
-                            commands: &mut startup_cmds,
-                            pool: &mut self.pool,
-                            input: &self.input_state,
-                            screen_pos: Vec2::new(win_size.width as f32, win_size.height as f32),
-                        };
-                        slot.scene.start(&mut ctx);
-                        slot.must_start = false;
-                        self.apply_commands(startup_cmds);
-                    }
-                }
-
-                let mut cmds = Commands::default();
-                {
-                    let slot = &mut self.scenes[*self.active_scene];
-                    let mut ctx = Ctx {
-                        screen_pos: Vec2::new(win_size.width as f32, win_size.height as f32),
-                        dt: self.dt,
-                        resources: &mut self.resources,
-                        commands: &mut cmds,
-                        pool: &mut self.pool,
-                        input: &self.input_state,
-                    };
-                    slot.scene.update(&mut ctx);
-                }
-                self.apply_commands(cmds);
-
-                self.rebuild_batches();
-
-                let r = self.renderer.as_mut().expect("renderer is live");
-
-                r.begin_frame();
-
-                if self.cameras.is_empty() {
-                } else {
-                    for cam in &self.cameras {
-                        r.bind_camera(cam);
-                        for batch in &self.batches {
-                            r.draw_sprites(batch);
-                        }
-                    }
+                if self.quit {
+                    event_loop.exit();
+                    return;
                 }
-
-                r.end_frame();
-
-                self.input_state.begin_frame();
                 self.win.as_ref().unwrap().request_redraw();
             }
+            WindowEvent::Moved(_) => {
+                if let Some(win) = &self.win {
+                    self.resources.insert(Monitors::query(win));
+                }
+            }
             WindowEvent::Resized(size) => {
+                let px = Vec2::new(size.width as f32, size.height as f32);
                 for c in &mut self.cameras {
-                    c.update_pixel_perfect(size.width as f32, size.height as f32);
+                    c.resize(px);
                 }
+                self.resize_settle = Some((px, Instant::now()));
                 let Some(r) = &mut self.renderer else { return };
                 r.handle_resize(size);
+                // keep drawing while the platform's live-resize loop blocks normal redraws
+                if let Some(win) = &self.win {
+                    win.request_redraw();
+                }
             }
             _ => (),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn(app: &mut App, x: f32, y: f32, layer: i32, sort_key: Option<u64>) {
+        let id = EntityId::from_raw(app.pool.entities.len() as u32);
+        let sprite = Sprite {
+            transform: Transform::from_xy(x, y),
+            size: Some(Vec2::ONE),
+            layer,
+            sort_key,
+            ..Default::default()
+        };
+        app.pool.entities.insert(id, sprite);
+    }
+
+    /// The x of every sprite drawn, in draw order.
+    fn drawn(app: &mut App) -> Vec<f32> {
+        app.rebuild_batches();
+        app.batches
+            .iter()
+            .flat_map(|b| &b.instances)
+            .map(|i| i.pos_size[0])
+            .collect()
+    }
+
+    #[test]
+    fn equal_sort_keys_keep_insertion_order() {
+        let mut app = App::new("test".into());
+        for x in 0..4 {
+            spawn(&mut app, x as f32, 0.0, 0, Some(7));
+        }
+        spawn(&mut app, 4.0, 0.0, 0, Some(3));
+        assert_eq!(drawn(&mut app), [4.0, 0.0, 1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn sort_key_overrides_y_sort() {
+        let mut app = App::new("test".into());
+        app.set_layer_sort(0, SortMode::YSort);
+        spawn(&mut app, 0.0, 0.0, 0, Some(1));
+        spawn(&mut app, 1.0, 10.0, 0, Some(2));
+        spawn(&mut app, 2.0, 20.0, 0, Some(0));
+        assert_eq!(drawn(&mut app), [2.0, 0.0, 1.0]);
+
+        // unkeyed, the higher sprite draws first
+        let mut app = App::new("test".into());
+        app.set_layer_sort(0, SortMode::YSort);
+        spawn(&mut app, 0.0, 0.0, 0, None);
+        spawn(&mut app, 1.0, 10.0, 0, None);
+        assert_eq!(drawn(&mut app), [1.0, 0.0]);
+    }
+
+    #[test]
+    fn keyed_sprites_draw_after_unkeyed_ones_of_every_layer() {
+        let mut app = App::new("test".into());
+        spawn(&mut app, 0.0, 0.0, 0, Some(0));
+        spawn(&mut app, 1.0, 0.0, 0, None);
+        spawn(&mut app, 2.0, 0.0, 1, None);
+        spawn(&mut app, 3.0, 0.0, -1, Some(u64::MAX));
+        spawn(&mut app, 4.0, 0.0, 5, Some(1));
+        assert_eq!(drawn(&mut app), [1.0, 2.0, 0.0, 4.0, 3.0]);
+    }
+
+    #[test]
+    fn equal_sort_keys_draw_in_spawn_order_across_textures_and_layers() {
+        let mut app = App::new("test".into());
+        for x in 0..6 {
+            spawn(&mut app, x as f32, 0.0, 0, Some(1));
+            let id = EntityId::from_raw(x);
+            let s = app.pool.entities.get_mut(&id).unwrap();
+            // textures hash in no particular order
+            s.tex = TextureId(1000 - x as u64 * 7);
+            s.layer = x as i32 % 3;
+        }
+        assert_eq!(drawn(&mut app), [0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+    }
+}
@@ -49,13 +49,13 @@ impl Scene for MainScene {
             return;
         };
 
-        if fps_timer.0.tick(Duration::from_secs_f32(ctx.dt)) {
-            if let Some(stats) = ctx.resources.get::<FpsStats>() {
-                info!(
-                    "Avg FPS {:.1} – Avg frame {:.2} ms",
-                    stats.fps, stats.frame_ms
-                );
-            }
+        if fps_timer.0.tick(Duration::from_secs_f32(ctx.dt))
+            && let Some(stats) = ctx.resources.get::<FpsStats>()
+        {
+            info!(
+                "Avg FPS {:.1} – Avg frame {:.2} ms",
+                stats.fps, stats.frame_ms
+            );
         }
 
         const SPEED: f32 = 150.0;
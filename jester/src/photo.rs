@@ -0,0 +1,157 @@
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use glam::Vec2;
+use jester_core::{Camera, InputState, Screenshot, Viewport};
+use tracing::{info, warn};
+use winit::{event::MouseButton, keyboard::KeyCode};
+
+/// Free camera for debugging and marketing shots.
+///
+/// While active the scene is paused, the UI, dialogue box and editor overlay
+/// are hidden, and the world is drawn through one full-window camera that
+/// starts where the first camera was. Pan with WASD, the arrow keys or by
+/// dragging with the right mouse button, zoom with the wheel or Q and E, and
+/// press [`PhotoMode::capture_key`] to save a PNG to [`PhotoMode::directory`].
+#[derive(Debug)]
+pub struct PhotoMode {
+    pub toggle_key: KeyCode,
+    pub capture_key: KeyCode,
+    /// Window pixels per second, whatever the zoom.
+    pub pan_speed: f32,
+    /// Zoom factor per wheel line, and per second with Q and E held.
+    pub zoom_step: f32,
+    pub directory: PathBuf,
+    camera: Option<Camera>,
+    drag_from: Option<Vec2>,
+    capture_pending: bool,
+    shots: u32,
+}
+
+impl Default for PhotoMode {
+    fn default() -> Self {
+        Self {
+            toggle_key: KeyCode::F10,
+            capture_key: KeyCode::F12,
+            pan_speed: 600.0,
+            zoom_step: 1.1,
+            directory: PathBuf::from("screenshots"),
+            camera: None,
+            drag_from: None,
+            capture_pending: false,
+            shots: 0,
+        }
+    }
+}
+
+impl PhotoMode {
+    pub fn is_active(&self) -> bool {
+        self.camera.is_some()
+    }
+
+    /// The free camera, `None` outside photo mode.
+    pub fn camera(&self) -> Option<&Camera> {
+        self.camera.as_ref()
+    }
+
+    /// Toggles the mode, moves the free camera and returns whether a
+    /// screenshot should be requested this frame.
+    pub fn update(
+        &mut self,
+        input: &InputState,
+        cameras: &[Camera],
+        screen: Vec2,
+        dt: f32,
+    ) -> bool {
+        if input.just_pressed(self.toggle_key) {
+            self.camera = match self.camera {
+                Some(_) => None,
+                None => {
+                    let first = cameras.first().copied().unwrap_or_default();
+                    let mut cam = first.with_viewport(Viewport::FULL);
+                    cam.resize(screen);
+                    cam.center += middle(&first, screen) - middle(&cam, screen);
+                    Some(cam)
+                }
+            };
+            self.drag_from = None;
+        }
+        let Some(cam) = &mut self.camera else {
+            return false;
+        };
+
+        let key = |a: KeyCode, b: KeyCode| input.key_pressed(a) || input.key_pressed(b);
+        let mut pan = Vec2::ZERO;
+        if key(KeyCode::KeyA, KeyCode::ArrowLeft) {
+            pan.x -= 1.0;
+        }
+        if key(KeyCode::KeyD, KeyCode::ArrowRight) {
+            pan.x += 1.0;
+        }
+        if key(KeyCode::KeyS, KeyCode::ArrowDown) {
+            pan.y -= 1.0;
+        }
+        if key(KeyCode::KeyW, KeyCode::ArrowUp) {
+            pan.y += 1.0;
+        }
+        cam.center += pan * self.pan_speed * dt / cam.scale();
+
+        let cursor = input.mouse_pos();
+        if let Some(from) = self.drag_from {
+            // window y runs down, world y up
+            let moved = cursor - from;
+            cam.center -= Vec2::new(moved.x, -moved.y) / cam.scale();
+        }
+        self.drag_from = input.mouse_pressed(MouseButton::Right).then_some(cursor);
+
+        let mut zoom = self.zoom_step.powf(input.scroll());
+        if input.key_pressed(KeyCode::KeyE) {
+            zoom *= self.zoom_step.powf(dt * 10.0);
+        }
+        if input.key_pressed(KeyCode::KeyQ) {
+            zoom /= self.zoom_step.powf(dt * 10.0);
+        }
+        let before = middle(cam, screen);
+        cam.zoom = (cam.zoom * zoom).clamp(0.01, 100.0);
+        cam.center += before - middle(cam, screen);
+
+        if input.just_pressed(self.capture_key) {
+            self.capture_pending = true;
+            return true;
+        }
+        false
+    }
+
+    /// Saves `shot` if it was requested with [`PhotoMode::capture_key`].
+    pub fn screenshot_taken(&mut self, shot: &Screenshot) {
+        if !std::mem::take(&mut self.capture_pending) {
+            return;
+        }
+        if let Err(e) = std::fs::create_dir_all(&self.directory) {
+            warn!(
+                "Photo mode: cannot create {}: {e}",
+                self.directory.display()
+            );
+            return;
+        }
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        self.shots += 1;
+        let path = self
+            .directory
+            .join(format!("photo_{secs}_{}.png", self.shots));
+        match shot.save_png(&path) {
+            Ok(()) => info!("Photo saved to {}", path.display()),
+            Err(e) => warn!("Photo mode: cannot save {}: {e}", path.display()),
+        }
+    }
+}
+
+/// World position at the middle of what `cam` shows, kept in place when zooming.
+fn middle(cam: &Camera, screen: Vec2) -> Vec2 {
+    let (min, size) = cam.output_viewport().to_pixels(screen);
+    cam.window_to_world(min + size * 0.5, screen)
+}
@@ -0,0 +1,253 @@
+//! Dynamic-library plugins with a small C ABI.
+//!
+//! A plugin is a `cdylib` exporting
+//!
+//! ```text
+//! #[unsafe(no_mangle)]
+//! pub extern "C" fn jester_plugin_register(host: *const HostApi) -> bool
+//! ```
+//!
+//! which checks `abi_version` and calls `register_system` for every system it
+//! provides. Systems run each frame after the active scene and talk to the
+//! game only through the function table in [`PluginCtx`], so plugins do not
+//! need to be built with the same compiler as the game.
+use std::{
+    ffi::{c_char, c_void, CStr},
+    path::Path,
+};
+
+use glam::Vec2;
+use jester_core::{Ctx, EntityId, Error, Sprite, TextureId};
+use libloading::Library;
+use tracing::{info, warn};
+
+/// Bumped on any layout change of the `repr(C)` types below.
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Name of the symbol every plugin library must export.
+pub const PLUGIN_ENTRY: &[u8] = b"jester_plugin_register\0";
+
+pub type PluginEntry = unsafe extern "C" fn(host: *const HostApi) -> bool;
+
+/// Handed to the plugin entry point for the duration of the call.
+#[repr(C)]
+pub struct HostApi {
+    pub abi_version: u32,
+    pub host: *mut c_void,
+    /// `name` must be a NUL-terminated UTF-8 string, it is copied.
+    pub register_system:
+        unsafe extern "C" fn(host: *mut c_void, name: *const c_char, system: PluginSystem),
+}
+
+/// A per-frame system provided by a plugin.
+///
+/// `user` is opaque to the host and passed back to every callback; `drop` is
+/// called once when the plugin is unloaded.
+#[repr(C)]
+pub struct PluginSystem {
+    pub user: *mut c_void,
+    pub start: Option<unsafe extern "C" fn(user: *mut c_void, ctx: *mut PluginCtx)>,
+    pub update: Option<unsafe extern "C" fn(user: *mut c_void, ctx: *mut PluginCtx)>,
+    pub drop: Option<unsafe extern "C" fn(user: *mut c_void)>,
+}
+
+/// C view of [`Ctx`], only valid during a `start`/`update` callback.
+#[repr(C)]
+pub struct PluginCtx {
+    pub dt: f32,
+    pub screen_w: f32,
+    pub screen_h: f32,
+    pub ctx: *mut c_void,
+    pub load_asset: unsafe extern "C" fn(ctx: *mut c_void, path: *const c_char) -> u64,
+    pub spawn_sprite:
+        unsafe extern "C" fn(ctx: *mut c_void, tex: u64, x: f32, y: f32, layer: i32) -> u32,
+    pub despawn: unsafe extern "C" fn(ctx: *mut c_void, id: u32),
+    pub set_position: unsafe extern "C" fn(ctx: *mut c_void, id: u32, x: f32, y: f32) -> bool,
+    pub get_position: unsafe extern "C" fn(ctx: *mut c_void, id: u32, out: *mut f32) -> bool,
+}
+
+struct LoadedSystem {
+    name: String,
+    system: PluginSystem,
+    started: bool,
+}
+
+// plugin systems are only ever called from the thread driving the App
+unsafe impl Send for LoadedSystem {}
+unsafe impl Sync for LoadedSystem {}
+
+/// Loaded plugin libraries and the systems they registered.
+#[derive(Default)]
+pub struct Plugins {
+    systems: Vec<LoadedSystem>,
+    libs: Vec<Library>,
+}
+
+impl Plugins {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads one library and returns how many systems it registered.
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<usize, Error> {
+        let path = path.as_ref();
+        let before = self.systems.len();
+        let lib = unsafe { Library::new(path) }
+            .map_err(|e| Error::Plugin(format!("{}: {e}", path.display())))?;
+        let ok = unsafe {
+            let entry = lib
+                .get::<PluginEntry>(PLUGIN_ENTRY)
+                .map_err(|e| Error::Plugin(format!("{}: {e}", path.display())))?;
+            let api = HostApi {
+                abi_version: PLUGIN_ABI_VERSION,
+                host: &mut self.systems as *mut Vec<LoadedSystem> as *mut c_void,
+                register_system,
+            };
+            entry(&api)
+        };
+        if !ok {
+            for s in self.systems.drain(before..) {
+                drop_system(s);
+            }
+            return Err(Error::Plugin(format!(
+                "{} refused to register (host ABI {PLUGIN_ABI_VERSION})",
+                path.display()
+            )));
+        }
+        let n = self.systems.len() - before;
+        info!("Loaded plugin {} ({n} systems)", path.display());
+        self.libs.push(lib);
+        Ok(n)
+    }
+
+    /// Loads every library in `dir` with the platform's dylib extension.
+    ///
+    /// Failing plugins are logged and skipped so one broken mod does not stop the game.
+    pub fn load_dir(&mut self, dir: impl AsRef<Path>) -> Result<usize, Error> {
+        let mut n = 0;
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(std::env::consts::DLL_EXTENSION) {
+                continue;
+            }
+            match self.load(&path) {
+                Ok(_) => n += 1,
+                Err(e) => warn!("{e}"),
+            }
+        }
+        Ok(n)
+    }
+
+    pub fn system_names(&self) -> impl Iterator<Item = &str> {
+        self.systems.iter().map(|s| s.name.as_str())
+    }
+
+    pub(crate) fn update(&mut self, ctx: &mut Ctx<'_>) {
+        let mut pctx = PluginCtx {
+            dt: ctx.dt,
+            screen_w: ctx.screen_pos.x,
+            screen_h: ctx.screen_pos.y,
+            ctx: ctx as *mut Ctx<'_> as *mut c_void,
+            load_asset,
+            spawn_sprite,
+            despawn,
+            set_position,
+            get_position,
+        };
+        for s in &mut self.systems {
+            unsafe {
+                if !s.started {
+                    s.started = true;
+                    if let Some(start) = s.system.start {
+                        start(s.system.user, &mut pctx);
+                    }
+                }
+                if let Some(update) = s.system.update {
+                    update(s.system.user, &mut pctx);
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Plugins {
+    fn drop(&mut self) {
+        // user data belongs to the libraries, release it before unmapping them
+        for s in self.systems.drain(..) {
+            drop_system(s);
+        }
+        self.libs.clear();
+    }
+}
+
+fn drop_system(s: LoadedSystem) {
+    if let Some(drop) = s.system.drop {
+        unsafe { drop(s.system.user) };
+    }
+}
+
+unsafe extern "C" fn register_system(host: *mut c_void, name: *const c_char, system: PluginSystem) {
+    let systems = unsafe { &mut *(host as *mut Vec<LoadedSystem>) };
+    let name = if name.is_null() {
+        String::from("<unnamed>")
+    } else {
+        unsafe { CStr::from_ptr(name) }
+            .to_string_lossy()
+            .into_owned()
+    };
+    systems.push(LoadedSystem {
+        name,
+        system,
+        started: false,
+    });
+}
+
+unsafe fn ctx<'a>(ctx: *mut c_void) -> &'a mut Ctx<'a> {
+    unsafe { &mut *(ctx as *mut Ctx<'a>) }
+}
+
+unsafe extern "C" fn load_asset(c: *mut c_void, path: *const c_char) -> u64 {
+    if path.is_null() {
+        return 0;
+    }
+    let path = unsafe { CStr::from_ptr(path) }.to_string_lossy();
+    unsafe { ctx(c) }.load_asset(path.as_ref()).0
+}
+
+unsafe extern "C" fn spawn_sprite(c: *mut c_void, tex: u64, x: f32, y: f32, layer: i32) -> u32 {
+    let mut sprite = Sprite {
+        tex: TextureId(tex),
+        layer,
+        ..Default::default()
+    };
+    sprite.transform.translation = Vec2::new(x, y);
+    unsafe { ctx(c) }.spawn_sprite(sprite).to_raw()
+}
+
+unsafe extern "C" fn despawn(c: *mut c_void, id: u32) {
+    unsafe { ctx(c) }.despawn(EntityId::from_raw(id));
+}
+
+unsafe extern "C" fn set_position(c: *mut c_void, id: u32, x: f32, y: f32) -> bool {
+    match unsafe { ctx(c) }.pool.sprite_mut(EntityId::from_raw(id)) {
+        Some(s) => {
+            s.transform.translation = Vec2::new(x, y);
+            true
+        }
+        None => false,
+    }
+}
+
+unsafe extern "C" fn get_position(c: *mut c_void, id: u32, out: *mut f32) -> bool {
+    match unsafe { ctx(c) }.pool.entities.get(&EntityId::from_raw(id)) {
+        Some(s) if !out.is_null() => {
+            let p = s.transform.translation;
+            unsafe {
+                *out = p.x;
+                *out.add(1) = p.y;
+            }
+            true
+        }
+        _ => false,
+    }
+}
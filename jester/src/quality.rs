@@ -0,0 +1,98 @@
+/// Lowers quality when frames blow the budget and restores it once there is headroom.
+///
+/// Add it with `App::add_resource`; `App` ticks it every frame. Systems read
+/// [`AdaptiveQuality::particle_scale`], or [`AdaptiveQuality::level`] for
+/// their own trade-offs, to size their work. The engine has no internal
+/// resolution to lower, so the level does not change how frames are drawn.
+#[derive(Debug, Clone)]
+pub struct AdaptiveQuality {
+    /// Frame budget in milliseconds.
+    pub target_frame_ms: f32,
+    /// Step down when the smoothed frame time exceeds `target * downgrade_ratio`.
+    pub downgrade_ratio: f32,
+    /// Step up when the smoothed frame time stays below `target * upgrade_ratio`.
+    pub upgrade_ratio: f32,
+    /// Consecutive frames a condition must hold before the level changes.
+    pub hold_frames: u32,
+    pub step: f32,
+    pub min_particle_scale: f32,
+    /// Weight of the newest sample in the frame time average.
+    pub smoothing: f32,
+    level: f32,
+    avg_ms: f32,
+    over: u32,
+    under: u32,
+}
+
+impl Default for AdaptiveQuality {
+    fn default() -> Self {
+        Self {
+            target_frame_ms: 1000.0 / 60.0,
+            downgrade_ratio: 1.1,
+            upgrade_ratio: 0.75,
+            hold_frames: 30,
+            step: 0.1,
+            min_particle_scale: 0.25,
+            smoothing: 0.1,
+            level: 1.0,
+            avg_ms: 0.0,
+            over: 0,
+            under: 0,
+        }
+    }
+}
+
+impl AdaptiveQuality {
+    pub fn with_target_fps(mut self, fps: f32) -> Self {
+        self.target_frame_ms = 1000.0 / fps;
+        self
+    }
+
+    /// 0.0 is the lowest quality, 1.0 the highest.
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    pub fn set_level(&mut self, level: f32) {
+        self.level = level.clamp(0.0, 1.0);
+        self.over = 0;
+        self.under = 0;
+    }
+
+    pub fn smoothed_frame_ms(&self) -> f32 {
+        self.avg_ms
+    }
+
+    pub fn particle_scale(&self) -> f32 {
+        self.min_particle_scale + (1.0 - self.min_particle_scale) * self.level
+    }
+
+    /// Feeds one frame. Returns `true` when the level changed.
+    pub fn tick(&mut self, dt: f32) -> bool {
+        let ms = dt * 1000.0;
+        self.avg_ms = if self.avg_ms == 0.0 {
+            ms
+        } else {
+            self.avg_ms + (ms - self.avg_ms) * self.smoothing
+        };
+
+        if self.avg_ms > self.target_frame_ms * self.downgrade_ratio {
+            self.over += 1;
+            self.under = 0;
+        } else if self.avg_ms < self.target_frame_ms * self.upgrade_ratio {
+            self.under += 1;
+            self.over = 0;
+        } else {
+            self.over = 0;
+            self.under = 0;
+        }
+
+        let before = self.level;
+        if self.over >= self.hold_frames {
+            self.set_level(self.level - self.step);
+        } else if self.under >= self.hold_frames {
+            self.set_level(self.level + self.step);
+        }
+        self.level != before
+    }
+}
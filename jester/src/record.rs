@@ -0,0 +1,142 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+};
+
+use jester_core::Screenshot;
+use tracing::info;
+
+/// Where a [`FrameRecorder`] writes frames.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecordOutput {
+    /// Numbered PNGs in this directory: `frame_000000.png`, `frame_000001.png`…
+    Png(PathBuf),
+    /// A video encoded by `ffmpeg`, which must be on the `PATH`.
+    Ffmpeg(PathBuf),
+}
+
+/// Offline render mode for trailers and bug repros.
+///
+/// While enabled with `App::record_frames` the app steps every frame by a
+/// fixed `1 / fps` seconds, however long it took to render, and reads each
+/// presented frame back to the output. The same scene code therefore gives the
+/// same video on any machine.
+///
+/// ```ignore
+/// app.record_frames(FrameRecorder::png("capture", 60).with_frame_count(600));
+/// // or straight to a video
+/// app.record_frames(FrameRecorder::ffmpeg("trailer.mp4", 60));
+/// ```
+#[derive(Debug)]
+pub struct FrameRecorder {
+    pub output: RecordOutput,
+    pub fps: u32,
+    /// Frames to record before the app quits, `None` records until it is closed.
+    pub frame_count: Option<u64>,
+    /// Extra `ffmpeg` arguments placed before the output path.
+    pub ffmpeg_args: Vec<String>,
+    frame: u64,
+    ffmpeg: Option<(Child, u32, u32)>,
+}
+
+impl FrameRecorder {
+    pub fn new(output: RecordOutput, fps: u32) -> Self {
+        Self {
+            output,
+            fps: fps.max(1),
+            frame_count: None,
+            ffmpeg_args: vec![
+                "-c:v".into(),
+                "libx264".into(),
+                "-pix_fmt".into(),
+                "yuv420p".into(),
+            ],
+            frame: 0,
+            ffmpeg: None,
+        }
+    }
+
+    pub fn png(dir: impl Into<PathBuf>, fps: u32) -> Self {
+        Self::new(RecordOutput::Png(dir.into()), fps)
+    }
+
+    pub fn ffmpeg(path: impl Into<PathBuf>, fps: u32) -> Self {
+        Self::new(RecordOutput::Ffmpeg(path.into()), fps)
+    }
+
+    pub fn with_frame_count(mut self, frames: u64) -> Self {
+        self.frame_count = Some(frames);
+        self
+    }
+
+    /// Seconds every recorded frame advances the simulation by.
+    pub fn dt(&self) -> f32 {
+        1.0 / self.fps as f32
+    }
+
+    /// Frames written so far.
+    pub fn frames(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.frame_count.is_some_and(|n| self.frame >= n)
+    }
+
+    /// Writes the next frame.
+    pub fn write(&mut self, shot: &Screenshot) -> io::Result<()> {
+        match &self.output {
+            RecordOutput::Png(dir) => {
+                if self.frame == 0 {
+                    std::fs::create_dir_all(dir)?;
+                }
+                let path = dir.join(format!("frame_{:06}.png", self.frame));
+                shot.save_png(&path).map_err(io::Error::other)?;
+            }
+            RecordOutput::Ffmpeg(path) => {
+                if self.ffmpeg.is_none() {
+                    let child = Command::new("ffmpeg")
+                        .args(["-y", "-loglevel", "error", "-f", "rawvideo"])
+                        .args(["-pix_fmt", "rgba", "-s"])
+                        .arg(format!("{}x{}", shot.width, shot.height))
+                        .arg("-r")
+                        .arg(self.fps.to_string())
+                        .args(["-i", "-"])
+                        .args(&self.ffmpeg_args)
+                        .arg(path)
+                        .stdin(Stdio::piped())
+                        .spawn()?;
+                    self.ffmpeg = Some((child, shot.width, shot.height));
+                }
+                let (child, w, h) = self.ffmpeg.as_mut().expect("spawned above");
+                if (shot.width, shot.height) != (*w, *h) {
+                    return Err(io::Error::other(format!(
+                        "window resized to {}x{} while recording at {w}x{h}",
+                        shot.width, shot.height
+                    )));
+                }
+                let stdin = child.stdin.as_mut().expect("stdin is piped");
+                stdin.write_all(&shot.pixels)?;
+            }
+        }
+        self.frame += 1;
+        Ok(())
+    }
+
+    /// Closes the video, waiting for `ffmpeg` to finish encoding.
+    pub fn finish(&mut self) -> io::Result<()> {
+        if let Some((mut child, ..)) = self.ffmpeg.take() {
+            drop(child.stdin.take());
+            let status = child.wait()?;
+            if !status.success() {
+                return Err(io::Error::other(format!("ffmpeg exited with {status}")));
+            }
+        }
+        let to = match &self.output {
+            RecordOutput::Png(p) | RecordOutput::Ffmpeg(p) => p,
+        };
+        info!("Recorded {} frames to {}", self.frame, to.display());
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+//! Input recording and replay for bug repros, see `App::record_input`.
+//!
+//! A recording is text: a `frame <dt>` line per frame followed by the input
+//! events that arrived before it, one per line:
+//!
+//! ```text
+//! frame 0.016667
+//! key 0 KeyD 1
+//! cursor 412.5 300
+//! button 0 South 1
+//! ```
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use glam::Vec2;
+use jester_core::{key_from_name, key_name, Error, GamepadAxis, GamepadButton};
+use winit::{event::MouseButton, keyboard::KeyCode};
+
+/// One change to the input state; keyboards and gamepads by device index.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum InputEvent {
+    Key(u32, KeyCode, bool),
+    Mouse(MouseButton, bool),
+    Cursor(Vec2),
+    Scroll(f32),
+    PadButton(u32, GamepadButton, bool),
+    PadAxis(u32, GamepadAxis, f32),
+    PadConnected(u32),
+    PadDisconnected(u32),
+}
+
+pub(crate) struct InputRecorder {
+    out: BufWriter<File>,
+    pending: Vec<InputEvent>,
+}
+
+impl InputRecorder {
+    pub fn create(path: &Path) -> Result<Self, Error> {
+        Ok(Self {
+            out: BufWriter::new(File::create(path)?),
+            pending: Vec::new(),
+        })
+    }
+
+    pub fn push(&mut self, ev: InputEvent) {
+        self.pending.push(ev);
+    }
+
+    /// Writes the frame about to run with the events since the last one,
+    /// flushed so a crash keeps everything up to it.
+    pub fn frame(&mut self, dt: f32) -> io::Result<()> {
+        writeln!(self.out, "frame {dt}")?;
+        for ev in self.pending.drain(..) {
+            match ev {
+                InputEvent::Key(d, k, down) => match key_name(k) {
+                    Some(name) => writeln!(self.out, "key {d} {name} {}", down as u8)?,
+                    None => continue,
+                },
+                InputEvent::Mouse(b, down) => {
+                    let name = match b {
+                        MouseButton::Other(n) => n.to_string(),
+                        b => format!("{b:?}"),
+                    };
+                    writeln!(self.out, "mouse {name} {}", down as u8)?
+                }
+                InputEvent::Cursor(p) => writeln!(self.out, "cursor {} {}", p.x, p.y)?,
+                InputEvent::Scroll(lines) => writeln!(self.out, "scroll {lines}")?,
+                InputEvent::PadButton(d, b, down) => {
+                    writeln!(self.out, "button {d} {b:?} {}", down as u8)?
+                }
+                InputEvent::PadAxis(d, a, v) => writeln!(self.out, "axis {d} {a:?} {v}")?,
+                InputEvent::PadConnected(d) => writeln!(self.out, "connect {d}")?,
+                InputEvent::PadDisconnected(d) => writeln!(self.out, "disconnect {d}")?,
+            }
+        }
+        self.out.flush()
+    }
+}
+
+/// Frames of a recording, played back in place of live input.
+pub(crate) struct InputReplay {
+    frames: VecDeque<(f32, Vec<InputEvent>)>,
+}
+
+impl InputReplay {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let src = std::fs::read_to_string(path)?;
+        Self::parse(&src).map_err(|(line, msg)| {
+            let msg = format!("{}:{line}: {msg}", path.display());
+            Error::Io(io::Error::new(io::ErrorKind::InvalidData, msg))
+        })
+    }
+
+    fn parse(src: &str) -> Result<Self, (usize, String)> {
+        let mut frames: VecDeque<(f32, Vec<InputEvent>)> = VecDeque::new();
+        for (n, line) in src.lines().enumerate() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let bad = || (n + 1, format!("bad line `{line}`"));
+            let num = |s: &str| s.parse::<f32>().map_err(|_| bad());
+            let idx = |s: &str| s.parse::<u32>().map_err(|_| bad());
+            let down = |s: &str| match s {
+                "1" => Ok(true),
+                "0" => Ok(false),
+                _ => Err(bad()),
+            };
+            let ev = match parts.as_slice() {
+                [] => continue,
+                ["frame", dt] => {
+                    frames.push_back((num(dt)?, Vec::new()));
+                    continue;
+                }
+                ["key", d, k, s] => {
+                    InputEvent::Key(idx(d)?, key_from_name(k).ok_or_else(bad)?, down(s)?)
+                }
+                ["mouse", b, s] => InputEvent::Mouse(mouse_button(b).ok_or_else(bad)?, down(s)?),
+                ["cursor", x, y] => InputEvent::Cursor(Vec2::new(num(x)?, num(y)?)),
+                ["scroll", v] => InputEvent::Scroll(num(v)?),
+                ["button", d, b, s] => {
+                    let b = GamepadButton::ALL
+                        .into_iter()
+                        .find(|v| format!("{v:?}") == *b);
+                    InputEvent::PadButton(idx(d)?, b.ok_or_else(bad)?, down(s)?)
+                }
+                ["axis", d, a, v] => {
+                    let a = GamepadAxis::ALL
+                        .into_iter()
+                        .find(|v| format!("{v:?}") == *a);
+                    InputEvent::PadAxis(idx(d)?, a.ok_or_else(bad)?, num(v)?)
+                }
+                ["connect", d] => InputEvent::PadConnected(idx(d)?),
+                ["disconnect", d] => InputEvent::PadDisconnected(idx(d)?),
+                _ => return Err(bad()),
+            };
+            match frames.back_mut() {
+                Some((_, events)) => events.push(ev),
+                None => return Err((n + 1, "event before the first frame".to_owned())),
+            }
+        }
+        Ok(Self { frames })
+    }
+
+    /// The next frame's dt and input, `None` once the recording is over.
+    pub fn next_frame(&mut self) -> Option<(f32, Vec<InputEvent>)> {
+        self.frames.pop_front()
+    }
+}
+
+fn mouse_button(name: &str) -> Option<MouseButton> {
+    Some(match name {
+        "Left" => MouseButton::Left,
+        "Right" => MouseButton::Right,
+        "Middle" => MouseButton::Middle,
+        "Back" => MouseButton::Back,
+        "Forward" => MouseButton::Forward,
+        n => MouseButton::Other(n.parse().ok()?),
+    })
+}
@@ -0,0 +1,306 @@
+//! Player options saved between runs, see [`Settings`].
+//!
+//! The file is text, a `schema <n>` line followed by one option per line:
+//!
+//! ```text
+//! schema 1
+//! master_volume 0.8
+//! window 1920 1080
+//! texture_filter Nearest
+//! bind jump Space
+//! custom difficulty hard
+//! ```
+use std::{
+    env,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use jester_core::{
+    key_from_name, key_name, ButtonState, Error, InputState, PresentMode, QualitySettings,
+    TextureFilter,
+};
+use tracing::warn;
+use winit::keyboard::KeyCode;
+
+/// Rewrites the options of a file saved with an older schema, see [`Settings::with_schema`].
+pub type Migration = fn(from: u32, options: &mut Vec<(String, String)>);
+
+/// Options the player changes in a settings menu, saved to the platform's
+/// config directory and applied with `App::apply_settings`.
+///
+/// Options missing from the file keep the values set before
+/// [`Settings::load`], so new options and keybinds get their defaults when
+/// an older file is read. Volumes are kept for the game's audio, keybinds
+/// by action name for its input handling.
+///
+/// ```ignore
+/// let settings = Settings::new("game")
+///     .with_bind("jump", KeyCode::Space)
+///     .with_schema(2, |from, options| {
+///         // schema 1 called it `interact`
+///         if from < 2 {
+///             for (name, value) in options.iter_mut() {
+///                 if name == "bind" && value.starts_with("interact ") {
+///                     *value = value.replacen("interact", "use", 1);
+///                 }
+///             }
+///         }
+///     })
+///     .load()?;
+/// app.apply_settings(settings);
+/// // options menu
+/// let settings = ctx.resources.get_mut::<Settings>().unwrap();
+/// settings.bind("jump", KeyCode::KeyW);
+/// settings.save()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// 0..1, like the other volumes.
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub effects_volume: f32,
+    /// Window size in pixels, the app's default when `None`.
+    pub window_size: Option<(u32, u32)>,
+    pub fullscreen: bool,
+    pub present_mode: PresentMode,
+    pub quality: QualitySettings,
+    keybinds: Vec<(String, KeyCode)>,
+    custom: Vec<(String, String)>,
+    path: PathBuf,
+    schema: u32,
+    migrate: Option<Migration>,
+}
+
+impl Settings {
+    /// Defaults saved to `settings.txt` in `app_name`'s config directory, see [`config_dir`].
+    pub fn new(app_name: &str) -> Self {
+        let dir = config_dir(app_name).unwrap_or_default();
+        Self::at(dir.join("settings.txt"))
+    }
+
+    /// Defaults saved to `path`.
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 1.0,
+            effects_volume: 1.0,
+            window_size: None,
+            fullscreen: false,
+            present_mode: PresentMode::default(),
+            quality: QualitySettings::default(),
+            keybinds: Vec::new(),
+            custom: Vec::new(),
+            path: path.into(),
+            schema: 1,
+            migrate: None,
+        }
+    }
+
+    /// Default key of `action`.
+    pub fn with_bind(mut self, action: &str, key: KeyCode) -> Self {
+        self.bind(action, key);
+        self
+    }
+
+    /// Version of the game's options, 1 by default; files saved with a lower
+    /// one go through `migrate` before they are read.
+    pub fn with_schema(mut self, schema: u32, migrate: Migration) -> Self {
+        self.schema = schema;
+        self.migrate = Some(migrate);
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn key(&self, action: &str) -> Option<KeyCode> {
+        self.keybinds
+            .iter()
+            .find(|(a, _)| a == action)
+            .map(|(_, k)| *k)
+    }
+
+    /// State of the key bound to `action`, released when it has none.
+    pub fn action(&self, action: &str, input: &InputState) -> ButtonState {
+        self.key(action)
+            .map_or_else(ButtonState::default, |k| input.key(k))
+    }
+
+    pub fn bind(&mut self, action: &str, key: KeyCode) {
+        match self.keybinds.iter_mut().find(|(a, _)| a == action) {
+            Some((_, k)) => *k = key,
+            None => self.keybinds.push((action.to_owned(), key)),
+        }
+    }
+
+    pub fn keybinds(&self) -> impl Iterator<Item = (&str, KeyCode)> {
+        self.keybinds.iter().map(|(a, k)| (a.as_str(), *k))
+    }
+
+    /// A game-specific option, saved as text.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.custom
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Sets a game-specific option; `value` must fit on one line.
+    pub fn set(&mut self, name: &str, value: impl ToString) {
+        let value = value.to_string();
+        match self.custom.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = value,
+            None => self.custom.push((name.to_owned(), value)),
+        }
+    }
+
+    /// Reads the saved options over these, keeping them when nothing was saved yet.
+    ///
+    /// Options that do not parse are skipped with a warning rather than
+    /// failing, so a hand-edited file never keeps the game from starting.
+    pub fn load(mut self) -> Result<Self, Error> {
+        let src = match std::fs::read_to_string(&self.path) {
+            Ok(src) => src,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(self),
+            Err(e) => return Err(e.into()),
+        };
+        let mut schema = 1;
+        let mut options = Vec::new();
+        for line in src.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let (name, value) = line.split_once(' ').unwrap_or((line, ""));
+            match name {
+                "schema" => {
+                    schema = value.trim().parse().map_err(|_| {
+                        let msg = format!("{}: bad schema `{value}`", self.path.display());
+                        Error::Settings(msg)
+                    })?
+                }
+                _ => options.push((name.to_owned(), value.trim().to_owned())),
+            }
+        }
+        if schema < self.schema
+            && let Some(migrate) = self.migrate
+        {
+            migrate(schema, &mut options);
+        }
+        for (name, value) in &options {
+            if self.read_option(name, value).is_none() {
+                warn!("{}: ignoring option `{name} {value}`", self.path.display());
+            }
+        }
+        Ok(self)
+    }
+
+    /// Writes the options to [`Settings::path`], creating its directory.
+    pub fn save(&self) -> Result<(), Error> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        // written next to the file and moved over it, so a crash never leaves half a file
+        let tmp = self.path.with_extension("tmp");
+        let mut out = io::BufWriter::new(std::fs::File::create(&tmp)?);
+        writeln!(out, "schema {}", self.schema)?;
+        writeln!(out, "master_volume {}", self.master_volume)?;
+        writeln!(out, "music_volume {}", self.music_volume)?;
+        writeln!(out, "effects_volume {}", self.effects_volume)?;
+        if let Some((w, h)) = self.window_size {
+            writeln!(out, "window {w} {h}")?;
+        }
+        writeln!(out, "fullscreen {}", self.fullscreen as u8)?;
+        writeln!(out, "present_mode {:?}", self.present_mode)?;
+        writeln!(out, "texture_filter {:?}", self.quality.texture_filter)?;
+        writeln!(out, "particle_density {}", self.quality.particle_density)?;
+        writeln!(out, "post_effects {}", self.quality.post_effects as u8)?;
+        for (action, key) in &self.keybinds {
+            if let Some(name) = key_name(*key) {
+                writeln!(out, "bind {action} {name}")?;
+            }
+        }
+        for (name, value) in &self.custom {
+            writeln!(out, "custom {name} {value}")?;
+        }
+        out.into_inner().map_err(io::IntoInnerError::into_error)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    fn read_option(&mut self, name: &str, value: &str) -> Option<()> {
+        let flag = |v: &str| match v {
+            "1" => Some(true),
+            "0" => Some(false),
+            _ => None,
+        };
+        let volume = |v: &str| v.parse::<f32>().ok().map(|v| v.clamp(0.0, 1.0));
+        match name {
+            "master_volume" => self.master_volume = volume(value)?,
+            "music_volume" => self.music_volume = volume(value)?,
+            "effects_volume" => self.effects_volume = volume(value)?,
+            "window" => {
+                let (w, h) = value.split_once(' ')?;
+                let (w, h) = (w.parse().ok()?, h.trim().parse().ok()?);
+                if w == 0 || h == 0 {
+                    return None;
+                }
+                self.window_size = Some((w, h));
+            }
+            "fullscreen" => self.fullscreen = flag(value)?,
+            // written before present modes could be chosen
+            "vsync" => {
+                self.present_mode = match flag(value)? {
+                    true => PresentMode::Fifo,
+                    false => PresentMode::Mailbox,
+                }
+            }
+            "present_mode" => {
+                self.present_mode = match value {
+                    "Fifo" => PresentMode::Fifo,
+                    "Mailbox" => PresentMode::Mailbox,
+                    "Immediate" => PresentMode::Immediate,
+                    _ => return None,
+                }
+            }
+            "texture_filter" => {
+                self.quality.texture_filter = match value {
+                    "Nearest" => TextureFilter::Nearest,
+                    "Linear" => TextureFilter::Linear,
+                    _ => return None,
+                }
+            }
+            // written while quality settings had a sample count no backend honored
+            "msaa" => {}
+            "particle_density" => self.quality.particle_density = volume(value)?,
+            "post_effects" => self.quality.post_effects = flag(value)?,
+            "bind" => {
+                let (action, key) = value.split_once(' ')?;
+                self.bind(action, key_from_name(key.trim())?);
+            }
+            "custom" => {
+                let (name, value) = value.split_once(' ').unwrap_or((value, ""));
+                self.set(name, value);
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+}
+
+/// `app_name`'s directory under the platform's per-user config directory:
+/// `%APPDATA%` on Windows, `~/Library/Application Support` on macOS and
+/// `$XDG_CONFIG_HOME` or `~/.config` elsewhere.
+pub fn config_dir(app_name: &str) -> Option<PathBuf> {
+    let var = |name| {
+        env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    let base = if cfg!(windows) {
+        var("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|h| h.join("Library/Application Support"))
+    } else {
+        var("XDG_CONFIG_HOME").or_else(|| var("HOME").map(|h| h.join(".config")))
+    };
+    Some(base?.join(app_name))
+}
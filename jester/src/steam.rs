@@ -0,0 +1,195 @@
+//! Steamworks hooks through the flat C API of the redistributable `steam_api` library.
+//!
+//! The library is loaded at runtime, so builds without the Steamworks SDK still link;
+//! [`Steam::init`] fails cleanly when it is missing or the Steam client is not running.
+use std::{
+    ffi::{c_char, c_int, c_void, CStr, CString},
+    path::PathBuf,
+};
+
+use jester_core::Error;
+use libloading::Library;
+use tracing::{info, warn};
+
+#[cfg(all(windows, target_pointer_width = "64"))]
+const LIB_NAME: &str = "steam_api64.dll";
+#[cfg(all(windows, target_pointer_width = "32"))]
+const LIB_NAME: &str = "steam_api.dll";
+#[cfg(target_os = "macos")]
+const LIB_NAME: &str = "libsteam_api.dylib";
+#[cfg(not(any(windows, target_os = "macos")))]
+const LIB_NAME: &str = "libsteam_api.so";
+
+// interface accessor versions of the Steamworks SDK this module targets (1.58+)
+const USER_STATS: &[u8] = b"SteamAPI_SteamUserStats_v012\0";
+const FRIENDS: &[u8] = b"SteamAPI_SteamFriends_v017\0";
+const USER: &[u8] = b"SteamAPI_SteamUser_v023\0";
+
+type InitFlat = unsafe extern "C" fn(err: *mut c_char) -> c_int;
+type Init = unsafe extern "C" fn() -> bool;
+type Void = unsafe extern "C" fn();
+type Accessor = unsafe extern "C" fn() -> *mut c_void;
+type StatsFn = unsafe extern "C" fn(*mut c_void, *const c_char) -> bool;
+type StoreFn = unsafe extern "C" fn(*mut c_void) -> bool;
+type PresenceFn = unsafe extern "C" fn(*mut c_void, *const c_char, *const c_char) -> bool;
+type ClearPresenceFn = unsafe extern "C" fn(*mut c_void);
+type DataFolderFn = unsafe extern "C" fn(*mut c_void, *mut c_char, c_int) -> bool;
+
+/// A live Steamworks session, shut down on drop.
+///
+/// Register it with [`App::enable_steam`](crate::App::enable_steam); scenes reach it
+/// through `ctx.resources.get_mut::<Steam>()`.
+pub struct Steam {
+    lib: Library,
+    user_stats: *mut c_void,
+    friends: *mut c_void,
+    user: *mut c_void,
+    stats_dirty: bool,
+}
+
+// the Steamworks interfaces are thread safe, the App drives callbacks from one thread
+unsafe impl Send for Steam {}
+unsafe impl Sync for Steam {}
+
+impl Steam {
+    /// Loads `steam_api` and initializes it.
+    ///
+    /// During development put a `steam_appid.txt` next to the executable, otherwise
+    /// Steam refuses to initialize games not launched from the client.
+    pub fn init() -> Result<Self, Error> {
+        let lib = unsafe { Library::new(LIB_NAME) }
+            .map_err(|e| Error::Steam(format!("{LIB_NAME}: {e}")))?;
+        unsafe {
+            if let Ok(init) = lib.get::<InitFlat>(b"SteamAPI_InitFlat\0") {
+                let mut err = [0 as c_char; 1024];
+                if init(err.as_mut_ptr()) != 0 {
+                    let msg = CStr::from_ptr(err.as_ptr()).to_string_lossy();
+                    return Err(Error::Steam(msg.into_owned()));
+                }
+            } else {
+                let init = lib
+                    .get::<Init>(b"SteamAPI_Init\0")
+                    .map_err(|e| Error::Steam(e.to_string()))?;
+                if !init() {
+                    return Err(Error::Steam("SteamAPI_Init failed".into()));
+                }
+            }
+        }
+        let accessor = |name: &[u8]| unsafe {
+            lib.get::<Accessor>(name)
+                .map(|f| f())
+                .unwrap_or(std::ptr::null_mut())
+        };
+        let user_stats = accessor(USER_STATS);
+        let friends = accessor(FRIENDS);
+        let user = accessor(USER);
+        info!("Steam initialized");
+        Ok(Self {
+            lib,
+            user_stats,
+            friends,
+            user,
+            stats_dirty: false,
+        })
+    }
+
+    /// Pumps Steam callbacks and flushes pending achievement changes, called once per frame.
+    pub fn run_callbacks(&mut self) {
+        self.call::<Void, _>(b"SteamAPI_RunCallbacks\0", |f| unsafe { f() });
+        if self.stats_dirty {
+            self.stats_dirty = false;
+            let stats = self.user_stats;
+            let stored = self
+                .call::<StoreFn, _>(b"SteamAPI_ISteamUserStats_StoreStats\0", |f| unsafe {
+                    f(stats)
+                });
+            if stored != Some(true) {
+                warn!("Steam StoreStats failed");
+            }
+        }
+    }
+
+    /// Unlocks an achievement by its API name; stored on the next frame.
+    pub fn unlock_achievement(&mut self, name: &str) -> bool {
+        self.set_stat(b"SteamAPI_ISteamUserStats_SetAchievement\0", name)
+    }
+
+    pub fn clear_achievement(&mut self, name: &str) -> bool {
+        self.set_stat(b"SteamAPI_ISteamUserStats_ClearAchievement\0", name)
+    }
+
+    /// Sets a rich presence key, e.g. `("steam_display", "#Status_InLevel")`.
+    pub fn set_rich_presence(&mut self, key: &str, value: &str) -> bool {
+        let (Ok(key), Ok(value)) = (CString::new(key), CString::new(value)) else {
+            return false;
+        };
+        let friends = self.friends;
+        if friends.is_null() {
+            return false;
+        }
+        self.call::<PresenceFn, _>(b"SteamAPI_ISteamFriends_SetRichPresence\0", |f| unsafe {
+            f(friends, key.as_ptr(), value.as_ptr())
+        })
+        .unwrap_or(false)
+    }
+
+    pub fn clear_rich_presence(&mut self) {
+        let friends = self.friends;
+        if friends.is_null() {
+            return;
+        }
+        self.call::<ClearPresenceFn, _>(
+            b"SteamAPI_ISteamFriends_ClearRichPresence\0",
+            |f| unsafe { f(friends) },
+        );
+    }
+
+    /// The per-user, per-game folder Steam manages; point Auto-Cloud at saves written here.
+    pub fn cloud_save_dir(&self) -> Option<PathBuf> {
+        let user = self.user;
+        if user.is_null() {
+            return None;
+        }
+        let mut buf = [0 as c_char; 4096];
+        let ok = self
+            .call::<DataFolderFn, _>(b"SteamAPI_ISteamUser_GetUserDataFolder\0", |f| unsafe {
+                f(user, buf.as_mut_ptr(), buf.len() as c_int)
+            });
+        if ok != Some(true) {
+            return None;
+        }
+        let path = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        Some(PathBuf::from(path.to_string_lossy().into_owned()))
+    }
+
+    fn set_stat(&mut self, symbol: &[u8], name: &str) -> bool {
+        let Ok(name) = CString::new(name) else {
+            return false;
+        };
+        let stats = self.user_stats;
+        if stats.is_null() {
+            return false;
+        }
+        let ok = self
+            .call::<StatsFn, _>(symbol, |f| unsafe { f(stats, name.as_ptr()) })
+            .unwrap_or(false);
+        self.stats_dirty |= ok;
+        ok
+    }
+
+    fn call<F: Copy, R>(&self, symbol: &[u8], f: impl FnOnce(F) -> R) -> Option<R> {
+        match unsafe { self.lib.get::<F>(symbol) } {
+            Ok(sym) => Some(f(*sym)),
+            Err(e) => {
+                warn!("Steam symbol missing: {e}");
+                None
+            }
+        }
+    }
+}
+
+impl Drop for Steam {
+    fn drop(&mut self) {
+        self.call::<Void, _>(b"SteamAPI_Shutdown\0", |f| unsafe { f() });
+    }
+}
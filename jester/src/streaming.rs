@@ -0,0 +1,239 @@
+use std::path::{Path, PathBuf};
+
+use glam::{IVec2, Vec2};
+use hashbrown::HashMap;
+use jester_core::{Aabb, Color, Ctx, DebugDraw, EntityId, Sprite, TextureId};
+use tracing::warn;
+
+/// One sprite of a chunk, positioned relative to the chunk origin.
+#[derive(Debug, Clone)]
+pub struct ChunkSprite {
+    pub texture: PathBuf,
+    pub sprite: Sprite,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Chunk {
+    pub sprites: Vec<ChunkSprite>,
+}
+
+impl Chunk {
+    /// Parses the text chunk format: one `texture x y [layer]` line per sprite,
+    /// `#` starts a comment.
+    pub fn parse(src: &str) -> Self {
+        let mut sprites = Vec::new();
+        for (n, line) in src.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let parsed = match parts.as_slice() {
+                [tex, x, y] => x.parse().ok().zip(y.parse().ok()).map(|p| (tex, p, 0)),
+                [tex, x, y, layer] => x
+                    .parse()
+                    .ok()
+                    .zip(y.parse().ok())
+                    .zip(layer.parse().ok())
+                    .map(|(p, l)| (tex, p, l)),
+                _ => None,
+            };
+            let Some((tex, (x, y), layer)) = parsed else {
+                warn!("chunk line {}: expected `texture x y [layer]`", n + 1);
+                continue;
+            };
+            let mut sprite = Sprite {
+                layer,
+                ..Default::default()
+            };
+            sprite.transform.translation = Vec2::new(x, y);
+            sprites.push(ChunkSprite {
+                texture: PathBuf::from(tex),
+                sprite,
+            });
+        }
+        Self { sprites }
+    }
+}
+
+pub type ChunkGenerator = Box<dyn FnMut(IVec2) -> Chunk + Send>;
+
+pub enum ChunkSource {
+    /// `{dir}/{x}_{y}.chunk` files, missing files are empty chunks.
+    Directory(PathBuf),
+    Generator(ChunkGenerator),
+}
+
+impl ChunkSource {
+    pub fn generator<F: FnMut(IVec2) -> Chunk + Send + 'static>(f: F) -> Self {
+        Self::Generator(Box::new(f))
+    }
+
+    fn load(&mut self, coord: IVec2) -> Chunk {
+        match self {
+            Self::Directory(dir) => {
+                let path = dir.join(format!("{}_{}.chunk", coord.x, coord.y));
+                match std::fs::read_to_string(&path) {
+                    Ok(src) => Chunk::parse(&src),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Chunk::default(),
+                    Err(e) => {
+                        warn!("failed to read {}: {e}", path.display());
+                        Chunk::default()
+                    }
+                }
+            }
+            Self::Generator(f) => f(coord),
+        }
+    }
+}
+
+struct LoadedChunk {
+    entities: Vec<EntityId>,
+    textures: Vec<TextureId>,
+}
+
+/// Keeps the chunks around a focus point (usually the camera or player) spawned.
+///
+/// Chunks within `load_radius` of the focus chunk are spawned, chunks beyond
+/// `unload_radius` are despawned; the gap between the two avoids thrashing at
+/// chunk borders. Textures are reference counted across chunks so each one is
+/// requested once while any loaded chunk uses it, and unloaded with the
+/// last chunk using it; sprites outside the chunks should not share them.
+pub struct ChunkStreamer {
+    pub chunk_size: f32,
+    pub load_radius: i32,
+    pub unload_radius: i32,
+    source: ChunkSource,
+    loaded: HashMap<IVec2, LoadedChunk>,
+    texture_refs: HashMap<TextureId, usize>,
+}
+
+impl ChunkStreamer {
+    pub fn new(source: ChunkSource, chunk_size: f32) -> Self {
+        Self {
+            chunk_size,
+            load_radius: 1,
+            unload_radius: 2,
+            source,
+            loaded: HashMap::new(),
+            texture_refs: HashMap::new(),
+        }
+    }
+
+    pub fn with_radius(mut self, load: i32, unload: i32) -> Self {
+        self.load_radius = load;
+        self.unload_radius = unload.max(load);
+        self
+    }
+
+    pub fn chunk_at(&self, world: Vec2) -> IVec2 {
+        (world / self.chunk_size).floor().as_ivec2()
+    }
+
+    pub fn is_loaded(&self, coord: IVec2) -> bool {
+        self.loaded.contains_key(&coord)
+    }
+
+    pub fn loaded_chunks(&self) -> impl Iterator<Item = IVec2> + '_ {
+        self.loaded.keys().copied()
+    }
+
+    /// The world area of the chunk at `coord`.
+    pub fn chunk_bounds(&self, coord: IVec2) -> Aabb {
+        let min = coord.as_vec2() * self.chunk_size;
+        Aabb::new(min, min + Vec2::splat(self.chunk_size))
+    }
+
+    /// Outlines the loaded chunks, e.g. with `ctx.debug_draw()` next to a
+    /// [`CullOverlay`](crate::prelude::CullOverlay) to check what streams in.
+    pub fn draw_chunks(&self, draw: &mut DebugDraw, color: Color) {
+        for &coord in self.loaded.keys() {
+            draw.rect(self.chunk_bounds(coord), color);
+        }
+    }
+
+    /// Textures referenced by at least one loaded chunk.
+    pub fn resident_textures(&self) -> impl Iterator<Item = TextureId> + '_ {
+        self.texture_refs.keys().copied()
+    }
+
+    pub fn update(&mut self, ctx: &mut Ctx<'_>, focus: Vec2) {
+        let center = self.chunk_at(focus);
+
+        let stale: Vec<IVec2> = self
+            .loaded
+            .keys()
+            .copied()
+            .filter(|c| (*c - center).abs().max_element() > self.unload_radius)
+            .collect();
+        for coord in stale {
+            self.unload(ctx, coord);
+        }
+
+        let r = self.load_radius;
+        for y in -r..=r {
+            for x in -r..=r {
+                let coord = center + IVec2::new(x, y);
+                if !self.loaded.contains_key(&coord) {
+                    self.load(ctx, coord);
+                }
+            }
+        }
+    }
+
+    /// Despawns every loaded chunk, e.g. before leaving the scene.
+    pub fn clear(&mut self, ctx: &mut Ctx<'_>) {
+        let all: Vec<IVec2> = self.loaded.keys().copied().collect();
+        for coord in all {
+            self.unload(ctx, coord);
+        }
+    }
+
+    fn load(&mut self, ctx: &mut Ctx<'_>, coord: IVec2) {
+        let chunk = self.source.load(coord);
+        let origin = coord.as_vec2() * self.chunk_size;
+        let mut loaded = LoadedChunk {
+            entities: Vec::with_capacity(chunk.sprites.len()),
+            textures: Vec::new(),
+        };
+        for ChunkSprite {
+            texture,
+            mut sprite,
+        } in chunk.sprites
+        {
+            sprite.tex = self.acquire_texture(ctx, &texture);
+            sprite.transform.translation += origin;
+            loaded.textures.push(sprite.tex);
+            loaded.entities.push(ctx.spawn_sprite(sprite));
+        }
+        self.loaded.insert(coord, loaded);
+    }
+
+    fn unload(&mut self, ctx: &mut Ctx<'_>, coord: IVec2) {
+        let Some(chunk) = self.loaded.remove(&coord) else {
+            return;
+        };
+        for id in chunk.entities {
+            ctx.despawn(id);
+        }
+        for tex in chunk.textures {
+            if let Some(n) = self.texture_refs.get_mut(&tex) {
+                *n -= 1;
+                if *n == 0 {
+                    self.texture_refs.remove(&tex);
+                    ctx.unload_asset(tex);
+                }
+            }
+        }
+    }
+
+    fn acquire_texture(&mut self, ctx: &mut Ctx<'_>, path: &Path) -> TextureId {
+        let id = TextureId::from_path(path);
+        let refs = self.texture_refs.entry(id).or_insert(0);
+        if *refs == 0 {
+            ctx.load_asset(path);
+        }
+        *refs += 1;
+        id
+    }
+}
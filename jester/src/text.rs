@@ -0,0 +1,73 @@
+use jester_core::{
+    Backend, EntityId, EntityPool, GlyphAtlas, Renderer, SpriteBatch, Text, TextStyle,
+};
+use tracing::warn;
+
+/// Glyph atlases of the pool's [`Text`]s, one per font, size and color.
+#[derive(Default)]
+pub(crate) struct TextAtlases {
+    // kept once created: their texture slots cannot be freed
+    atlases: Vec<(TextStyle, GlyphAtlas)>,
+}
+
+impl TextAtlases {
+    /// Adds the glyphs of every text to its atlas and uploads atlases that changed.
+    ///
+    /// Must run before the frame begins, re-uploading waits for the GPU.
+    pub fn prepare<B: Backend>(&mut self, renderer: &mut Renderer<B>, pool: &EntityPool) {
+        for t in pool.texts.values() {
+            let i = match self.find(&t.style) {
+                Some(i) => i,
+                None => {
+                    let atlas = GlyphAtlas::new(&t.style.font, t.style.size);
+                    self.atlases.push((t.style.clone(), atlas));
+                    self.atlases.len() - 1
+                }
+            };
+            self.atlases[i].1.prepare(&t.text);
+        }
+        for (style, atlas) in &mut self.atlases {
+            if !atlas.finish_frame() {
+                continue;
+            }
+            // the atlas is white, its texels take the color instead of every instance
+            let c = style.color;
+            let mut pixels = atlas.pixels().to_vec();
+            for px in pixels.chunks_exact_mut(4) {
+                px[0] = (c.r.clamp(0.0, 1.0) * 255.0) as u8;
+                px[1] = (c.g.clamp(0.0, 1.0) * 255.0) as u8;
+                px[2] = (c.b.clamp(0.0, 1.0) * 255.0) as u8;
+                px[3] = (px[3] as f32 * c.a.clamp(0.0, 1.0)) as u8;
+            }
+            let (tex, w, h) = (atlas.tex, atlas.width, atlas.height);
+            if let Err(e) = renderer.update_texture(tex, w, h, &pixels) {
+                warn!("failed to upload glyph atlas: {e}");
+            }
+        }
+    }
+
+    /// One batch per run of texts sharing an atlas, by layer and then spawn order.
+    pub fn batches(&self, pool: &EntityPool) -> Vec<SpriteBatch> {
+        let mut texts: Vec<(&EntityId, &Text)> = pool.texts.iter().collect();
+        texts.sort_by_key(|(id, t)| (t.layer, id.to_raw()));
+        let mut out: Vec<SpriteBatch> = Vec::new();
+        for (_, t) in texts {
+            let Some(i) = self.find(&t.style) else {
+                continue;
+            };
+            let atlas = &self.atlases[i].1;
+            let instances = atlas.layout(&t.text, t.top_left);
+            match out.last_mut() {
+                Some(b) if b.tex == atlas.tex => b.instances.extend(instances),
+                _ => out.push(SpriteBatch::new(atlas.tex, instances)),
+            }
+        }
+        out
+    }
+
+    fn find(&self, style: &TextStyle) -> Option<usize> {
+        self.atlases.iter().position(|(s, _)| {
+            s.font.ptr_eq(&style.font) && s.size == style.size && s.color == style.color
+        })
+    }
+}
@@ -0,0 +1,122 @@
+use jester_core::WindowPlacement;
+use tracing::warn;
+use winit::window::{Fullscreen, Window, WindowAttributes, WindowLevel};
+
+/// Window options applied when the window is created.
+///
+/// The defaults give a regular decorated, resizable game window; the overlay
+/// options are meant for desktop tools such as stream widgets.
+#[derive(Debug, Clone)]
+pub struct WindowConfig {
+    pub size: Option<(u32, u32)>,
+    pub resizable: bool,
+    pub decorations: bool,
+    pub always_on_top: bool,
+    /// Hides the window from the taskbar (Windows) or marks it a utility window (X11).
+    pub skip_taskbar: bool,
+    /// Lets mouse input pass through to whatever is below the window.
+    pub click_through: bool,
+    /// Borderless fullscreen on the current monitor.
+    pub fullscreen: bool,
+    pub placement: WindowPlacement,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            size: None,
+            resizable: true,
+            decorations: true,
+            always_on_top: false,
+            skip_taskbar: false,
+            click_through: false,
+            fullscreen: false,
+            placement: WindowPlacement::Default,
+        }
+    }
+}
+
+impl WindowConfig {
+    /// Borderless, always-on-top, skip-taskbar and click-through.
+    pub fn overlay() -> Self {
+        Self {
+            decorations: false,
+            always_on_top: true,
+            skip_taskbar: true,
+            click_through: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_size(mut self, w: u32, h: u32) -> Self {
+        self.size = Some((w, h));
+        self
+    }
+
+    pub fn with_fullscreen(mut self, fullscreen: bool) -> Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    pub fn with_placement(mut self, placement: WindowPlacement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    pub(crate) fn attributes(&self, title: &str) -> WindowAttributes {
+        let mut attrs = Window::default_attributes()
+            .with_title(title)
+            .with_resizable(self.resizable)
+            .with_decorations(self.decorations);
+        if let Some((w, h)) = self.size {
+            attrs = attrs.with_inner_size(winit::dpi::PhysicalSize::new(w, h));
+        }
+        if self.fullscreen {
+            attrs = attrs.with_fullscreen(Some(Fullscreen::Borderless(None)));
+        }
+        if self.always_on_top {
+            attrs = attrs.with_window_level(WindowLevel::AlwaysOnTop);
+        }
+        if self.skip_taskbar {
+            attrs = skip_taskbar(attrs);
+        }
+        attrs
+    }
+
+    /// Options that can only be set on a live window.
+    pub(crate) fn apply(&self, win: &Window) {
+        self.placement.apply(win);
+        if self.click_through
+            && let Err(e) = win.set_cursor_hittest(false)
+        {
+            warn!("Click-through is not supported here: {e}");
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn skip_taskbar(attrs: WindowAttributes) -> WindowAttributes {
+    use winit::platform::windows::WindowAttributesExtWindows;
+    attrs.with_skip_taskbar(true)
+}
+
+#[cfg(all(
+    unix,
+    not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+))]
+fn skip_taskbar(attrs: WindowAttributes) -> WindowAttributes {
+    use winit::platform::x11::{WindowAttributesExtX11, WindowType};
+    attrs.with_x11_window_type(vec![WindowType::Utility])
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+    )
+)))]
+fn skip_taskbar(attrs: WindowAttributes) -> WindowAttributes {
+    warn!("skip_taskbar is not supported on this platform");
+    attrs
+}
@@ -0,0 +1,61 @@
+#![cfg(all(feature = "null", not(feature = "vulkan")))]
+
+use std::sync::{Arc, Mutex};
+
+use jester::prelude::*;
+use jester::DefaultBackend;
+
+#[derive(Default)]
+struct Level {
+    tex: Arc<Mutex<Option<TextureId>>>,
+    updates: u32,
+}
+
+impl Scene for Level {
+    fn start(&mut self, ctx: &mut Ctx<'_>) {
+        let tex = ctx.create_texture_from_rgba(1, 1, &[255; 4]);
+        for x in 0..3 {
+            ctx.spawn_sprite(Sprite {
+                tex,
+                transform: Transform::from_xy(x as f32 * 10.0, 0.0),
+                ..Default::default()
+            });
+        }
+        ctx.spawn_camera(Camera::default());
+        *self.tex.lock().unwrap() = Some(tex);
+    }
+
+    fn update(&mut self, ctx: &mut Ctx<'_>) {
+        self.updates += 1;
+        if self.updates == 3 {
+            ctx.quit();
+        }
+    }
+}
+
+#[test]
+fn steps_scenes_without_a_window() {
+    let mut app = App::headless("headless".into(), DefaultBackend::new(640, 480));
+    let level = Level::default();
+    let tex = level.tex.clone();
+    app.add_scene(level);
+    app.set_start_scene::<Level>();
+
+    assert!(app.step(1.0 / 60.0));
+    assert!(app.step(1.0 / 60.0));
+    assert!(!app.step(1.0 / 60.0));
+    assert_eq!(app.frame_count(), 3);
+
+    let backend = app.renderer().unwrap().backend();
+    assert_eq!(backend.frames(), 3);
+    assert_eq!(backend.instances_of(tex.lock().unwrap().unwrap()), 3);
+}
+
+#[test]
+fn step_needs_a_headless_app() {
+    let mut app = App::new("windowed".into());
+    app.add_scene(Level::default());
+    app.set_start_scene::<Level>();
+    assert!(!app.step(1.0 / 60.0));
+    assert_eq!(app.frame_count(), 0);
+}
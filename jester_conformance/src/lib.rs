@@ -0,0 +1,370 @@
+//! Behavior checks every [`Backend`] is expected to pass.
+//!
+//! Alternative backends run the suite from an integration test:
+//!
+//! ```text
+//! #[test]
+//! fn conformance() {
+//!     jester_conformance::run_windowed::<MyBackend>().assert_passed();
+//! }
+//! ```
+//!
+//! or call [`run`] on a renderer they already created.
+use std::{
+    fmt,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use glam::Vec2;
+use jester_core::{
+    Backend, BlendMode, Camera, Color, Fill, MaterialId, Panel, PanelInstance, Renderer,
+    SpriteBatch, SpriteInstance, TextureFormat, TextureId, Transform, Viewport, MAX_PANELS,
+    MAX_SPRITES, MAX_TEXTURES,
+};
+use winit::{
+    application::ApplicationHandler,
+    dpi::PhysicalSize,
+    event::WindowEvent,
+    event_loop::{ActiveEventLoop, EventLoop},
+    window::{Window, WindowId},
+};
+
+type Check<B> = fn(&mut Renderer<B>) -> Result<(), String>;
+
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    Pass,
+    Fail(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub backend: String,
+    pub results: Vec<CheckResult>,
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.results
+            .iter()
+            .all(|r| matches!(r.outcome, Outcome::Pass))
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &CheckResult> {
+        self.results
+            .iter()
+            .filter(|r| matches!(r.outcome, Outcome::Fail(_)))
+    }
+
+    /// Panics with every failed check, for use inside `#[test]`.
+    pub fn assert_passed(&self) {
+        assert!(self.passed(), "{self}");
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "conformance report for {}", self.backend)?;
+        for r in &self.results {
+            match &r.outcome {
+                Outcome::Pass => writeln!(f, "  ok   {}", r.name)?,
+                Outcome::Fail(msg) => writeln!(f, "  FAIL {}: {msg}", r.name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs every check against `renderer`.
+///
+/// The renderer is left usable but holds up to [`MAX_TEXTURES`] textures afterwards.
+pub fn run<B: Backend>(renderer: &mut Renderer<B>) -> Report {
+    let checks: [(&'static str, Check<B>); 11] = [
+        ("empty frame", empty_frame),
+        ("empty batches", empty_batches),
+        ("unknown texture", unknown_texture),
+        ("max sprites", max_sprites),
+        ("sprite overflow", sprite_overflow),
+        ("max panels", max_panels),
+        ("cameras and viewports", cameras),
+        ("resize during frame", resize_during_frame),
+        ("oversized texture", oversized_texture),
+        ("texture formats", texture_formats),
+        ("texture limits", texture_limits),
+    ];
+    let mut report = Report {
+        backend: renderer.adapter_info().backend.to_string(),
+        results: Vec::new(),
+    };
+    for (name, check) in checks {
+        let outcome = match catch_unwind(AssertUnwindSafe(|| check(renderer))) {
+            Ok(Ok(())) => Outcome::Pass,
+            Ok(Err(msg)) => Outcome::Fail(msg),
+            Err(panic) => Outcome::Fail(format!("panicked: {}", panic_message(&panic))),
+        };
+        report.results.push(CheckResult { name, outcome });
+    }
+    report
+}
+
+/// Opens a window, creates a `Renderer<B>` for it and runs the suite.
+///
+/// The event loop may run off the main thread, as `#[test]`s do, except on
+/// macOS and iOS, where tests must run with `harness = false`.
+pub fn run_windowed<B: Backend>() -> Report {
+    struct Runner<B: Backend> {
+        report: Option<Report>,
+        _backend: std::marker::PhantomData<B>,
+    }
+
+    impl<B: Backend> ApplicationHandler for Runner<B> {
+        fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+            if self.report.is_some() {
+                return;
+            }
+            let attrs = Window::default_attributes()
+                .with_title("jester conformance")
+                .with_inner_size(PhysicalSize::new(640, 480));
+            let report = match event_loop.create_window(attrs) {
+                Ok(win) => match Renderer::<B>::new("jester conformance", &win) {
+                    Ok(mut r) => run(&mut r),
+                    Err(e) => failed_init(format!("backend init failed: {e}")),
+                },
+                Err(e) => failed_init(format!("window creation failed: {e}")),
+            };
+            self.report = Some(report);
+            event_loop.exit();
+        }
+
+        fn window_event(&mut self, _: &ActiveEventLoop, _: WindowId, _: WindowEvent) {}
+    }
+
+    let mut runner = Runner::<B> {
+        report: None,
+        _backend: std::marker::PhantomData,
+    };
+    let mut builder = EventLoop::builder();
+    #[cfg(all(
+        unix,
+        not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+    ))]
+    winit::platform::x11::EventLoopBuilderExtX11::with_any_thread(&mut builder, true);
+    #[cfg(windows)]
+    winit::platform::windows::EventLoopBuilderExtWindows::with_any_thread(&mut builder, true);
+    let res = builder.build().map(|eloop| eloop.run_app(&mut runner));
+    match (runner.report, res) {
+        (Some(report), _) => report,
+        (None, Err(e)) => failed_init(format!("event loop failed: {e}")),
+        (None, Ok(r)) => failed_init(format!("event loop exited early: {r:?}")),
+    }
+}
+
+fn failed_init(msg: String) -> Report {
+    Report {
+        backend: String::from("<uninitialized>"),
+        results: vec![CheckResult {
+            name: "init",
+            outcome: Outcome::Fail(msg),
+        }],
+    }
+}
+
+fn panic_message(panic: &Box<dyn std::any::Any + Send>) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| String::from("<non-string panic>"))
+}
+
+fn checker_texture<B: Backend>(r: &mut Renderer<B>) -> Result<TextureId, String> {
+    r.create_texture_from_fn(4, 4, |x, y| {
+        if (x + y) % 2 == 0 {
+            Color::WHITE
+        } else {
+            Color::BLACK
+        }
+    })
+    .map_err(|e| format!("create_texture failed: {e}"))
+}
+
+fn batch(tex: TextureId, n: usize) -> SpriteBatch {
+    SpriteBatch {
+        tex,
+        blend: BlendMode::Alpha,
+        palette: None,
+        cutout: false,
+        material: MaterialId::DEFAULT,
+        baked: None,
+        retained: None,
+        instances: (0..n)
+            .map(|i| SpriteInstance {
+                pos_size: [(i % 100) as f32 * 4.0, (i / 100) as f32 * 4.0, 4.0, 4.0],
+                ..Default::default()
+            })
+            .collect(),
+    }
+}
+
+fn frame<B: Backend>(r: &mut Renderer<B>, draw: impl FnOnce(&mut Renderer<B>)) {
+    r.begin_frame();
+    r.bind_camera(&Camera::screen_space());
+    draw(r);
+    r.end_frame();
+}
+
+fn empty_frame<B: Backend>(r: &mut Renderer<B>) -> Result<(), String> {
+    for _ in 0..3 {
+        r.begin_frame();
+        r.end_frame();
+    }
+    Ok(())
+}
+
+fn empty_batches<B: Backend>(r: &mut Renderer<B>) -> Result<(), String> {
+    let tex = checker_texture(r)?;
+    frame(r, |r| {
+        r.draw_sprites(&batch(tex, 0));
+        r.draw_panels(&[]);
+    });
+    Ok(())
+}
+
+fn unknown_texture<B: Backend>(r: &mut Renderer<B>) -> Result<(), String> {
+    frame(r, |r| r.draw_sprites(&batch(TextureId(u64::MAX - 1), 10)));
+    Ok(())
+}
+
+fn max_sprites<B: Backend>(r: &mut Renderer<B>) -> Result<(), String> {
+    let tex = checker_texture(r)?;
+    frame(r, |r| r.draw_sprites(&batch(tex, MAX_SPRITES)));
+    // several batches adding up to the limit
+    frame(r, |r| {
+        for _ in 0..4 {
+            r.draw_sprites(&batch(tex, MAX_SPRITES / 4));
+        }
+    });
+    Ok(())
+}
+
+fn sprite_overflow<B: Backend>(r: &mut Renderer<B>) -> Result<(), String> {
+    // past the per-frame limit instances may be dropped, but must not crash
+    let tex = checker_texture(r)?;
+    frame(r, |r| {
+        for _ in 0..3 {
+            r.draw_sprites(&batch(tex, MAX_SPRITES));
+        }
+    });
+    frame(r, |r| r.draw_sprites(&batch(tex, 1)));
+    Ok(())
+}
+
+fn max_panels<B: Backend>(r: &mut Renderer<B>) -> Result<(), String> {
+    let panel = Panel {
+        transform: Transform::from_xy(10.0, 10.0),
+        size: Vec2::splat(8.0),
+        fill: Fill::Solid(Color::WHITE),
+        ..Default::default()
+    };
+    let panels: Vec<PanelInstance> = vec![panel.instance(); MAX_PANELS + 16];
+    frame(r, |r| r.draw_panels(&panels));
+    Ok(())
+}
+
+fn cameras<B: Backend>(r: &mut Renderer<B>) -> Result<(), String> {
+    let tex = checker_texture(r)?;
+    let sprites = batch(tex, 64);
+    let halves = [
+        Viewport::new(0.0, 0.0, 0.5, 1.0),
+        Viewport::new(0.5, 0.0, 0.5, 1.0),
+    ];
+    r.begin_frame();
+    for vp in halves {
+        r.bind_camera(&Camera::screen_space().with_viewport(vp));
+        r.draw_sprites(&sprites);
+    }
+    let mut zoomed = Camera::pixel_perfect(640.0, 480.0);
+    zoomed.zoom = 3.0;
+    r.bind_camera(&zoomed);
+    r.draw_sprites(&sprites);
+    r.end_frame();
+    Ok(())
+}
+
+fn resize_during_frame<B: Backend>(r: &mut Renderer<B>) -> Result<(), String> {
+    let tex = checker_texture(r)?;
+    for size in [(800, 600), (800, 600), (320, 200), (0, 0), (640, 480)] {
+        r.begin_frame();
+        r.bind_camera(&Camera::screen_space());
+        r.handle_resize(PhysicalSize::new(size.0, size.1));
+        r.draw_sprites(&batch(tex, 16));
+        r.end_frame();
+        frame(r, |r| r.draw_sprites(&batch(tex, 16)));
+    }
+    Ok(())
+}
+
+fn oversized_texture<B: Backend>(r: &mut Renderer<B>) -> Result<(), String> {
+    // wider than the backend allows, so the renderer has to tile it
+    let w = r.backend().max_texture_size() + 1;
+    let tex = r
+        .create_texture_from_rgba(w, 2, &vec![255; (w * 2 * 4) as usize])
+        .map_err(|e| format!("{w}x2 texture failed: {e}"))?;
+    match r.texture_meta(tex) {
+        Some(m) if (m.w, m.h) == (w, 2) => {}
+        other => return Err(format!("{w}x2 texture reported {other:?}")),
+    }
+    frame(r, |r| r.draw_sprites(&batch(tex, 4)));
+    Ok(())
+}
+
+fn texture_formats<B: Backend>(r: &mut Renderer<B>) -> Result<(), String> {
+    for format in [
+        TextureFormat::Rgba8,
+        TextureFormat::Rgba16,
+        TextureFormat::R8,
+    ] {
+        let tex = TextureId::generated();
+        let texels = vec![255; 3 * 5 * format.bytes_per_texel()];
+        r.upload_texture_with_format(tex, 3, 5, format, &texels)
+            .map_err(|e| format!("{format:?} texture failed: {e}"))?;
+        match r.texture_meta(tex) {
+            Some(m) if (m.w, m.h) == (3, 5) => {}
+            other => return Err(format!("{format:?} texture reported {other:?}")),
+        }
+        frame(r, |r| r.draw_sprites(&batch(tex, 4)));
+    }
+    Ok(())
+}
+
+fn texture_limits<B: Backend>(r: &mut Renderer<B>) -> Result<(), String> {
+    // odd sizes must round-trip their metadata
+    for (w, h) in [(1, 1), (3, 5), (17, 1), (1, 33)] {
+        let tex = r
+            .create_texture_from_rgba(w, h, &vec![255; (w * h * 4) as usize])
+            .map_err(|e| format!("{w}x{h} texture failed: {e}"))?;
+        match r.texture_meta(tex) {
+            Some(m) if (m.w, m.h) == (w, h) => {}
+            other => return Err(format!("{w}x{h} texture reported {other:?}")),
+        }
+    }
+    // every texture up to the limit must be creatable and drawable
+    let mut last = None;
+    while r.texture_count() < MAX_TEXTURES {
+        let n = r.texture_count();
+        let tex = r
+            .create_texture_from_rgba(1, 1, &[255; 4])
+            .map_err(|e| format!("texture {n} of {MAX_TEXTURES} failed: {e}"))?;
+        last = Some(tex);
+    }
+    if let Some(tex) = last {
+        frame(r, |r| r.draw_sprites(&batch(tex, 1)));
+    }
+    Ok(())
+}
@@ -0,0 +1,8 @@
+use b_null::NullBackend;
+use jester_core::Renderer;
+
+#[test]
+fn null_backend_conforms() {
+    let mut r = Renderer::from_backend(NullBackend::new(640, 480));
+    jester_conformance::run(&mut r).assert_passed();
+}
@@ -0,0 +1,9 @@
+use b_vk::VkBackend;
+
+// needs a display and a Vulkan driver:
+// cargo test -p jester_conformance --test vulkan -- --ignored
+#[test]
+#[ignore = "opens a window on a Vulkan device"]
+fn vulkan_backend_conforms() {
+    jester_conformance::run_windowed::<VkBackend>().assert_passed();
+}
@@ -0,0 +1,52 @@
+use crate::{ColorVision, InputTimings};
+
+/// Player-facing accessibility options, honored by the app while registered
+/// as a resource with `App::add_resource`.
+///
+/// With the `accesskit` feature the UI set with [`Ctx::set_ui`](crate::Ctx::set_ui)
+/// is exposed to screen readers, labelled with [`UiNode::with_label`](crate::UiNode::with_label),
+/// and announcements are read out; without it they are only logged.
+#[derive(Clone, Debug, Default)]
+pub struct Accessibility {
+    /// Draws UI panels in black and white with strong borders and a yellow focus ring.
+    pub high_contrast: bool,
+    /// Announces the label of every UI node that gains focus, for games that
+    /// voice their own UI; screen readers already read the focused node.
+    pub announce_focus: bool,
+    /// Key and button repeat and hold timings, copied to `ctx.input` every frame.
+    pub input: InputTimings,
+    /// Colorblind filter for every camera whose own filter leaves color vision `Normal`.
+    pub color_vision: ColorVision,
+    announcements: Vec<String>,
+}
+
+impl Accessibility {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_high_contrast(mut self, high_contrast: bool) -> Self {
+        self.high_contrast = high_contrast;
+        self
+    }
+
+    pub fn with_input_timings(mut self, input: InputTimings) -> Self {
+        self.input = input;
+        self
+    }
+
+    pub fn with_color_vision(mut self, color_vision: ColorVision) -> Self {
+        self.color_vision = color_vision;
+        self
+    }
+
+    /// Queues `text` to be read out, e.g. "Level complete" or a pickup.
+    pub fn announce(&mut self, text: impl Into<String>) {
+        self.announcements.push(text.into());
+    }
+
+    /// Announcements queued since the last call, oldest first.
+    pub fn take_announcements(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.announcements)
+    }
+}
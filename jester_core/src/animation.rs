@@ -0,0 +1,160 @@
+use crate::EntityId;
+
+/// What an [`Animation`] does after its last frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LoopMode {
+    /// Starts over from the first frame.
+    #[default]
+    Loop,
+    /// Stays on the last frame, sending [`AnimationFinished`].
+    Once,
+    /// Plays backwards to the first frame, then forwards again.
+    PingPong,
+}
+
+/// Sent through `ctx.events` when a [`LoopMode::Once`] animation reaches its last frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AnimationFinished(pub EntityId);
+
+/// Flipbook animation of a sprite through UV rects of its texture.
+///
+/// Inserted into [`EntityPool::animations`](crate::EntityPool::animations),
+/// the app advances it every frame after the scenes update and sets the
+/// sprite's `uv` to the current frame.
+///
+/// ```ignore
+/// // one 32x32 cell of the sheet
+/// let hero = ctx.spawn_sprite(Sprite {
+///     tex: sheet,
+///     size: Some(Vec2::splat(32.0)),
+///     ..Default::default()
+/// });
+/// // 8 frames in the top row of a 8x4 sheet
+/// ctx.pool.animations.insert(hero, Animation::from_grid(8, 4, 0..8, 12.0));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Animation {
+    /// `uv` of each frame, see [`Sprite::uv`](crate::Sprite::uv).
+    pub frames: Vec<[f32; 4]>,
+    /// Frames per second of frames without a duration.
+    pub fps: f32,
+    /// Seconds each frame shows, e.g. from an Aseprite export; frames past
+    /// its end show for `1 / fps`.
+    pub durations: Vec<f32>,
+    pub mode: LoopMode,
+    /// Advances only while `true`.
+    pub playing: bool,
+    elapsed: f32,
+}
+
+impl Animation {
+    pub fn new(frames: Vec<[f32; 4]>, fps: f32) -> Self {
+        Self {
+            frames,
+            fps,
+            durations: Vec::new(),
+            mode: LoopMode::default(),
+            playing: true,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Frames from the cells `cells` of a sheet split into `columns` by
+    /// `rows` equal cells, numbered row by row from the top-left.
+    pub fn from_grid(
+        columns: u32,
+        rows: u32,
+        cells: impl IntoIterator<Item = u32>,
+        fps: f32,
+    ) -> Self {
+        let (w, h) = (1.0 / columns.max(1) as f32, 1.0 / rows.max(1) as f32);
+        let frames = cells
+            .into_iter()
+            .map(|i| {
+                let (col, row) = ((i % columns.max(1)) as f32, (i / columns.max(1)) as f32);
+                // v grows upwards from the image bottom
+                [
+                    col * w,
+                    1.0 - (row + 1.0) * h,
+                    (col + 1.0) * w,
+                    1.0 - row * h,
+                ]
+            })
+            .collect();
+        Self::new(frames, fps)
+    }
+
+    pub fn with_mode(mut self, mode: LoopMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Seconds frame `i` shows.
+    pub fn duration(&self, i: usize) -> f32 {
+        match self.durations.get(i) {
+            Some(d) => d.max(0.0),
+            None if self.fps > 0.0 => 1.0 / self.fps,
+            // a stopped clock never leaves the frame
+            None => f32::INFINITY,
+        }
+    }
+
+    /// Index into `frames` of the frame shown.
+    pub fn frame(&self) -> usize {
+        let n = self.frames.len();
+        if n <= 1 {
+            return 0;
+        }
+        // one cycle: there and, for ping-pong, back without repeating the ends
+        let back = match self.mode {
+            LoopMode::PingPong => 1..n - 1,
+            _ => 0..0,
+        };
+        let cycle = || (0..n).chain(back.clone().rev());
+        let total: f32 = cycle().map(|i| self.duration(i)).sum();
+        let mut t = match self.mode {
+            LoopMode::Once => self.elapsed,
+            _ if total > 0.0 && total.is_finite() => self.elapsed % total,
+            _ => self.elapsed,
+        };
+        for i in cycle() {
+            t -= self.duration(i);
+            if t < 0.0 {
+                return i;
+            }
+        }
+        match self.mode {
+            LoopMode::Once => n - 1,
+            _ => 0,
+        }
+    }
+
+    /// `uv` of the frame shown, `None` without frames.
+    pub fn uv(&self) -> Option<[f32; 4]> {
+        self.frames.get(self.frame()).copied()
+    }
+
+    /// Whether a [`LoopMode::Once`] animation has reached its last frame.
+    pub fn is_finished(&self) -> bool {
+        let last = self.frames.len().saturating_sub(1);
+        self.mode == LoopMode::Once && self.elapsed >= (0..last).map(|i| self.duration(i)).sum()
+    }
+
+    /// Jumps back to the first frame and plays.
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+        self.playing = true;
+    }
+
+    /// Advances by `dt` seconds; returns `true` when this finished a
+    /// [`LoopMode::Once`] animation. Called by the app every frame.
+    pub fn tick(&mut self, dt: f32) -> bool {
+        if !self.playing || self.is_finished() {
+            return false;
+        }
+        self.elapsed += dt;
+        self.is_finished()
+    }
+}
@@ -0,0 +1,160 @@
+use std::path::{Path, PathBuf};
+
+use hashbrown::HashMap;
+
+use crate::{json::Json, Animation, Error, LoopMode, TextureAtlas, TextureId};
+
+/// A sprite sheet exported from Aseprite: the frames as a [`TextureAtlas`]
+/// and the tags as [`Animation`] clips, loaded with `Ctx::load_asset` or
+/// `Ctx::load_aseprite`.
+///
+/// Reads the JSON of File > Export Sprite Sheet with either frame layout
+/// ("Hash" or "Array") and "Tags" ticked. Frames keep their durations;
+/// trimmed frames draw at their trimmed size.
+///
+/// ```ignore
+/// let hero = ctx.load_asset("assets/hero.json");
+/// let hero = ctx.aseprite(hero).expect("hero.json is a sprite sheet").clone();
+/// let id = ctx.spawn_sprite(Sprite::from_atlas(&hero.atlas, "0"));
+/// ctx.pool.animations.insert(id, hero.clip("run").unwrap());
+/// ```
+#[derive(Clone, Debug)]
+pub struct AsepriteSheet {
+    /// One region per frame, named by its index: `"0"` is the first frame.
+    pub atlas: TextureAtlas,
+    /// The sheet image, next to the JSON unless it says otherwise.
+    pub image: PathBuf,
+    /// Seconds each frame shows.
+    pub durations: Vec<f32>,
+    clips: HashMap<String, Animation>,
+}
+
+impl AsepriteSheet {
+    /// Reads the export at `path`, whose image path is relative to its directory.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let src = std::fs::read_to_string(path)?;
+        Self::parse(&src, path.parent().unwrap_or(Path::new(""))).map_err(|e| match e {
+            Error::Aseprite(msg) => Error::Aseprite(format!("{}: {msg}", path.display())),
+            e => e,
+        })
+    }
+
+    /// Parses an export whose image path is relative to `dir`; the atlas
+    /// texture is the id `Ctx::load_asset` gives that image.
+    pub fn parse(src: &str, dir: &Path) -> Result<Self, Error> {
+        let err = |msg: &str| Error::Aseprite(msg.to_owned());
+        let json = Json::parse(src).map_err(Error::Aseprite)?;
+        let meta = json.get("meta").ok_or_else(|| err("no `meta`"))?;
+        let image = meta
+            .get("image")
+            .and_then(Json::as_str)
+            .ok_or_else(|| err("no `meta.image`"))?;
+        let image = dir.join(image);
+        let size = meta.get("size").ok_or_else(|| err("no `meta.size`"))?;
+        let (width, height) = (dim(size, "w")?, dim(size, "h")?);
+
+        // "Array" exports list the frames, "Hash" ones key them by file name
+        let frames: Vec<&Json> = match json.get("frames") {
+            Some(Json::Arr(frames)) => frames.iter().collect(),
+            Some(Json::Obj(frames)) => frames.iter().map(|(_, f)| f).collect(),
+            _ => return Err(err("no `frames`")),
+        };
+        let mut atlas = TextureAtlas::new(TextureId::from_path(&image), width, height);
+        let mut durations = Vec::with_capacity(frames.len());
+        for (i, f) in frames.iter().enumerate() {
+            let rect = f
+                .get("frame")
+                .ok_or_else(|| err(&format!("frame {i} has no `frame` rect")))?;
+            let [x, y, w, h] = [
+                dim(rect, "x")?,
+                dim(rect, "y")?,
+                dim(rect, "w")?,
+                dim(rect, "h")?,
+            ];
+            atlas.add_region(&i.to_string(), x, y, w, h);
+            // milliseconds, 100 being Aseprite's default
+            let ms = f.get("duration").and_then(Json::as_f64).unwrap_or(100.0);
+            durations.push(ms as f32 / 1000.0);
+        }
+
+        let mut sheet = Self {
+            atlas,
+            image,
+            durations,
+            clips: HashMap::new(),
+        };
+        let tags = meta
+            .get("frameTags")
+            .and_then(Json::as_array)
+            .unwrap_or(&[]);
+        for tag in tags {
+            let name = tag
+                .get("name")
+                .and_then(Json::as_str)
+                .ok_or_else(|| err("tag without a name"))?;
+            let (from, to) = (dim(tag, "from")? as usize, dim(tag, "to")? as usize);
+            if from > to || to >= frames.len() {
+                return Err(err(&format!("tag `{name}` spans missing frames")));
+            }
+            let direction = tag.get("direction").and_then(Json::as_str);
+            let mut indices: Vec<usize> = (from..=to).collect();
+            if matches!(direction, Some("reverse" | "pingpong_reverse")) {
+                indices.reverse();
+            }
+            let mut clip = sheet.animation(&indices);
+            if matches!(direction, Some("pingpong" | "pingpong_reverse")) {
+                clip.mode = LoopMode::PingPong;
+            }
+            sheet.clips.insert(name.to_owned(), clip);
+        }
+        Ok(sheet)
+    }
+
+    /// The clip of the tag `name`, looping.
+    pub fn clip(&self, name: &str) -> Option<Animation> {
+        self.clips.get(name).cloned()
+    }
+
+    pub fn clips(&self) -> impl Iterator<Item = (&str, &Animation)> {
+        self.clips.iter().map(|(n, a)| (n.as_str(), a))
+    }
+
+    /// Every frame in order, e.g. for sheets without tags.
+    pub fn all_frames(&self) -> Animation {
+        self.animation(&(0..self.durations.len()).collect::<Vec<_>>())
+    }
+
+    fn animation(&self, indices: &[usize]) -> Animation {
+        let frames = indices
+            .iter()
+            .filter_map(|i| self.atlas.uv(&i.to_string()))
+            .collect();
+        let mut clip = Animation::new(frames, 10.0);
+        clip.durations = indices.iter().map(|&i| self.durations[i]).collect();
+        clip
+    }
+}
+
+/// The sheets `Ctx::load_asset` read from Aseprite exports, by the id of
+/// their image; kept in the scene resources.
+#[derive(Clone, Debug, Default)]
+pub struct AsepriteSheets {
+    sheets: HashMap<TextureId, AsepriteSheet>,
+}
+
+impl AsepriteSheets {
+    pub fn get(&self, tex: TextureId) -> Option<&AsepriteSheet> {
+        self.sheets.get(&tex)
+    }
+
+    pub(crate) fn insert(&mut self, sheet: AsepriteSheet) {
+        self.sheets.insert(sheet.atlas.tex, sheet);
+    }
+}
+
+fn dim(obj: &Json, key: &str) -> Result<u32, Error> {
+    obj.get(key)
+        .and_then(Json::as_u32)
+        .ok_or_else(|| Error::Aseprite(format!("missing or negative `{key}`")))
+}
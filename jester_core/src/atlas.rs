@@ -0,0 +1,110 @@
+use glam::Vec2;
+use tracing::warn;
+
+use crate::{Sprite, TextureId};
+
+/// Named regions of one texture, so many sprites share a single texture
+/// slot and descriptor set, see [`Sprite::from_atlas`].
+///
+/// Regions are texel rects from the texture's top-left corner, as image
+/// editors show them.
+///
+/// ```ignore
+/// let sheet = ctx.load_asset("assets/props.png");
+/// let atlas = TextureAtlas::new(sheet, 256, 256)
+///     .with_region("crate", 0, 0, 32, 32)
+///     .with_region("barrel", 32, 0, 32, 48);
+/// ctx.spawn_sprite(Sprite::from_atlas(&atlas, "barrel"));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextureAtlas {
+    pub tex: TextureId,
+    pub width: u32,
+    pub height: u32,
+    regions: Vec<(String, [u32; 4])>,
+}
+
+impl TextureAtlas {
+    /// An atlas without regions over the `width` by `height` texture `tex`.
+    pub fn new(tex: TextureId, width: u32, height: u32) -> Self {
+        Self {
+            tex,
+            width,
+            height,
+            regions: Vec::new(),
+        }
+    }
+
+    /// Splits the texture into `cell_w` by `cell_h` cells named by their
+    /// index, `"0"`, `"1"` and so on row by row from the top-left.
+    pub fn grid(tex: TextureId, width: u32, height: u32, cell_w: u32, cell_h: u32) -> Self {
+        let mut atlas = Self::new(tex, width, height);
+        let (columns, rows) = (width / cell_w.max(1), height / cell_h.max(1));
+        for i in 0..columns * rows {
+            let (x, y) = (i % columns * cell_w, i / columns * cell_h);
+            atlas.add_region(&i.to_string(), x, y, cell_w, cell_h);
+        }
+        atlas
+    }
+
+    pub fn with_region(mut self, name: &str, x: u32, y: u32, w: u32, h: u32) -> Self {
+        self.add_region(name, x, y, w, h);
+        self
+    }
+
+    /// Adds the texel rect `x, y, w, h` as `name`, replacing a region of that name.
+    pub fn add_region(&mut self, name: &str, x: u32, y: u32, w: u32, h: u32) {
+        let rect = [x, y, w, h];
+        match self.regions.iter_mut().find(|(n, _)| n == name) {
+            Some((_, r)) => *r = rect,
+            None => self.regions.push((name.to_owned(), rect)),
+        }
+    }
+
+    /// Texel rect `x, y, w, h` of `name`.
+    pub fn region(&self, name: &str) -> Option<[u32; 4]> {
+        self.regions
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, r)| *r)
+    }
+
+    pub fn regions(&self) -> impl Iterator<Item = (&str, [u32; 4])> {
+        self.regions.iter().map(|(n, r)| (n.as_str(), *r))
+    }
+
+    /// `uv` of `name`, see [`Sprite::uv`].
+    pub fn uv(&self, name: &str) -> Option<[f32; 4]> {
+        let [x, y, w, h] = self.region(name)?;
+        let (width, height) = (self.width.max(1) as f32, self.height.max(1) as f32);
+        // v grows upwards from the image bottom
+        Some([
+            x as f32 / width,
+            1.0 - (y + h) as f32 / height,
+            (x + w) as f32 / width,
+            1.0 - y as f32 / height,
+        ])
+    }
+}
+
+impl Sprite {
+    /// A sprite showing the region `name` of `atlas` at its texel size.
+    ///
+    /// Without such a region it shows the whole texture, with a warning.
+    pub fn from_atlas(atlas: &TextureAtlas, name: &str) -> Self {
+        let (uv, size) = match (atlas.uv(name), atlas.region(name)) {
+            (Some(uv), Some([_, _, w, h])) => (uv, Vec2::new(w as f32, h as f32)),
+            _ => {
+                warn!("texture atlas has no region {name:?}");
+                let size = Vec2::new(atlas.width as f32, atlas.height as f32);
+                ([0.0, 0.0, 1.0, 1.0], size)
+            }
+        };
+        Self {
+            tex: atlas.tex,
+            uv,
+            size: Some(size),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use glam::Vec2;
+
+use crate::{Aabb, Camera, EntityId, EntityPool};
+
+/// What happens to a confined entity crossing the [`WorldBounds`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EdgeMode {
+    /// Stops at the edge with the whole sprite inside.
+    #[default]
+    Clamp,
+    /// Comes back in at the opposite edge once its center leaves, e.g. for asteroids.
+    Wrap,
+}
+
+/// The extent of the level: cameras never show past it and chosen entities
+/// stay inside it.
+///
+/// Add it with `App::add_resource`; every frame after sprites move, the app
+/// moves confined entities back in and then every camera so its view stays
+/// within the rect. A view larger than the rect is centered on it instead.
+///
+/// ```ignore
+/// app.add_resource(WorldBounds::new(Aabb::new(Vec2::ZERO, Vec2::new(3200.0, 1800.0))));
+/// // in start
+/// ctx.resources
+///     .get_mut::<WorldBounds>()
+///     .unwrap()
+///     .confine(player, EdgeMode::Clamp);
+/// ```
+#[derive(Clone, Debug)]
+pub struct WorldBounds {
+    pub rect: Aabb,
+    /// Keeps cameras inside `rect`; on by default.
+    pub confine_cameras: bool,
+    entities: Vec<(EntityId, EdgeMode)>,
+}
+
+impl WorldBounds {
+    pub fn new(rect: Aabb) -> Self {
+        Self {
+            rect,
+            confine_cameras: true,
+            entities: Vec::new(),
+        }
+    }
+
+    /// Keeps `id` inside the bounds, replacing any mode it had.
+    pub fn confine(&mut self, id: EntityId, mode: EdgeMode) {
+        self.release(id);
+        self.entities.push((id, mode));
+    }
+
+    pub fn release(&mut self, id: EntityId) {
+        self.entities.retain(|(e, _)| *e != id);
+    }
+
+    pub fn mode_of(&self, id: EntityId) -> Option<EdgeMode> {
+        self.entities
+            .iter()
+            .find(|(e, _)| *e == id)
+            .map(|(_, m)| *m)
+    }
+
+    /// Moves the confined entities back inside, forgetting despawned ones;
+    /// called by the app after sprites move.
+    pub fn confine_entities(&mut self, pool: &mut EntityPool) {
+        let rect = self.rect;
+        self.entities.retain(|(id, mode)| {
+            let Some(s) = pool.entities.get(id) else {
+                return false;
+            };
+            let half = s.size.unwrap_or(Vec2::ONE) * s.transform.scale.abs() * 0.5;
+            let p = s.transform.translation;
+            let moved = match mode {
+                EdgeMode::Clamp => clamp_box(p, half, rect),
+                EdgeMode::Wrap => wrap(p, rect),
+            };
+            // only touched when moved, so resting sprites keep their cached batch
+            if moved != p
+                && let Some(s) = pool.sprite_mut(*id)
+            {
+                s.transform.translation = moved;
+            }
+            true
+        });
+    }
+
+    /// Moves `cam` so its view of a `screen` sized window stays inside the
+    /// bounds, called by the app before drawing.
+    pub fn confine_camera(&self, cam: &mut Camera, screen: Vec2) {
+        if !self.confine_cameras {
+            return;
+        }
+        let (_, size) = cam.output_viewport().to_pixels(screen);
+        let view = size / cam.scale();
+        // `center` is the view's bottom-left corner
+        cam.center = clamp_box(cam.center + view * 0.5, view * 0.5, self.rect) - view * 0.5;
+    }
+
+    /// Whether `point` lies inside the bounds, edges included.
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.rect.min).all() && point.cmple(self.rect.max).all()
+    }
+}
+
+// center of a box of half size `half` moved inside `rect`, or onto its center where too big
+fn clamp_box(center: Vec2, half: Vec2, rect: Aabb) -> Vec2 {
+    let axis = |c: f32, h: f32, min: f32, max: f32| match max - min <= 2.0 * h {
+        true => (min + max) * 0.5,
+        false => c.clamp(min + h, max - h),
+    };
+    Vec2::new(
+        axis(center.x, half.x, rect.min.x, rect.max.x),
+        axis(center.y, half.y, rect.min.y, rect.max.y),
+    )
+}
+
+fn wrap(p: Vec2, rect: Aabb) -> Vec2 {
+    let size = rect.size();
+    let axis = |v: f32, min: f32, len: f32| match len > 0.0 {
+        true => min + (v - min).rem_euclid(len),
+        false => min,
+    };
+    Vec2::new(axis(p.x, rect.min.x, size.x), axis(p.y, rect.min.y, size.y))
+}
@@ -0,0 +1,333 @@
+use glam::Vec2;
+
+/// An axis-aligned box in world units, y up.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Aabb {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Aabb {
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self { min, max }
+    }
+
+    /// Matches a [`Sprite`](crate::Sprite) whose translation is its center.
+    pub fn from_center_size(center: Vec2, size: Vec2) -> Self {
+        let half = size * 0.5;
+        Self::new(center - half, center + half)
+    }
+
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    pub fn translated(&self, by: Vec2) -> Self {
+        Self::new(self.min + by, self.max + by)
+    }
+
+    /// Whether the boxes share area; touching edges do not count.
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.x < other.max.x
+            && other.min.x < self.max.x
+            && self.min.y < other.max.y
+            && other.min.y < self.max.y
+    }
+
+    pub fn contains(&self, pt: Vec2) -> bool {
+        pt.x >= self.min.x && pt.y >= self.min.y && pt.x < self.max.x && pt.y < self.max.y
+    }
+
+    /// Where the segment from `origin` to `origin + delta` first enters the box.
+    ///
+    /// A segment starting inside hits at `t == 0` with a zero normal. Touching
+    /// counts as outside: a segment along a face or leaving it misses, one
+    /// starting on a face and moving in hits at `t == 0` with its normal.
+    pub fn segment_hit(&self, origin: Vec2, delta: Vec2) -> Option<SegmentHit> {
+        let (mut t_enter, mut t_exit) = (0.0f32, 1.0f32);
+        let mut normal = Vec2::ZERO;
+        for axis in 0..2 {
+            let (o, d) = (origin[axis], delta[axis]);
+            let (lo, hi) = (self.min[axis], self.max[axis]);
+            if d == 0.0 {
+                // parallel to this slab, either always inside it or never
+                if o <= lo || o >= hi {
+                    return None;
+                }
+                continue;
+            }
+            let (t0, t1) = ((lo - o) / d, (hi - o) / d);
+            let (t0, t1) = (t0.min(t1), t0.max(t1));
+            // starting on a face counts as entering through it
+            if t0 > t_enter || (t0 == t_enter && normal == Vec2::ZERO) {
+                t_enter = t0;
+                // the face entered is the one facing against the motion
+                normal = Vec2::ZERO;
+                normal[axis] = -d.signum();
+            }
+            t_exit = t_exit.min(t1);
+            if t_enter >= t_exit {
+                return None;
+            }
+        }
+        Some(SegmentHit {
+            t: t_enter,
+            point: origin + delta * t_enter,
+            normal,
+        })
+    }
+
+    /// Where `self` moved by `delta` first touches `other`, so fast bodies cannot tunnel through it.
+    pub fn sweep_hit(&self, delta: Vec2, other: &Aabb) -> Option<SegmentHit> {
+        // the other box grown by our half size turns the sweep into a segment test
+        let half = self.size() * 0.5;
+        let grown = Aabb::new(other.min - half, other.max + half);
+        let hit = grown.segment_hit(self.center(), delta)?;
+        Some(SegmentHit {
+            point: hit.point - hit.normal * half,
+            ..hit
+        })
+    }
+}
+
+/// A box rotated by `rotation` radians counter-clockwise about its center.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Obb {
+    pub center: Vec2,
+    /// Half the width and height before rotating.
+    pub half: Vec2,
+    pub rotation: f32,
+}
+
+impl Obb {
+    pub fn new(center: Vec2, size: Vec2, rotation: f32) -> Self {
+        Self {
+            center,
+            half: size * 0.5,
+            rotation,
+        }
+    }
+
+    /// The box's own x and y directions in the world.
+    pub fn axes(&self) -> [Vec2; 2] {
+        let x = Vec2::from_angle(self.rotation);
+        [x, x.perp()]
+    }
+
+    /// Counter-clockwise from the bottom-left before rotating.
+    pub fn corners(&self) -> [Vec2; 4] {
+        let [x, y] = self.axes();
+        let (x, y) = (x * self.half.x, y * self.half.y);
+        let c = self.center;
+        [c - x - y, c + x - y, c + x + y, c - x + y]
+    }
+
+    /// The smallest axis-aligned box around this one.
+    pub fn aabb(&self) -> Aabb {
+        let [x, y] = self.axes();
+        let extent = (x * self.half.x).abs() + (y * self.half.y).abs();
+        Aabb::new(self.center - extent, self.center + extent)
+    }
+
+    pub fn contains(&self, pt: Vec2) -> bool {
+        let d = pt - self.center;
+        let [x, y] = self.axes();
+        d.dot(x).abs() <= self.half.x && d.dot(y).abs() <= self.half.y
+    }
+
+    /// Whether the boxes share area, by separating axes; touching edges do not count.
+    pub fn intersects(&self, other: &Obb) -> bool {
+        let (a, b) = (self.corners(), other.corners());
+        self.axes().into_iter().chain(other.axes()).all(|axis| {
+            let span = |pts: &[Vec2; 4]| {
+                pts.iter()
+                    .map(|p| p.dot(axis))
+                    .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), d| {
+                        (lo.min(d), hi.max(d))
+                    })
+            };
+            let ((a_lo, a_hi), (b_lo, b_hi)) = (span(&a), span(&b));
+            a_lo < b_hi && b_lo < a_hi
+        })
+    }
+}
+
+impl From<Aabb> for Obb {
+    fn from(aabb: Aabb) -> Self {
+        Self::new(aabb.center(), aabb.size(), 0.0)
+    }
+}
+
+/// The area a sprite covers, see [`Sprite::world_bounds`](crate::Sprite::world_bounds):
+/// axis-aligned unless rotated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bounds {
+    Aabb(Aabb),
+    Obb(Obb),
+}
+
+impl Bounds {
+    /// The smallest axis-aligned box around these bounds, e.g. for culling.
+    pub fn aabb(&self) -> Aabb {
+        match self {
+            Self::Aabb(b) => *b,
+            Self::Obb(b) => b.aabb(),
+        }
+    }
+
+    pub fn contains(&self, pt: Vec2) -> bool {
+        match self {
+            Self::Aabb(b) => b.contains(pt),
+            Self::Obb(b) => b.contains(pt),
+        }
+    }
+
+    /// Whether the bounds share area; touching edges do not count.
+    pub fn intersects(&self, other: &Bounds) -> bool {
+        match (self, other) {
+            (Self::Aabb(a), Self::Aabb(b)) => a.intersects(b),
+            (a, b) => a.aabb().intersects(&b.aabb()) && a.obb().intersects(&b.obb()),
+        }
+    }
+
+    fn obb(&self) -> Obb {
+        match self {
+            Self::Aabb(b) => Obb::from(*b),
+            Self::Obb(b) => *b,
+        }
+    }
+}
+
+/// First contact of a moving point or box, see [`Aabb::segment_hit`] and [`Colliders::sweep`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SegmentHit {
+    /// Fraction of the movement done at contact, `0.0..=1.0`.
+    pub t: f32,
+    /// Contact point; for sweeps, on the edge of the moving box.
+    pub point: Vec2,
+    /// Unit surface normal at the contact, pointing back at the mover.
+    pub normal: Vec2,
+}
+
+/// How a [`Collider`] affects bodies moving into it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColliderKind {
+    #[default]
+    Solid,
+    /// Only stops bodies falling onto its top, e.g. platforms jumped through from below.
+    OneWay,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Collider {
+    pub aabb: Aabb,
+    pub kind: ColliderKind,
+}
+
+impl Collider {
+    pub fn solid(aabb: Aabb) -> Self {
+        Self {
+            aabb,
+            kind: ColliderKind::Solid,
+        }
+    }
+
+    pub fn one_way(aabb: Aabb) -> Self {
+        Self {
+            aabb,
+            kind: ColliderKind::OneWay,
+        }
+    }
+}
+
+/// Static level geometry that bodies collide against.
+#[derive(Clone, Debug, Default)]
+pub struct Colliders {
+    pub colliders: Vec<Collider>,
+}
+
+impl Colliders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, collider: Collider) {
+        self.colliders.push(collider);
+    }
+
+    /// Adds a `cols` by `rows` tile grid whose bottom-left tile starts at `origin`.
+    ///
+    /// `kind(col, row)` classifies each tile, row 0 being the top row as in most
+    /// tilemap editors. Horizontal runs of equal tiles become one collider, so
+    /// bodies do not catch on the seams between tiles.
+    pub fn add_tiles<F>(&mut self, origin: Vec2, tile: Vec2, cols: u32, rows: u32, mut kind: F)
+    where
+        F: FnMut(u32, u32) -> Option<ColliderKind>,
+    {
+        for row in 0..rows {
+            let y = origin.y + (rows - 1 - row) as f32 * tile.y;
+            let mut run: Option<(u32, ColliderKind)> = None;
+            for col in 0..=cols {
+                let k = if col < cols { kind(col, row) } else { None };
+                if run.map(|(_, rk)| Some(rk)) == Some(k) {
+                    continue;
+                }
+                if let Some((start, rk)) = run.take() {
+                    let min = Vec2::new(origin.x + start as f32 * tile.x, y);
+                    let max = Vec2::new(origin.x + col as f32 * tile.x, y + tile.y);
+                    self.push(Collider {
+                        aabb: Aabb::new(min, max),
+                        kind: rk,
+                    });
+                }
+                run = k.map(|k| (col, k));
+            }
+        }
+    }
+
+    /// The first collider hit by the segment from `origin` to `origin + delta`, with its index.
+    pub fn raycast(&self, origin: Vec2, delta: Vec2) -> Option<(usize, SegmentHit)> {
+        self.first_hit(delta, |aabb| aabb.segment_hit(origin, delta))
+    }
+
+    /// The first collider `body` runs into when moved by `delta`, with its index.
+    ///
+    /// Unlike testing overlaps after the move, thin walls stop bodies moving
+    /// farther than their own size in one step, e.g. bullets.
+    pub fn sweep(&self, body: Aabb, delta: Vec2) -> Option<(usize, SegmentHit)> {
+        self.first_hit(delta, |aabb| body.sweep_hit(delta, aabb))
+    }
+
+    fn first_hit<F>(&self, delta: Vec2, mut hit: F) -> Option<(usize, SegmentHit)>
+    where
+        F: FnMut(&Aabb) -> Option<SegmentHit>,
+    {
+        self.colliders
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| {
+                let h = hit(&c.aabb)?;
+                // one-way platforms only stop things coming down onto their top
+                let blocks = match c.kind {
+                    ColliderKind::Solid => true,
+                    ColliderKind::OneWay => delta.y < 0.0 && h.normal == Vec2::Y,
+                };
+                blocks.then_some((i, h))
+            })
+            .min_by(|a, b| a.1.t.total_cmp(&b.1.t))
+    }
+
+    /// Colliders overlapping `area`, or touching it.
+    pub fn near(&self, area: Aabb) -> impl Iterator<Item = &Collider> {
+        self.colliders.iter().filter(move |c| {
+            c.aabb.min.x <= area.max.x
+                && area.min.x <= c.aabb.max.x
+                && c.aabb.min.y <= area.max.y
+                && area.min.y <= c.aabb.max.y
+        })
+    }
+}
@@ -0,0 +1,59 @@
+use hashbrown::HashMap;
+
+/// Named actions that cannot be used again until some time has passed.
+///
+/// ```ignore
+/// if ctx.input.just_pressed(KeyCode::Space) && self.cooldowns.trigger("dash", 0.8) {
+///     // dash
+/// }
+/// self.cooldowns.tick(ctx.dt);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cooldowns {
+    remaining: HashMap<String, f32>,
+}
+
+impl Cooldowns {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_ready(&self, action: &str) -> bool {
+        !self.remaining.contains_key(action)
+    }
+
+    /// Seconds until `action` is ready, 0 when it is.
+    pub fn remaining(&self, action: &str) -> f32 {
+        self.remaining.get(action).copied().unwrap_or(0.0)
+    }
+
+    /// Starts the cooldown of `action` if it is ready; returns whether it was,
+    /// i.e. whether the action may happen.
+    pub fn trigger(&mut self, action: &str, secs: f32) -> bool {
+        if !self.is_ready(action) {
+            return false;
+        }
+        self.start(action, secs);
+        true
+    }
+
+    /// Starts or restarts the cooldown of `action`, ready or not.
+    pub fn start(&mut self, action: &str, secs: f32) {
+        if secs > 0.0 {
+            self.remaining.insert(action.to_owned(), secs);
+        }
+    }
+
+    /// Makes `action` ready right away.
+    pub fn reset(&mut self, action: &str) {
+        self.remaining.remove(action);
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        self.remaining.retain(|_, left| {
+            *left -= dt;
+            *left > 0.0
+        });
+    }
+}
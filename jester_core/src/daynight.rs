@@ -0,0 +1,161 @@
+use crate::{Color, Events};
+
+/// Sent through `ctx.events` when [`DayNight::night`] crosses one half, and at midnight.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DayNightEvent {
+    Dusk,
+    Dawn,
+    /// The clock passed midnight, starting this day.
+    NewDay(u32),
+}
+
+/// In-game clock tinting the whole frame by the time of day.
+///
+/// Add it with `App::add_resource`; the app advances it every frame, sends a
+/// [`DayNightEvent`] at dusk, dawn and midnight, and multiplies the final
+/// image, UI included, with [`DayNight::tint`]. Fade lights in with
+/// [`DayNight::night`], e.g. as their alpha.
+///
+/// ```ignore
+/// app.add_resource(DayNight::new().with_hour(6.0).with_day_length(600.0));
+///
+/// // in render, a lamp's glow fading in at dusk
+/// let night = ctx.resources.get::<DayNight>().map_or(0.0, DayNight::night);
+/// let glow = Mesh::convex(&self.lamp_glow, Color::rgba(1.0, 0.8, 0.5, night));
+/// ctx.draw_mesh(MeshBatch::new(self.glow_tex, glow).with_blend(BlendMode::Additive));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct DayNight {
+    /// Time of day in hours, `0.0..24.0`.
+    pub hour: f32,
+    /// In-game hours per real second.
+    pub hours_per_second: f32,
+    pub paused: bool,
+    /// Tints by hour, sorted, blended in between and around midnight.
+    tints: Vec<(f32, Color)>,
+    day: u32,
+}
+
+impl Default for DayNight {
+    fn default() -> Self {
+        let night = Color::rgb(0.3, 0.35, 0.6);
+        Self {
+            hour: 12.0,
+            hours_per_second: 24.0 / 600.0,
+            paused: false,
+            tints: vec![
+                (5.0, night),
+                (7.0, Color::rgb(1.0, 0.8, 0.65)),
+                (9.0, Color::WHITE),
+                (17.0, Color::WHITE),
+                (19.0, Color::rgb(1.0, 0.6, 0.45)),
+                (21.0, night),
+            ],
+            day: 0,
+        }
+    }
+}
+
+impl DayNight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_hour(mut self, hour: f32) -> Self {
+        self.hour = hour.rem_euclid(24.0);
+        self
+    }
+
+    /// Real seconds a whole day takes.
+    pub fn with_day_length(mut self, seconds: f32) -> Self {
+        self.hours_per_second = 24.0 / seconds.max(f32::EPSILON);
+        self
+    }
+
+    /// Replaces the tints, given as `(hour, color)`.
+    pub fn with_tints(mut self, tints: impl IntoIterator<Item = (f32, Color)>) -> Self {
+        self.tints.clear();
+        for (hour, color) in tints {
+            self = self.with_tint(hour, color);
+        }
+        self
+    }
+
+    /// Adds or replaces the tint at `hour`.
+    pub fn with_tint(mut self, hour: f32, color: Color) -> Self {
+        let hour = hour.rem_euclid(24.0);
+        match self.tints.binary_search_by(|(h, _)| h.total_cmp(&hour)) {
+            Ok(i) => self.tints[i].1 = color,
+            Err(i) => self.tints.insert(i, (hour, color)),
+        }
+        self
+    }
+
+    /// Days since the clock started, counting up at every midnight.
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// The tint at the current hour.
+    pub fn tint(&self) -> Color {
+        self.tint_at(self.hour)
+    }
+
+    pub fn tint_at(&self, hour: f32) -> Color {
+        let (Some(&first), Some(&last)) = (self.tints.first(), self.tints.last()) else {
+            return Color::WHITE;
+        };
+        let hour = hour.rem_euclid(24.0);
+        let next = self.tints.partition_point(|(h, _)| *h <= hour);
+        // before the first or after the last tint, blend across midnight
+        let (from, to) = match next {
+            0 => ((last.0 - 24.0, last.1), first),
+            n if n == self.tints.len() => (last, (first.0 + 24.0, first.1)),
+            n => (self.tints[n - 1], self.tints[n]),
+        };
+        let span = to.0 - from.0;
+        let t = if span > 0.0 {
+            ((hour - from.0).rem_euclid(24.0) / span).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        from.1.lerp(to.1, t)
+    }
+
+    /// How dark it is, 0 under the brightest tint and 1 under the darkest.
+    pub fn night(&self) -> f32 {
+        let luma = |c: Color| 0.2126 * c.r + 0.7152 * c.g + 0.0722 * c.b;
+        let (min, max) = self
+            .tints
+            .iter()
+            .map(|(_, c)| luma(*c))
+            .fold((f32::MAX, f32::MIN), |(lo, hi), l| (lo.min(l), hi.max(l)));
+        if max - min <= f32::EPSILON {
+            return 0.0;
+        }
+        ((max - luma(self.tint())) / (max - min)).clamp(0.0, 1.0)
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.night() > 0.5
+    }
+
+    /// Advances the clock by `dt` real seconds, sending the events it passes.
+    pub fn update(&mut self, dt: f32, events: &mut Events) {
+        if self.paused {
+            return;
+        }
+        let was_night = self.is_night();
+        self.hour += dt * self.hours_per_second;
+        while self.hour >= 24.0 {
+            self.hour -= 24.0;
+            self.day += 1;
+            events.send(DayNightEvent::NewDay(self.day));
+        }
+        match (was_night, self.is_night()) {
+            (false, true) => events.send(DayNightEvent::Dusk),
+            (true, false) => events.send(DayNightEvent::Dawn),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::f32::consts::TAU;
+
+use glam::Vec2;
+
+use crate::{Aabb, Color, MeshVertex};
+
+/// Outlines drawn over the world for one frame, e.g. to see colliders and
+/// paths, filled through [`Ctx::debug_draw`](crate::Ctx::debug_draw).
+///
+/// The app draws them through every camera after the scene and clears
+/// them once the frame is shown; while paused the last ones stay.
+///
+/// ```ignore
+/// let red = Color::rgb(1.0, 0.0, 0.0);
+/// ctx.debug_draw()
+///     .rect(self.hitbox, red)
+///     .path(&self.route, Color::WHITE)
+///     .circle(self.target, 8.0, red);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DebugDraw {
+    // every two vertices are one segment
+    vertices: Vec<MeshVertex>,
+}
+
+impl DebugDraw {
+    pub fn line(&mut self, a: Vec2, b: Vec2, color: Color) -> &mut Self {
+        self.vertices.extend([
+            MeshVertex::new(a, Vec2::ZERO, color),
+            MeshVertex::new(b, Vec2::ZERO, color),
+        ]);
+        self
+    }
+
+    /// Segments through `points` in order, open at the ends.
+    pub fn path(&mut self, points: &[Vec2], color: Color) -> &mut Self {
+        for pair in points.windows(2) {
+            self.line(pair[0], pair[1], color);
+        }
+        self
+    }
+
+    /// Segments through `points`, closed back to the first.
+    pub fn polygon(&mut self, points: &[Vec2], color: Color) -> &mut Self {
+        self.path(points, color);
+        if let (Some(&first), Some(&last)) = (points.first(), points.last())
+            && points.len() > 2
+        {
+            self.line(last, first, color);
+        }
+        self
+    }
+
+    pub fn rect(&mut self, aabb: Aabb, color: Color) -> &mut Self {
+        let (min, max) = (aabb.min, aabb.max);
+        self.polygon(
+            &[min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)],
+            color,
+        )
+    }
+
+    /// A circle of radius `r` in world units, with more segments the larger it is.
+    pub fn circle(&mut self, center: Vec2, r: f32, color: Color) -> &mut Self {
+        let segments = (r.abs().sqrt() * 4.0).clamp(12.0, 64.0) as usize;
+        let points: Vec<Vec2> = (0..segments)
+            .map(|i| center + Vec2::from_angle(i as f32 / segments as f32 * TAU) * r)
+            .collect();
+        self.polygon(&points, color)
+    }
+
+    /// Line-list vertices, two per segment.
+    pub fn vertices(&self) -> &[MeshVertex] {
+        &self.vertices
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Moves the shapes of `other` into this one.
+    pub fn append(&mut self, other: &mut Self) {
+        self.vertices.append(&mut other.vertices);
+    }
+
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+    }
+}
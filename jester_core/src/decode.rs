@@ -0,0 +1,121 @@
+use std::path::Path;
+
+use image::{
+    error::DecodingError, DynamicImage, GrayImage, ImageBuffer, ImageError, ImageFormat,
+    ImageResult, Rgba, RgbaImage,
+};
+
+/// Texel layout of a texture on the GPU.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TextureFormat {
+    #[default]
+    Rgba8,
+    /// 16 bits per channel in native byte order, e.g. from 16-bit PNGs.
+    Rgba16,
+    /// One channel, sampled as opaque grey. Palette indices and masks read its red channel.
+    R8,
+}
+
+impl TextureFormat {
+    pub fn bytes_per_texel(self) -> usize {
+        match self {
+            Self::Rgba8 => 4,
+            Self::Rgba16 => 8,
+            Self::R8 => 1,
+        }
+    }
+}
+
+/// Texels decoded from an image file, in the most compact format that keeps their precision.
+#[derive(Debug, Clone)]
+pub struct DecodedImage {
+    pub width: u32,
+    pub height: u32,
+    pub format: TextureFormat,
+    /// Row-major from the top-left texel.
+    pub data: Vec<u8>,
+}
+
+impl DecodedImage {
+    /// Decodes `path`, reading QOI files directly into RGBA-8 without an intermediate copy.
+    pub fn open<P: AsRef<Path>>(path: P) -> ImageResult<Self> {
+        let path = path.as_ref();
+        let is_qoi = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("qoi"));
+        if is_qoi {
+            let bytes = std::fs::read(path).map_err(ImageError::IoError)?;
+            return Self::from_qoi(&bytes);
+        }
+        Ok(Self::from_dynamic(image::open(path)?))
+    }
+
+    /// Decodes an image file already in memory, its format guessed from its first bytes.
+    pub fn from_bytes(bytes: &[u8]) -> ImageResult<Self> {
+        if bytes.starts_with(b"qoif") {
+            return Self::from_qoi(bytes);
+        }
+        Ok(Self::from_dynamic(image::load_from_memory(bytes)?))
+    }
+
+    pub fn from_qoi(bytes: &[u8]) -> ImageResult<Self> {
+        let qoi_err =
+            |e: qoi::Error| ImageError::Decoding(DecodingError::new(ImageFormat::Qoi.into(), e));
+        let mut decoder = qoi::Decoder::new(bytes)
+            .map_err(qoi_err)?
+            .with_channels(qoi::Channels::Rgba);
+        let (width, height) = (decoder.header().width, decoder.header().height);
+        Ok(Self {
+            width,
+            height,
+            format: TextureFormat::Rgba8,
+            data: decoder.decode_to_vec().map_err(qoi_err)?,
+        })
+    }
+
+    pub fn from_dynamic(img: DynamicImage) -> Self {
+        let (width, height) = (img.width(), img.height());
+        let (format, data) = match img {
+            DynamicImage::ImageLuma8(g) => (TextureFormat::R8, g.into_raw()),
+            DynamicImage::ImageRgba8(i) => (TextureFormat::Rgba8, i.into_raw()),
+            img @ (DynamicImage::ImageLuma16(_)
+            | DynamicImage::ImageLumaA16(_)
+            | DynamicImage::ImageRgb16(_)
+            | DynamicImage::ImageRgba16(_)) => (
+                TextureFormat::Rgba16,
+                bytemuck::cast_slice(img.to_rgba16().as_raw()).to_vec(),
+            ),
+            img => (TextureFormat::Rgba8, img.to_rgba8().into_raw()),
+        };
+        Self {
+            width,
+            height,
+            format,
+            data,
+        }
+    }
+
+    pub fn to_rgba8(&self) -> RgbaImage {
+        let pixels = rgba8_from(self.format, self.width, self.height, &self.data);
+        RgbaImage::from_raw(self.width, self.height, pixels).expect("sized by format")
+    }
+}
+
+/// Converts texels of any [`TextureFormat`] to RGBA-8, for backends without native support.
+pub fn rgba8_from(format: TextureFormat, w: u32, h: u32, data: &[u8]) -> Vec<u8> {
+    match format {
+        TextureFormat::Rgba8 => data.to_vec(),
+        TextureFormat::Rgba16 => {
+            let texels: Vec<u16> = data
+                .chunks_exact(2)
+                .map(|c| u16::from_ne_bytes([c[0], c[1]]))
+                .collect();
+            let img = ImageBuffer::<Rgba<u16>, _>::from_raw(w, h, texels).expect("sized by format");
+            DynamicImage::ImageRgba16(img).to_rgba8().into_raw()
+        }
+        TextureFormat::R8 => {
+            let img = GrayImage::from_raw(w, h, data.to_vec()).expect("sized by format");
+            DynamicImage::ImageLuma8(img).to_rgba8().into_raw()
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use winit::keyboard::KeyCode;
+
+use crate::{AxisCurves, GamepadAxis, GamepadButton, InputState, InputTimings};
+
+/// A keyboard or gamepad, numbered by the app in the order they are first used.
+///
+/// Most platforms report every keyboard as the same device, so players
+/// sharing a keyboard need their own keys rather than their own device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputDevice {
+    Keyboard(u32),
+    Gamepad(u32),
+}
+
+/// Input of every keyboard and gamepad on its own, and of the local players
+/// they are assigned to, next to the merged `ctx.input`.
+///
+/// Assign with [`Ctx::assign_device_to_player`](crate::Ctx::assign_device_to_player);
+/// a player's state merges all of their devices. The mouse only feeds the
+/// merged state.
+///
+/// ```ignore
+/// // lobby: the first button pressed on a device joins a new player
+/// for device in ctx.devices.just_used() {
+///     if ctx.devices.player_of(device).is_none() {
+///         ctx.assign_device_to_player(device, self.players);
+///         self.players += 1;
+///     }
+/// }
+/// // game
+/// if let Some(input) = ctx.devices.player(1)
+///     && input.pad_just_pressed(GamepadButton::South)
+/// {
+///     self.jump(1);
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct InputDevices {
+    devices: Vec<(InputDevice, InputState)>,
+    assigned: Vec<(InputDevice, usize)>,
+    players: Vec<(usize, InputState)>,
+}
+
+impl InputDevices {
+    /// Devices used or connected so far, in that order.
+    pub fn devices(&self) -> impl Iterator<Item = InputDevice> + '_ {
+        self.devices.iter().map(|(d, _)| *d)
+    }
+
+    /// Input of `device` alone.
+    pub fn state(&self, device: InputDevice) -> Option<&InputState> {
+        self.devices
+            .iter()
+            .find(|(d, _)| *d == device)
+            .map(|(_, s)| s)
+    }
+
+    /// Devices with a key or button pressed this frame, e.g. for "press any button to join".
+    pub fn just_used(&self) -> impl Iterator<Item = InputDevice> + '_ {
+        self.devices
+            .iter()
+            .filter(|(_, s)| s.any_just_pressed())
+            .map(|(d, _)| *d)
+    }
+
+    pub fn player_of(&self, device: InputDevice) -> Option<usize> {
+        self.assigned
+            .iter()
+            .find(|(d, _)| *d == device)
+            .map(|(_, p)| *p)
+    }
+
+    pub fn devices_of(&self, player: usize) -> impl Iterator<Item = InputDevice> + '_ {
+        self.assigned
+            .iter()
+            .filter(move |(_, p)| *p == player)
+            .map(|(d, _)| *d)
+    }
+
+    /// Merged input of the devices assigned to `player`, `None` if it has none.
+    pub fn player(&self, player: usize) -> Option<&InputState> {
+        self.players
+            .iter()
+            .find(|(p, _)| *p == player)
+            .map(|(_, s)| s)
+    }
+
+    /// Assigns `device` to `player`, or back to no one with `None`, called by the app.
+    ///
+    /// Keys and buttons already down on the device count for the new player
+    /// once they are pressed again.
+    pub fn assign(&mut self, device: InputDevice, player: Option<usize>) {
+        self.assigned.retain(|(d, _)| *d != device);
+        if let Some(p) = player {
+            self.assigned.push((device, p));
+        }
+        let assigned = &self.assigned;
+        self.players
+            .retain(|(p, _)| assigned.iter().any(|(_, q)| q == p));
+        if let Some(p) = player
+            && self.player(p).is_none()
+        {
+            self.players.push((p, InputState::default()));
+        }
+    }
+
+    /// Forgets a disconnected device and its assignment, called by the app.
+    pub fn remove(&mut self, device: InputDevice) {
+        self.devices.retain(|(d, _)| *d != device);
+        self.assign(device, None);
+    }
+
+    /// Registers `device` before anything is pressed on it, e.g. a gamepad being connected.
+    pub fn connect(&mut self, device: InputDevice) {
+        self.device_mut(device);
+    }
+
+    pub fn set_key_down(&mut self, device: InputDevice, k: KeyCode, down: bool) {
+        for s in self.states_mut(device) {
+            s.set_key_down(k, down);
+        }
+    }
+
+    pub fn set_pad_button(&mut self, device: InputDevice, b: GamepadButton, down: bool) {
+        for s in self.states_mut(device) {
+            s.set_pad_button(b, down);
+        }
+    }
+
+    pub fn set_pad_axis(&mut self, device: InputDevice, a: GamepadAxis, value: f32) {
+        for s in self.states_mut(device) {
+            s.set_pad_axis(a, value);
+        }
+    }
+
+    /// See [`InputState::tick`].
+    pub fn tick(&mut self, timings: InputTimings, axis_curves: AxisCurves, dt: f32) {
+        for s in self.all_mut() {
+            s.timings = timings;
+            s.axis_curves = axis_curves;
+            s.tick(dt);
+        }
+    }
+
+    pub fn begin_frame(&mut self) {
+        for s in self.all_mut() {
+            s.begin_frame();
+        }
+    }
+
+    fn all_mut(&mut self) -> impl Iterator<Item = &mut InputState> {
+        let devices = self.devices.iter_mut().map(|(_, s)| s);
+        devices.chain(self.players.iter_mut().map(|(_, s)| s))
+    }
+
+    fn device_mut(&mut self, device: InputDevice) -> &mut InputState {
+        let idx = match self.devices.iter().position(|(d, _)| *d == device) {
+            Some(i) => i,
+            None => {
+                self.devices.push((device, InputState::default()));
+                self.devices.len() - 1
+            }
+        };
+        &mut self.devices[idx].1
+    }
+
+    // the device's own state and its player's
+    fn states_mut(&mut self, device: InputDevice) -> impl Iterator<Item = &mut InputState> {
+        self.device_mut(device);
+        let player = self.player_of(device);
+        let own = self
+            .devices
+            .iter_mut()
+            .filter(move |(d, _)| *d == device)
+            .map(|(_, s)| s);
+        let players = self
+            .players
+            .iter_mut()
+            .filter(move |(p, _)| Some(*p) == player)
+            .map(|(_, s)| s);
+        own.chain(players)
+    }
+}
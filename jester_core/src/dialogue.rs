@@ -0,0 +1,635 @@
+use std::{fmt, path::Path, sync::Arc};
+
+use hashbrown::HashMap;
+use tracing::warn;
+
+use crate::{Error, Events};
+
+/// Statements run without reaching a line or choice before a dialogue is assumed stuck in a loop.
+const MAX_STEPS: usize = 10_000;
+
+/// A variable of a dialogue, see [`DialogueRunner::set_var`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Number(f32),
+    Text(String),
+}
+
+impl Value {
+    /// `false`, `0` and `""` are false, like unset variables.
+    pub fn truthy(&self) -> bool {
+        match self {
+            Value::Bool(b) => *b,
+            Value::Number(n) => *n != 0.0,
+            Value::Text(s) => !s.is_empty(),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(b) => b.fmt(f),
+            Value::Number(n) => n.fmt(f),
+            Value::Text(s) => s.fmt(f),
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(n: f32) -> Self {
+        Value::Number(n)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(n: i32) -> Self {
+        Value::Number(n as f32)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Text(s.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Text(s)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Operand {
+    Var(String),
+    Literal(Value),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Condition {
+    Truthy(Operand),
+    Not(Operand),
+    Cmp(Operand, CmpOp, Operand),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SetOp {
+    Assign,
+    Add,
+    Sub,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Kind {
+    Line {
+        speaker: Option<String>,
+        text: String,
+    },
+    Choice {
+        text: String,
+        target: Option<String>,
+    },
+    Jump(String),
+    Set(String, SetOp, Operand),
+    Stop,
+    Command(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Statement {
+    kind: Kind,
+    cond: Option<Condition>,
+}
+
+/// A parsed dialogue script: named nodes of lines, choices and variable updates.
+///
+/// ```text
+/// // comments start with two slashes
+/// === gate
+/// Guard: Halt! Who goes there, {name}?
+/// <<set met_guard = true>>
+/// -> A traveler. => traveler
+/// -> Here, take this. => bribe <<if gold >= 10>>
+/// -> Nobody.
+/// Guard: Then be on your way, nobody.
+///
+/// === traveler
+/// Guard: Move along.
+///
+/// === bribe
+/// <<set gold -= 10>>
+/// <<shake 0.5>>
+/// Guard: Much obliged. => gate
+/// ```
+///
+/// - `=== name` starts a node; the dialogue ends after its last statement.
+/// - `Speaker: text` or plain `text` is a line, `{var}` shows a variable.
+/// - `-> text => node` is a choice; consecutive choices are offered together
+///   and a choice without a target carries on after them.
+/// - `=> node` jumps, `<<stop>>` ends the dialogue.
+/// - `<<set var = value>>`, `+=` and `-=` update variables; values are
+///   `true`, `false`, numbers, `"text"` or other variables.
+/// - Any other `<<command args>>` is sent as [`DialogueEvent::Command`].
+/// - Any statement may end with `<<if cond>>`: `var`, `!var` or two values
+///   compared with `==`, `!=`, `<`, `<=`, `>` or `>=`. Unset variables are false.
+#[derive(Clone, Debug, Default)]
+pub struct Dialogue {
+    nodes: HashMap<String, Vec<Statement>>,
+}
+
+impl Dialogue {
+    pub fn parse(src: &str) -> Result<Self, Error> {
+        let mut nodes: HashMap<String, Vec<Statement>> = HashMap::new();
+        let mut node: Option<String> = None;
+        let mut targets = Vec::new();
+        for (n, raw) in src.lines().enumerate() {
+            let line_no = n + 1;
+            let err = |msg: &str| Error::Dialogue(format!("line {line_no}: {msg}"));
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix("===") {
+                let name = name.trim();
+                if name.is_empty() {
+                    return Err(err("node without a name"));
+                }
+                if nodes.insert(name.to_owned(), Vec::new()).is_some() {
+                    return Err(err(&format!("node `{name}` defined twice")));
+                }
+                node = Some(name.to_owned());
+                continue;
+            }
+            let Some(node) = &node else {
+                return Err(err("statement before the first `=== node`"));
+            };
+            let stmt = parse_statement(line).map_err(|e| err(&e))?;
+            // a line ending in `=> node` is a line followed by a jump
+            let (stmt, jump) = match stmt.kind {
+                Kind::Line { speaker, text } => match text.rsplit_once("=>") {
+                    Some((text, target)) if is_name(target.trim()) => (
+                        Statement {
+                            kind: Kind::Line {
+                                speaker,
+                                text: text.trim_end().to_owned(),
+                            },
+                            cond: stmt.cond.clone(),
+                        },
+                        Some(Statement {
+                            kind: Kind::Jump(target.trim().to_owned()),
+                            cond: stmt.cond,
+                        }),
+                    ),
+                    _ => (
+                        Statement {
+                            kind: Kind::Line { speaker, text },
+                            cond: stmt.cond,
+                        },
+                        None,
+                    ),
+                },
+                _ => (stmt, None),
+            };
+            for s in std::iter::once(&stmt).chain(&jump) {
+                match &s.kind {
+                    Kind::Jump(t)
+                    | Kind::Choice {
+                        target: Some(t), ..
+                    } => targets.push((line_no, t.clone())),
+                    _ => {}
+                }
+            }
+            let body = nodes.get_mut(node).expect("inserted on its header");
+            body.push(stmt);
+            body.extend(jump);
+        }
+        for (line_no, target) in targets {
+            if !nodes.contains_key(&target) {
+                return Err(Error::Dialogue(format!(
+                    "line {line_no}: no node named `{target}`"
+                )));
+            }
+        }
+        Ok(Self { nodes })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn has_node(&self, name: &str) -> bool {
+        self.nodes.contains_key(name)
+    }
+
+    pub fn node_names(&self) -> impl Iterator<Item = &str> {
+        self.nodes.keys().map(String::as_str)
+    }
+}
+
+fn is_name(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+}
+
+fn parse_statement(line: &str) -> Result<Statement, String> {
+    // a trailing `<<if cond>>` guards any statement
+    let (line, cond) = match line.rfind("<<if ") {
+        Some(at) if line.ends_with(">>") => {
+            let cond = &line[at + 5..line.len() - 2];
+            (line[..at].trim_end(), Some(parse_condition(cond)?))
+        }
+        _ => (line, None),
+    };
+    let kind = if let Some(choice) = line.strip_prefix("->") {
+        let (text, target) = match choice.rsplit_once("=>") {
+            Some((text, target)) => {
+                let target = target.trim();
+                if !is_name(target) {
+                    return Err(format!("bad node name `{target}`"));
+                }
+                (text, Some(target.to_owned()))
+            }
+            None => (choice, None),
+        };
+        Kind::Choice {
+            text: text.trim().to_owned(),
+            target,
+        }
+    } else if let Some(target) = line.strip_prefix("=>") {
+        let target = target.trim();
+        if !is_name(target) {
+            return Err(format!("bad node name `{target}`"));
+        }
+        Kind::Jump(target.to_owned())
+    } else if let Some(inner) = line.strip_prefix("<<").and_then(|l| l.strip_suffix(">>")) {
+        let inner = inner.trim();
+        match inner.split_once(char::is_whitespace) {
+            Some(("set", rest)) => parse_set(rest.trim())?,
+            None if inner == "stop" => Kind::Stop,
+            None if inner == "set" => return Err("`set` without a variable".to_owned()),
+            _ => Kind::Command(inner.to_owned()),
+        }
+    } else {
+        match line.split_once(": ") {
+            Some((speaker, text)) if !speaker.contains(['{', '<']) => Kind::Line {
+                speaker: Some(speaker.trim().to_owned()),
+                text: text.trim().to_owned(),
+            },
+            _ => Kind::Line {
+                speaker: None,
+                text: line.to_owned(),
+            },
+        }
+    };
+    Ok(Statement { kind, cond })
+}
+
+fn parse_set(s: &str) -> Result<Kind, String> {
+    let (var, op, value) = if let Some((var, value)) = s.split_once("+=") {
+        (var, SetOp::Add, value)
+    } else if let Some((var, value)) = s.split_once("-=") {
+        (var, SetOp::Sub, value)
+    } else if let Some((var, value)) = s.split_once('=') {
+        (var, SetOp::Assign, value)
+    } else {
+        return Err(format!("expected `var = value` in `set {s}`"));
+    };
+    let var = var.trim().trim_start_matches('$');
+    if !is_name(var) {
+        return Err(format!("bad variable name `{var}`"));
+    }
+    Ok(Kind::Set(var.to_owned(), op, parse_operand(value)?))
+}
+
+fn parse_condition(s: &str) -> Result<Condition, String> {
+    const OPS: [(&str, CmpOp); 6] = [
+        ("==", CmpOp::Eq),
+        ("!=", CmpOp::Ne),
+        ("<=", CmpOp::Le),
+        (">=", CmpOp::Ge),
+        ("<", CmpOp::Lt),
+        (">", CmpOp::Gt),
+    ];
+    let s = s.trim();
+    for (token, op) in OPS {
+        if let Some((a, b)) = s.split_once(token) {
+            return Ok(Condition::Cmp(parse_operand(a)?, op, parse_operand(b)?));
+        }
+    }
+    match s.strip_prefix('!') {
+        Some(var) => Ok(Condition::Not(parse_operand(var)?)),
+        None => Ok(Condition::Truthy(parse_operand(s)?)),
+    }
+}
+
+fn parse_operand(s: &str) -> Result<Operand, String> {
+    let s = s.trim();
+    if let Some(text) = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        return Ok(Operand::Literal(Value::Text(text.to_owned())));
+    }
+    match s {
+        "true" => return Ok(Operand::Literal(Value::Bool(true))),
+        "false" => return Ok(Operand::Literal(Value::Bool(false))),
+        _ => {}
+    }
+    if let Ok(n) = s.parse::<f32>() {
+        return Ok(Operand::Literal(Value::Number(n)));
+    }
+    let var = s.trim_start_matches('$');
+    match is_name(var) {
+        true => Ok(Operand::Var(var.to_owned())),
+        false => Err(format!("expected a value, got `{s}`")),
+    }
+}
+
+/// A line of dialogue, with variables already filled in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DialogueLine {
+    pub speaker: Option<String>,
+    pub text: String,
+}
+
+/// What a running dialogue is waiting on.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DialogueStep {
+    /// Continue with [`DialogueRunner::advance`].
+    Line(DialogueLine),
+    /// Pick one with [`DialogueRunner::choose`]; choices whose condition failed are left out.
+    Choices(Vec<String>),
+}
+
+/// Sent through `ctx.events` as a dialogue runs.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DialogueEvent {
+    Started(String),
+    /// A `<<command args>>` the dialogue format does not know, for the game to act on.
+    Command(String),
+    Ended,
+}
+
+/// Changes to the app's [`DialogueRunner`], queued with the `Ctx::*_dialogue` methods.
+#[derive(Clone, Debug)]
+pub enum DialogueCommand {
+    Start(Arc<Dialogue>, String),
+    Advance,
+    Choose(usize),
+    Stop,
+    SetVar(String, Value),
+}
+
+/// Steps through a [`Dialogue`], keeping its variables across dialogues.
+///
+/// The app owns one, readable through `ctx.dialogue` and driven with
+/// [`Ctx::start_dialogue`](crate::Ctx::start_dialogue) and friends.
+#[derive(Clone, Debug, Default)]
+pub struct DialogueRunner {
+    vars: HashMap<String, Value>,
+    dialogue: Option<Arc<Dialogue>>,
+    node: String,
+    pc: usize,
+    step: Option<DialogueStep>,
+    /// Target of each offered choice, `None` carrying on at `pc`.
+    targets: Vec<Option<String>>,
+}
+
+impl DialogueRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.dialogue.is_some()
+    }
+
+    /// The node being run.
+    pub fn node(&self) -> Option<&str> {
+        self.dialogue.as_ref().map(|_| self.node.as_str())
+    }
+
+    pub fn current(&self) -> Option<&DialogueStep> {
+        self.step.as_ref()
+    }
+
+    pub fn var(&self, name: &str) -> Option<&Value> {
+        self.vars.get(name)
+    }
+
+    pub fn set_var(&mut self, name: impl Into<String>, value: impl Into<Value>) {
+        self.vars.insert(name.into(), value.into());
+    }
+
+    /// Every variable set so far, e.g. to save them.
+    pub fn vars(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.vars.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Runs `dialogue` from `node` up to its first line or choice, replacing any running one.
+    pub fn start(&mut self, dialogue: Arc<Dialogue>, node: &str, events: &mut Events) {
+        if !dialogue.has_node(node) {
+            warn!("dialogue has no node `{node}`");
+            return;
+        }
+        self.dialogue = Some(dialogue);
+        self.node = node.to_owned();
+        self.pc = 0;
+        events.send(DialogueEvent::Started(node.to_owned()));
+        self.run(events);
+    }
+
+    /// Moves past the current line; does nothing while choices are offered.
+    pub fn advance(&mut self, events: &mut Events) {
+        if let Some(DialogueStep::Line(_)) = self.step {
+            self.run(events);
+        }
+    }
+
+    /// Picks choice `index` of the offered ones.
+    pub fn choose(&mut self, index: usize, events: &mut Events) {
+        let Some(DialogueStep::Choices(_)) = self.step else {
+            return;
+        };
+        let Some(target) = self.targets.get(index).cloned() else {
+            warn!("dialogue choice {index} is out of range");
+            return;
+        };
+        if let Some(target) = target {
+            self.node = target;
+            self.pc = 0;
+        }
+        self.run(events);
+    }
+
+    pub fn stop(&mut self, events: &mut Events) {
+        if self.dialogue.take().is_some() {
+            self.step = None;
+            self.targets.clear();
+            events.send(DialogueEvent::Ended);
+        }
+    }
+
+    pub fn apply(&mut self, cmd: DialogueCommand, events: &mut Events) {
+        match cmd {
+            DialogueCommand::Start(dialogue, node) => self.start(dialogue, &node, events),
+            DialogueCommand::Advance => self.advance(events),
+            DialogueCommand::Choose(i) => self.choose(i, events),
+            DialogueCommand::Stop => self.stop(events),
+            DialogueCommand::SetVar(name, value) => self.set_var(name, value),
+        }
+    }
+
+    /// Runs statements until the next line or choice, or the end.
+    fn run(&mut self, events: &mut Events) {
+        self.step = None;
+        self.targets.clear();
+        let Some(dialogue) = self.dialogue.clone() else {
+            return;
+        };
+        for steps in 0.. {
+            if steps == MAX_STEPS {
+                warn!(
+                    "dialogue node `{}` looped without showing a line",
+                    self.node
+                );
+                break;
+            }
+            let body = &dialogue.nodes[&self.node];
+            let Some(stmt) = body.get(self.pc) else {
+                break;
+            };
+            self.pc += 1;
+            if let Some(cond) = &stmt.cond
+                && !self.eval(cond)
+            {
+                continue;
+            }
+            match &stmt.kind {
+                Kind::Line { speaker, text } => {
+                    self.step = Some(DialogueStep::Line(DialogueLine {
+                        speaker: speaker.as_deref().map(|s| self.interpolate(s)),
+                        text: self.interpolate(text),
+                    }));
+                    return;
+                }
+                Kind::Choice { .. } => {
+                    // offer this choice and the ones right after it together
+                    let mut texts = Vec::new();
+                    let mut at = self.pc - 1;
+                    while let Some(Statement {
+                        kind: Kind::Choice { text, target },
+                        cond,
+                    }) = body.get(at)
+                    {
+                        at += 1;
+                        if cond.as_ref().is_none_or(|c| self.eval(c)) {
+                            texts.push(self.interpolate(text));
+                            self.targets.push(target.clone());
+                        }
+                    }
+                    self.pc = at;
+                    if !texts.is_empty() {
+                        self.step = Some(DialogueStep::Choices(texts));
+                        return;
+                    }
+                }
+                Kind::Jump(target) => {
+                    self.node = target.clone();
+                    self.pc = 0;
+                }
+                Kind::Set(var, op, value) => {
+                    let value = self.resolve(value);
+                    let old = self.vars.get(var);
+                    let new = match (op, old, value) {
+                        (SetOp::Assign, _, v) => v,
+                        (SetOp::Add, Some(Value::Number(a)), Value::Number(b)) => {
+                            Value::Number(a + b)
+                        }
+                        (SetOp::Sub, Some(Value::Number(a)), Value::Number(b)) => {
+                            Value::Number(a - b)
+                        }
+                        (SetOp::Add, None, Value::Number(b)) => Value::Number(b),
+                        (SetOp::Sub, None, Value::Number(b)) => Value::Number(-b),
+                        (SetOp::Add, Some(Value::Text(a)), b) => Value::Text(format!("{a}{b}")),
+                        (_, old, value) => {
+                            warn!("dialogue: cannot apply {op:?} {value} to `{var}` = {old:?}");
+                            continue;
+                        }
+                    };
+                    self.vars.insert(var.clone(), new);
+                }
+                Kind::Stop => break,
+                Kind::Command(cmd) => events.send(DialogueEvent::Command(self.interpolate(cmd))),
+            }
+        }
+        self.dialogue = None;
+        events.send(DialogueEvent::Ended);
+    }
+
+    fn resolve(&self, operand: &Operand) -> Value {
+        match operand {
+            Operand::Literal(v) => v.clone(),
+            Operand::Var(name) => self.vars.get(name).cloned().unwrap_or(Value::Bool(false)),
+        }
+    }
+
+    fn eval(&self, cond: &Condition) -> bool {
+        match cond {
+            Condition::Truthy(o) => self.resolve(o).truthy(),
+            Condition::Not(o) => !self.resolve(o).truthy(),
+            Condition::Cmp(a, op, b) => {
+                let (a, b) = (self.resolve(a), self.resolve(b));
+                let ord = match (&a, &b) {
+                    (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
+                    (Value::Text(a), Value::Text(b)) => Some(a.cmp(b)),
+                    (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+                    _ => None,
+                };
+                match op {
+                    CmpOp::Eq => a == b,
+                    CmpOp::Ne => a != b,
+                    CmpOp::Lt => ord.is_some_and(|o| o.is_lt()),
+                    CmpOp::Le => ord.is_some_and(|o| o.is_le()),
+                    CmpOp::Gt => ord.is_some_and(|o| o.is_gt()),
+                    CmpOp::Ge => ord.is_some_and(|o| o.is_ge()),
+                }
+            }
+        }
+    }
+
+    /// Replaces `{var}` with the variable's value; unknown variables show as empty.
+    fn interpolate(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(open) = rest.find('{') {
+            let Some(close) = rest[open..].find('}') else {
+                break;
+            };
+            out.push_str(&rest[..open]);
+            let name = rest[open + 1..open + close].trim();
+            if let Some(v) = self.vars.get(name) {
+                out.push_str(&v.to_string());
+            }
+            rest = &rest[open + close + 1..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
@@ -0,0 +1,170 @@
+use glam::Vec2;
+
+use crate::{Camera, Ease};
+
+/// Named cameras of which the one with the highest priority is shown,
+/// blending smoothly whenever another one takes over.
+///
+/// Add it with `App::add_resource`; every frame the app ticks the virtual
+/// cameras and shows the active one through camera 0, spawning it if there
+/// is none. Move the virtual cameras rather than camera 0, which is
+/// overwritten. The blend moves the view's middle and zoom from where the
+/// view was towards the new camera, which keeps moving meanwhile.
+///
+/// ```ignore
+/// let mut director = CameraDirector::default();
+/// director.add("gameplay", Camera::default(), 0);
+/// app.add_resource(director);
+/// // cutscene starts
+/// let close_up = Camera::new(hero_pos, 2.0);
+/// let d = ctx.resources.get_mut::<CameraDirector>().unwrap();
+/// d.add("cutscene", close_up, 10);
+/// // and ends, blending back to gameplay
+/// d.remove("cutscene");
+/// ```
+#[derive(Clone, Debug)]
+pub struct CameraDirector {
+    /// Seconds a change of camera takes, 0 cuts.
+    pub blend_time: f32,
+    pub blend_ease: Ease,
+    cameras: Vec<(String, Camera, i32)>,
+    active: Option<String>,
+    // view shown when the blend started and seconds since
+    blend: Option<(Camera, f32)>,
+    output: Option<Camera>,
+    screen: Vec2,
+}
+
+impl Default for CameraDirector {
+    fn default() -> Self {
+        Self {
+            blend_time: 0.5,
+            blend_ease: Ease::InOutCubic,
+            cameras: Vec::new(),
+            active: None,
+            blend: None,
+            output: None,
+            screen: Vec2::ZERO,
+        }
+    }
+}
+
+impl CameraDirector {
+    /// Adds a virtual camera, replacing the one named `name`; of cameras
+    /// sharing the highest priority the last added is shown.
+    pub fn add(&mut self, name: &str, mut camera: Camera, priority: i32) {
+        self.remove(name);
+        if self.screen != Vec2::ZERO {
+            resize_kept(&mut camera, self.screen);
+        }
+        self.cameras.push((name.to_owned(), camera, priority));
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Camera> {
+        let i = self.cameras.iter().position(|(n, ..)| n == name)?;
+        Some(self.cameras.remove(i).1)
+    }
+
+    pub fn camera(&self, name: &str) -> Option<&Camera> {
+        self.cameras
+            .iter()
+            .find(|(n, ..)| n == name)
+            .map(|(_, c, _)| c)
+    }
+
+    pub fn camera_mut(&mut self, name: &str) -> Option<&mut Camera> {
+        self.cameras
+            .iter_mut()
+            .find(|(n, ..)| n == name)
+            .map(|(_, c, _)| c)
+    }
+
+    pub fn priority(&self, name: &str) -> Option<i32> {
+        self.cameras
+            .iter()
+            .find(|(n, ..)| n == name)
+            .map(|(.., p)| *p)
+    }
+
+    /// Changes the priority of `name`, returning `false` without such a camera.
+    pub fn set_priority(&mut self, name: &str, priority: i32) -> bool {
+        match self.cameras.iter_mut().find(|(n, ..)| n == name) {
+            Some((.., p)) => *p = priority,
+            None => return false,
+        }
+        true
+    }
+
+    /// Name of the camera shown, or being blended to, as of the last [`CameraDirector::update`].
+    pub fn active(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+
+    pub fn is_blending(&self) -> bool {
+        self.blend.is_some()
+    }
+
+    /// Ends a running blend, showing the active camera right away.
+    pub fn cut(&mut self) {
+        self.blend = None;
+    }
+
+    /// Ticks the virtual cameras and returns the view to show in a `screen`
+    /// sized window, `None` without cameras; called by the app every frame.
+    pub fn update(&mut self, dt: f32, screen: Vec2) -> Option<Camera> {
+        if screen != self.screen {
+            self.screen = screen;
+            for (_, c, _) in &mut self.cameras {
+                resize_kept(c, screen);
+            }
+            if let Some((from, _)) = &mut self.blend {
+                resize_kept(from, screen);
+            }
+        }
+        for (_, c, _) in &mut self.cameras {
+            c.tick(dt);
+        }
+        // `max_by_key` keeps the last of equal keys
+        let (name, target, _) = self.cameras.iter().max_by_key(|(.., p)| *p)?;
+        if self.active.as_deref() != Some(name.as_str()) {
+            self.active = Some(name.clone());
+            self.blend = match (self.output, self.blend_time > 0.0) {
+                (Some(shown), true) => Some((shown, 0.0)),
+                _ => None,
+            };
+        }
+        let mut out = *target;
+        if let Some((from, elapsed)) = &mut self.blend {
+            *elapsed += dt;
+            let t = match self.blend_time > 0.0 {
+                true => self.blend_ease.apply(*elapsed / self.blend_time),
+                false => 1.0,
+            };
+            let middle = view_middle(from, screen).lerp(view_middle(target, screen), t);
+            out.zoom = from.zoom + (target.zoom - from.zoom) * t;
+            out.center = middle - view_size(&out, screen) * 0.5;
+            if *elapsed >= self.blend_time {
+                self.blend = None;
+            }
+        }
+        self.output = Some(out);
+        Some(out)
+    }
+}
+
+fn view_size(cam: &Camera, screen: Vec2) -> Vec2 {
+    let (_, size) = cam.output_viewport().to_pixels(screen);
+    size / cam.scale()
+}
+
+// `center` is the view's bottom-left corner
+fn view_middle(cam: &Camera, screen: Vec2) -> Vec2 {
+    cam.center + view_size(cam, screen) * 0.5
+}
+
+// resizing recenters on the origin, keep the middle of the view where it was
+fn resize_kept(cam: &mut Camera, screen: Vec2) {
+    let middle = view_middle(cam, screen);
+    cam.resize(screen);
+    cam.center = middle - view_size(cam, screen) * 0.5;
+}
@@ -0,0 +1,32 @@
+/// Shape of a transition over time, e.g. of [`Camera::zoom_to`](crate::Camera::zoom_to).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Ease {
+    Linear,
+    InQuad,
+    OutQuad,
+    InOutQuad,
+    InCubic,
+    /// Fast start settling gently, the usual choice for cameras.
+    #[default]
+    OutCubic,
+    InOutCubic,
+}
+
+impl Ease {
+    /// Progress at time `t`, both 0..1; `t` outside is clamped.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::InQuad => t * t,
+            Self::OutQuad => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::InOutQuad if t < 0.5 => 2.0 * t * t,
+            Self::InOutQuad => 1.0 - (-2.0 * t + 2.0).powi(2) * 0.5,
+            Self::InCubic => t * t * t,
+            Self::OutCubic => 1.0 - (1.0 - t).powi(3),
+            Self::InOutCubic if t < 0.5 => 4.0 * t * t * t,
+            Self::InOutCubic => 1.0 - (-2.0 * t + 2.0).powi(3) * 0.5,
+        }
+    }
+}
@@ -1,3 +1,10 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::TextureId;
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("no renderer")]
@@ -10,4 +17,85 @@ pub enum Error {
     EventLoop(#[from] winit::error::EventLoopError),
     #[error("image error: {0}")]
     Image(#[from] image::ImageError),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("font error: {0}")]
+    Font(String),
+    #[error("plugin error: {0}")]
+    Plugin(String),
+    #[error("steam error: {0}")]
+    Steam(String),
+    #[error("dialogue error: {0}")]
+    Dialogue(String),
+    #[error("launch option error: {0}")]
+    Launch(String),
+    #[error("settings error: {0}")]
+    Settings(String),
+    #[error("aseprite error: {0}")]
+    Aseprite(String),
+    #[error("tilemap error: {0}")]
+    Tilemap(String),
+    #[error("backend error: {0}")]
+    Backend(String),
+    #[error("asset error: {0}")]
+    Asset(#[from] AssetError),
+}
+
+/// Why an image file did not become a texture.
+///
+/// Sent through `ctx.events` by the app for every failed load, including
+/// failed retries, so scenes can show what is missing.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum AssetError {
+    #[error("{} not found", path.display())]
+    NotFound { path: PathBuf, tex: TextureId },
+    /// The file exists but could not be read, e.g. a network drive timing out.
+    #[error("failed to read {}: {reason}", path.display())]
+    ReadFailed {
+        path: PathBuf,
+        tex: TextureId,
+        kind: io::ErrorKind,
+        reason: String,
+    },
+    #[error("failed to decode {}: {reason}", path.display())]
+    DecodeFailed {
+        path: PathBuf,
+        tex: TextureId,
+        reason: String,
+    },
+    /// `path` hashes to the id of another file already loaded from `loaded`.
+    #[error("{} has the same texture id as {}", path.display(), loaded.display())]
+    IdCollision {
+        path: PathBuf,
+        tex: TextureId,
+        loaded: PathBuf,
+    },
+    #[error("failed to upload {} to the gpu: {reason}", path.display())]
+    GpuUploadFailed {
+        path: PathBuf,
+        tex: TextureId,
+        reason: String,
+    },
+}
+
+impl AssetError {
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::NotFound { path, .. }
+            | Self::ReadFailed { path, .. }
+            | Self::DecodeFailed { path, .. }
+            | Self::IdCollision { path, .. }
+            | Self::GpuUploadFailed { path, .. } => path,
+        }
+    }
+
+    pub fn tex(&self) -> TextureId {
+        match self {
+            Self::NotFound { tex, .. }
+            | Self::ReadFailed { tex, .. }
+            | Self::DecodeFailed { tex, .. }
+            | Self::IdCollision { tex, .. }
+            | Self::GpuUploadFailed { tex, .. } => *tex,
+        }
+    }
 }
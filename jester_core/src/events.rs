@@ -0,0 +1,86 @@
+use std::{
+    any::{Any, TypeId},
+    fmt,
+};
+
+use hashbrown::HashMap;
+
+/// A `Vec<E>` whose `E` is erased, so queues of any event type share one map.
+trait Queue: Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    /// Moves the events of `other`, a queue of the same type, to the end of `self`.
+    fn append(&mut self, other: &mut dyn Queue);
+}
+
+impl<E: Any + Send + Sync> Queue for Vec<E> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn append(&mut self, other: &mut dyn Queue) {
+        let other = other.as_any_mut().downcast_mut::<Vec<E>>();
+        Vec::append(self, other.expect("keyed by type"));
+    }
+}
+
+/// Typed events sent during one frame and readable by every scene during the next.
+///
+/// Any `Send + Sync` type works as an event; send with [`Ctx::send_event`](crate::Ctx::send_event)
+/// and read with [`Events::read`] through `ctx.events`.
+#[derive(Default)]
+pub struct Events {
+    current: HashMap<TypeId, Box<dyn Queue>>,
+    queued: HashMap<TypeId, Box<dyn Queue>>,
+}
+
+impl fmt::Debug for Events {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Events")
+            .field("current", &self.current.len())
+            .field("queued", &self.queued.len())
+            .finish()
+    }
+}
+
+impl Events {
+    /// Queues `event` for the next [`Events::begin_frame`].
+    pub fn send<E: Any + Send + Sync>(&mut self, event: E) {
+        self.queued
+            .entry(TypeId::of::<E>())
+            .or_insert_with(|| Box::new(Vec::<E>::new()))
+            .as_any_mut()
+            .downcast_mut::<Vec<E>>()
+            .expect("keyed by type")
+            .push(event);
+    }
+
+    /// Events of type `E` delivered this frame, oldest first.
+    pub fn read<E: Any + Send + Sync>(&self) -> &[E] {
+        self.current
+            .get(&TypeId::of::<E>())
+            .and_then(|q| q.as_any().downcast_ref::<Vec<E>>())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Moves the events queued in `other` behind the ones queued here.
+    pub fn append(&mut self, other: &mut Events) {
+        for (ty, mut events) in other.queued.drain() {
+            match self.queued.get_mut(&ty) {
+                Some(queue) => queue.append(events.as_mut()),
+                None => {
+                    self.queued.insert(ty, events);
+                }
+            }
+        }
+    }
+
+    /// Delivers the queued events and drops the ones from the previous frame.
+    pub fn begin_frame(&mut self) {
+        self.current = std::mem::take(&mut self.queued);
+    }
+}
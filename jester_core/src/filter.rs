@@ -0,0 +1,177 @@
+use glam::Vec2;
+
+use crate::{Color, Fill, Panel, Transform};
+
+/// A color vision deficiency, each missing one of the three cone types.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorBlindness {
+    /// No red cones; reds look dark and close to greens.
+    Protanopia,
+    /// No green cones, the most common; reds and greens are confused.
+    Deuteranopia,
+    /// No blue cones; blues and greens, and yellows and pinks, are confused.
+    Tritanopia,
+}
+
+/// How a [`ScreenFilter`] accounts for color blindness.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorVision {
+    #[default]
+    Normal,
+    /// Shows the picture as a viewer with the deficiency sees it, to check a palette.
+    Simulate(ColorBlindness),
+    /// Daltonization: moves the contrast such a viewer would miss into colors they can tell apart.
+    Daltonize(ColorBlindness),
+}
+
+/// Full-screen effects applied to a camera's output after everything it shows is drawn.
+///
+/// All strengths are 0 for off; the presets are starting points to tweak.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ScreenFilter {
+    /// Barrel distortion of the picture, around 0.1 for a CRT tube.
+    pub curvature: f32,
+    /// How much every other pixel row is darkened, 0..1.
+    pub scanlines: f32,
+    /// Red and blue channel offset in pixels.
+    pub chromatic_aberration: f32,
+    /// Animated noise amount, 0..1.
+    pub grain: f32,
+    /// Darkening towards the corners, 0..1.
+    pub vignette: f32,
+    /// How much of the previous frame stays visible, 0..1, so moving sprites leave fading trails.
+    pub trail: f32,
+    /// Applied last, over the other effects.
+    pub color_vision: ColorVision,
+}
+
+impl ScreenFilter {
+    pub const NONE: Self = Self {
+        curvature: 0.0,
+        scanlines: 0.0,
+        chromatic_aberration: 0.0,
+        grain: 0.0,
+        vignette: 0.0,
+        trail: 0.0,
+        color_vision: ColorVision::Normal,
+    };
+    pub const CRT: Self = Self {
+        curvature: 0.12,
+        scanlines: 0.35,
+        chromatic_aberration: 1.0,
+        grain: 0.03,
+        vignette: 0.4,
+        ..Self::NONE
+    };
+    pub const SCANLINES: Self = Self {
+        scanlines: 0.3,
+        ..Self::NONE
+    };
+    pub const VHS: Self = Self {
+        scanlines: 0.15,
+        chromatic_aberration: 2.5,
+        grain: 0.08,
+        vignette: 0.2,
+        ..Self::NONE
+    };
+    pub const TRAILS: Self = Self {
+        trail: 0.8,
+        ..Self::NONE
+    };
+    pub const FILM: Self = Self {
+        grain: 0.06,
+        vignette: 0.5,
+        ..Self::NONE
+    };
+
+    /// Only simulates `kind`, e.g. bound to a debug key while designing levels.
+    pub const fn simulate(kind: ColorBlindness) -> Self {
+        Self {
+            color_vision: ColorVision::Simulate(kind),
+            ..Self::NONE
+        }
+    }
+
+    /// Only corrects for `kind`, for players who enable it in the options.
+    pub const fn daltonize(kind: ColorBlindness) -> Self {
+        Self {
+            color_vision: ColorVision::Daltonize(kind),
+            ..Self::NONE
+        }
+    }
+
+    pub fn is_none(&self) -> bool {
+        *self == Self::NONE
+    }
+}
+
+/// Gamma and brightness of the whole window, the usual options menu sliders.
+///
+/// Applied in the final composition pass, on top of every camera's
+/// [`ScreenFilter`], while registered with `App::add_resource`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayCalibration {
+    /// Above 1 lifts the shadows, below 1 darkens them; 1 leaves colors as drawn.
+    pub gamma: f32,
+    /// Multiplies every color after the gamma curve; 1 leaves colors as drawn.
+    pub brightness: f32,
+}
+
+impl Default for DisplayCalibration {
+    fn default() -> Self {
+        Self::NEUTRAL
+    }
+}
+
+impl DisplayCalibration {
+    pub const NEUTRAL: Self = Self {
+        gamma: 1.0,
+        brightness: 1.0,
+    };
+
+    pub fn new(gamma: f32, brightness: f32) -> Self {
+        Self { gamma, brightness }
+    }
+
+    /// Panels for a calibration screen centered on `center`, `size` wide.
+    ///
+    /// A row of near-black squares on black above a row of near-white squares
+    /// on white, each a step further from the background. The player raises
+    /// gamma until the faintest dark square shows and lowers brightness until
+    /// the faintest light one does.
+    ///
+    /// ```ignore
+    /// for panel in DisplayCalibration::test_pattern(Vec2::ZERO, 640.0) {
+    ///     ctx.spawn_panel(panel);
+    /// }
+    /// ```
+    pub fn test_pattern(center: Vec2, size: f32) -> Vec<Panel> {
+        const STEPS: usize = 8;
+        // 1 to 8 steps of 2% from the background
+        const STEP: f32 = 0.02;
+        let square = size / (STEPS as f32 * 1.5 + 0.5);
+        let row = |y: f32, background: f32, towards: f32| {
+            let bg = Panel {
+                transform: Transform::from_xy(center.x, y),
+                size: Vec2::new(size, square * 2.0),
+                fill: Fill::Solid(Color::rgb(background, background, background)),
+                ..Default::default()
+            };
+            let squares = (0..STEPS).map(move |i| {
+                let level = background + towards * STEP * (i + 1) as f32;
+                let x = center.x - size / 2.0 + square * (1.5 * i as f32 + 1.0);
+                Panel {
+                    transform: Transform::from_xy(x, y),
+                    size: Vec2::splat(square),
+                    fill: Fill::Solid(Color::rgb(level, level, level)),
+                    ..Default::default()
+                }
+            });
+            std::iter::once(bg).chain(squares)
+        };
+        row(center.y + square, 0.0, 1.0)
+            .chain(row(center.y - square, 1.0, -1.0))
+            .collect()
+    }
+}
@@ -0,0 +1,211 @@
+use glam::Vec2;
+use winit::{event::MouseButton, keyboard::KeyCode};
+
+use crate::{
+    ui::PanelInstance, Color, Events, Fill, GamepadAxis, GamepadButton, InputState, Panel,
+    Transform, UiLayout, UiRect,
+};
+
+/// Sent by the UI set with [`Ctx::set_ui`](crate::Ctx::set_ui), readable through `ctx.events`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UiEvent {
+    /// A focusable node was activated by click, key or gamepad.
+    ButtonClicked(String),
+    FocusChanged(String),
+    /// Escape or the east gamepad button, e.g. to close a menu.
+    Cancelled,
+}
+
+/// Stick deflection that counts as a navigation press.
+const STICK_PRESS: f32 = 0.6;
+/// Deflection the stick must fall back under before it navigates again.
+const STICK_RELEASE: f32 = 0.3;
+
+/// Keyboard, mouse and gamepad focus over the focusable nodes of the UI set with
+/// [`Ctx::set_ui`](crate::Ctx::set_ui).
+///
+/// Tab and shift+tab cycle in tree order; arrows, the d-pad and the left stick
+/// move to the nearest node in that direction, arrows and the d-pad repeating
+/// while held as set by [`InputTimings`](crate::InputTimings). Enter, space and
+/// the south button activate the focused node, escape and the east button
+/// cancel. Hovering focuses a node and clicking activates it.
+#[derive(Clone, Debug)]
+pub struct UiFocus {
+    focused: Option<String>,
+    activated: Option<String>,
+    cancelled: bool,
+    stick_held: bool,
+    mouse: Vec2,
+    /// Drawn around the focused node, `None` leaves focus to [`UiNode::focus_panel`](crate::UiNode::focus_panel).
+    pub highlight: Option<Panel>,
+}
+
+impl Default for UiFocus {
+    fn default() -> Self {
+        Self {
+            focused: None,
+            activated: None,
+            cancelled: false,
+            stick_held: false,
+            mouse: Vec2::ZERO,
+            highlight: Some(Panel {
+                fill: Fill::Solid(Color::TRANSPARENT),
+                corner_radius: 4.0,
+                border_width: 2.0,
+                border_color: Color::WHITE,
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+impl UiFocus {
+    pub fn focused(&self) -> Option<&str> {
+        self.focused.as_deref()
+    }
+
+    /// The node activated this frame.
+    pub fn activated(&self) -> Option<&str> {
+        self.activated.as_deref()
+    }
+
+    pub fn is_activated(&self, name: &str) -> bool {
+        self.activated.as_deref() == Some(name)
+    }
+
+    /// Whether back was pressed this frame, e.g. to close a menu.
+    pub fn cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    pub fn set_focus(&mut self, name: Option<String>) {
+        self.focused = name;
+    }
+
+    /// Applies this frame's input to `layout` and sends the resulting [`UiEvent`]s.
+    /// The first focusable node gets focus when none has it.
+    pub fn update(&mut self, layout: &UiLayout, input: &InputState, events: &mut Events) {
+        self.activated = None;
+        self.cancelled = false;
+        let nodes: Vec<(&str, UiRect)> = layout.focusable().collect();
+        let current = self
+            .focused
+            .as_deref()
+            .and_then(|f| nodes.iter().position(|(name, _)| *name == f));
+        let Some(mut current) = current.or((!nodes.is_empty()).then_some(0)) else {
+            self.focused = None;
+            return;
+        };
+
+        let mouse = input.mouse_pos();
+        let hovered = nodes.iter().position(|(_, r)| r.contains(mouse));
+        if mouse != self.mouse
+            && let Some(i) = hovered
+        {
+            current = i;
+        }
+        self.mouse = mouse;
+
+        if input.just_pressed(KeyCode::Tab) {
+            let shift =
+                input.key_pressed(KeyCode::ShiftLeft) || input.key_pressed(KeyCode::ShiftRight);
+            current = match shift {
+                true => (current + nodes.len() - 1) % nodes.len(),
+                false => (current + 1) % nodes.len(),
+            };
+        }
+        if let Some(dir) = self.direction(input) {
+            current = nearest(&nodes, current, dir).unwrap_or(current);
+        }
+
+        let clicked = input.mouse_just_pressed(MouseButton::Left) && hovered.is_some();
+        if clicked {
+            current = hovered.expect("checked above");
+        }
+        let name = nodes[current].0;
+        if self.focused.as_deref() != Some(name) {
+            events.send(UiEvent::FocusChanged(name.to_owned()));
+            self.focused = Some(name.to_owned());
+        }
+        let activate = [KeyCode::Enter, KeyCode::NumpadEnter, KeyCode::Space]
+            .into_iter()
+            .any(|k| input.just_pressed(k))
+            || input.pad_just_pressed(GamepadButton::South);
+        if activate || clicked {
+            self.activated = self.focused.clone();
+            events.send(UiEvent::ButtonClicked(name.to_owned()));
+            layout.activate(name, events);
+        }
+        self.cancelled =
+            input.just_pressed(KeyCode::Escape) || input.pad_just_pressed(GamepadButton::East);
+        if self.cancelled {
+            events.send(UiEvent::Cancelled);
+        }
+    }
+
+    /// The [`UiFocus::highlight`] ring around the focused node, in screen space.
+    pub fn highlight_instance(&self, layout: &UiLayout) -> Option<PanelInstance> {
+        let ring = self.highlight?;
+        let rect = layout.get(self.focused.as_deref()?)?;
+        let c = rect.screen_center(layout.screen);
+        let panel = Panel {
+            transform: Transform::from_xy(c.x, c.y),
+            size: rect.size + Vec2::splat(ring.border_width * 2.0 + 2.0),
+            ..ring
+        };
+        Some(panel.instance())
+    }
+
+    /// Navigation direction pressed this frame, y down.
+    fn direction(&mut self, input: &InputState) -> Option<Vec2> {
+        let pressed = |k: KeyCode, b: GamepadButton| input.key_repeated(k) || input.pad_repeated(b);
+        if pressed(KeyCode::ArrowUp, GamepadButton::DPadUp) {
+            return Some(Vec2::NEG_Y);
+        }
+        if pressed(KeyCode::ArrowDown, GamepadButton::DPadDown) {
+            return Some(Vec2::Y);
+        }
+        if pressed(KeyCode::ArrowLeft, GamepadButton::DPadLeft) {
+            return Some(Vec2::NEG_X);
+        }
+        if pressed(KeyCode::ArrowRight, GamepadButton::DPadRight) {
+            return Some(Vec2::X);
+        }
+        // the stick navigates once per push, not every frame it is held
+        let stick = Vec2::new(
+            input.pad_axis(GamepadAxis::LeftStickX),
+            -input.pad_axis(GamepadAxis::LeftStickY),
+        );
+        let deflection = stick.abs().max_element();
+        if self.stick_held {
+            self.stick_held = deflection > STICK_RELEASE;
+            return None;
+        }
+        if deflection < STICK_PRESS {
+            return None;
+        }
+        self.stick_held = true;
+        Some(match stick.x.abs() > stick.y.abs() {
+            true => Vec2::new(stick.x.signum(), 0.0),
+            false => Vec2::new(0.0, stick.y.signum()),
+        })
+    }
+}
+
+/// The node closest to `from` in direction `dir`, preferring ones in line with it.
+fn nearest(nodes: &[(&str, UiRect)], from: usize, dir: Vec2) -> Option<usize> {
+    let center = |r: &UiRect| r.min + r.size * 0.5;
+    let origin = center(&nodes[from].1);
+    nodes
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != from)
+        .filter_map(|(i, (_, r))| {
+            let d = center(r) - origin;
+            let along = d.dot(dir);
+            let across = d.perp_dot(dir).abs();
+            (along > 0.0).then_some((i, along + across * 2.0))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
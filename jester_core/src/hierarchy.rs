@@ -0,0 +1,131 @@
+use hashbrown::HashMap;
+
+use crate::{EntityId, SpriteStore, Transform};
+
+/// A child sprite's transform relative to its parent's, see [`Hierarchy::set_parent`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LocalTransform(pub Transform);
+
+/// A sprite's transform in the world, as last propagated and drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GlobalTransform(pub Transform);
+
+/// Parent links between sprites, kept in [`EntityPool::hierarchy`](crate::EntityPool::hierarchy).
+///
+/// A child follows its parent's translation, scale and rotation: once per
+/// frame, after velocities and animations, the app propagates every
+/// child's [`LocalTransform`] and writes the result into the child's
+/// `Sprite::transform`, so move children through their local transform.
+/// [`Hierarchy::global`] gives that propagation's transforms, so gameplay
+/// math sees what was drawn even for parents moved since.
+///
+/// A child whose parent is despawned keeps its last world transform.
+///
+/// ```ignore
+/// let ship = ctx.spawn_sprite(hull);
+/// let turret = ctx.spawn_sprite(gun);
+/// ctx.set_parent(turret, ship, Transform::from_xy(0.0, 12.0));
+/// // later, aim from where the turret was drawn
+/// let muzzle = ctx.global_transform(turret).unwrap().0.translation;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Hierarchy {
+    parents: HashMap<EntityId, (EntityId, LocalTransform)>,
+    // every sprite linked to a parent or child, as of the last propagation
+    globals: HashMap<EntityId, GlobalTransform>,
+}
+
+impl Hierarchy {
+    /// Makes `child` follow `parent` at `local`, unless `parent` is `child`
+    /// or one of its descendants; returns whether the link was made.
+    pub fn set_parent(&mut self, child: EntityId, parent: EntityId, local: Transform) -> bool {
+        let mut up = Some(parent);
+        while let Some(id) = up {
+            if id == child {
+                return false;
+            }
+            up = self.parent(id);
+        }
+        self.parents.insert(child, (parent, LocalTransform(local)));
+        true
+    }
+
+    /// Unlinks `child`, which stays where it was last drawn.
+    pub fn remove_parent(&mut self, child: EntityId) {
+        self.parents.remove(&child);
+    }
+
+    pub fn parent(&self, child: EntityId) -> Option<EntityId> {
+        self.parents.get(&child).map(|(p, _)| *p)
+    }
+
+    pub fn children(&self, parent: EntityId) -> impl Iterator<Item = EntityId> + '_ {
+        self.parents
+            .iter()
+            .filter(move |(_, (p, _))| *p == parent)
+            .map(|(c, _)| *c)
+    }
+
+    pub fn local(&self, child: EntityId) -> Option<LocalTransform> {
+        self.parents.get(&child).map(|(_, l)| *l)
+    }
+
+    /// Applied from the next propagation on.
+    pub fn local_mut(&mut self, child: EntityId) -> Option<&mut LocalTransform> {
+        self.parents.get_mut(&child).map(|(_, l)| l)
+    }
+
+    /// World transform of `id` as last propagated; for a sprite outside any
+    /// hierarchy that is its own transform.
+    pub fn global(&self, id: EntityId, sprites: &SpriteStore) -> Option<GlobalTransform> {
+        self.globals
+            .get(&id)
+            .copied()
+            .or_else(|| sprites.get(&id).map(|s| GlobalTransform(s.transform)))
+    }
+
+    /// Drops links to or from `id`, e.g. once it is despawned.
+    pub fn remove(&mut self, id: EntityId) {
+        self.parents.remove(&id);
+        self.globals.remove(&id);
+    }
+
+    pub fn clear(&mut self) {
+        self.parents.clear();
+        self.globals.clear();
+    }
+
+    /// Computes every linked sprite's world transform, parents first, and
+    /// writes those of children into their sprites.
+    pub fn propagate(&mut self, sprites: &mut SpriteStore) {
+        // links whose ends are gone leave the child where it was
+        self.parents
+            .retain(|c, (p, _)| sprites.contains_key(c) && sprites.contains_key(p));
+        self.globals.clear();
+        let ids: Vec<EntityId> = self.parents.keys().copied().collect();
+        for id in ids {
+            self.resolve(id, sprites);
+        }
+        for (id, g) in &self.globals {
+            // written only when changed so the sprite keeps its cached batch
+            if self.parents.contains_key(id)
+                && sprites.get(id).is_some_and(|s| s.transform != g.0)
+                && let Some(s) = sprites.get_mut(id)
+            {
+                s.transform = g.0;
+            }
+        }
+    }
+
+    fn resolve(&mut self, id: EntityId, sprites: &SpriteStore) -> Transform {
+        if let Some(g) = self.globals.get(&id) {
+            return g.0;
+        }
+        let global = match self.parents.get(&id).copied() {
+            Some((parent, local)) => self.resolve(parent, sprites).mul_transform(&local.0),
+            None => sprites.get(&id).map(|s| s.transform).unwrap_or_default(),
+        };
+        self.globals.insert(id, GlobalTransform(global));
+        global
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use image::{ImageError, ImageResult};
+use tracing::warn;
+
+use crate::{DecodedImage, TextureFormat};
+
+const MAGIC: &[u8; 4] = b"JTEX";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 17;
+/// Source files [`ImportCache::import_dir`] converts.
+const EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "bmp", "tga", "gif", "qoi"];
+
+/// Decoded textures kept on disk, so a launch copies texels instead of
+/// decoding PNGs; set with `Renderer::set_import_cache`.
+///
+/// Each source image is converted once into a `.jtex` file named after a
+/// hash of the source's bytes: the texels as the GPU takes them behind a
+/// short header. An edited source hashes differently and is imported again
+/// the first time it loads, so the cache never goes stale; files of old
+/// versions stay until the directory is deleted.
+///
+/// Only images are imported. Aseprite sheets are covered through the PNG
+/// of their JSON export, but native `.aseprite` files have no loader to
+/// import for. Neither do Tiled `.tmx` maps, since tilemaps load from LDtk
+/// projects. TTF fonts and LDtk projects are read as they are.
+///
+/// ```ignore
+/// // at build time, or behind a loading screen on first run
+/// let cache = ImportCache::new("target/jester-cache");
+/// cache.import_dir("assets")?;
+/// renderer.set_import_cache(Some(cache));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ImportCache {
+    dir: PathBuf,
+}
+
+impl ImportCache {
+    /// A cache in `dir`, created on the first import.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Where the converted texels of an image with these bytes are kept.
+    pub fn cached_path(&self, source: &[u8]) -> PathBuf {
+        self.dir.join(format!("{:016x}.jtex", content_hash(source)))
+    }
+
+    /// Decodes the image at `path` like [`DecodedImage::open`], from the
+    /// cache when it was imported before and into it otherwise.
+    ///
+    /// A cache that cannot be written only costs the next launch the decode.
+    pub fn open(&self, path: impl AsRef<Path>) -> ImageResult<DecodedImage> {
+        let source = std::fs::read(path.as_ref()).map_err(ImageError::IoError)?;
+        let cached = self.cached_path(&source);
+        if let Some(img) = std::fs::read(&cached).ok().and_then(|b| read_jtex(&b)) {
+            return Ok(img);
+        }
+        let img = DecodedImage::from_bytes(&source)?;
+        if let Err(e) = self.write(&cached, &img) {
+            warn!("failed to cache {}: {e}", path.as_ref().display());
+        }
+        Ok(img)
+    }
+
+    /// Imports every image under `root` not in the cache yet, returning how
+    /// many were converted.
+    pub fn import_dir(&self, root: impl AsRef<Path>) -> io::Result<usize> {
+        let mut imported = 0;
+        let mut dirs = vec![root.as_ref().to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                let is_image = path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| EXTENSIONS.iter().any(|known| e.eq_ignore_ascii_case(known)));
+                if !is_image {
+                    continue;
+                }
+                let source = std::fs::read(&path)?;
+                let cached = self.cached_path(&source);
+                if cached.exists() {
+                    continue;
+                }
+                match DecodedImage::from_bytes(&source) {
+                    Ok(img) => {
+                        self.write(&cached, &img)?;
+                        imported += 1;
+                    }
+                    Err(e) => warn!("skipped importing {}: {e}", path.display()),
+                }
+            }
+        }
+        Ok(imported)
+    }
+
+    fn write(&self, cached: &Path, img: &DecodedImage) -> io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let mut bytes = Vec::with_capacity(HEADER_LEN + img.data.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&img.width.to_le_bytes());
+        bytes.extend_from_slice(&img.height.to_le_bytes());
+        bytes.push(match img.format {
+            TextureFormat::Rgba8 => 0,
+            TextureFormat::Rgba16 => 1,
+            TextureFormat::R8 => 2,
+        });
+        bytes.extend_from_slice(&img.data);
+        // moved into place, so a crash never leaves half a file; named apart
+        // from other threads and processes writing the same image at once
+        static WRITES: AtomicU64 = AtomicU64::new(0);
+        let n = WRITES.fetch_add(1, Ordering::Relaxed);
+        let tmp = cached.with_extension(format!("{}.{n}.tmp", std::process::id()));
+        std::fs::write(&tmp, bytes)
+            .and_then(|()| std::fs::rename(&tmp, cached))
+            .inspect_err(|_| {
+                std::fs::remove_file(&tmp).ok();
+            })
+    }
+}
+
+/// Decodes `path` through `cache` if there is one.
+pub(crate) fn open_image(cache: Option<&ImportCache>, path: &Path) -> ImageResult<DecodedImage> {
+    match cache {
+        Some(c) => c.open(path),
+        None => DecodedImage::open(path),
+    }
+}
+
+/// The image in a `.jtex` file, `None` if it is cut short or of another version.
+fn read_jtex(bytes: &[u8]) -> Option<DecodedImage> {
+    let (header, data) = bytes.split_at_checked(HEADER_LEN)?;
+    let word = |at: usize| u32::from_le_bytes(header[at..at + 4].try_into().expect("4 bytes"));
+    if &header[..4] != MAGIC || word(4) != VERSION {
+        return None;
+    }
+    let (width, height) = (word(8), word(12));
+    let format = match header[16] {
+        0 => TextureFormat::Rgba8,
+        1 => TextureFormat::Rgba16,
+        2 => TextureFormat::R8,
+        _ => return None,
+    };
+    let len = width as usize * height as usize * format.bytes_per_texel();
+    (data.len() == len).then(|| DecodedImage {
+        width,
+        height,
+        format,
+        data: data.to_vec(),
+    })
+}
+
+/// FNV-1a, the same on every platform and compiler version unlike `DefaultHasher`.
+fn content_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |h, &b| {
+        (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
@@ -1,69 +1,585 @@
 use glam::Vec2;
 use winit::{event::MouseButton, keyboard::KeyCode};
 
+/// Gamepad buttons by position, Xbox `A` and PlayStation cross are both [`GamepadButton::South`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    South,
+    East,
+    West,
+    North,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    LeftShoulder,
+    RightShoulder,
+    LeftTrigger,
+    RightTrigger,
+    LeftThumb,
+    RightThumb,
+    Select,
+    Start,
+}
+
+impl GamepadButton {
+    pub const ALL: [Self; 16] = [
+        Self::South,
+        Self::East,
+        Self::West,
+        Self::North,
+        Self::DPadUp,
+        Self::DPadDown,
+        Self::DPadLeft,
+        Self::DPadRight,
+        Self::LeftShoulder,
+        Self::RightShoulder,
+        Self::LeftTrigger,
+        Self::RightTrigger,
+        Self::LeftThumb,
+        Self::RightThumb,
+        Self::Select,
+        Self::Start,
+    ];
+}
+
+/// Analog gamepad inputs, sticks in `-1.0..=1.0` with y up, triggers in `0.0..=1.0`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+impl GamepadAxis {
+    pub const ALL: [Self; 6] = [
+        Self::LeftStickX,
+        Self::LeftStickY,
+        Self::RightStickX,
+        Self::RightStickY,
+        Self::LeftTrigger,
+        Self::RightTrigger,
+    ];
+}
+
+/// How far a raw [`GamepadAxis`] value must move to count, and how it
+/// maps to what [`InputState::pad_axis`] returns.
+///
+/// Deflection inside `dead_zone` reads 0 and from `saturation` on reads
+/// full; in between it is rescaled to `0.0..=1.0` and raised to
+/// `exponent`, so values above 1 give finer control near the center.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AxisCurve {
+    pub dead_zone: f32,
+    pub saturation: f32,
+    pub exponent: f32,
+}
+
+impl AxisCurve {
+    /// Values as the gamepad reports them.
+    pub const RAW: Self = Self {
+        dead_zone: 0.0,
+        saturation: 1.0,
+        exponent: 1.0,
+    };
+
+    pub fn with_dead_zone(mut self, dead_zone: f32) -> Self {
+        self.dead_zone = dead_zone;
+        self
+    }
+
+    pub fn with_saturation(mut self, saturation: f32) -> Self {
+        self.saturation = saturation;
+        self
+    }
+
+    pub fn with_exponent(mut self, exponent: f32) -> Self {
+        self.exponent = exponent;
+        self
+    }
+
+    /// `raw` after this curve, keeping its sign.
+    pub fn apply(&self, raw: f32) -> f32 {
+        let dead_zone = self.dead_zone.clamp(0.0, 1.0);
+        let range = self.saturation.min(1.0) - dead_zone;
+        if raw.abs() <= dead_zone {
+            return 0.0;
+        }
+        if range <= 0.0 {
+            return raw.signum();
+        }
+        let t = ((raw.abs() - dead_zone) / range).min(1.0);
+        t.powf(self.exponent.max(0.0)).copysign(raw)
+    }
+}
+
+impl Default for AxisCurve {
+    fn default() -> Self {
+        // enough to hide the drift of worn sticks
+        Self::RAW.with_dead_zone(0.15)
+    }
+}
+
+/// An [`AxisCurve`] per [`GamepadAxis`], copied to `ctx.input` every frame
+/// while registered as a resource with `App::add_resource`.
+///
+/// ```ignore
+/// let sticks = AxisCurve::default().with_dead_zone(0.2).with_exponent(2.0);
+/// app.add_resource(AxisCurves::default().with_sticks(sticks));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AxisCurves {
+    // indexed like `GamepadAxis::ALL`
+    curves: [AxisCurve; 6],
+}
+
+impl AxisCurves {
+    /// Every axis unshaped.
+    pub const RAW: Self = Self {
+        curves: [AxisCurve::RAW; 6],
+    };
+
+    pub fn get(&self, axis: GamepadAxis) -> AxisCurve {
+        self.curves[axis as usize]
+    }
+
+    pub fn set(&mut self, axis: GamepadAxis, curve: AxisCurve) {
+        self.curves[axis as usize] = curve;
+    }
+
+    pub fn with(mut self, axis: GamepadAxis, curve: AxisCurve) -> Self {
+        self.set(axis, curve);
+        self
+    }
+
+    /// Sets the curve of both axes of both sticks.
+    pub fn with_sticks(self, curve: AxisCurve) -> Self {
+        [
+            GamepadAxis::LeftStickX,
+            GamepadAxis::LeftStickY,
+            GamepadAxis::RightStickX,
+            GamepadAxis::RightStickY,
+        ]
+        .into_iter()
+        .fold(self, |curves, axis| curves.with(axis, curve))
+    }
+
+    pub fn with_triggers(self, curve: AxisCurve) -> Self {
+        self.with(GamepadAxis::LeftTrigger, curve)
+            .with(GamepadAxis::RightTrigger, curve)
+    }
+}
+
+impl Default for AxisCurves {
+    fn default() -> Self {
+        Self {
+            curves: [AxisCurve::default(); 6],
+        }
+        // triggers rest at 0 without drifting
+        .with_triggers(AxisCurve::RAW.with_dead_zone(0.05))
+    }
+}
+
+/// When held keys and buttons repeat and count as held, in seconds.
+///
+/// See [`InputState::key_repeated`] and [`InputState::key_held`]; players who
+/// press slowly or unsteadily need longer delays.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InputTimings {
+    /// Time before a held key first repeats.
+    pub repeat_delay: f32,
+    /// Time between repeats after that.
+    pub repeat_interval: f32,
+    /// Time a key must be down to count as held.
+    pub hold_time: f32,
+}
+
+impl Default for InputTimings {
+    fn default() -> Self {
+        Self {
+            repeat_delay: 0.4,
+            repeat_interval: 0.1,
+            hold_time: 0.5,
+        }
+    }
+}
+
+/// One key or button this frame, see [`InputState::key`].
+///
+/// A button changes at most once per frame: presses and releases arriving
+/// faster than frames are drawn wait for the next frame rather than being
+/// lost, so a tap shorter than a frame is still seen as pressed for one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ButtonState {
+    pub pressed: bool,
+    /// Went down this frame, `pressed` is then set as well.
+    pub just_pressed: bool,
+    /// Went up this frame.
+    pub just_released: bool,
+}
+
+impl ButtonState {
+    /// Either button, e.g. a key and the gamepad button of one action.
+    pub fn or(self, other: Self) -> Self {
+        Self {
+            pressed: self.pressed || other.pressed,
+            just_pressed: self.just_pressed || other.just_pressed,
+            just_released: self.just_released || other.just_released,
+        }
+    }
+}
+
+/// Longest window [`InputState::buffered_pressed`] looks back, in seconds.
+pub const MAX_INPUT_BUFFER: f32 = 1.0;
+
+/// Buttons of one kind: down as of the last tick, the edges of this frame
+/// and the changes received since.
+#[derive(Clone, Debug)]
+struct Buttons<T> {
+    down: Vec<T>,
+    pressed: Vec<T>,
+    released: Vec<T>,
+    pending: Vec<(T, bool)>,
+    // seconds since each button last went down, up to `MAX_INPUT_BUFFER`
+    since_pressed: Vec<(T, f32)>,
+}
+
+impl<T> Default for Buttons<T> {
+    fn default() -> Self {
+        Self {
+            down: Vec::new(),
+            pressed: Vec::new(),
+            released: Vec::new(),
+            pending: Vec::new(),
+            since_pressed: Vec::new(),
+        }
+    }
+}
+
+impl<T: Copy + PartialEq> Buttons<T> {
+    fn state(&self, b: T) -> ButtonState {
+        ButtonState {
+            pressed: self.down.contains(&b),
+            just_pressed: self.pressed.contains(&b),
+            just_released: self.released.contains(&b),
+        }
+    }
+
+    fn set(&mut self, b: T, down: bool) {
+        self.pending.push((b, down));
+    }
+
+    fn pressed_within(&self, b: T, window: f32) -> bool {
+        self.since_pressed
+            .iter()
+            .any(|(x, t)| *x == b && *t <= window)
+    }
+
+    // applies the pending changes in order, one per button and frame, `dt` after the last
+    fn update(&mut self, dt: f32) {
+        for (_, t) in &mut self.since_pressed {
+            *t += dt;
+        }
+        self.since_pressed.retain(|(_, t)| *t <= MAX_INPUT_BUFFER);
+        let mut deferred = Vec::new();
+        for (b, down) in std::mem::take(&mut self.pending) {
+            let changed = self.pressed.contains(&b)
+                || self.released.contains(&b)
+                || deferred.iter().any(|(d, _)| *d == b);
+            if changed {
+                deferred.push((b, down));
+                continue;
+            }
+            // repeats of the current state, e.g. OS key repeat, change nothing
+            match (down, self.down.contains(&b)) {
+                (true, false) => {
+                    self.down.push(b);
+                    self.pressed.push(b);
+                }
+                (false, true) => {
+                    self.down.retain(|x| *x != b);
+                    self.released.push(b);
+                }
+                _ => {}
+            }
+        }
+        self.pending = deferred;
+        for &b in &self.pressed {
+            match self.since_pressed.iter_mut().find(|(x, _)| *x == b) {
+                Some((_, t)) => *t = 0.0,
+                None => self.since_pressed.push((b, 0.0)),
+            }
+        }
+    }
+
+    fn clear_edges(&mut self) {
+        self.pressed.clear();
+        self.released.clear();
+    }
+}
+
+/// Keys, mouse and gamepad buttons as of this frame.
+///
+/// Changes received through the `set_*` methods show from the next
+/// [`InputState::tick`] on, see [`ButtonState`] for how they are spread
+/// across frames.
 #[derive(Default, Clone, Debug)]
 pub struct InputState {
-    pressed: smallvec::SmallVec<[KeyCode; 32]>,
-    just_pressed: smallvec::SmallVec<[KeyCode; 32]>,
-    just_released: smallvec::SmallVec<[KeyCode; 32]>,
+    pub timings: InputTimings,
+    /// Shape of the values [`InputState::pad_axis`] returns.
+    pub axis_curves: AxisCurves,
+
+    keys: Buttons<KeyCode>,
 
     mouse_pos: Vec2,
-    mouse_pressed: smallvec::SmallVec<[MouseButton; 8]>,
-    mouse_just_pressed: smallvec::SmallVec<[MouseButton; 8]>,
-    mouse_just_released: smallvec::SmallVec<[MouseButton; 8]>,
+    mouse: Buttons<MouseButton>,
+    scroll: f32,
+
+    // every connected gamepad feeds the same state
+    pad: Buttons<GamepadButton>,
+    pad_axes: smallvec::SmallVec<[(GamepadAxis, f32); 6]>,
+
+    // seconds each key and button has been down, and the ones repeating this frame
+    key_held: Vec<(KeyCode, f32)>,
+    key_repeats: Vec<KeyCode>,
+    pad_held: Vec<(GamepadButton, f32)>,
+    pad_repeats: Vec<GamepadButton>,
 }
 
 impl InputState {
+    pub fn key(&self, k: KeyCode) -> ButtonState {
+        self.keys.state(k)
+    }
+    pub fn mouse_button(&self, b: MouseButton) -> ButtonState {
+        self.mouse.state(b)
+    }
+    pub fn pad_button(&self, b: GamepadButton) -> ButtonState {
+        self.pad.state(b)
+    }
+
     pub fn key_pressed(&self, k: KeyCode) -> bool {
-        self.pressed.contains(&k)
+        self.key(k).pressed
     }
     pub fn just_pressed(&self, k: KeyCode) -> bool {
-        self.just_pressed.contains(&k)
+        self.key(k).just_pressed
     }
     pub fn just_released(&self, k: KeyCode) -> bool {
-        self.just_released.contains(&k)
+        self.key(k).just_released
     }
 
     pub fn mouse_pressed(&self, b: MouseButton) -> bool {
-        self.mouse_pressed.contains(&b)
+        self.mouse_button(b).pressed
+    }
+    pub fn mouse_just_pressed(&self, b: MouseButton) -> bool {
+        self.mouse_button(b).just_pressed
+    }
+    pub fn mouse_just_released(&self, b: MouseButton) -> bool {
+        self.mouse_button(b).just_released
     }
     pub fn mouse_pos(&self) -> Vec2 {
         self.mouse_pos
     }
+    /// Mouse wheel movement this frame in lines, positive when scrolled away from the user.
+    pub fn scroll(&self) -> f32 {
+        self.scroll
+    }
+
+    pub fn pad_pressed(&self, b: GamepadButton) -> bool {
+        self.pad_button(b).pressed
+    }
+    pub fn pad_just_pressed(&self, b: GamepadButton) -> bool {
+        self.pad_button(b).just_pressed
+    }
+    pub fn pad_just_released(&self, b: GamepadButton) -> bool {
+        self.pad_button(b).just_released
+    }
+    /// `a` after its curve in [`InputState::axis_curves`].
+    pub fn pad_axis(&self, a: GamepadAxis) -> f32 {
+        self.axis_curves.get(a).apply(self.pad_axis_raw(a))
+    }
+    /// `a` as the gamepad reported it.
+    pub fn pad_axis_raw(&self, a: GamepadAxis) -> f32 {
+        self.pad_axes
+            .iter()
+            .find(|(axis, _)| *axis == a)
+            .map_or(0.0, |(_, v)| *v)
+    }
+
+    /// Whether `k` went down within the last `window` seconds, this frame
+    /// included, e.g. so a jump pressed just before landing still counts.
+    ///
+    /// Stays true for the whole window, so act on it only when the press can
+    /// take effect, like landing, and the action itself ends that state.
+    /// Windows longer than [`MAX_INPUT_BUFFER`] are cut to it.
+    ///
+    /// ```ignore
+    /// if grounded && ctx.input.buffered_pressed(KeyCode::Space, 0.15) {
+    ///     jump();
+    /// }
+    /// ```
+    pub fn buffered_pressed(&self, k: KeyCode, window: f32) -> bool {
+        self.keys.pressed_within(k, window)
+    }
+    pub fn mouse_buffered_pressed(&self, b: MouseButton, window: f32) -> bool {
+        self.mouse.pressed_within(b, window)
+    }
+    pub fn pad_buffered_pressed(&self, b: GamepadButton, window: f32) -> bool {
+        self.pad.pressed_within(b, window)
+    }
+
+    /// Whether any key or gamepad button went down this frame.
+    pub fn any_just_pressed(&self) -> bool {
+        !self.keys.pressed.is_empty() || !self.pad.pressed.is_empty()
+    }
+
+    /// Pressed this frame or auto-repeating after being held, e.g. for menu navigation.
+    pub fn key_repeated(&self, k: KeyCode) -> bool {
+        self.just_pressed(k) || self.key_repeats.contains(&k)
+    }
+    /// Down for at least [`InputTimings::hold_time`].
+    pub fn key_held(&self, k: KeyCode) -> bool {
+        self.key_held_for(k) >= self.timings.hold_time
+    }
+    pub fn key_held_for(&self, k: KeyCode) -> f32 {
+        held_for(&self.key_held, k)
+    }
+    pub fn pad_repeated(&self, b: GamepadButton) -> bool {
+        self.pad_just_pressed(b) || self.pad_repeats.contains(&b)
+    }
+    pub fn pad_held(&self, b: GamepadButton) -> bool {
+        self.pad_held_for(b) >= self.timings.hold_time
+    }
+    pub fn pad_held_for(&self, b: GamepadButton) -> f32 {
+        held_for(&self.pad_held, b)
+    }
+
+    /// Applies the button changes received since the last tick and advances
+    /// hold times and repeats by `dt`, called by the app before scenes update.
+    pub fn tick(&mut self, dt: f32) {
+        self.keys.update(dt);
+        self.mouse.update(dt);
+        self.pad.update(dt);
+        let t = self.timings;
+        advance(
+            &self.keys.down,
+            &self.keys.pressed,
+            &mut self.key_held,
+            &mut self.key_repeats,
+            t,
+            dt,
+        );
+        advance(
+            &self.pad.down,
+            &self.pad.pressed,
+            &mut self.pad_held,
+            &mut self.pad_repeats,
+            t,
+            dt,
+        );
+    }
 
     pub fn begin_frame(&mut self) {
-        self.just_pressed.clear();
-        self.just_released.clear();
-        self.mouse_just_pressed.clear();
-        self.mouse_just_released.clear();
+        self.keys.clear_edges();
+        self.mouse.clear_edges();
+        self.scroll = 0.0;
+        self.pad.clear_edges();
     }
     pub fn set_mouse_pos(&mut self, pos: Vec2) {
         self.mouse_pos = pos;
     }
+    pub fn add_scroll(&mut self, lines: f32) {
+        self.scroll += lines;
+    }
     pub fn set_key_down(&mut self, k: KeyCode, down: bool) {
-        match down {
-            true if !self.pressed.contains(&k) => {
-                self.pressed.push(k);
-                self.just_pressed.push(k);
-            }
-            false if self.pressed.contains(&k) => {
-                self.pressed.retain(|x| *x != k);
-                self.just_released.push(k);
-            }
-            _ => {}
+        self.keys.set(k, down);
+    }
+    pub fn set_pad_button(&mut self, b: GamepadButton, down: bool) {
+        self.pad.set(b, down);
+    }
+    pub fn set_pad_axis(&mut self, a: GamepadAxis, value: f32) {
+        match self.pad_axes.iter_mut().find(|(axis, _)| *axis == a) {
+            Some((_, v)) => *v = value,
+            None => self.pad_axes.push((a, value)),
         }
     }
     pub fn set_mouse_btn(&mut self, b: MouseButton, down: bool) {
-        match down {
-            true if !self.mouse_pressed.contains(&b) => {
-                self.mouse_pressed.push(b);
-                self.mouse_just_pressed.push(b);
-            }
-            false if self.mouse_pressed.contains(&b) => {
-                self.mouse_pressed.retain(|x| *x != b);
-                self.mouse_just_released.push(b);
+        self.mouse.set(b, down);
+    }
+}
+
+fn held_for<T: PartialEq>(held: &[(T, f32)], k: T) -> f32 {
+    held.iter().find(|(h, _)| *h == k).map_or(0.0, |(_, t)| *t)
+}
+
+fn advance<T: Copy + PartialEq>(
+    pressed: &[T],
+    just_pressed: &[T],
+    held: &mut Vec<(T, f32)>,
+    repeats: &mut Vec<T>,
+    t: InputTimings,
+    dt: f32,
+) {
+    // how many repeats a key held for `time` has produced
+    let count = |time: f32| match time < t.repeat_delay {
+        true => 0,
+        false => ((time - t.repeat_delay) / t.repeat_interval.max(f32::EPSILON)) as u32 + 1,
+    };
+    repeats.clear();
+    held.retain(|(k, _)| pressed.contains(k));
+    for &k in pressed {
+        match held.iter_mut().find(|(h, _)| *h == k) {
+            // released and pressed again since the last tick
+            Some((_, time)) if just_pressed.contains(&k) => *time = 0.0,
+            Some((_, time)) => {
+                let before = count(*time);
+                *time += dt;
+                if count(*time) > before {
+                    repeats.push(k);
+                }
             }
-            _ => {}
+            None => held.push((k, 0.0)),
         }
     }
 }
+
+/// Name of `k` as written in config and recording files, the variant name, e.g. `"KeyW"`.
+pub fn key_name(k: KeyCode) -> Option<&'static str> {
+    KEY_NAMES.iter().find(|(_, c)| *c == k).map(|(n, _)| *n)
+}
+
+/// Inverse of [`key_name`].
+pub fn key_from_name(name: &str) -> Option<KeyCode> {
+    KEY_NAMES.iter().find(|(n, _)| *n == name).map(|(_, c)| *c)
+}
+
+macro_rules! key_names {
+    ($($key:ident)*) => {
+        const KEY_NAMES: &[(&str, KeyCode)] = &[$((stringify!($key), KeyCode::$key)),*];
+    };
+}
+
+key_names! {
+    Backquote Backslash BracketLeft BracketRight Comma Digit0 Digit1 Digit2 Digit3
+    Digit4 Digit5 Digit6 Digit7 Digit8 Digit9 Equal IntlBackslash IntlRo IntlYen KeyA
+    KeyB KeyC KeyD KeyE KeyF KeyG KeyH KeyI KeyJ KeyK KeyL KeyM KeyN KeyO KeyP
+    KeyQ KeyR KeyS KeyT KeyU KeyV KeyW KeyX KeyY KeyZ Minus Period Quote Semicolon
+    Slash AltLeft AltRight Backspace CapsLock ContextMenu ControlLeft ControlRight
+    Enter SuperLeft SuperRight ShiftLeft ShiftRight Space Tab Convert KanaMode Lang1
+    Lang2 Lang3 Lang4 Lang5 NonConvert Delete End Help Home Insert PageDown PageUp
+    ArrowDown ArrowLeft ArrowRight ArrowUp NumLock Numpad0 Numpad1 Numpad2 Numpad3
+    Numpad4 Numpad5 Numpad6 Numpad7 Numpad8 Numpad9 NumpadAdd NumpadBackspace
+    NumpadClear NumpadClearEntry NumpadComma NumpadDecimal NumpadDivide NumpadEnter
+    NumpadEqual NumpadHash NumpadMemoryAdd NumpadMemoryClear NumpadMemoryRecall
+    NumpadMemoryStore NumpadMemorySubtract NumpadMultiply NumpadParenLeft NumpadParenRight
+    NumpadStar NumpadSubtract Escape Fn FnLock PrintScreen ScrollLock Pause BrowserBack
+    BrowserFavorites BrowserForward BrowserHome BrowserRefresh BrowserSearch BrowserStop
+    Eject LaunchApp1 LaunchApp2 LaunchMail MediaPlayPause MediaSelect MediaStop
+    MediaTrackNext MediaTrackPrevious Power Sleep AudioVolumeDown AudioVolumeMute
+    AudioVolumeUp WakeUp Meta Hyper Turbo Abort Resume Suspend Again Copy Cut Find
+    Open Paste Props Select Undo Hiragana Katakana F1 F2 F3 F4 F5 F6 F7 F8 F9
+    F10 F11 F12 F13 F14 F15 F16 F17 F18 F19 F20 F21 F22 F23 F24 F25 F26 F27
+    F28 F29 F30 F31 F32 F33 F34 F35
+}
@@ -0,0 +1,225 @@
+use hashbrown::HashMap;
+
+/// How many of each item fit in one inventory slot.
+///
+/// Kept apart from [`Inventory`] so saved inventories pick up rule changes
+/// made between game versions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StackRules {
+    /// Limit of items without their own, 1 for unstackable.
+    pub default_max: u32,
+    max: HashMap<String, u32>,
+}
+
+impl Default for StackRules {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl StackRules {
+    pub fn new(default_max: u32) -> Self {
+        Self {
+            default_max,
+            max: HashMap::new(),
+        }
+    }
+
+    pub fn with_max(mut self, item: impl Into<String>, max: u32) -> Self {
+        self.max.insert(item.into(), max);
+        self
+    }
+
+    pub fn max_stack(&self, item: &str) -> u32 {
+        self.max
+            .get(item)
+            .copied()
+            .unwrap_or(self.default_max)
+            .max(1)
+    }
+}
+
+/// Some number of one item, in a slot of an [`Inventory`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ItemStack {
+    pub item: String,
+    pub count: u32,
+}
+
+/// A fixed number of slots holding stacks of items, named by free-form ids.
+///
+/// ```ignore
+/// let rules = StackRules::new(1).with_max("potion", 10).with_max("arrow", 99);
+/// let mut bag = Inventory::new(12);
+/// let left_over = bag.add(&rules, "arrow", 150); // two slots, 0 left over
+/// if bag.remove("potion", 1) == 1 {
+///     // drink it
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Inventory {
+    slots: Vec<Option<ItemStack>>,
+}
+
+impl Inventory {
+    pub fn new(slots: usize) -> Self {
+        Self {
+            slots: vec![None; slots],
+        }
+    }
+
+    pub fn slots(&self) -> &[Option<ItemStack>] {
+        &self.slots
+    }
+
+    pub fn slot(&self, index: usize) -> Option<&ItemStack> {
+        self.slots.get(index)?.as_ref()
+    }
+
+    /// Grows or shrinks to `slots`, returning the stacks of the slots dropped.
+    pub fn resize(&mut self, slots: usize) -> Vec<ItemStack> {
+        let dropped = match slots < self.slots.len() {
+            true => self.slots.drain(slots..).flatten().collect(),
+            false => Vec::new(),
+        };
+        self.slots.resize(slots, None);
+        dropped
+    }
+
+    pub fn count(&self, item: &str) -> u32 {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|s| s.item == item)
+            .map(|s| s.count)
+            .sum()
+    }
+
+    pub fn contains(&self, item: &str, count: u32) -> bool {
+        self.count(item) >= count
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.slots.iter().all(Option::is_some)
+    }
+
+    /// How many of `item` would fit, topping up stacks before taking empty slots.
+    pub fn room_for(&self, rules: &StackRules, item: &str) -> u32 {
+        let max = rules.max_stack(item);
+        self.slots
+            .iter()
+            .map(|s| match s {
+                Some(s) if s.item == item => max.saturating_sub(s.count),
+                Some(_) => 0,
+                None => max,
+            })
+            .sum()
+    }
+
+    /// Adds up to `count` of `item`, topping up existing stacks first, and
+    /// returns how many did not fit.
+    pub fn add(&mut self, rules: &StackRules, item: &str, count: u32) -> u32 {
+        let max = rules.max_stack(item);
+        let mut left = count;
+        for stack in self.slots.iter_mut().flatten() {
+            if left == 0 {
+                break;
+            }
+            if stack.item == item && stack.count < max {
+                let moved = left.min(max - stack.count);
+                stack.count += moved;
+                left -= moved;
+            }
+        }
+        for slot in &mut self.slots {
+            if left == 0 {
+                break;
+            }
+            if slot.is_none() {
+                let moved = left.min(max);
+                *slot = Some(ItemStack {
+                    item: item.to_owned(),
+                    count: moved,
+                });
+                left -= moved;
+            }
+        }
+        left
+    }
+
+    /// Removes up to `count` of `item`, emptying the last stacks first, and
+    /// returns how many were removed.
+    pub fn remove(&mut self, item: &str, count: u32) -> u32 {
+        let mut left = count;
+        for slot in self.slots.iter_mut().rev() {
+            if left == 0 {
+                break;
+            }
+            let Some(stack) = slot else {
+                continue;
+            };
+            if stack.item != item {
+                continue;
+            }
+            let moved = left.min(stack.count);
+            stack.count -= moved;
+            left -= moved;
+            if stack.count == 0 {
+                *slot = None;
+            }
+        }
+        count - left
+    }
+
+    /// Empties slot `index`, returning what it held.
+    pub fn take(&mut self, index: usize) -> Option<ItemStack> {
+        self.slots.get_mut(index)?.take()
+    }
+
+    /// Moves the stack in slot `from` onto slot `to`, as when dragging it there:
+    /// stacks of the same item merge up to their limit, others swap places.
+    pub fn move_stack(&mut self, rules: &StackRules, from: usize, to: usize) {
+        if from == to || from >= self.slots.len() || to >= self.slots.len() {
+            return;
+        }
+        let (Some(src), Some(dst)) = (&self.slots[from], &self.slots[to]) else {
+            self.slots.swap(from, to);
+            return;
+        };
+        if src.item != dst.item {
+            self.slots.swap(from, to);
+            return;
+        }
+        let max = rules.max_stack(&src.item);
+        let moved = src.count.min(max.saturating_sub(dst.count));
+        if let Some(dst) = &mut self.slots[to] {
+            dst.count += moved;
+        }
+        if let Some(src) = &mut self.slots[from] {
+            src.count -= moved;
+            if src.count == 0 {
+                self.slots[from] = None;
+            }
+        }
+    }
+
+    /// Moves `count` items out of the stack in slot `index` into the first empty slot.
+    ///
+    /// Returns the slot the new stack went to, `None` if there is no room or
+    /// the stack holds no more than `count`.
+    pub fn split(&mut self, index: usize, count: u32) -> Option<usize> {
+        let stack = self.slots.get(index)?.as_ref()?;
+        if count == 0 || count >= stack.count {
+            return None;
+        }
+        let item = stack.item.clone();
+        let empty = self.slots.iter().position(Option::is_none)?;
+        if let Some(stack) = &mut self.slots[index] {
+            stack.count -= count;
+        }
+        self.slots[empty] = Some(ItemStack { item, count });
+        Some(empty)
+    }
+}
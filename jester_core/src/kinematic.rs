@@ -0,0 +1,176 @@
+use glam::Vec2;
+
+use crate::{Aabb, ColliderKind, Colliders};
+
+/// Gap kept between a body and what it touches, so resting contacts stay detectable.
+const SKIN: f32 = 0.01;
+
+/// A box moved through [`Colliders`] by velocity rather than forces, for platformer characters.
+///
+/// [`CharacterController::move_and_slide`] stops at walls while keeping the
+/// other axis of motion, walks up ledges no taller than `step_height`, sticks
+/// to the ground when walking down them, and lands on one-way platforms.
+#[derive(Clone, Copy, Debug)]
+pub struct CharacterController {
+    /// Collision box centered on the position.
+    pub size: Vec2,
+    /// Tallest ledge climbed without jumping, 0 to disable.
+    pub step_height: f32,
+    /// Falls through one-way platforms while set, e.g. while down is held.
+    pub drop_through: bool,
+    on_ground: bool,
+    on_ceiling: bool,
+    on_wall: bool,
+}
+
+impl CharacterController {
+    pub fn new(size: Vec2) -> Self {
+        Self {
+            size,
+            step_height: 0.0,
+            drop_through: false,
+            on_ground: false,
+            on_ceiling: false,
+            on_wall: false,
+        }
+    }
+
+    pub fn with_step_height(mut self, step_height: f32) -> Self {
+        self.step_height = step_height;
+        self
+    }
+
+    /// Standing on something after the last move; jumping is usually only allowed then.
+    pub fn on_ground(&self) -> bool {
+        self.on_ground
+    }
+
+    pub fn on_ceiling(&self) -> bool {
+        self.on_ceiling
+    }
+
+    pub fn on_wall(&self) -> bool {
+        self.on_wall
+    }
+
+    /// Moves the body centered at `position` by `velocity * dt`, zeroing the
+    /// velocity along any axis that hit something.
+    pub fn move_and_slide(
+        &mut self,
+        position: &mut Vec2,
+        velocity: &mut Vec2,
+        dt: f32,
+        world: &Colliders,
+    ) {
+        let was_on_ground = self.on_ground;
+        let delta = *velocity * dt;
+        let mut body = Aabb::from_center_size(*position, self.size);
+
+        // horizontal, climbing a step if a wall is in the way
+        let mut dx = sweep_x(world, body, delta.x);
+        let blocked = (dx - delta.x).abs() > SKIN;
+        let mut stepped = false;
+        if blocked && was_on_ground && self.step_height > 0.0 {
+            let up = sweep_y(world, body, self.step_height, true);
+            let lifted = body.translated(Vec2::new(0.0, up));
+            let lifted_dx = sweep_x(world, lifted, delta.x);
+            if lifted_dx.abs() > dx.abs() + SKIN {
+                let moved = lifted.translated(Vec2::new(lifted_dx, 0.0));
+                let down = sweep_y(world, moved, -up, self.drop_through);
+                body = moved.translated(Vec2::new(0.0, down));
+                dx = lifted_dx;
+                stepped = true;
+            }
+        }
+        if !stepped {
+            body = body.translated(Vec2::new(dx, 0.0));
+        }
+        self.on_wall = (dx - delta.x).abs() > SKIN;
+        if self.on_wall {
+            velocity.x = 0.0;
+        }
+
+        // vertical
+        let dy = sweep_y(world, body, delta.y, self.drop_through);
+        body = body.translated(Vec2::new(0.0, dy));
+        let blocked_y = (dy - delta.y).abs() > SKIN;
+        self.on_ceiling = blocked_y && delta.y > 0.0;
+        self.on_ground = blocked_y && delta.y < 0.0;
+        if blocked_y {
+            velocity.y = 0.0;
+        }
+
+        if delta.y <= 0.0 && !self.on_ground {
+            // stay grounded walking off a ledge no taller than a step
+            let reach = if was_on_ground {
+                self.step_height.max(SKIN * 2.0)
+            } else {
+                SKIN * 2.0
+            };
+            let down = sweep_y(world, body, -reach, self.drop_through);
+            if down > -reach {
+                body = body.translated(Vec2::new(0.0, down));
+                self.on_ground = true;
+                velocity.y = velocity.y.max(0.0);
+            }
+        }
+        *position = body.center();
+    }
+}
+
+/// How far `body` can move by `dx` before touching a solid collider.
+fn sweep_x(world: &Colliders, body: Aabb, dx: f32) -> f32 {
+    if dx == 0.0 {
+        return 0.0;
+    }
+    let area = Aabb::new(
+        body.min + Vec2::new(dx.min(0.0), 0.0),
+        body.max + Vec2::new(dx.max(0.0), 0.0),
+    );
+    let mut allowed = dx;
+    for c in world.near(area) {
+        let overlaps_y = c.aabb.min.y < body.max.y - SKIN && body.min.y + SKIN < c.aabb.max.y;
+        if c.kind != ColliderKind::Solid || !overlaps_y {
+            continue;
+        }
+        if dx > 0.0 && c.aabb.min.x >= body.max.x - SKIN {
+            allowed = allowed.min(c.aabb.min.x - body.max.x);
+        } else if dx < 0.0 && c.aabb.max.x <= body.min.x + SKIN {
+            allowed = allowed.max(c.aabb.max.x - body.min.x);
+        }
+    }
+    allowed
+}
+
+/// How far `body` can move by `dy` before touching a collider that blocks it.
+fn sweep_y(world: &Colliders, body: Aabb, dy: f32, drop_through: bool) -> f32 {
+    if dy == 0.0 {
+        return 0.0;
+    }
+    let area = Aabb::new(
+        body.min + Vec2::new(0.0, dy.min(0.0)),
+        body.max + Vec2::new(0.0, dy.max(0.0)),
+    );
+    let mut allowed = dy;
+    for c in world.near(area) {
+        let overlaps_x = c.aabb.min.x < body.max.x - SKIN && body.min.x + SKIN < c.aabb.max.x;
+        if !overlaps_x {
+            continue;
+        }
+        let below = c.aabb.max.y <= body.min.y + SKIN;
+        let blocks = match c.kind {
+            ColliderKind::Solid => true,
+            // only from above, and only if the body was fully above it
+            ColliderKind::OneWay => dy < 0.0 && below && !drop_through,
+        };
+        if !blocks {
+            continue;
+        }
+        if dy > 0.0 && c.aabb.min.y >= body.max.y - SKIN {
+            allowed = allowed.min(c.aabb.min.y - body.max.y);
+        } else if dy < 0.0 && below {
+            allowed = allowed.max(c.aabb.max.y - body.min.y);
+        }
+    }
+    allowed
+}
@@ -0,0 +1,556 @@
+use std::{any::Any, fmt, sync::Arc};
+
+use glam::Vec2;
+use hashbrown::HashMap;
+
+use crate::{ui::PanelInstance, Events, Panel, Transform};
+
+/// A size along one axis of a [`UiNode`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Length {
+    /// Just big enough for the children and padding.
+    #[default]
+    Auto,
+    Px(f32),
+    /// Of the parent's size inside its padding, `0.0..=100.0`.
+    Percent(f32),
+}
+
+impl Length {
+    fn resolve(self, parent: f32) -> Option<f32> {
+        match self {
+            Self::Auto => None,
+            Self::Px(px) => Some(px),
+            Self::Percent(p) => Some(parent * p / 100.0),
+        }
+    }
+}
+
+/// Axis children are laid out along.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    /// Left to right.
+    Row,
+    /// Top to bottom.
+    #[default]
+    Column,
+}
+
+/// Distribution of leftover space along the main axis.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Justify {
+    #[default]
+    Start,
+    Center,
+    End,
+    SpaceBetween,
+    SpaceAround,
+}
+
+/// Placement of children across the main axis.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+    /// Fill the parent, unless the child has a fixed size on that axis.
+    #[default]
+    Stretch,
+}
+
+/// Space around the four sides of a node, in pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Edges {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl Edges {
+    pub const ZERO: Self = Self::all(0.0);
+
+    pub const fn all(v: f32) -> Self {
+        Self::xy(v, v)
+    }
+
+    pub const fn xy(x: f32, y: f32) -> Self {
+        Self {
+            left: x,
+            right: x,
+            top: y,
+            bottom: y,
+        }
+    }
+
+    fn size(&self) -> Vec2 {
+        Vec2::new(self.left + self.right, self.top + self.bottom)
+    }
+
+    fn min(&self) -> Vec2 {
+        Vec2::new(self.left, self.top)
+    }
+}
+
+/// Pins a node to a point of its parent instead of placing it in the flow.
+///
+/// `point` is a fraction of the parent's inner area (origin top-left), and the
+/// same fraction of the node sits on it, so [`Anchor::BOTTOM_RIGHT`] keeps the
+/// node in the bottom right corner at any window size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Anchor {
+    pub point: Vec2,
+    /// Pixels added after anchoring, y down.
+    pub offset: Vec2,
+}
+
+impl Anchor {
+    pub const TOP_LEFT: Self = Self::new(0.0, 0.0);
+    pub const TOP: Self = Self::new(0.5, 0.0);
+    pub const TOP_RIGHT: Self = Self::new(1.0, 0.0);
+    pub const LEFT: Self = Self::new(0.0, 0.5);
+    pub const CENTER: Self = Self::new(0.5, 0.5);
+    pub const RIGHT: Self = Self::new(1.0, 0.5);
+    pub const BOTTOM_LEFT: Self = Self::new(0.0, 1.0);
+    pub const BOTTOM: Self = Self::new(0.5, 1.0);
+    pub const BOTTOM_RIGHT: Self = Self::new(1.0, 1.0);
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self {
+            point: Vec2::new(x, y),
+            offset: Vec2::ZERO,
+        }
+    }
+
+    pub fn with_offset(mut self, x: f32, y: f32) -> Self {
+        self.offset = Vec2::new(x, y);
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Style {
+    pub direction: Direction,
+    pub width: Length,
+    pub height: Length,
+    pub padding: Edges,
+    pub margin: Edges,
+    /// Pixels between consecutive children.
+    pub gap: f32,
+    pub justify: Justify,
+    pub align: Align,
+    /// Share of the parent's leftover main axis space, 0 keeps the node at its own size.
+    pub grow: f32,
+    pub anchor: Option<Anchor>,
+}
+
+/// A box of a declarative UI tree, laid out flexbox-style by [`UiNode::layout`].
+///
+/// ```ignore
+/// let hud = UiNode::row()
+///     .with_padding(Edges::all(16.0))
+///     .with_child(UiNode::new().named("health").with_size(Length::Px(200.0), Length::Px(24.0)))
+///     .with_child(UiNode::new().with_grow(1.0))
+///     .with_child(UiNode::new().named("score").with_size(Length::Px(120.0), Length::Px(24.0)));
+/// ctx.set_ui(Some(hud));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct UiNode {
+    pub name: Option<String>,
+    pub style: Style,
+    /// Drawn over the node's rect; its transform and size are set by the layout.
+    pub panel: Option<Panel>,
+    /// Replaces `panel` while the node has focus.
+    pub focus_panel: Option<Panel>,
+    /// Reachable with tab, arrows and the d-pad, see [`UiFocus`](crate::UiFocus). Needs a name.
+    pub focusable: bool,
+    pub on_activate: Option<OnActivate>,
+    /// Read out by screen readers and announced on focus, see [`Accessibility`](crate::Accessibility).
+    pub label: Option<String>,
+    pub children: Vec<UiNode>,
+}
+
+/// A typed event a node sends when activated, see [`UiNode::on_activate`].
+#[derive(Clone)]
+pub struct OnActivate(Arc<dyn Fn(&mut Events) + Send + Sync>);
+
+impl OnActivate {
+    pub fn new<E: Any + Clone + Send + Sync>(event: E) -> Self {
+        Self(Arc::new(move |events| events.send(event.clone())))
+    }
+}
+
+impl fmt::Debug for OnActivate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OnActivate")
+    }
+}
+
+impl UiNode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn row() -> Self {
+        Self::new().with_direction(Direction::Row)
+    }
+
+    pub fn column() -> Self {
+        Self::new().with_direction(Direction::Column)
+    }
+
+    /// A focusable node, activated with enter, space, a click or the south gamepad button.
+    pub fn button(name: impl Into<String>) -> Self {
+        Self::new().named(name).with_focusable(true)
+    }
+
+    /// Makes the node's rect available through [`UiLayout::get`].
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.style.direction = direction;
+        self
+    }
+
+    pub fn with_size(mut self, width: Length, height: Length) -> Self {
+        self.style.width = width;
+        self.style.height = height;
+        self
+    }
+
+    pub fn with_padding(mut self, padding: Edges) -> Self {
+        self.style.padding = padding;
+        self
+    }
+
+    pub fn with_margin(mut self, margin: Edges) -> Self {
+        self.style.margin = margin;
+        self
+    }
+
+    pub fn with_gap(mut self, gap: f32) -> Self {
+        self.style.gap = gap;
+        self
+    }
+
+    pub fn with_justify(mut self, justify: Justify) -> Self {
+        self.style.justify = justify;
+        self
+    }
+
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.style.align = align;
+        self
+    }
+
+    pub fn with_grow(mut self, grow: f32) -> Self {
+        self.style.grow = grow;
+        self
+    }
+
+    pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+        self.style.anchor = Some(anchor);
+        self
+    }
+
+    pub fn with_panel(mut self, panel: Panel) -> Self {
+        self.panel = Some(panel);
+        self
+    }
+
+    pub fn with_focus_panel(mut self, panel: Panel) -> Self {
+        self.focus_panel = Some(panel);
+        self
+    }
+
+    pub fn with_focusable(mut self, focusable: bool) -> Self {
+        self.focusable = focusable;
+        self
+    }
+
+    /// Sends `event` whenever the node is activated, next to the generic
+    /// [`UiEvent::ButtonClicked`](crate::UiEvent::ButtonClicked).
+    pub fn on_activate<E: Any + Clone + Send + Sync>(mut self, event: E) -> Self {
+        self.on_activate = Some(OnActivate::new(event));
+        self
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_child(mut self, child: UiNode) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn with_children(mut self, children: impl IntoIterator<Item = UiNode>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    /// Places the tree, this node filling `screen` pixels unless it has its own size or anchor.
+    pub fn layout(&self, screen: Vec2) -> UiLayout {
+        let mut out = UiLayout {
+            screen,
+            ..Default::default()
+        };
+        let s = &self.style;
+        let size = match s.anchor {
+            Some(_) => self.outer_size(screen) - s.margin.size(),
+            None => Vec2::new(
+                s.width
+                    .resolve(screen.x)
+                    .unwrap_or(screen.x - s.margin.size().x),
+                s.height
+                    .resolve(screen.y)
+                    .unwrap_or(screen.y - s.margin.size().y),
+            ),
+        };
+        let min = match s.anchor {
+            Some(a) => (screen - size) * a.point + a.offset,
+            None => s.margin.min(),
+        };
+        self.place(UiRect { min, size }, &mut out);
+        out
+    }
+
+    /// Size this node wants including margins, `parent` being the parent's size inside its padding.
+    fn outer_size(&self, parent: Vec2) -> Vec2 {
+        let s = &self.style;
+        let width = s.width.resolve(parent.x);
+        let height = s.height.resolve(parent.y);
+        let size = match (width, height) {
+            (Some(w), Some(h)) => Vec2::new(w, h),
+            _ => {
+                let pad = s.padding.size();
+                let fixed = Vec2::new(width.unwrap_or(0.0), height.unwrap_or(0.0));
+                let content = self.content_size((fixed - pad).max(Vec2::ZERO)) + pad;
+                Vec2::new(width.unwrap_or(content.x), height.unwrap_or(content.y))
+            }
+        };
+        size + s.margin.size()
+    }
+
+    /// Size the flowing children need inside the padding.
+    fn content_size(&self, inner: Vec2) -> Vec2 {
+        let main = self.main_axis();
+        let mut along = 0.0;
+        let mut across = 0.0f32;
+        let mut flowing = 0usize;
+        for child in self.children.iter().filter(|c| c.style.anchor.is_none()) {
+            let size = child.outer_size(inner);
+            along += size[main];
+            across = across.max(size[1 - main]);
+            flowing += 1;
+        }
+        along += self.style.gap * flowing.saturating_sub(1) as f32;
+        let mut out = Vec2::ZERO;
+        out[main] = along;
+        out[1 - main] = across;
+        out
+    }
+
+    fn main_axis(&self) -> usize {
+        match self.style.direction {
+            Direction::Row => 0,
+            Direction::Column => 1,
+        }
+    }
+
+    fn place(&self, rect: UiRect, out: &mut UiLayout) {
+        let index = out.rects.len();
+        out.rects.push(rect);
+        out.panels.push((self.panel, self.focus_panel));
+        out.labels.push(self.label.clone());
+        if let Some(name) = &self.name {
+            out.names.insert(name.clone(), index);
+            if self.focusable {
+                out.focusable
+                    .push((name.clone(), index, self.on_activate.clone()));
+            }
+        }
+
+        let s = &self.style;
+        let inner = UiRect {
+            min: rect.min + s.padding.min(),
+            size: (rect.size - s.padding.size()).max(Vec2::ZERO),
+        };
+        let main = self.main_axis();
+        let cross = 1 - main;
+
+        // base sizes along the main axis, margins included
+        let flowing: Vec<&UiNode> = self
+            .children
+            .iter()
+            .filter(|c| c.style.anchor.is_none())
+            .collect();
+        let mut sizes: Vec<Vec2> = flowing.iter().map(|c| c.outer_size(inner.size)).collect();
+        let gaps = s.gap * flowing.len().saturating_sub(1) as f32;
+        let used: f32 = sizes.iter().map(|sz| sz[main]).sum::<f32>() + gaps;
+        let mut free = (inner.size[main] - used).max(0.0);
+        let grow: f32 = flowing.iter().map(|c| c.style.grow.max(0.0)).sum();
+        if grow > 0.0 {
+            for (c, sz) in flowing.iter().zip(&mut sizes) {
+                sz[main] += free * c.style.grow.max(0.0) / grow;
+            }
+            free = 0.0;
+        }
+
+        let n = flowing.len() as f32;
+        let (mut pen, spacing) = match s.justify {
+            Justify::Start => (0.0, 0.0),
+            Justify::Center => (free * 0.5, 0.0),
+            Justify::End => (free, 0.0),
+            Justify::SpaceBetween if n > 1.0 => (0.0, free / (n - 1.0)),
+            Justify::SpaceBetween => (0.0, 0.0),
+            Justify::SpaceAround => (free / n.max(1.0) * 0.5, free / n.max(1.0)),
+        };
+        for (c, mut sz) in flowing.into_iter().zip(sizes) {
+            let fixed_cross = match cross {
+                0 => c.style.width != Length::Auto,
+                _ => c.style.height != Length::Auto,
+            };
+            if s.align == Align::Stretch && !fixed_cross {
+                sz[cross] = inner.size[cross];
+            }
+            let slack = inner.size[cross] - sz[cross];
+            let offset = match s.align {
+                Align::Start | Align::Stretch => 0.0,
+                Align::Center => slack * 0.5,
+                Align::End => slack,
+            };
+            let mut min = inner.min;
+            min[main] += pen;
+            min[cross] += offset;
+            let margin = &c.style.margin;
+            c.place(
+                UiRect {
+                    min: min + margin.min(),
+                    size: (sz - margin.size()).max(Vec2::ZERO),
+                },
+                out,
+            );
+            pen += sz[main] + s.gap + spacing;
+        }
+
+        for c in self.children.iter() {
+            let Some(anchor) = c.style.anchor else {
+                continue;
+            };
+            let size = c.outer_size(inner.size) - c.style.margin.size();
+            let min = inner.min + (inner.size - size) * anchor.point + anchor.offset;
+            c.place(UiRect { min, size }, out);
+        }
+    }
+}
+
+/// A laid out rectangle in window pixels, origin top-left and y down.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct UiRect {
+    pub min: Vec2,
+    pub size: Vec2,
+}
+
+impl UiRect {
+    pub fn max(&self) -> Vec2 {
+        self.min + self.size
+    }
+
+    pub fn contains(&self, pt: Vec2) -> bool {
+        let max = self.max();
+        pt.x >= self.min.x && pt.y >= self.min.y && pt.x < max.x && pt.y < max.y
+    }
+
+    /// Center in the screen space of [`Camera::screen_space`](crate::Camera::screen_space), y up.
+    pub fn screen_center(&self, screen: Vec2) -> Vec2 {
+        let c = self.min + self.size * 0.5;
+        Vec2::new(c.x, screen.y - c.y)
+    }
+}
+
+/// Result of [`UiNode::layout`] for one window size.
+///
+/// Register it as a resource to read back the rects of the UI set with
+/// [`Ctx::set_ui`](crate::Ctx::set_ui), e.g. to place text inside a node.
+#[derive(Clone, Debug, Default)]
+pub struct UiLayout {
+    pub screen: Vec2,
+    /// Every node depth-first, parents before their children.
+    pub rects: Vec<UiRect>,
+    /// Normal and focused look of each node.
+    panels: Vec<(Option<Panel>, Option<Panel>)>,
+    labels: Vec<Option<String>>,
+    names: HashMap<String, usize>,
+    focusable: Vec<(String, usize, Option<OnActivate>)>,
+}
+
+impl UiLayout {
+    pub fn get(&self, name: &str) -> Option<UiRect> {
+        self.names.get(name).map(|&i| self.rects[i])
+    }
+
+    /// Names and rects of the focusable nodes in tree order.
+    pub fn focusable(&self) -> impl Iterator<Item = (&str, UiRect)> {
+        self.focusable
+            .iter()
+            .map(|(name, i, _)| (name.as_str(), self.rects[*i]))
+    }
+
+    /// The [`UiNode::label`] of the node `name`.
+    pub fn label(&self, name: &str) -> Option<&str> {
+        self.labels[*self.names.get(name)?].as_deref()
+    }
+
+    /// Labelled and focusable nodes in tree order, for screen readers: the
+    /// node's index in `rects`, its label (its name when it has none), its
+    /// rect and, for focusable nodes, its name.
+    pub fn accessible(&self) -> impl Iterator<Item = (usize, &str, UiRect, Option<&str>)> {
+        let mut focusable = self.focusable.iter().peekable();
+        self.labels
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, label)| {
+                let name = focusable
+                    .next_if(|(_, index, _)| *index == i)
+                    .map(|(name, ..)| name.as_str());
+                let label = label.as_deref().or(name)?;
+                Some((i, label, self.rects[i], name))
+            })
+    }
+
+    /// Sends the [`UiNode::on_activate`] event of the focusable node `name`, if it has one.
+    pub fn activate(&self, name: &str, events: &mut Events) {
+        let node = self.focusable.iter().find(|(n, ..)| n == name);
+        if let Some((_, _, Some(on_activate))) = node {
+            (on_activate.0)(events);
+        }
+    }
+
+    /// The panels of the tree in draw order, in [`Camera::screen_space`](crate::Camera::screen_space) units.
+    pub fn panels(&self, focused: Option<&str>) -> Vec<PanelInstance> {
+        let focused = focused.and_then(|name| self.names.get(name)).copied();
+        self.rects
+            .iter()
+            .zip(&self.panels)
+            .enumerate()
+            .filter_map(|(i, (rect, (panel, focus_panel)))| {
+                let panel = match focus_panel {
+                    Some(_) if focused == Some(i) => focus_panel,
+                    _ => panel,
+                };
+                let c = rect.screen_center(self.screen);
+                let panel = Panel {
+                    transform: Transform::from_xy(c.x, c.y),
+                    size: rect.size,
+                    ..(*panel)?
+                };
+                Some(panel.instance())
+            })
+            .collect()
+    }
+}
@@ -1,36 +1,489 @@
-pub use error::Error;
+pub use accessibility::Accessibility;
+pub use animation::{Animation, AnimationFinished, LoopMode};
+pub use aseprite::{AsepriteSheet, AsepriteSheets};
+pub use atlas::TextureAtlas;
+pub use bounds::{EdgeMode, WorldBounds};
+pub use collision::{Aabb, Bounds, Collider, ColliderKind, Colliders, Obb, SegmentHit};
+pub use color::Color;
+pub use cooldown::Cooldowns;
+pub use daynight::{DayNight, DayNightEvent};
+pub use debug_draw::DebugDraw;
+pub use decode::{rgba8_from, DecodedImage, TextureFormat};
+pub use devices::{InputDevice, InputDevices};
+pub use dialogue::{
+    Dialogue, DialogueCommand, DialogueEvent, DialogueLine, DialogueRunner, DialogueStep, Value,
+};
+pub use director::CameraDirector;
+pub use ease::Ease;
+pub use error::{AssetError, Error};
+pub use events::Events;
+pub use filter::{ColorBlindness, ColorVision, DisplayCalibration, ScreenFilter};
+pub use focus::{UiEvent, UiFocus};
 use glam::Vec2;
-pub use input::InputState;
-pub use render::{constants::*, Backend, Renderer};
-pub use scene::{Commands, Ctx, EntityId, EntityPool, Resources, Scene, SceneKey};
-pub use sprite::{Sprite, SpriteBatch, SpriteInstance, TextureId};
+pub use hierarchy::{GlobalTransform, Hierarchy, LocalTransform};
+pub use import::ImportCache;
+pub use input::{
+    key_from_name, key_name, AxisCurve, AxisCurves, ButtonState, GamepadAxis, GamepadButton,
+    InputState, InputTimings, MAX_INPUT_BUFFER,
+};
+pub use inventory::{Inventory, ItemStack, StackRules};
+pub use kinematic::CharacterController;
+pub use layout::{
+    Align, Anchor, Direction, Edges, Justify, Length, OnActivate, Style, UiLayout, UiNode, UiRect,
+};
+pub use loader::{AssetState, AssetStates};
+pub use mesh::{Mesh, MeshBatch, MeshVertex};
+pub use minimap::{MapMarker, Minimap};
+pub use msdf::MsdfAtlas;
+pub use polyline::{LineCap, LineJoin, Polyline};
+pub use progress::{Achievement, Progress, ProgressEvent, ProgressState, UnlockCondition};
+pub use quality::{QualityPreset, QualitySettings};
+pub use render::{
+    constants::*, AdapterInfo, AddressMode, Backend, BatchStats, LoadPriority, MemoryStats,
+    PresentMode, PresentStats, PresentTimingSource, RenderStats, Renderer, Retry, SamplerDesc,
+    Screenshot, TextureFilter,
+};
+pub use residency::ResidencyStats;
+pub use rope::{Rope, RopeEnd};
+pub use scene::{
+    Commands, Ctx, EntityId, EntityPool, RenderCtx, Resources, Scene, SceneDraw, SceneKey,
+};
+pub use shortcuts::{Chord, Modifiers, ShortcutConflict, Shortcuts};
+pub use split::{Controls, KeySet, PlayerInput, SplitLayout, SplitPlayer, SplitScreen};
+pub use sprite::{
+    AlphaSorting, BlendMode, MaterialId, Palette, RetainedBatch, SortMode, Sprite, SpriteBatch,
+    SpriteInstance, TextureId,
+};
+pub use sprite_store::{SpriteGroup, SpriteStore};
+pub use stats::{Modifier, ModifierKind, Stats};
+pub use steering::{Flock, Steering, Velocity, Wander};
+pub use text::{rasterize_text, Font, Glyph, GlyphAtlas, Text, TextStyle};
+pub use tilemap::{TileLayer, Tilemap, CHUNK_TILES};
+pub use trigger::{Trigger, TriggerEvent, TriggerTracker};
+pub use ui::{Fill, Panel, PanelInstance};
+pub use validate::{FrameValidator, ValidationIssue};
+pub use window::{MonitorInfo, MonitorSelect, Monitors, WindowPlacement};
 
+mod accessibility;
+mod animation;
+mod aseprite;
+mod atlas;
+mod bounds;
+mod collision;
+mod color;
+mod cooldown;
+mod daynight;
+mod debug_draw;
+mod decode;
+mod devices;
+mod dialogue;
+mod director;
+mod ease;
 mod error;
+mod events;
+mod filter;
+mod focus;
+mod hierarchy;
+mod import;
 mod input;
+mod inventory;
+mod json;
+mod kinematic;
+mod layout;
+mod loader;
+mod mesh;
+mod minimap;
+mod msdf;
+mod polyline;
+mod progress;
+mod quality;
 mod render;
+mod residency;
+mod rope;
 mod scene;
+mod shortcuts;
+mod split;
 mod sprite;
+mod sprite_store;
+mod stats;
+mod steering;
+mod text;
+mod tilemap;
+mod tiling;
+mod trigger;
+mod ui;
+mod validate;
+mod window;
+
+/// Window region a camera renders to, in fractions of the window size (origin top-left).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+impl Viewport {
+    pub const FULL: Self = Self::new(0.0, 0.0, 1.0, 1.0);
+
+    pub const fn new(x: f32, y: f32, w: f32, h: f32) -> Self {
+        Self { x, y, w, h }
+    }
+
+    /// Top-left corner and size in pixels for a window of size `screen`.
+    pub fn to_pixels(&self, screen: Vec2) -> (Vec2, Vec2) {
+        (
+            Vec2::new(self.x, self.y) * screen,
+            Vec2::new(self.w, self.h) * screen,
+        )
+    }
+
+    pub fn contains(&self, screen_pt: Vec2, screen: Vec2) -> bool {
+        let (min, size) = self.to_pixels(screen);
+        let max = min + size;
+        screen_pt.x >= min.x && screen_pt.y >= min.y && screen_pt.x < max.x && screen_pt.y < max.y
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+/// How a camera maps its design resolution onto the window when it is resized.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScalePolicy {
+    /// One world unit per pixel, the visible area grows with the window.
+    #[default]
+    PixelPerfect,
+    /// The design area fills the viewport, distorting its aspect ratio.
+    Stretch,
+    /// The design width always fits, more or less height is visible.
+    FitWidth,
+    /// The design height always fits, more or less width is visible.
+    FitHeight,
+    /// The whole design area is visible with bars on the excess axis.
+    Letterbox,
+    /// Like [`ScalePolicy::Letterbox`] but only whole multiples, for crisp pixel art.
+    IntegerScale,
+}
+
+/// What the app paints a camera's letterbox bars with, see [`Camera::letterbox_bars`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BarFill {
+    Color(Color),
+    /// Stretched over each bar.
+    Texture(TextureId),
+}
+
+impl Default for BarFill {
+    fn default() -> Self {
+        Self::Color(Color::BLACK)
+    }
+}
 
 #[derive(Clone, Copy, Debug)]
 pub struct Camera {
     pub center: glam::Vec2,
     pub zoom: f32,
+    pub viewport: Viewport,
+    pub policy: ScalePolicy,
+    pub design_size: Vec2,
+    /// Post-processing of this camera's region, see [`ScreenFilter`].
+    pub filter: ScreenFilter,
+    /// Paints the bars of [`ScalePolicy::Letterbox`] and [`ScalePolicy::IntegerScale`].
+    pub bars: BarFill,
+    /// Bounds [`Camera::set_zoom`] and [`Camera::zoom_to`] keep `zoom` in.
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+    /// Easing of [`Camera::zoom_to`].
+    pub zoom_ease: Ease,
+    // results of the last `resize`, see `output_viewport` and `scale`
+    fit: Viewport,
+    stretch: Vec2,
+    zoom_anim: Option<ZoomAnim>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct ZoomAnim {
+    from: f32,
+    to: f32,
+    elapsed: f32,
+    duration: f32,
+    // window point and size whose world point stays put
+    anchor: Option<(Vec2, Vec2)>,
 }
 
 impl Camera {
+    /// A camera at `center` with `zoom`, everything else as in
+    /// [`Camera::default`]; the remaining settings go through the
+    /// `with_*` methods, as cameras cannot be built as struct literals.
+    pub fn new(center: Vec2, zoom: f32) -> Self {
+        Self {
+            center,
+            zoom,
+            ..Default::default()
+        }
+    }
+
     pub fn pixel_perfect(w: f32, h: f32) -> Self {
         Self {
             center: Vec2::new(-w * 0.5, -h * 0.5),
-            zoom: 1.0,
+            ..Default::default()
+        }
+    }
+
+    /// Maps world units 1:1 to window pixels with the origin at the bottom-left corner.
+    pub fn screen_space() -> Self {
+        Self::default()
+    }
+
+    /// A camera showing `design` world units under `policy`, origin at the center.
+    pub fn with_design_size(design: Vec2, policy: ScalePolicy) -> Self {
+        Self {
+            center: -design * 0.5,
+            policy,
+            design_size: design,
+            ..Default::default()
         }
     }
 
+    pub fn with_center(mut self, center: Vec2) -> Self {
+        self.center = center;
+        self
+    }
+
+    /// Sets `zoom` within the limits.
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = self.clamp_zoom(zoom);
+        self
+    }
+
+    pub fn with_viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    pub fn with_policy(mut self, policy: ScalePolicy, design: Vec2) -> Self {
+        self.policy = policy;
+        self.design_size = design;
+        self
+    }
+
+    pub fn with_bars(mut self, bars: BarFill) -> Self {
+        self.bars = bars;
+        self
+    }
+
+    pub fn with_zoom_ease(mut self, ease: Ease) -> Self {
+        self.zoom_ease = ease;
+        self
+    }
+
+    pub fn with_filter(mut self, filter: ScreenFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_zoom_limits(mut self, min: f32, max: f32) -> Self {
+        self.min_zoom = min;
+        self.max_zoom = max;
+        self.zoom = self.clamp_zoom(self.zoom);
+        self
+    }
+
+    /// Sets `zoom` within the limits, stopping any [`Camera::zoom_to`].
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom_anim = None;
+        self.zoom = self.clamp_zoom(zoom);
+    }
+
+    /// Sets `zoom` within the limits keeping the world point under window
+    /// point `screen_pt` in place, e.g. to zoom towards the cursor.
+    pub fn zoom_about(&mut self, zoom: f32, screen_pt: Vec2, screen: Vec2) {
+        self.zoom_anim = None;
+        self.apply_zoom(self.clamp_zoom(zoom), Some((screen_pt, screen)));
+    }
+
+    /// Eases `zoom` to `target`, clamped to the limits, over `duration`
+    /// seconds of [`Camera::tick`]; the view keeps its bottom-left corner.
+    /// Scenes zoom the app's cameras with [`Ctx::zoom_camera`].
+    pub fn zoom_to(&mut self, target: f32, duration: f32) {
+        self.start_zoom(target, duration, None);
+    }
+
+    /// Like [`Camera::zoom_to`], keeping the world point under window point
+    /// `screen_pt` in place throughout.
+    pub fn zoom_to_about(&mut self, target: f32, duration: f32, screen_pt: Vec2, screen: Vec2) {
+        self.start_zoom(target, duration, Some((screen_pt, screen)));
+    }
+
+    /// Whether a [`Camera::zoom_to`] is still running.
+    pub fn is_zooming(&self) -> bool {
+        self.zoom_anim.is_some()
+    }
+
+    /// Advances [`Camera::zoom_to`] by `dt` seconds, called by the app every frame.
+    pub fn tick(&mut self, dt: f32) {
+        let Some(mut anim) = self.zoom_anim else {
+            return;
+        };
+        anim.elapsed += dt;
+        let t = match anim.duration > 0.0 {
+            true => anim.elapsed / anim.duration,
+            false => 1.0,
+        };
+        let zoom = anim.from + (anim.to - anim.from) * self.zoom_ease.apply(t);
+        self.apply_zoom(zoom, anim.anchor);
+        self.zoom_anim = (t < 1.0).then_some(anim);
+    }
+
+    fn start_zoom(&mut self, target: f32, duration: f32, anchor: Option<(Vec2, Vec2)>) {
+        self.zoom_anim = Some(ZoomAnim {
+            from: self.zoom,
+            to: self.clamp_zoom(target),
+            elapsed: 0.0,
+            duration,
+            anchor,
+        });
+    }
+
+    fn apply_zoom(&mut self, zoom: f32, anchor: Option<(Vec2, Vec2)>) {
+        let Some((pt, screen)) = anchor else {
+            self.zoom = zoom;
+            return;
+        };
+        let before = self.window_to_world(pt, screen);
+        self.zoom = zoom;
+        self.center += before - self.window_to_world(pt, screen);
+    }
+
+    fn clamp_zoom(&self, zoom: f32) -> f32 {
+        zoom.clamp(self.min_zoom, self.max_zoom.max(self.min_zoom))
+    }
+
     pub fn update_pixel_perfect(&mut self, new_w: f32, new_h: f32) {
         self.center = Vec2::new(-new_w * 0.5, -new_h * 0.5);
     }
+
+    /// Re-applies the [`ScalePolicy`] for a window of size `screen`.
+    pub fn resize(&mut self, screen: Vec2) {
+        let (_, region) = self.viewport.to_pixels(screen);
+        let design = self.design_size.max(Vec2::ONE);
+        let ratio = region / design;
+        self.fit = Viewport::FULL;
+        self.stretch = Vec2::ONE;
+        match self.policy {
+            ScalePolicy::PixelPerfect => self.update_pixel_perfect(region.x, region.y),
+            ScalePolicy::Stretch => {
+                self.stretch = ratio;
+                self.center = -design * 0.5;
+            }
+            ScalePolicy::FitWidth => {
+                self.stretch = Vec2::splat(ratio.x);
+                self.center = -region / ratio.x * 0.5;
+            }
+            ScalePolicy::FitHeight => {
+                self.stretch = Vec2::splat(ratio.y);
+                self.center = -region / ratio.y * 0.5;
+            }
+            ScalePolicy::Letterbox | ScalePolicy::IntegerScale => {
+                let mut s = ratio.min_element();
+                if self.policy == ScalePolicy::IntegerScale {
+                    s = s.floor().max(1.0);
+                }
+                let size = (design * s / region).min(Vec2::ONE);
+                let min = (Vec2::ONE - size) * 0.5;
+                self.fit = Viewport::new(min.x, min.y, size.x, size.y);
+                self.stretch = Vec2::splat(s);
+                self.center = -design * 0.5;
+            }
+        }
+    }
+
+    /// The window region actually drawn to: [`Camera::viewport`] minus any letterbox bars.
+    pub fn output_viewport(&self) -> Viewport {
+        let (v, f) = (self.viewport, self.fit);
+        Viewport::new(v.x + f.x * v.w, v.y + f.y * v.h, v.w * f.w, v.h * f.h)
+    }
+
+    /// Parts of the viewport in a `screen` sized window that
+    /// [`Camera::output_viewport`] leaves empty, as minimum corner (origin
+    /// top-left) and size in pixels; none unless the policy letterboxes.
+    pub fn letterbox_bars(&self, screen: Vec2) -> impl Iterator<Item = (Vec2, Vec2)> {
+        let (min, size) = self.viewport.to_pixels(screen);
+        let (out_min, out_size) = self.output_viewport().to_pixels(screen);
+        let (max, out_max) = (min + size, out_min + out_size);
+        [
+            (min, Vec2::new(out_min.x - min.x, size.y)),
+            (
+                Vec2::new(out_max.x, min.y),
+                Vec2::new(max.x - out_max.x, size.y),
+            ),
+            (
+                Vec2::new(out_min.x, min.y),
+                Vec2::new(out_size.x, out_min.y - min.y),
+            ),
+            (
+                Vec2::new(out_min.x, out_max.y),
+                Vec2::new(out_size.x, max.y - out_max.y),
+            ),
+        ]
+        .into_iter()
+        // bars thinner than half a pixel are rounding error
+        .filter(|(_, size)| size.x >= 0.5 && size.y >= 0.5)
+    }
+
+    /// Pixels per world unit on each axis, `zoom` combined with the scale policy.
+    pub fn scale(&self) -> Vec2 {
+        self.stretch * self.zoom
+    }
+
+    /// What the sprite and mesh vertex shaders transform by, drawing into a
+    /// `screen` sized target: the output viewport's size in pixels, then
+    /// `center` and [`Camera::scale`].
+    pub fn view_constants(&self, screen: Vec2) -> [f32; 6] {
+        let (_, size) = self.output_viewport().to_pixels(screen);
+        let scale = self.scale();
+        [
+            size.x,
+            size.y,
+            self.center.x,
+            self.center.y,
+            scale.x,
+            scale.y,
+        ]
+    }
+
+    /// The world area the camera shows in a `screen` sized window, e.g. for culling.
+    pub fn view_aabb(&self, screen: Vec2) -> Aabb {
+        let (_, size) = self.output_viewport().to_pixels(screen);
+        Aabb::new(self.center, self.center + size / self.scale())
+    }
+
+    /// World position to window coordinates (origin top-left, y down), as the sprite shader maps it.
+    pub fn world_to_window(&self, world: Vec2, screen: Vec2) -> Vec2 {
+        let (min, size) = self.output_viewport().to_pixels(screen);
+        let p = (world - self.center) * self.scale();
+        Vec2::new(min.x + p.x, min.y + size.y - p.y)
+    }
+    /// Inverse of [`Camera::world_to_window`], e.g. for mouse picking.
+    pub fn window_to_world(&self, window_pt: Vec2, screen: Vec2) -> Vec2 {
+        let (min, size) = self.output_viewport().to_pixels(screen);
+        let local = window_pt - min;
+        Vec2::new(local.x, size.y - local.y) / self.scale() + self.center
+    }
+    /// World position relative to the window center, y up, by `zoom` alone.
+    #[deprecated(
+        note = "ignores the viewport, scale policy and where sprites draw; use `world_to_window`"
+    )]
     pub fn world_to_screen(&self, world: Vec2, screen: Vec2) -> Vec2 {
         (world - self.center) * self.zoom + screen * 0.5
     }
+    /// Inverse of [`Camera::world_to_screen`].
+    #[deprecated(note = "use `window_to_world`")]
     pub fn screen_to_world(&self, screen_pt: Vec2, screen: Vec2) -> Vec2 {
         (screen_pt - screen * 0.5) / self.zoom + self.center
     }
@@ -41,11 +494,22 @@ impl Default for Camera {
         Self {
             center: glam::Vec2::ZERO,
             zoom: 1.0,
+            viewport: Viewport::FULL,
+            policy: ScalePolicy::PixelPerfect,
+            design_size: Vec2::ZERO,
+            filter: ScreenFilter::NONE,
+            bars: BarFill::default(),
+            min_zoom: 0.01,
+            max_zoom: 100.0,
+            zoom_ease: Ease::default(),
+            fit: Viewport::FULL,
+            stretch: Vec2::ONE,
+            zoom_anim: None,
         }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Transform {
     pub translation: Vec2,
     pub scale: Vec2,
@@ -86,6 +550,16 @@ impl Transform {
         self.scale = scale;
         self
     }
+
+    /// `child`, given relative to this transform, in this transform's space.
+    pub fn mul_transform(&self, child: &Transform) -> Transform {
+        let offset = Vec2::from_angle(self.rotation).rotate(child.translation * self.scale);
+        Transform {
+            translation: self.translation + offset,
+            scale: self.scale * child.scale,
+            rotation: self.rotation + child.rotation,
+        }
+    }
 }
 
 impl From<Transform> for [f32; 4] {
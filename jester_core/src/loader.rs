@@ -0,0 +1,174 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    thread::{self, JoinHandle},
+};
+
+use hashbrown::HashMap;
+use image::ImageError;
+
+use crate::{import::open_image, AssetError, DecodedImage, ImportCache, TextureId};
+
+/// Where a texture asked for by file is, see [`Renderer::asset_state`](crate::Renderer::asset_state).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AssetState {
+    /// Queued, decoding on a worker thread, or waiting to be retried.
+    Loading,
+    Ready,
+    /// Given up on; the error was returned or sent through the asset errors.
+    Failed,
+}
+
+/// The [`AssetState`] of every texture asked for by file, as of the end of
+/// the last frame; scenes see it as `ctx.assets`.
+///
+/// ```ignore
+/// // a loading screen
+/// let level = [ctx.load_asset_async("tiles.png"), ctx.load_asset_async("hero.png")];
+/// // later, in update
+/// self.bar_width = 200.0 * ctx.assets.progress(level);
+/// if ctx.assets.all_ready(level) {
+///     ctx.goto_scene::<Level>();
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AssetStates {
+    states: HashMap<TextureId, AssetState>,
+}
+
+impl AssetStates {
+    /// `None` for textures never asked for, or unloaded since.
+    pub fn get(&self, tex: TextureId) -> Option<AssetState> {
+        self.states.get(&tex).copied()
+    }
+
+    pub fn all_ready(&self, textures: impl IntoIterator<Item = TextureId>) -> bool {
+        textures
+            .into_iter()
+            .all(|t| self.get(t) == Some(AssetState::Ready))
+    }
+
+    /// Fraction of `textures` ready or failed, 1 for none, e.g. for a progress bar.
+    pub fn progress(&self, textures: impl IntoIterator<Item = TextureId>) -> f32 {
+        let (mut done, mut total) = (0, 0);
+        for t in textures {
+            total += 1;
+            done += matches!(self.get(t), Some(AssetState::Ready | AssetState::Failed)) as u32;
+        }
+        match total {
+            0 => 1.0,
+            _ => done as f32 / total as f32,
+        }
+    }
+
+    pub(crate) fn set(&mut self, tex: TextureId, state: AssetState) {
+        self.states.insert(tex, state);
+    }
+
+    pub(crate) fn remove(&mut self, tex: TextureId) {
+        self.states.remove(&tex);
+    }
+}
+
+/// A file a worker decoded, to be uploaded on the render thread.
+pub(crate) struct Decoded {
+    pub tex: TextureId,
+    pub path: PathBuf,
+    pub image: Result<DecodedImage, AssetError>,
+}
+
+type Job = (TextureId, PathBuf, Option<ImportCache>);
+
+/// Worker threads decoding image files, started by the first background load.
+pub(crate) struct Loader {
+    jobs: Option<mpsc::Sender<Job>>,
+    done: mpsc::Receiver<Decoded>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl Loader {
+    pub fn new() -> Self {
+        // one core is left to the render thread
+        let threads = thread::available_parallelism().map_or(2, |n| (n.get() - 1).clamp(1, 4));
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let queue = Arc::new(Mutex::new(queue));
+        let (results, done) = mpsc::channel();
+        let workers = (0..threads)
+            .map(|i| {
+                let queue = Arc::clone(&queue);
+                let results = results.clone();
+                thread::Builder::new()
+                    .name(format!("jester-loader-{i}"))
+                    .spawn(move || loop {
+                        let job = queue.lock().expect("loader queue poisoned").recv();
+                        let Ok((tex, path, cache)) = job else {
+                            return;
+                        };
+                        let image = decode(cache.as_ref(), tex, &path);
+                        if results.send(Decoded { tex, path, image }).is_err() {
+                            return;
+                        }
+                    })
+                    .expect("failed to spawn a loader thread")
+            })
+            .collect();
+        Self {
+            jobs: Some(jobs),
+            done,
+            workers,
+        }
+    }
+
+    /// Files decoding at once; more are held back so priorities still count.
+    pub fn capacity(&self) -> usize {
+        self.workers.len() * 2
+    }
+
+    pub fn send(&self, tex: TextureId, path: PathBuf, cache: Option<ImportCache>) {
+        if let Some(jobs) = &self.jobs {
+            jobs.send((tex, path, cache)).ok();
+        }
+    }
+
+    /// A decoded file, if any finished since.
+    pub fn try_recv(&self) -> Option<Decoded> {
+        self.done.try_recv().ok()
+    }
+}
+
+impl Drop for Loader {
+    fn drop(&mut self) {
+        // closing the queue ends every worker once it is out of jobs
+        self.jobs = None;
+        for w in self.workers.drain(..) {
+            w.join().ok();
+        }
+    }
+}
+
+/// Decodes `path` through `cache` if there is one, on any thread.
+pub(crate) fn decode(
+    cache: Option<&ImportCache>,
+    tex: TextureId,
+    path: &Path,
+) -> Result<DecodedImage, AssetError> {
+    open_image(cache, path).map_err(|e| match e {
+        ImageError::IoError(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            AssetError::NotFound {
+                path: path.to_path_buf(),
+                tex,
+            }
+        }
+        ImageError::IoError(e) => AssetError::ReadFailed {
+            path: path.to_path_buf(),
+            tex,
+            kind: e.kind(),
+            reason: e.to_string(),
+        },
+        e => AssetError::DecodeFailed {
+            path: path.to_path_buf(),
+            tex,
+            reason: e.to_string(),
+        },
+    })
+}
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use glam::Vec2;
+
+use crate::{BlendMode, Color, TextureId};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C)]
+pub struct MeshVertex {
+    /// World position in pixels, like [`Transform::translation`](crate::Transform::translation).
+    pub pos: [f32; 2],
+    /// Texture coordinates, v up like [`Sprite::uv`](crate::Sprite::uv).
+    pub uv: [f32; 2],
+    /// Multiplies the texel, per vertex and interpolated in between.
+    pub color: [f32; 4],
+}
+
+impl MeshVertex {
+    pub fn new(pos: Vec2, uv: Vec2, color: Color) -> Self {
+        Self {
+            pos: pos.to_array(),
+            uv: uv.to_array(),
+            color: [color.r, color.g, color.b, color.a],
+        }
+    }
+}
+
+/// Triangles with arbitrary vertices rather than the unit quad sprites
+/// draw with, e.g. for destructible terrain, water surfaces or shadows.
+///
+/// Every three indices are one triangle; winding does not matter.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Mesh {
+    pub vertices: Vec<MeshVertex>,
+    pub indices: Vec<u32>,
+}
+
+impl Mesh {
+    pub fn new(vertices: Vec<MeshVertex>, indices: Vec<u32>) -> Self {
+        Self { vertices, indices }
+    }
+
+    /// A convex polygon, fanned out from its first point, with UVs spanning its bounding box.
+    pub fn convex(points: &[Vec2], color: Color) -> Self {
+        let mut mesh = Self::default();
+        mesh.push_convex(points, color);
+        mesh
+    }
+
+    /// Adds a convex polygon, see [`Mesh::convex`].
+    pub fn push_convex(&mut self, points: &[Vec2], color: Color) {
+        if points.len() < 3 {
+            return;
+        }
+        let min = points.iter().copied().reduce(Vec2::min).expect("not empty");
+        let max = points.iter().copied().reduce(Vec2::max).expect("not empty");
+        let size = (max - min).max(Vec2::splat(f32::EPSILON));
+        let first = self.vertices.len() as u32;
+        self.vertices.extend(
+            points
+                .iter()
+                .map(|&p| MeshVertex::new(p, (p - min) / size, color)),
+        );
+        for i in 1..points.len() as u32 - 1 {
+            self.indices.extend([first, first + i, first + i + 1]);
+        }
+    }
+
+    /// Adds a triangle.
+    pub fn push_triangle(&mut self, vertices: [MeshVertex; 3]) {
+        let first = self.vertices.len() as u32;
+        self.vertices.extend(vertices);
+        self.indices.extend([first, first + 1, first + 2]);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.len() < 3
+    }
+}
+
+/// A [`Mesh`] drawn with one texture, the mesh counterpart of a
+/// [`SpriteBatch`](crate::SpriteBatch).
+///
+/// The mesh is shared so a scene can keep it and submit it every frame
+/// without copying; draw with a 1×1 white texture for plain colours.
+#[derive(Clone, Debug)]
+pub struct MeshBatch {
+    pub tex: TextureId,
+    pub blend: BlendMode,
+    pub mesh: Arc<Mesh>,
+}
+
+impl MeshBatch {
+    pub fn new(tex: TextureId, mesh: impl Into<Arc<Mesh>>) -> Self {
+        Self {
+            tex,
+            blend: BlendMode::default(),
+            mesh: mesh.into(),
+        }
+    }
+
+    pub fn with_blend(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+}
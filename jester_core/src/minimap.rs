@@ -0,0 +1,267 @@
+use glam::Vec2;
+use hashbrown::HashMap;
+
+use crate::{
+    Camera, Color, Ctx, EntityId, EntityPool, Fill, Panel, PanelInstance, SpriteBatch, TextureId,
+    Transform, Viewport,
+};
+
+/// How a tagged entity shows on the [`Minimap`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MapMarker {
+    pub color: Color,
+    /// Width and height in window pixels, whatever the zoom.
+    pub size: f32,
+    pub round: bool,
+}
+
+impl MapMarker {
+    pub fn dot(color: Color, size: f32) -> Self {
+        Self {
+            color,
+            size,
+            round: true,
+        }
+    }
+
+    pub fn square(color: Color, size: f32) -> Self {
+        Self {
+            color,
+            size,
+            round: false,
+        }
+    }
+}
+
+/// Overview of the world drawn in a corner of the window.
+///
+/// Add it with `App::add_resource`; the app then draws the sprites of
+/// [`Minimap::layers`] scaled down into [`Minimap::viewport`] after the
+/// cameras, with a marker over every entity tagged with [`Minimap::mark`].
+/// Markers of entities outside the map stick to its edge. Sprites of a
+/// layer draw in batch order, so `SortMode::YSort` does not apply here.
+///
+/// The map is drawn straight into its window region, framed with UI using
+/// [`Minimap::rect`], unless [`Minimap::render_to_texture`] gave it a
+/// texture to draw into instead, for a UI widget or sprite to show.
+///
+/// ```ignore
+/// app.add_resource(
+///     Minimap::new()
+///         .with_layers([0, 1])
+///         .following(player)
+///         .with_zoom(0.1),
+/// );
+/// // in start, once enemies exist
+/// let map = ctx.resources.get_mut::<Minimap>().unwrap();
+/// map.mark(player, MapMarker::dot(Color::WHITE, 6.0));
+/// for &e in &self.enemies {
+///     map.mark(e, MapMarker::dot(Color::rgb(1.0, 0.2, 0.2), 4.0));
+/// }
+/// // or shown by a sprite of the HUD
+/// let mut map = ctx.resources.take::<Minimap>().unwrap();
+/// let tex = map.render_to_texture(ctx, 256, 256);
+/// ctx.resources.insert(map);
+/// ctx.spawn_sprite(Sprite { tex, layer: 100, ..Default::default() });
+/// ```
+#[derive(Clone, Debug)]
+pub struct Minimap {
+    /// Region of the window it covers, unless it has a texture.
+    pub viewport: Viewport,
+    /// World position at the middle of the map, unless it is following an entity.
+    pub center: Vec2,
+    pub follow: Option<EntityId>,
+    /// Window pixels per world unit.
+    pub zoom: f32,
+    /// Layers drawn, every layer if `None`.
+    pub layers: Option<Vec<i32>>,
+    pub background: Color,
+    pub border_color: Color,
+    pub border_width: f32,
+    pub visible: bool,
+    markers: HashMap<EntityId, MapMarker>,
+    // render target drawn into instead of the window, and its size
+    texture: Option<(TextureId, Vec2)>,
+}
+
+impl Default for Minimap {
+    fn default() -> Self {
+        Self {
+            viewport: Viewport::new(0.78, 0.02, 0.2, 0.2),
+            center: Vec2::ZERO,
+            follow: None,
+            zoom: 0.1,
+            layers: None,
+            background: Color::rgba(0.0, 0.0, 0.0, 0.6),
+            border_color: Color::WHITE,
+            border_width: 2.0,
+            visible: true,
+            markers: HashMap::new(),
+            texture: None,
+        }
+    }
+}
+
+impl Minimap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    pub fn with_layers(mut self, layers: impl IntoIterator<Item = i32>) -> Self {
+        self.layers = Some(layers.into_iter().collect());
+        self
+    }
+
+    /// Keeps `id` in the middle of the map.
+    pub fn following(mut self, id: EntityId) -> Self {
+        self.follow = Some(id);
+        self
+    }
+
+    pub fn shows_layer(&self, layer: i32) -> bool {
+        self.layers.as_ref().is_none_or(|l| l.contains(&layer))
+    }
+
+    /// Shows `id` as `marker`, replacing any marker it had.
+    pub fn mark(&mut self, id: EntityId, marker: MapMarker) {
+        self.markers.insert(id, marker);
+    }
+
+    pub fn unmark(&mut self, id: EntityId) {
+        self.markers.remove(&id);
+    }
+
+    pub fn markers(&self) -> impl Iterator<Item = (&EntityId, &MapMarker)> {
+        self.markers.iter()
+    }
+
+    /// Draws the map into a new `w` by `h` render target from the next
+    /// frame on rather than into the window, and returns the texture, e.g.
+    /// for a sprite of the HUD; see [`Ctx::render_to_texture`].
+    ///
+    /// The map then fills the texture, and `screen` arguments of the
+    /// drawing methods are the texture's size; see [`Minimap::canvas`].
+    pub fn render_to_texture(&mut self, ctx: &mut Ctx<'_>, w: u32, h: u32) -> TextureId {
+        let tex = ctx.create_render_target(w, h);
+        self.texture = Some((tex, Vec2::new(w as f32, h as f32)));
+        tex
+    }
+
+    /// The render target of [`Minimap::render_to_texture`], if any.
+    pub fn texture(&self) -> Option<TextureId> {
+        self.texture.map(|(tex, _)| tex)
+    }
+
+    /// The size of what the map is drawn into: the texture's, or `screen`.
+    pub fn canvas(&self, screen: Vec2) -> Vec2 {
+        self.texture.map_or(screen, |(_, size)| size)
+    }
+
+    fn region(&self) -> Viewport {
+        match self.texture {
+            Some(_) => Viewport::FULL,
+            None => self.viewport,
+        }
+    }
+
+    /// Top-left corner and size in window pixels, or texture pixels.
+    pub fn rect(&self, screen: Vec2) -> (Vec2, Vec2) {
+        self.region().to_pixels(screen)
+    }
+
+    /// The camera drawing the map for a window of size `screen`.
+    pub fn camera(&self, pool: &EntityPool, screen: Vec2) -> Camera {
+        let center = self
+            .follow
+            .and_then(|id| pool.entities.get(&id))
+            .map_or(self.center, |s| s.transform.translation);
+        let mut cam = Camera::default().with_viewport(self.region());
+        cam.resize(screen);
+        cam.zoom = self.zoom.max(f32::EPSILON);
+        let (_, size) = self.rect(screen);
+        cam.center = center - size / cam.scale() * 0.5;
+        cam
+    }
+
+    /// The world position under window position `screen_pt`, if it is on the map, e.g. to ping.
+    ///
+    /// With a texture, `screen_pt` is in texture pixels from its top-left
+    /// corner and `screen` its size.
+    pub fn to_world(&self, screen_pt: Vec2, pool: &EntityPool, screen: Vec2) -> Option<Vec2> {
+        self.region()
+            .contains(screen_pt, screen)
+            .then(|| self.camera(pool, screen).window_to_world(screen_pt, screen))
+    }
+
+    /// One batch per sprite group of the shown layers, in draw order,
+    /// leaving out sprites of the map's own texture.
+    pub fn batches(&self, pool: &EntityPool) -> Vec<SpriteBatch> {
+        pool.entities
+            .groups()
+            .filter(|g| self.shows_layer(g.first().layer))
+            .filter(|g| Some(g.first().tex) != self.texture())
+            .map(|g| {
+                let s = g.first();
+                SpriteBatch {
+                    tex: s.tex,
+                    blend: s.blend,
+                    palette: s.palette.map(|p| p.tex),
+                    cutout: false,
+                    material: s.material,
+                    baked: g.baked(),
+                    retained: None,
+                    instances: g.instances().to_vec(),
+                }
+            })
+            .collect()
+    }
+
+    /// Background and border, for a camera in screen space.
+    pub fn frame(&self, screen: Vec2) -> PanelInstance {
+        let (min, size) = self.rect(screen);
+        let mid = min + size * 0.5;
+        Panel {
+            transform: Transform::from_xy(mid.x, screen.y - mid.y),
+            size,
+            fill: Fill::Solid(self.background),
+            border_width: self.border_width,
+            border_color: self.border_color,
+            ..Default::default()
+        }
+        .instance()
+    }
+
+    /// Markers of the tagged entities still alive, for a camera in screen space.
+    pub fn marker_panels(&self, pool: &EntityPool, screen: Vec2) -> Vec<PanelInstance> {
+        let cam = self.camera(pool, screen);
+        let (min, size) = self.rect(screen);
+        self.markers
+            .iter()
+            .filter_map(|(id, m)| {
+                let world = pool.entities.get(id)?.transform.translation;
+                let half = Vec2::splat(m.size * 0.5).min(size * 0.5);
+                let at = cam
+                    .world_to_window(world, screen)
+                    .clamp(min + half, min + size - half);
+                let panel = Panel {
+                    transform: Transform::from_xy(at.x, screen.y - at.y),
+                    size: Vec2::splat(m.size),
+                    fill: Fill::Solid(m.color),
+                    corner_radius: if m.round { m.size * 0.5 } else { 0.0 },
+                    ..Default::default()
+                };
+                Some(panel.instance())
+            })
+            .collect()
+    }
+}
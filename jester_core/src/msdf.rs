@@ -0,0 +1,502 @@
+use glam::Vec2;
+use hashbrown::HashMap;
+use ttf_parser::OutlineBuilder;
+
+use crate::{
+    sprite::MaterialId, text::Glyph, Error, Font, Sprite, SpriteInstance, TextureId, Transform,
+};
+
+/// Printable ASCII as a multi-channel signed distance field, drawn with [`MaterialId::MSDF`].
+///
+/// Unlike a [`GlyphAtlas`](crate::GlyphAtlas) one atlas serves every text size
+/// and camera zoom: the shader rebuilds the outline from distances instead of
+/// scaling coverage, and splitting them over three channels keeps corners sharp.
+/// [`Glyph`] metrics of this atlas are in ems.
+#[derive(Debug)]
+pub struct MsdfAtlas {
+    pub tex: TextureId,
+    pub width: u32,
+    pub height: u32,
+    /// Atlas texels per em.
+    pub em_size: f32,
+    /// Texels over which the field fades from inside to outside, passed to the shader.
+    pub range: f32,
+    ascent: f32,
+    line_height: f32,
+    glyphs: HashMap<char, Glyph>,
+}
+
+impl MsdfAtlas {
+    const WIDTH: u32 = 512;
+    const PADDING: u32 = 1;
+    const RANGE: u32 = 4;
+
+    /// Bakes `' '..='~'` at `em_size` texels per em, 32 is plenty for most fonts.
+    ///
+    /// Returns the atlas plus the RGBA-8 pixels to upload under `atlas.tex`.
+    pub fn ascii(font: &Font, em_size: f32) -> Result<(Self, Vec<u8>), Error> {
+        let face = font.face()?;
+        let units = face.units_per_em() as f32;
+        let scale = em_size / units;
+        let range = Self::RANGE as i32;
+
+        // shelf-pack the fields left to right, top to bottom
+        let mut placed = Vec::new();
+        let (mut x, mut y, mut shelf) = (Self::PADDING, Self::PADDING, 0);
+        for c in ' '..='~' {
+            let Some(id) = face.glyph_index(c) else {
+                continue;
+            };
+            let advance = face.glyph_hor_advance(id).unwrap_or(0) as f32 / units;
+            let mut outline = Outline::new(scale);
+            let Some(bbox) = face.outline_glyph(id, &mut outline) else {
+                placed.push((c, advance, None, 0, 0));
+                continue;
+            };
+            // CFF outlines may leave their last contour open
+            outline.close();
+            let left = (bbox.x_min as f32 * scale).floor() as i32 - range;
+            let bottom = (bbox.y_min as f32 * scale).floor() as i32 - range;
+            let top = (bbox.y_max as f32 * scale).ceil() as i32 + range;
+            let right = (bbox.x_max as f32 * scale).ceil() as i32 + range;
+            let (w, h) = ((right - left) as u32, (top - bottom) as u32);
+            if x + w + Self::PADDING > Self::WIDTH {
+                x = Self::PADDING;
+                y += shelf + Self::PADDING;
+                shelf = 0;
+            }
+            let segments = outline.segments();
+            let cell = Cell {
+                left,
+                top,
+                w,
+                h,
+                boundary: boundary(&segments),
+                segments,
+            };
+            placed.push((c, advance, Some(cell), x, y));
+            x += w + Self::PADDING;
+            shelf = shelf.max(h);
+        }
+        let width = Self::WIDTH;
+        let height = (y + shelf + Self::PADDING).next_power_of_two();
+
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        let mut glyphs = HashMap::new();
+        for (c, advance, cell, gx, gy) in placed {
+            let Some(cell) = cell else {
+                glyphs.insert(
+                    c,
+                    Glyph {
+                        uv: [0.0; 4],
+                        size: Vec2::ZERO,
+                        offset: Vec2::ZERO,
+                        advance,
+                    },
+                );
+                continue;
+            };
+            let field = cell.field(Self::RANGE as f32);
+            for row in 0..cell.h {
+                for col in 0..cell.w {
+                    let i = (((gy + row) * width + gx + col) * 4) as usize;
+                    let j = ((row * cell.w + col) * 4) as usize;
+                    pixels[i..i + 4].copy_from_slice(&field[j..j + 4]);
+                }
+            }
+            let (u0, u1) = (
+                gx as f32 / width as f32,
+                (gx + cell.w) as f32 / width as f32,
+            );
+            let (v0, v1) = (
+                gy as f32 / height as f32,
+                (gy + cell.h) as f32 / height as f32,
+            );
+            glyphs.insert(
+                c,
+                Glyph {
+                    uv: [u0, 1.0 - v1, u1, 1.0 - v0],
+                    size: Vec2::new(cell.w as f32, cell.h as f32) / em_size,
+                    offset: Vec2::new(cell.left as f32, (cell.top - cell.h as i32) as f32)
+                        / em_size,
+                    advance,
+                },
+            );
+        }
+
+        let atlas = Self {
+            tex: TextureId::generated(),
+            width,
+            height,
+            em_size,
+            range: Self::RANGE as f32,
+            ascent: face.ascender() as f32 / units,
+            line_height: (face.ascender() - face.descender() + face.line_gap()) as f32 / units,
+            glyphs,
+        };
+        Ok((atlas, pixels))
+    }
+
+    /// Distance between baselines for text `size` world units high per em.
+    pub fn line_height(&self, size: f32) -> f32 {
+        self.line_height * size
+    }
+
+    pub fn glyph(&self, c: char) -> Option<&Glyph> {
+        self.glyphs.get(&c).or_else(|| self.glyphs.get(&'?'))
+    }
+
+    /// Width and height of `text` laid out by [`MsdfAtlas::layout`].
+    pub fn measure(&self, text: &str, size: f32) -> Vec2 {
+        let mut w = 0.0f32;
+        let mut lines = 0;
+        for line in text.split('\n') {
+            w = w.max(
+                line.chars()
+                    .filter_map(|c| self.glyph(c))
+                    .map(|g| g.advance)
+                    .sum(),
+            );
+            lines += 1;
+        }
+        Vec2::new(w, lines as f32 * self.line_height) * size
+    }
+
+    /// One instance per visible glyph, `top_left` being the world position (y up) of the first line.
+    ///
+    /// Draw them in a batch with [`MaterialId::MSDF`]; `custom[0]` carries [`MsdfAtlas::range`].
+    pub fn layout(&self, text: &str, top_left: Vec2, size: f32) -> Vec<SpriteInstance> {
+        let mut out = Vec::with_capacity(text.len());
+        for (row, line) in text.split('\n').enumerate() {
+            let baseline = top_left.y - (self.ascent + row as f32 * self.line_height) * size;
+            let mut pen = top_left.x;
+            for c in line.chars() {
+                let Some(g) = self.glyph(c) else { continue };
+                if g.size.x > 0.0 && g.size.y > 0.0 {
+                    let center = Vec2::new(pen, baseline) + (g.offset + g.size * 0.5) * size;
+                    let extent = g.size * size;
+                    out.push(SpriteInstance {
+                        pos_size: [center.x, center.y, extent.x, extent.y],
+                        uv: g.uv,
+                        custom: [self.range, 0.0, 0.0, 0.0],
+                        ..Default::default()
+                    });
+                }
+                pen += g.advance * size;
+            }
+        }
+        out
+    }
+
+    /// [`MsdfAtlas::layout`] as sprites ready to spawn, one per glyph.
+    pub fn sprites(&self, text: &str, top_left: Vec2, size: f32) -> Vec<Sprite> {
+        self.layout(text, top_left, size)
+            .into_iter()
+            .map(|g| Sprite {
+                transform: Transform::from_xy(g.pos_size[0], g.pos_size[1]),
+                size: Some(Vec2::new(g.pos_size[2], g.pos_size[3])),
+                uv: g.uv,
+                tex: self.tex,
+                custom: g.custom,
+                material: MaterialId::MSDF,
+                ..Default::default()
+            })
+            .collect()
+    }
+}
+
+/// Sharper turns than this (sine of the angle) between outline edges are corners.
+const CORNER_SIN: f32 = 0.14;
+const QUAD_STEPS: usize = 8;
+const CUBIC_STEPS: usize = 12;
+
+/// Channel masks, adjacent edges at a corner never share two channels.
+const WHITE: u8 = 0b111;
+const COLORS: [u8; 3] = [0b110, 0b101, 0b011];
+
+/// A glyph's field in the atlas, positions in texels with y up from the baseline.
+struct Cell {
+    left: i32,
+    top: i32,
+    w: u32,
+    h: u32,
+    segments: Vec<Segment>,
+    /// The pieces of `segments` between inside and outside, overlapping contours
+    /// (common in variable fonts) would otherwise leave edges within the glyph.
+    boundary: Vec<Segment>,
+}
+
+impl Cell {
+    /// RGBA-8 texels: distances per channel in RGB, the true distance in alpha.
+    fn field(&self, range: f32) -> Vec<u8> {
+        let mut texels = Vec::with_capacity((self.w * self.h) as usize);
+        for row in 0..self.h {
+            for col in 0..self.w {
+                let p = Vec2::new(
+                    self.left as f32 + col as f32 + 0.5,
+                    self.top as f32 - row as f32 - 0.5,
+                );
+                let sd = distances(p, &self.boundary);
+                let inside = winding(p, &self.segments) != 0;
+                texels.push((sd.0, if inside { sd.1 } else { -sd.1 }));
+            }
+        }
+        // outer contours run clockwise in TrueType and counter-clockwise in CFF fonts
+        let agree = texels
+            .iter()
+            .filter(|(ch, sd)| (median(*ch) > 0.0) == (*sd > 0.0))
+            .count();
+        let flip = if agree * 2 < texels.len() { -1.0 } else { 1.0 };
+
+        let encode = |d: f32| ((d / range + 0.5).clamp(0.0, 1.0) * 255.0).round() as u8;
+        let mut out = Vec::with_capacity(texels.len() * 4);
+        for (ch, sd) in texels {
+            let mut ch = ch.map(|d| d * flip);
+            // where the channels disagree on the side, fall back to a plain distance field
+            if (median(ch) > 0.0) != (sd > 0.0) {
+                ch = [sd; 3];
+            }
+            out.extend_from_slice(&[encode(ch[0]), encode(ch[1]), encode(ch[2]), encode(sd)]);
+        }
+        out
+    }
+}
+
+fn median(v: [f32; 3]) -> f32 {
+    v[0].min(v[1]).max(v[0].max(v[1]).min(v[2]))
+}
+
+/// Signed distance from `p` to the nearest segment of each channel, positive
+/// on the segments' left, and the unsigned distance to the nearest of all.
+fn distances(p: Vec2, segments: &[Segment]) -> ([f32; 3], f32) {
+    let mut best = [(f32::MAX, 0.0f32); 3];
+    let mut min = f32::MAX;
+    for s in segments {
+        let ab = s.b - s.a;
+        let ap = p - s.a;
+        let len2 = ab.length_squared();
+        let t = ap.dot(ab) / len2;
+        let d = (ap - ab * t.clamp(0.0, 1.0)).length();
+        let cross = ab.perp_dot(ap);
+        // beyond the ends of an edge the distance to its extension keeps corners sharp
+        let signed = if (t < 0.0 && s.first) || (t > 1.0 && s.last) {
+            cross / len2.sqrt()
+        } else {
+            d.copysign(cross)
+        };
+        min = min.min(d);
+        for (ch, b) in best.iter_mut().enumerate() {
+            let closer = d < b.0 - 1e-4 || (d < b.0 + 1e-4 && signed.abs() > b.1.abs());
+            if s.color & (1 << ch) != 0 && closer {
+                *b = (d, signed);
+            }
+        }
+    }
+    (best.map(|b| b.1), min)
+}
+
+/// Winding number of the outline around `p`, non-zero inside.
+fn winding(p: Vec2, segments: &[Segment]) -> i32 {
+    let mut winding = 0;
+    for s in segments {
+        // crossings of a ray towards +x
+        if (s.a.y <= p.y) != (s.b.y <= p.y) {
+            let x = s.a.x + (p.y - s.a.y) / (s.b.y - s.a.y) * (s.b.x - s.a.x);
+            if x > p.x {
+                winding += if s.b.y > s.a.y { 1 } else { -1 };
+            }
+        }
+    }
+    winding
+}
+
+/// Splits `segments` into pieces of about a texel and keeps those with the
+/// inside of the glyph on exactly one side.
+fn boundary(segments: &[Segment]) -> Vec<Segment> {
+    let mut out = Vec::new();
+    for s in segments {
+        let ab = s.b - s.a;
+        let n = (ab.length().ceil() as usize).max(1);
+        let normal = ab.perp().normalize_or_zero() * 0.01;
+        for i in 0..n {
+            let a = s.a + ab * (i as f32 / n as f32);
+            let b = s.a + ab * ((i + 1) as f32 / n as f32);
+            let mid = (a + b) * 0.5;
+            let left = winding(mid + normal, segments) != 0;
+            let right = winding(mid - normal, segments) != 0;
+            if left != right {
+                out.push(Segment {
+                    a,
+                    b,
+                    first: s.first && i == 0,
+                    last: s.last && i == n - 1,
+                    ..*s
+                });
+            }
+        }
+    }
+    out
+}
+
+#[derive(Clone, Copy)]
+struct Segment {
+    a: Vec2,
+    b: Vec2,
+    color: u8,
+    /// Index of the outline edge (line or curve) this piece belongs to.
+    edge: usize,
+    first: bool,
+    last: bool,
+}
+
+/// Glyph outline with curves flattened, one polyline per edge.
+struct Outline {
+    scale: f32,
+    contours: Vec<Vec<Vec<Vec2>>>,
+    start: Vec2,
+    cursor: Vec2,
+}
+
+impl Outline {
+    fn new(scale: f32) -> Self {
+        Self {
+            scale,
+            contours: Vec::new(),
+            start: Vec2::ZERO,
+            cursor: Vec2::ZERO,
+        }
+    }
+
+    fn point(&self, x: f32, y: f32) -> Vec2 {
+        Vec2::new(x, y) * self.scale
+    }
+
+    fn edge(&mut self, mut points: Vec<Vec2>) {
+        points.dedup();
+        if points.len() < 2 {
+            return;
+        }
+        self.cursor = points[points.len() - 1];
+        match self.contours.last_mut() {
+            Some(c) => c.push(points),
+            None => self.contours.push(vec![points]),
+        }
+    }
+
+    /// Flattens the outline and colours its edges so every corner separates channels.
+    fn segments(&self) -> Vec<Segment> {
+        let mut out = Vec::new();
+        for edges in &self.contours {
+            let n = edges.len();
+            if n == 0 {
+                continue;
+            }
+            let start_dir = |e: &[Vec2]| (e[1] - e[0]).normalize_or_zero();
+            let end_dir = |e: &[Vec2]| (e[e.len() - 1] - e[e.len() - 2]).normalize_or_zero();
+            let corners: Vec<usize> = (0..n)
+                .filter(|&i| {
+                    let (a, b) = (end_dir(&edges[(i + n - 1) % n]), start_dir(&edges[i]));
+                    a.dot(b) <= 0.0 || a.perp_dot(b).abs() > CORNER_SIN
+                })
+                .collect();
+
+            let first = out.len();
+            for (i, e) in edges.iter().enumerate() {
+                for (j, w) in e.windows(2).enumerate() {
+                    out.push(Segment {
+                        a: w[0],
+                        b: w[1],
+                        color: WHITE,
+                        edge: i,
+                        first: j == 0,
+                        last: j == e.len() - 2,
+                    });
+                }
+            }
+            let segs = &mut out[first..];
+            match corners.len() {
+                // smooth contours need no channel split
+                0 => {}
+                // a teardrop: three colours along the contour, starting at the corner
+                1 => {
+                    let m = segs.len();
+                    let at = segs.iter().position(|s| s.edge == corners[0]).unwrap_or(0);
+                    if m >= 3 {
+                        for k in 0..m {
+                            segs[(at + k) % m].color = [COLORS[1], WHITE, COLORS[2]][k * 3 / m];
+                        }
+                    }
+                }
+                c => {
+                    for (k, &corner) in corners.iter().enumerate() {
+                        let mut color = COLORS[k % 3];
+                        // the last run also meets the first one
+                        if k == c - 1 && color == COLORS[0] {
+                            color = COLORS[1];
+                        }
+                        let end = corners[(k + 1) % c];
+                        let mut i = corner;
+                        loop {
+                            for s in segs.iter_mut().filter(|s| s.edge == i) {
+                                s.color = color;
+                            }
+                            i = (i + 1) % n;
+                            if i == end {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.close();
+        self.contours.push(Vec::new());
+        self.start = self.point(x, y);
+        self.cursor = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let p = self.point(x, y);
+        self.edge(vec![self.cursor, p]);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (a, c, b) = (self.cursor, self.point(x1, y1), self.point(x, y));
+        let points = (0..=QUAD_STEPS)
+            .map(|i| {
+                let t = i as f32 / QUAD_STEPS as f32;
+                a.lerp(c, t).lerp(c.lerp(b, t), t)
+            })
+            .collect();
+        self.edge(points);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (a, c1, c2, b) = (
+            self.cursor,
+            self.point(x1, y1),
+            self.point(x2, y2),
+            self.point(x, y),
+        );
+        let points = (0..=CUBIC_STEPS)
+            .map(|i| {
+                let t = i as f32 / CUBIC_STEPS as f32;
+                let (p, q, r) = (a.lerp(c1, t), c1.lerp(c2, t), c2.lerp(b, t));
+                p.lerp(q, t).lerp(q.lerp(r, t), t)
+            })
+            .collect();
+        self.edge(points);
+    }
+
+    fn close(&mut self) {
+        if self.cursor != self.start {
+            self.edge(vec![self.cursor, self.start]);
+        }
+    }
+}
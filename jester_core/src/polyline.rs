@@ -0,0 +1,228 @@
+use std::f32::consts::{FRAC_PI_8, PI};
+
+use glam::Vec2;
+
+use crate::{Color, Mesh, MeshVertex};
+
+/// How two segments of a [`Polyline`] meet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineJoin {
+    /// Outer edges extended until they meet, cut to a bevel past [`Polyline::miter_limit`].
+    #[default]
+    Miter,
+    Bevel,
+    Round,
+}
+
+/// How the ends of an open [`Polyline`] look.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineCap {
+    /// Square, flush with the end points.
+    #[default]
+    Butt,
+    /// Square, reaching half the thickness past the end points.
+    Square,
+    Round,
+}
+
+/// A line strip with thickness, expanded into a [`Mesh`] for lasers, ropes
+/// and path visualization.
+///
+/// UVs run from 0 to 1 along the whole line in u and across it in v, so a
+/// texture can fade a laser towards its edges or repeat along a rope.
+/// Segments overlap at joins, which shows where a translucent line bends.
+///
+/// ```ignore
+/// let line = Polyline::new(path, 4.0)
+///     .with_join(LineJoin::Round)
+///     .with_cap(LineCap::Round)
+///     .with_color(Color::rgb(1.0, 0.2, 0.2));
+/// ctx.draw_mesh(MeshBatch::new(white, line.to_mesh()));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polyline {
+    pub points: Vec<Vec2>,
+    /// Full width in world pixels.
+    pub thickness: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    /// Longest miter, in multiples of half the thickness, before it is beveled.
+    pub miter_limit: f32,
+    pub color: Color,
+    /// Joins the last point back to the first; closed lines have no caps.
+    pub closed: bool,
+}
+
+impl Polyline {
+    pub fn new(points: impl Into<Vec<Vec2>>, thickness: f32) -> Self {
+        Self {
+            points: points.into(),
+            thickness,
+            join: LineJoin::default(),
+            cap: LineCap::default(),
+            miter_limit: 4.0,
+            color: Color::WHITE,
+            closed: false,
+        }
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn closed(mut self) -> Self {
+        self.closed = true;
+        self
+    }
+
+    pub fn to_mesh(&self) -> Mesh {
+        let mut mesh = Mesh::default();
+        self.push_to(&mut mesh);
+        mesh
+    }
+
+    /// Adds the line's triangles to `mesh`, e.g. to draw many lines as one mesh.
+    pub fn push_to(&self, mesh: &mut Mesh) {
+        let mut points = self.points.clone();
+        points.dedup();
+        if self.closed && points.len() > 2 && points.first() == points.last() {
+            points.pop();
+        }
+        let half = self.thickness * 0.5;
+        if points.len() < 2 || half <= 0.0 {
+            return;
+        }
+        let closed = self.closed && points.len() > 2;
+        let n = points.len();
+        if !closed && self.cap == LineCap::Square {
+            let start = (points[0] - points[1]).normalize() * half;
+            let end = (points[n - 1] - points[n - 2]).normalize() * half;
+            points[0] += start;
+            points[n - 1] += end;
+        }
+
+        let segments = if closed { n } else { n - 1 };
+        let mut along = vec![0.0; segments + 1];
+        for i in 0..segments {
+            along[i + 1] = along[i] + points[i].distance(points[(i + 1) % n]);
+        }
+        let total = along[segments].max(f32::EPSILON);
+        let u = |i: usize| along[i] / total;
+        let mut out = Builder {
+            mesh,
+            color: self.color,
+        };
+
+        for i in 0..segments {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            let normal = (b - a).normalize().perp() * half;
+            out.quad([
+                (a - normal, Vec2::new(u(i), 0.0)),
+                (a + normal, Vec2::new(u(i), 1.0)),
+                (b + normal, Vec2::new(u(i + 1), 1.0)),
+                (b - normal, Vec2::new(u(i + 1), 0.0)),
+            ]);
+        }
+
+        let joins = if closed { 0..n } else { 1..n - 1 };
+        for i in joins {
+            let p = points[i];
+            let d0 = (p - points[(i + n - 1) % n]).normalize();
+            let d1 = (points[(i + 1) % n] - p).normalize();
+            let turn = d0.perp_dot(d1);
+            if turn.abs() < 1e-4 {
+                continue;
+            }
+            // the gap opens on the outside of the turn
+            let side = -turn.signum();
+            let (n0, n1) = (d0.perp() * side, d1.perp() * side);
+            let v = (side > 0.0) as u8 as f32;
+            // a closed line's first point is also its end
+            let at = Vec2::new(if i == 0 { 1.0 } else { u(i) }, 0.5);
+            let edge = Vec2::new(at.x, v);
+            match self.join {
+                LineJoin::Bevel => {
+                    out.triangle([(p, at), (p + n0 * half, edge), (p + n1 * half, edge)])
+                }
+                LineJoin::Miter => {
+                    let miter = (n0 + n1).normalize();
+                    let len = 1.0 / miter.dot(n0);
+                    out.triangle([(p, at), (p + n0 * half, edge), (p + n1 * half, edge)]);
+                    if len <= self.miter_limit {
+                        out.triangle([
+                            (p + n0 * half, edge),
+                            (p + miter * len * half, edge),
+                            (p + n1 * half, edge),
+                        ]);
+                    }
+                }
+                LineJoin::Round => out.fan(p, at, edge, n0, n0.angle_to(n1), half),
+            }
+        }
+
+        if !closed && self.cap == LineCap::Round {
+            let start = (points[0] - points[1]).normalize();
+            let end = (points[n - 1] - points[n - 2]).normalize();
+            let first = Vec2::new(0.0, 0.5);
+            out.fan(points[0], first, first, start.perp(), -PI, half);
+            let last = Vec2::new(1.0, 0.5);
+            out.fan(points[n - 1], last, last, end.perp(), -PI, half);
+        }
+    }
+}
+
+struct Builder<'a> {
+    mesh: &'a mut Mesh,
+    color: Color,
+}
+
+impl Builder<'_> {
+    fn vertex(&mut self, (pos, uv): (Vec2, Vec2)) -> u32 {
+        self.mesh
+            .vertices
+            .push(MeshVertex::new(pos, uv, self.color));
+        self.mesh.vertices.len() as u32 - 1
+    }
+
+    fn triangle(&mut self, corners: [(Vec2, Vec2); 3]) {
+        let [a, b, c] = corners.map(|c| self.vertex(c));
+        self.mesh.indices.extend([a, b, c]);
+    }
+
+    fn quad(&mut self, corners: [(Vec2, Vec2); 4]) {
+        let [a, b, c, d] = corners.map(|c| self.vertex(c));
+        self.mesh.indices.extend([a, b, c, a, c, d]);
+    }
+
+    /// Pie slice around `center` from direction `from`, turning by `angle` radians.
+    fn fan(
+        &mut self,
+        center: Vec2,
+        center_uv: Vec2,
+        rim_uv: Vec2,
+        from: Vec2,
+        angle: f32,
+        radius: f32,
+    ) {
+        let steps = (angle.abs() / FRAC_PI_8).ceil().max(1.0) as u32;
+        let c = self.vertex((center, center_uv));
+        let mut prev = self.vertex((center + from * radius, rim_uv));
+        for s in 1..=steps {
+            let dir = Vec2::from_angle(angle * s as f32 / steps as f32).rotate(from);
+            let next = self.vertex((center + dir * radius, rim_uv));
+            self.mesh.indices.extend([c, prev, next]);
+            prev = next;
+        }
+    }
+}
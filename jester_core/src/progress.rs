@@ -0,0 +1,223 @@
+use std::{any::Any, fmt};
+
+use hashbrown::{HashMap, HashSet};
+
+use crate::Events;
+
+/// What an achievement needs before it unlocks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UnlockCondition {
+    /// The counter reached at least this value.
+    Counter(String, u64),
+    Flag(String),
+    All(Vec<UnlockCondition>),
+    Any(Vec<UnlockCondition>),
+}
+
+impl UnlockCondition {
+    pub fn counter(name: impl Into<String>, at_least: u64) -> Self {
+        Self::Counter(name.into(), at_least)
+    }
+
+    pub fn flag(name: impl Into<String>) -> Self {
+        Self::Flag(name.into())
+    }
+
+    pub fn is_met(&self, state: &ProgressState) -> bool {
+        match self {
+            Self::Counter(name, at_least) => state.counter(name) >= *at_least,
+            Self::Flag(name) => state.flag(name),
+            Self::All(all) => all.iter().all(|c| c.is_met(state)),
+            Self::Any(any) => any.iter().any(|c| c.is_met(state)),
+        }
+    }
+
+    /// How close `state` is to meeting the condition, `0.0..=1.0`, e.g. for a progress bar.
+    pub fn fraction(&self, state: &ProgressState) -> f32 {
+        match self {
+            Self::Counter(_, 0) => 1.0,
+            Self::Counter(name, at_least) => {
+                (state.counter(name) as f32 / *at_least as f32).min(1.0)
+            }
+            Self::Flag(name) => state.flag(name) as u8 as f32,
+            Self::All(all) if all.is_empty() => 1.0,
+            Self::All(all) => all.iter().map(|c| c.fraction(state)).sum::<f32>() / all.len() as f32,
+            Self::Any(any) => any.iter().map(|c| c.fraction(state)).fold(0.0, f32::max),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Achievement {
+    /// Also the API name the achievement is mirrored to Steam under.
+    pub id: String,
+    pub condition: UnlockCondition,
+}
+
+/// Counters, flags and unlocked achievements, the part of [`Progress`] worth saving.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProgressState {
+    counters: HashMap<String, u64>,
+    flags: HashSet<String>,
+    unlocked: HashSet<String>,
+}
+
+impl ProgressState {
+    pub fn counter(&self, name: &str) -> u64 {
+        self.counters.get(name).copied().unwrap_or(0)
+    }
+
+    pub fn add(&mut self, counter: &str, n: u64) {
+        let c = self.counters.entry_ref(counter).or_default();
+        *c = c.saturating_add(n);
+    }
+
+    /// Raises `counter` to `value` if it is lower, e.g. for a best score.
+    pub fn record(&mut self, counter: &str, value: u64) {
+        let c = self.counters.entry_ref(counter).or_default();
+        *c = (*c).max(value);
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+
+    pub fn set_flag(&mut self, name: &str) {
+        self.flags.insert(name.to_owned());
+    }
+
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.contains(id)
+    }
+
+    pub fn unlocked(&self) -> impl Iterator<Item = &str> {
+        self.unlocked.iter().map(String::as_str)
+    }
+}
+
+/// Sent through `ctx.events` the frame after an achievement unlocks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProgressEvent {
+    Unlocked(String),
+}
+
+type Handler = Box<dyn Fn(&Events, &mut ProgressState) + Send + Sync>;
+
+/// Tracks counters and flags, fed by hand or from events, and unlocks
+/// achievements once their conditions hold.
+///
+/// Add it with `App::add_resource`; the app feeds it the events of every frame
+/// and, with the `steam` feature and a Steam session, unlocks the Steam
+/// achievement of the same name. Save [`Progress::state`] and restore it with
+/// [`Progress::with_state`].
+///
+/// ```ignore
+/// let progress = Progress::new()
+///     .on_event(|e: &EnemyKilled, s| s.add("kills", 1))
+///     .on_event(|e: &TriggerEvent, s| {
+///         if let TriggerEvent::Enter { .. } = e {
+///             s.set_flag("found_secret");
+///         }
+///     })
+///     .with_achievement("slayer", UnlockCondition::counter("kills", 100))
+///     .with_achievement("explorer", UnlockCondition::flag("found_secret"));
+/// app.add_resource(progress);
+/// ```
+#[derive(Default)]
+pub struct Progress {
+    state: ProgressState,
+    achievements: Vec<Achievement>,
+    handlers: Vec<Handler>,
+    just_unlocked: Vec<String>,
+}
+
+impl fmt::Debug for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Progress")
+            .field("state", &self.state)
+            .field("achievements", &self.achievements)
+            .field("handlers", &self.handlers.len())
+            .finish()
+    }
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_state(mut self, state: ProgressState) -> Self {
+        self.state = state;
+        self
+    }
+
+    pub fn with_achievement(mut self, id: impl Into<String>, condition: UnlockCondition) -> Self {
+        self.achievements.push(Achievement {
+            id: id.into(),
+            condition,
+        });
+        self
+    }
+
+    /// Runs `f` on every event of type `E`, to count or flag what happened.
+    pub fn on_event<E, F>(mut self, f: F) -> Self
+    where
+        E: Any + Send + Sync,
+        F: Fn(&E, &mut ProgressState) + Send + Sync + 'static,
+    {
+        self.handlers.push(Box::new(move |events, state| {
+            for e in events.read::<E>() {
+                f(e, state);
+            }
+        }));
+        self
+    }
+
+    pub fn state(&self) -> &ProgressState {
+        &self.state
+    }
+
+    /// Direct access, e.g. to count something no event reports; unlocks are checked on the next update.
+    pub fn state_mut(&mut self) -> &mut ProgressState {
+        &mut self.state
+    }
+
+    pub fn achievements(&self) -> &[Achievement] {
+        &self.achievements
+    }
+
+    /// How close achievement `id` is to unlocking, `0.0..=1.0`.
+    pub fn fraction(&self, id: &str) -> f32 {
+        if self.state.is_unlocked(id) {
+            return 1.0;
+        }
+        self.achievements
+            .iter()
+            .find(|a| a.id == id)
+            .map_or(0.0, |a| a.condition.fraction(&self.state))
+    }
+
+    /// Achievements unlocked by the last [`Progress::update`].
+    pub fn just_unlocked(&self) -> &[String] {
+        &self.just_unlocked
+    }
+
+    /// Feeds the events delivered this frame to the handlers and unlocks the
+    /// achievements whose condition now holds, sending a [`ProgressEvent`] for each.
+    pub fn update(&mut self, events: &mut Events) {
+        for handler in &self.handlers {
+            handler(events, &mut self.state);
+        }
+        self.just_unlocked.clear();
+        for a in &self.achievements {
+            if !self.state.is_unlocked(&a.id) && a.condition.is_met(&self.state) {
+                self.just_unlocked.push(a.id.clone());
+            }
+        }
+        for id in &self.just_unlocked {
+            self.state.unlocked.insert(id.clone());
+            events.send(ProgressEvent::Unlocked(id.clone()));
+        }
+    }
+}
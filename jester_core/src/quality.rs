@@ -0,0 +1,103 @@
+use crate::TextureFilter;
+
+/// Named starting points for [`QualitySettings`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum QualityPreset {
+    Low,
+    Medium,
+    #[default]
+    High,
+}
+
+impl QualityPreset {
+    /// `low`, `medium` or `high`, in any case.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "low" => Some(Self::Low),
+            "medium" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+
+    /// The preset named by the `JESTER_QUALITY` environment variable, e.g. for a weak test machine.
+    pub fn from_env() -> Option<Self> {
+        Self::parse(&std::env::var("JESTER_QUALITY").ok()?)
+    }
+}
+
+/// Rendering quality the player can trade for frame rate.
+///
+/// Add it with `App::add_resource`; the app applies texture filtering to
+/// the renderer whenever it changes, and draws cameras
+/// without their [`ScreenFilter`](crate::ScreenFilter) while
+/// `post_effects` is off. Color vision filters stay, they are not an effect.
+/// Particle systems scale their spawn counts with
+/// [`QualitySettings::particles`].
+///
+/// ```ignore
+/// let preset = QualityPreset::from_env().unwrap_or_default();
+/// app.add_resource(QualitySettings::preset(preset));
+/// // options menu
+/// if let Some(q) = ctx.resources.get_mut::<QualitySettings>() {
+///     *q = QualitySettings::preset(QualityPreset::Low);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QualitySettings {
+    /// Pixel-art games usually keep [`TextureFilter::Nearest`] whatever the preset.
+    pub texture_filter: TextureFilter,
+    /// Fraction of particles spawned, 0..1.
+    pub particle_density: f32,
+    /// Whether cameras draw with their screen filters.
+    pub post_effects: bool,
+}
+
+impl Default for QualitySettings {
+    fn default() -> Self {
+        Self::preset(QualityPreset::default())
+    }
+}
+
+impl QualitySettings {
+    pub fn preset(preset: QualityPreset) -> Self {
+        match preset {
+            QualityPreset::Low => Self {
+                texture_filter: TextureFilter::Nearest,
+                particle_density: 0.25,
+                post_effects: false,
+            },
+            QualityPreset::Medium => Self {
+                texture_filter: TextureFilter::Linear,
+                particle_density: 0.5,
+                post_effects: true,
+            },
+            QualityPreset::High => Self {
+                texture_filter: TextureFilter::Linear,
+                particle_density: 1.0,
+                post_effects: true,
+            },
+        }
+    }
+
+    /// The preset these settings match, `None` once a field was changed on its own.
+    pub fn matching_preset(&self) -> Option<QualityPreset> {
+        [
+            QualityPreset::Low,
+            QualityPreset::Medium,
+            QualityPreset::High,
+        ]
+        .into_iter()
+        .find(|p| Self::preset(*p) == *self)
+    }
+
+    /// How many of `count` particles to spawn, at least one of a non-empty burst.
+    pub fn particles(&self, count: usize) -> usize {
+        if count == 0 {
+            return 0;
+        }
+        ((count as f32 * self.particle_density.clamp(0.0, 1.0)).round() as usize).max(1)
+    }
+}
@@ -1,16 +1,265 @@
 use crate::{
-    sprite::{SpriteBatch, TextureId},
-    Camera,
+    decode::{rgba8_from, DecodedImage, TextureFormat},
+    import::{open_image, ImportCache},
+    loader::{decode, AssetState, AssetStates, Decoded, Loader},
+    residency::{Residency, ResidencyStats, Streamed, PLACEHOLDER_SIZE, RELOADS_PER_FRAME},
+    sprite::{normalize_path, BlendMode, MaterialId, SpriteBatch, TextureId},
+    tiling::TiledTexture,
+    ui::PanelInstance,
+    AssetError, Camera, Color, DisplayCalibration, Error, MeshBatch, MeshVertex,
 };
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 use image::ImageResult;
-use tracing::info;
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tracing::warn;
 use winit::window::Window;
 
 pub mod constants {
     pub const MAX_SPRITES: usize = 10000;
     pub const MAX_TEXTURES: usize = 256;
+    pub const MAX_PANELS: usize = 1024;
     pub const VERTEX_COUNT: usize = 4;
+    /// Mesh vertices and indices per frame, across all meshes and cameras.
+    pub const MAX_MESH_VERTICES: usize = 65536;
+    pub const MAX_MESH_INDICES: usize = 3 * MAX_MESH_VERTICES;
+}
+
+/// What to do about a texture that failed to load, see [`Renderer::set_retry_policy`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Retry {
+    After(Duration),
+    GiveUp,
+}
+
+impl Retry {
+    /// Retries reads that failed, not missing or broken files, three times:
+    /// after half a second, one and two seconds.
+    pub fn default_policy(err: &AssetError, attempt: u32) -> Self {
+        match err {
+            AssetError::ReadFailed { .. } if attempt <= 3 => {
+                Self::After(Duration::from_millis(500 << (attempt - 1)))
+            }
+            _ => Self::GiveUp,
+        }
+    }
+}
+
+type RetryPolicy = Box<dyn FnMut(&AssetError, u32) -> Retry>;
+
+struct PendingLoad {
+    tex: TextureId,
+    path: PathBuf,
+    attempt: u32,
+    at: Instant,
+}
+
+/// How soon a prefetched texture loads, see [`Renderer::prefetch_texture`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LoadPriority {
+    /// Whenever there is time, e.g. art of the level after next.
+    Low,
+    #[default]
+    Normal,
+    /// Needed soon, e.g. the boss behind the next door.
+    High,
+}
+
+/// Time spent uploading textures decoded in the background after each
+/// frame, at least one uploads.
+const UPLOAD_BUDGET: Duration = Duration::from_millis(2);
+
+/// How textures are sampled between texels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextureFilter {
+    /// Blocky, for pixel art.
+    #[default]
+    Nearest,
+    /// Smooth, for scaled or rotated high-resolution art.
+    Linear,
+}
+
+/// How finished frames reach the display, see [`Backend::set_present_mode`].
+///
+/// A mode the display does not support falls back to the other one
+/// without vsync, then to [`PresentMode::Fifo`], which every display has.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PresentMode {
+    /// Vsync: waits for the display's refresh, so frames never tear but
+    /// the frame rate is capped at the refresh rate.
+    Fifo,
+    /// No tearing and no cap: only the newest frame is shown at the
+    /// refresh, the others are dropped.
+    #[default]
+    Mailbox,
+    /// Shown at once, lowest latency but frames may tear.
+    Immediate,
+}
+
+impl PresentMode {
+    /// Whether frames wait for the display's refresh.
+    pub fn is_vsync(self) -> bool {
+        self == Self::Fifo
+    }
+}
+
+/// What happens to uvs outside 0..1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AddressMode {
+    /// The edge texels stretch on.
+    #[default]
+    ClampToEdge,
+    /// The texture tiles.
+    Repeat,
+    /// The texture tiles, every other copy mirrored.
+    MirroredRepeat,
+}
+
+/// How one texture is sampled, see [`Renderer::set_sampler`].
+///
+/// ```ignore
+/// // smooth backdrop in an otherwise pixel-art game
+/// let sky = ctx.load_asset_with("assets/sky.png", SamplerDesc::linear());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SamplerDesc {
+    /// `None` follows [`Renderer::set_texture_filter`], as textures without a desc do.
+    pub filter: Option<TextureFilter>,
+    pub address_mode: AddressMode,
+}
+
+impl SamplerDesc {
+    pub fn nearest() -> Self {
+        Self {
+            filter: Some(TextureFilter::Nearest),
+            ..Self::default()
+        }
+    }
+
+    pub fn linear() -> Self {
+        Self {
+            filter: Some(TextureFilter::Linear),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_address_mode(mut self, mode: AddressMode) -> Self {
+        self.address_mode = mode;
+        self
+    }
+}
+
+/// Which GPU/driver the backend ended up on, for logs and bug reports.
+#[derive(Debug, Clone, Default)]
+pub struct AdapterInfo {
+    pub backend: &'static str,
+    pub name: String,
+    pub driver_version: u32,
+    pub api_version: String,
+}
+
+/// RGBA-8 copy of a presented frame, rows top to bottom.
+#[derive(Debug, Clone)]
+pub struct Screenshot {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Screenshot {
+    pub fn save_png<P: AsRef<std::path::Path>>(&self, path: P) -> ImageResult<()> {
+        image::save_buffer(
+            path,
+            &self.pixels,
+            self.width,
+            self.height,
+            image::ColorType::Rgba8,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PresentTimingSource {
+    /// Timestamps taken on the CPU around present calls.
+    #[default]
+    CpuEstimate,
+    /// Actual scan-out times reported by the display (e.g. `VK_GOOGLE_display_timing`).
+    DisplayTiming,
+}
+
+/// Presentation pacing over a recent window of frames.
+///
+/// Comparing this with CPU frame times tells whether a stutter came from the
+/// game loop or from presentation.
+#[derive(Debug, Clone, Default)]
+pub struct PresentStats {
+    pub source: PresentTimingSource,
+    pub refresh_interval: Option<std::time::Duration>,
+    pub last_interval: std::time::Duration,
+    pub average_interval: std::time::Duration,
+    pub max_interval: std::time::Duration,
+    /// Refresh cycles that passed without a new image, only counted when the refresh rate is known.
+    pub missed_vsyncs: u64,
+    pub presented: u64,
+}
+
+/// Draw work and GPU state changes of the last finished frame.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BatchStats {
+    pub draw_calls: u32,
+    pub instances: u32,
+    /// Switches between pipelines (blend modes, sprites vs panels).
+    pub pipeline_changes: u32,
+    pub texture_changes: u32,
+}
+
+/// GPU memory allocated by the backend, in bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    pub textures: u64,
+    /// Offscreen color and depth targets, these follow the window size.
+    pub render_targets: u64,
+    /// Vertex, instance and other buffers.
+    pub buffers: u64,
+}
+
+impl MemoryStats {
+    pub fn total(&self) -> u64 {
+        self.textures + self.render_targets + self.buffers
+    }
+}
+
+/// What the renderer reports once per frame; insert it as a resource to have the App fill it in.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderStats {
+    pub batches: BatchStats,
+    pub memory: MemoryStats,
+    /// Soft limit on [`MemoryStats::total`], see `App::set_memory_budget`.
+    pub memory_budget: Option<u64>,
+}
+
+impl RenderStats {
+    pub fn over_budget(&self) -> bool {
+        self.memory_budget.is_some_and(|b| self.memory.total() > b)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BoundState {
+    /// Blend mode, material, texture and palette slot.
+    Sprites(BlendMode, MaterialId, usize, Option<usize>),
+    Panels,
+    /// Blend mode and texture slot.
+    Mesh(BlendMode, usize),
+    /// Texture slot.
+    Lines(usize),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -23,36 +272,282 @@ pub struct Renderer<B: Backend> {
     backend: B,
     metadata: Vec<Option<TextureMeta>>,
     lut: HashMap<TextureId, usize>,
+    stats: BatchStats,
+    frame_stats: BatchStats,
+    bound: Option<BoundState>,
+    residency: Option<Residency>,
+    tiled: HashMap<TextureId, TiledTexture>,
+    retry: RetryPolicy,
+    retries: Vec<PendingLoad>,
+    asset_errors: Vec<AssetError>,
+    /// Normalized file each texture was loaded from.
+    paths: HashMap<TextureId, PathBuf>,
+    /// Texture first loaded with each decoded content hash.
+    by_content: HashMap<u64, TextureId>,
+    /// Textures sharing the slot of an identical one loaded before, and that one.
+    aliases: HashMap<TextureId, TextureId>,
+    /// Textures created with [`Renderer::create_render_target`].
+    targets: HashSet<TextureId>,
+    /// The render target drawn into, `None` for the frame.
+    target: Option<TextureId>,
+    /// Set with [`Renderer::set_sampler`], kept for textures loaded later.
+    samplers: HashMap<TextureId, SamplerDesc>,
+    /// Textures to load in the background, in the order they were asked for.
+    prefetch: Vec<(TextureId, PathBuf, LoadPriority)>,
+    loader: Option<Loader>,
+    /// Textures sent to the loader and not uploaded yet.
+    loading: HashSet<TextureId>,
+    assets: AssetStates,
+    import_cache: Option<ImportCache>,
 }
 
 impl<B: Backend> Renderer<B> {
     pub fn new(app_name: &str, window: &Window) -> Result<Self, B::Error> {
         assert!(!app_name.is_empty());
-        let backend = B::init(app_name, window)?;
-        Ok(Self {
+        Ok(Self::from_backend(B::init(app_name, window)?))
+    }
+
+    /// Wraps a backend created without a window, e.g. a headless one for tests.
+    pub fn from_backend(backend: B) -> Self {
+        Self {
             backend,
             metadata: Vec::new(),
             lut: HashMap::new(),
-        })
+            stats: BatchStats::default(),
+            frame_stats: BatchStats::default(),
+            bound: None,
+            residency: None,
+            tiled: HashMap::new(),
+            retry: Box::new(Retry::default_policy),
+            retries: Vec::new(),
+            asset_errors: Vec::new(),
+            paths: HashMap::new(),
+            by_content: HashMap::new(),
+            aliases: HashMap::new(),
+            targets: HashSet::new(),
+            target: None,
+            samplers: HashMap::new(),
+            prefetch: Vec::new(),
+            loader: None,
+            loading: HashSet::new(),
+            assets: AssetStates::default(),
+            import_cache: None,
+        }
     }
 
     pub fn begin_frame(&mut self) {
+        self.stats = BatchStats::default();
+        self.bound = None;
         self.backend.begin_frame()
     }
     pub fn end_frame(&mut self) {
-        self.backend.end_frame()
+        self.set_render_target(None);
+        self.frame_stats = self.stats;
+        self.backend.end_frame();
+        self.update_residency();
+        self.retry_loads();
+        self.load_background();
+    }
+    pub fn batch_stats(&self) -> BatchStats {
+        self.frame_stats
+    }
+    fn track(&mut self, state: BoundState, instances: usize) {
+        let prev = self.bound.replace(state);
+        match (prev, state) {
+            (Some(BoundState::Sprites(pb, pm, pt, pp)), BoundState::Sprites(b, m, t, p)) => {
+                self.stats.pipeline_changes += (pb != b || pm != m) as u32;
+                self.stats.texture_changes += (pt != t || pp != p) as u32;
+            }
+            (Some(BoundState::Panels), BoundState::Panels) => {}
+            (Some(BoundState::Mesh(pb, pt)), BoundState::Mesh(b, t)) => {
+                self.stats.pipeline_changes += (pb != b) as u32;
+                self.stats.texture_changes += (pt != t) as u32;
+            }
+            (Some(BoundState::Lines(pt)), BoundState::Lines(t)) => {
+                self.stats.texture_changes += (pt != t) as u32;
+            }
+            (_, BoundState::Sprites(..) | BoundState::Mesh(..) | BoundState::Lines(..)) => {
+                self.stats.pipeline_changes += 1;
+                self.stats.texture_changes += 1;
+            }
+            (_, BoundState::Panels) => self.stats.pipeline_changes += 1,
+        }
+        self.stats.draw_calls += 1;
+        self.stats.instances += instances as u32;
     }
     pub fn bind_camera(&mut self, camera: &Camera) {
         self.backend.bind_camera(camera)
     }
+    /// Seconds since start as seen by shaders, e.g. for [`Sprite::uv_scroll`](crate::Sprite::uv_scroll).
+    pub fn set_time(&mut self, seconds: f32) {
+        self.backend.set_time(seconds)
+    }
+    /// Gamma and brightness applied to the whole window from the next frame on.
+    pub fn set_calibration(&mut self, calibration: DisplayCalibration) {
+        self.backend.set_calibration(calibration)
+    }
+    /// Colour the whole window is multiplied by from the next frame on.
+    pub fn set_tint(&mut self, tint: Color) {
+        self.backend.set_tint(tint)
+    }
+    /// Caps presentation at the display's refresh rate when on, which is off by default.
+    pub fn set_vsync(&mut self, vsync: bool) {
+        self.backend.set_vsync(vsync)
+    }
+    /// [`PresentMode::Mailbox`] until changed.
+    pub fn set_present_mode(&mut self, mode: PresentMode) {
+        self.backend.set_present_mode(mode)
+    }
+    /// Filter of every texture without its own, see [`Renderer::set_sampler`].
+    pub fn set_texture_filter(&mut self, filter: TextureFilter) {
+        self.backend.set_texture_filter(filter)
+    }
+
+    /// Samples `tex` with `desc`, from now on if it is loaded and otherwise
+    /// once it is.
+    ///
+    /// Set it before loading the file: a texture already sharing its slot
+    /// with an identical file changes that file's sampling too.
+    pub fn set_sampler(&mut self, tex: TextureId, desc: SamplerDesc) {
+        self.samplers.insert(tex, desc);
+        self.apply_sampler(tex);
+    }
+
+    /// Sampling of `tex` as set with [`Renderer::set_sampler`].
+    pub fn sampler(&self, tex: TextureId) -> SamplerDesc {
+        self.samplers.get(&tex).copied().unwrap_or_default()
+    }
+
+    /// Gives the slots of `tex` its sampler.
+    fn apply_sampler(&mut self, tex: TextureId) {
+        let Some(&desc) = self.samplers.get(&tex) else {
+            return;
+        };
+        let slots: Vec<usize> = match self.tiled.get(&tex) {
+            Some(t) => t
+                .tiles
+                .iter()
+                .filter_map(|t| self.lut.get(t))
+                .copied()
+                .collect(),
+            None => self.lut.get(&tex).copied().into_iter().collect(),
+        };
+        for slot in slots {
+            self.backend.set_sampler(slot, desc);
+        }
+    }
     pub fn handle_resize(&mut self, size: winit::dpi::PhysicalSize<u32>) {
         self.backend.handle_resize(size)
     }
     pub fn draw_sprites(&mut self, batch: &SpriteBatch) {
+        if let Some(tiled) = self.tiled.get(&batch.tex) {
+            for part in tiled.split(batch) {
+                self.draw_sprites(&part);
+            }
+            return;
+        }
         let Some(idx) = self.lut.get(&batch.tex).copied() else {
             return;
         };
-        self.backend.draw_sprites(idx, batch)
+        if batch.instances.is_empty() {
+            return;
+        }
+        // an unknown palette draws the raw index texture rather than nothing
+        let palette = batch.palette.and_then(|p| self.lut.get(&p).copied());
+        let material = match (batch.effective_material(), palette) {
+            (MaterialId::PALETTE, None) => MaterialId::DEFAULT,
+            (m, _) => m,
+        };
+        if let Some(res) = &mut self.residency {
+            res.touch(batch.tex);
+            if let Some(p) = batch.palette {
+                res.touch(p);
+            }
+        }
+        self.track(
+            BoundState::Sprites(batch.blend, material, idx, palette),
+            batch.instances.len(),
+        );
+        match palette {
+            Some(p) => self.backend.draw_sprites_paletted(idx, p, batch),
+            None => self.backend.draw_sprites(idx, batch),
+        }
+    }
+
+    /// Draws `batch` with the bound camera; counts as one instance in [`BatchStats`].
+    ///
+    /// Textures too big for the backend, which sprites draw in tiles, draw
+    /// nothing, as do meshes with an index past their last vertex.
+    pub fn draw_mesh(&mut self, batch: &MeshBatch) {
+        let mesh = &batch.mesh;
+        if mesh.is_empty()
+            || mesh
+                .indices
+                .iter()
+                .any(|&i| i as usize >= mesh.vertices.len())
+        {
+            return;
+        }
+        let Some(idx) = self.lut.get(&batch.tex).copied() else {
+            return;
+        };
+        if let Some(res) = &mut self.residency {
+            res.touch(batch.tex);
+        }
+        self.track(BoundState::Mesh(batch.blend, idx), 1);
+        self.backend.draw_mesh(idx, batch);
+    }
+
+    /// Draws a line list, every two vertices one segment one pixel wide,
+    /// sampling `tex` like a mesh; [`DebugDraw`](crate::DebugDraw) uses a
+    /// white texture. Counts as one instance in [`BatchStats`].
+    pub fn draw_lines(&mut self, tex: TextureId, vertices: &[MeshVertex]) {
+        let vertices = &vertices[..vertices.len() / 2 * 2];
+        if vertices.is_empty() {
+            return;
+        }
+        let Some(idx) = self.lut.get(&tex).copied() else {
+            return;
+        };
+        self.track(BoundState::Lines(idx), 1);
+        self.backend.draw_lines(idx, vertices);
+    }
+
+    pub fn draw_panels(&mut self, panels: &[PanelInstance]) {
+        if panels.is_empty() {
+            return;
+        }
+        self.track(BoundState::Panels, panels.len());
+        self.backend.draw_panels(panels)
+    }
+
+    pub fn adapter_info(&self) -> AdapterInfo {
+        self.backend.adapter_info()
+    }
+    pub fn supports_depth(&self) -> bool {
+        self.backend.supports_depth()
+    }
+    pub fn memory_stats(&self) -> MemoryStats {
+        self.backend.memory_stats()
+    }
+    /// Builds the pipelines for every pair of `materials` and `blend_modes` ahead of use,
+    /// e.g. behind a loading screen, so their first draw does not stall a frame.
+    pub fn precompile(&mut self, materials: &[MaterialId], blend_modes: &[BlendMode]) {
+        self.backend.precompile(materials, blend_modes)
+    }
+    /// Adds a sprite fragment shader, see [`Backend::create_material`].
+    pub fn create_material(&mut self, spirv: &[u8]) -> Result<MaterialId, B::Error> {
+        self.backend.create_material(spirv)
+    }
+    /// Asks the backend to copy the next presented frame; fetch it with [`Renderer::take_screenshot`].
+    pub fn request_screenshot(&mut self) {
+        self.backend.request_screenshot()
+    }
+    pub fn take_screenshot(&mut self) -> Option<Screenshot> {
+        self.backend.take_screenshot()
+    }
+    pub fn present_stats(&self) -> Option<PresentStats> {
+        self.backend.present_stats()
     }
 
     pub fn backend(&self) -> &B {
@@ -62,21 +557,623 @@ impl<B: Backend> Renderer<B> {
     pub fn backend_mut(&mut self) -> &mut B {
         &mut self.backend
     }
+    /// Number of textures created on the backend so far.
+    pub fn texture_count(&self) -> usize {
+        self.metadata.iter().flatten().count()
+    }
     pub fn texture_meta(&self, tex: TextureId) -> Option<TextureMeta> {
+        if let Some(tiled) = self.tiled.get(&tex) {
+            return Some(tiled.meta);
+        }
         let slot = *self.lut.get(&tex)?;
         self.metadata.get(slot).and_then(|m| *m)
     }
 
-    pub fn load_texture_sync<P>(&mut self, tex_id: TextureId, path: P) -> ImageResult<()>
+    /// Loads an image file; with a [texture budget](Renderer::set_texture_budget) it is also streamed.
+    ///
+    /// A failed load is retried after later frames as the
+    /// [retry policy](Renderer::set_retry_policy) decides; sprites of the
+    /// texture draw nothing until it succeeds.
+    ///
+    /// Loading a file again under its id does nothing, while another file
+    /// under the same id is refused with [`AssetError::IdCollision`]. Files
+    /// decoding to exactly the texels of a texture loaded before share its
+    /// slot, unless textures are streamed.
+    pub fn load_texture_sync<P>(&mut self, tex_id: TextureId, path: P) -> Result<(), AssetError>
     where
-        P: AsRef<std::path::Path>,
+        P: AsRef<Path>,
     {
-        let img = image::open(path)?.to_rgba8();
-        let (w, h) = img.dimensions();
+        let path = path.as_ref();
+        self.prefetch.retain(|(t, _, _)| *t != tex_id);
+        // the background result is dropped when it comes
+        self.loading.remove(&tex_id);
+        let loaded = match self.check_path(tex_id, path) {
+            Ok(true) => return Ok(()),
+            Ok(false) => {
+                self.retries.retain(|p| p.tex != tex_id);
+                self.try_load(tex_id, path)
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = &loaded {
+            self.schedule_retry(e, 1);
+        }
+        loaded
+    }
+
+    /// Whether `tex_id` is loaded from `path` already, failing when it is
+    /// loaded from another file.
+    fn check_path(&self, tex_id: TextureId, path: &Path) -> Result<bool, AssetError> {
+        let Some(loaded) = self.paths.get(&tex_id) else {
+            return Ok(false);
+        };
+        if *loaded != normalize_path(path) {
+            return Err(AssetError::IdCollision {
+                path: path.to_path_buf(),
+                tex: tex_id,
+                loaded: loaded.clone(),
+            });
+        }
+        Ok(self.is_loaded(tex_id))
+    }
+
+    fn is_loaded(&self, tex: TextureId) -> bool {
+        self.lut.contains_key(&tex) || self.tiled.contains_key(&tex)
+    }
+
+    /// Where `tex` is, if it was asked for by file, see [`AssetStates`].
+    pub fn asset_state(&self, tex: TextureId) -> Option<AssetState> {
+        self.assets.get(tex)
+    }
+
+    pub fn asset_states(&self) -> &AssetStates {
+        &self.assets
+    }
+
+    /// Decodes an image file held in memory, e.g. read from an archive or
+    /// downloaded, like [`Renderer::load_texture_sync`] without the file.
+    ///
+    /// Loading bytes again under an id already loaded does nothing. Such
+    /// textures are never streamed out and failures are not retried.
+    pub fn load_texture_from_bytes(
+        &mut self,
+        tex_id: TextureId,
+        bytes: &[u8],
+    ) -> Result<(), Error> {
+        if self.is_loaded(tex_id) {
+            return Ok(());
+        }
+        let loaded = DecodedImage::from_bytes(bytes)
+            .map_err(Error::from)
+            .and_then(|img| {
+                self.upload_texture_with_format(
+                    tex_id, img.width, img.height, img.format, &img.data,
+                )
+                .map_err(|e| Error::Backend(e.to_string()))
+            });
+        let state = match loaded {
+            Ok(()) => AssetState::Ready,
+            Err(_) => AssetState::Failed,
+        };
+        self.assets.set(tex_id, state);
+        loaded
+    }
+
+    /// Frees `tex` on the backend, so its slot is reused by later loads,
+    /// and forgets its file, sampler and queued loads; returns whether it
+    /// existed. Call between frames, e.g. when leaving a level.
+    ///
+    /// A texture sharing its slot with identical files only gives up its id,
+    /// the slot is freed with the last of them. Sprites still using the id
+    /// draw nothing until it is loaded again.
+    pub fn unload_texture(&mut self, tex: TextureId) -> bool {
+        self.prefetch.retain(|(t, _, _)| *t != tex);
+        self.loading.remove(&tex);
+        self.retries.retain(|p| p.tex != tex);
+        self.assets.remove(tex);
+        self.samplers.remove(&tex);
+        self.paths.remove(&tex);
+        if let Some(tiled) = self.tiled.remove(&tex) {
+            for tile in tiled.tiles {
+                self.unload_texture(tile);
+            }
+            return true;
+        }
+        let Some(slot) = self.lut.remove(&tex) else {
+            return false;
+        };
+        if self.aliases.remove(&tex).is_some() || self.hand_over(tex).is_some() {
+            return true;
+        }
+        self.by_content.retain(|_, t| *t != tex);
+        if let Some(res) = &mut self.residency {
+            res.textures.remove(&tex);
+            res.wanted.retain(|t| *t != tex);
+        }
+        if self.target == Some(tex) {
+            self.set_render_target(None);
+        }
+        self.targets.remove(&tex);
+        self.metadata[slot] = None;
+        self.backend.destroy_texture(slot);
+        true
+    }
+
+    /// Makes another file decoding to the same texels as `tex` the owner of
+    /// its slot, if there is one, for `tex` to give the slot up.
+    fn hand_over(&mut self, tex: TextureId) -> Option<TextureId> {
+        let heir = self
+            .aliases
+            .iter()
+            .filter(|(_, first)| **first == tex)
+            .map(|(t, _)| *t)
+            .min_by_key(|t| t.0)?;
+        self.aliases.remove(&heir);
+        for first in self.aliases.values_mut().filter(|t| **t == tex) {
+            *first = heir;
+        }
+        for first in self.by_content.values_mut().filter(|t| **t == tex) {
+            *first = heir;
+        }
+        if let Some(res) = &mut self.residency
+            && let Some(streamed) = res.textures.remove(&tex)
+        {
+            res.textures.insert(heir, streamed);
+            for t in res.wanted.iter_mut().filter(|t| **t == tex) {
+                *t = heir;
+            }
+        }
+        Some(heir)
+    }
+
+    /// Decodes image files through `cache` from now on, see [`ImportCache`].
+    pub fn set_import_cache(&mut self, cache: Option<ImportCache>) {
+        self.import_cache = cache;
+    }
+
+    /// Decides whether and when failed loads are tried again, given the error
+    /// and how many attempts failed so far; [`Retry::default_policy`] until set.
+    ///
+    /// ```ignore
+    /// // assets on a flaky network share
+    /// renderer.set_retry_policy(|err, attempt| match err {
+    ///     AssetError::NotFound { .. } | AssetError::ReadFailed { .. } if attempt <= 10 => {
+    ///         Retry::After(Duration::from_secs(1))
+    ///     }
+    ///     _ => Retry::GiveUp,
+    /// });
+    /// ```
+    pub fn set_retry_policy<F>(&mut self, policy: F)
+    where
+        F: FnMut(&AssetError, u32) -> Retry + 'static,
+    {
+        self.retry = Box::new(policy);
+    }
+
+    /// Whether `tex` failed to load and is waiting to be tried again.
+    pub fn is_retrying(&self, tex: TextureId) -> bool {
+        self.retries.iter().any(|p| p.tex == tex)
+    }
+
+    /// Queues an image file to load in the background, so it is there
+    /// before it is drawn without stalling frames; higher priorities first,
+    /// then in the order asked for.
+    ///
+    /// Worker threads decode the file, and it uploads after a later frame
+    /// ends, a few textures each frame; [`Renderer::asset_state`] tells when
+    /// it is ready. Loading it with [`Renderer::load_texture_sync`]
+    /// meanwhile loads it at once; asking again only raises its priority.
+    pub fn prefetch_texture<P>(&mut self, tex_id: TextureId, path: P, priority: LoadPriority)
+    where
+        P: AsRef<Path>,
+    {
+        if self.is_loaded(tex_id) || self.loading.contains(&tex_id) {
+            return;
+        }
+        self.assets.set(tex_id, AssetState::Loading);
+        match self.prefetch.iter_mut().find(|(t, _, _)| *t == tex_id) {
+            Some((_, _, p)) => *p = (*p).max(priority),
+            None => self
+                .prefetch
+                .push((tex_id, path.as_ref().to_path_buf(), priority)),
+        }
+    }
+
+    /// Whether `tex` is queued by [`Renderer::prefetch_texture`] and not loaded yet.
+    pub fn is_prefetching(&self, tex: TextureId) -> bool {
+        self.loading.contains(&tex) || self.prefetch.iter().any(|(t, _, _)| *t == tex)
+    }
+
+    /// Textures queued by [`Renderer::prefetch_texture`] and not loaded yet.
+    pub fn prefetch_pending(&self) -> usize {
+        self.prefetch.len() + self.loading.len()
+    }
+
+    /// Hands queued files to the loader and uploads what it decoded.
+    fn load_background(&mut self) {
+        if self.prefetch.is_empty() && self.loading.is_empty() {
+            return;
+        }
+        let loader = self.loader.get_or_insert_with(Loader::new);
+        while self.loading.len() < loader.capacity() {
+            let next = self
+                .prefetch
+                .iter()
+                .enumerate()
+                .max_by_key(|(i, (_, _, p))| (*p, std::cmp::Reverse(*i)))
+                .map(|(i, _)| i);
+            let Some(i) = next else {
+                break;
+            };
+            let (tex, path, _) = self.prefetch.remove(i);
+            self.loading.insert(tex);
+            loader.send(tex, path, self.import_cache.clone());
+        }
+        let start = Instant::now();
+        while start.elapsed() < UPLOAD_BUDGET
+            && let Some(decoded) = self.loader.as_ref().and_then(Loader::try_recv)
+        {
+            self.finish_load(decoded);
+        }
+    }
+
+    fn finish_load(&mut self, Decoded { tex, path, image }: Decoded) {
+        // loaded meanwhile, or unloaded
+        if !self.loading.remove(&tex) {
+            return;
+        }
+        let loaded = match self.check_path(tex, &path) {
+            Ok(true) => return,
+            Ok(false) => image.and_then(|img| self.add_decoded(tex, &path, img)),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = loaded {
+            self.schedule_retry(&e, 1);
+            self.asset_errors.push(e);
+        }
+    }
+
+    /// Errors of the retries and prefetches since the last call, oldest first.
+    pub fn take_asset_errors(&mut self) -> Vec<AssetError> {
+        std::mem::take(&mut self.asset_errors)
+    }
+
+    fn try_load(&mut self, tex_id: TextureId, path: &Path) -> Result<(), AssetError> {
+        let img = decode(self.import_cache.as_ref(), tex_id, path)?;
+        self.add_decoded(tex_id, path, img)
+    }
+
+    /// Uploads the decoded image of the file at `path`, or shares the slot
+    /// of an identical one.
+    fn add_decoded(
+        &mut self,
+        tex_id: TextureId,
+        path: &Path,
+        img: DecodedImage,
+    ) -> Result<(), AssetError> {
+        let mut hasher = DefaultHasher::new();
+        (img.width, img.height, img.format, &img.data).hash(&mut hasher);
+        let content = hasher.finish();
+        // a shared slot cannot be evicted or sampled differently for one of its textures alone
+        if self.residency.is_none()
+            && let Some(&first) = self.by_content.get(&content)
+            && first != tex_id
+            && self.sampler(first) == self.sampler(tex_id)
+            && let Some(&slot) = self.lut.get(&first)
+        {
+            self.lut.insert(tex_id, slot);
+            self.aliases.insert(tex_id, first);
+            self.paths.insert(tex_id, normalize_path(path));
+            self.assets.set(tex_id, AssetState::Ready);
+            return Ok(());
+        }
+        let (w, h) = (img.width, img.height);
+        self.upload_texture_with_format(tex_id, w, h, img.format, &img.data)
+            .map_err(|e| AssetError::GpuUploadFailed {
+                path: path.to_path_buf(),
+                tex: tex_id,
+                reason: e.to_string(),
+            })?;
+        // textures as small as their placeholder gain nothing from eviction
+        if let Some(res) = &mut self.residency
+            && w.max(h) > PLACEHOLDER_SIZE
+            && let Some(&slot) = self.lut.get(&tex_id)
+        {
+            let rgba = img.to_rgba8();
+            let streamed = Streamed::new(path.to_path_buf(), slot, &rgba, res.frame);
+            res.textures.insert(tex_id, streamed);
+        }
+        self.by_content.insert(content, tex_id);
+        self.paths.insert(tex_id, normalize_path(path));
+        self.assets.set(tex_id, AssetState::Ready);
+        Ok(())
+    }
+
+    fn schedule_retry(&mut self, err: &AssetError, attempt: u32) {
+        let tex = err.tex();
+        match (self.retry)(err, attempt) {
+            Retry::After(delay) => {
+                self.retries.push(PendingLoad {
+                    tex,
+                    path: err.path().to_path_buf(),
+                    attempt,
+                    at: Instant::now() + delay,
+                });
+                self.assets.set(tex, AssetState::Loading);
+            }
+            // a file colliding with a loaded one fails, the loaded one stays ready
+            Retry::GiveUp if !self.is_loaded(tex) => self.assets.set(tex, AssetState::Failed),
+            Retry::GiveUp => {}
+        }
+    }
+
+    fn retry_loads(&mut self) {
+        if self.retries.is_empty() {
+            return;
+        }
+        let now = Instant::now();
+        let (due, waiting) = std::mem::take(&mut self.retries)
+            .into_iter()
+            .partition::<Vec<_>, _>(|p| p.at <= now);
+        self.retries = waiting;
+        for p in due {
+            if let Err(e) = self.try_load(p.tex, &p.path) {
+                self.schedule_retry(&e, p.attempt + 1);
+                self.asset_errors.push(e);
+            }
+        }
+    }
+
+    /// Caps the bytes of textures loaded from files from now on.
+    ///
+    /// Past the budget, the textures drawn longest ago are swapped for a tiny
+    /// low resolution copy after the frame ends. Drawing one again reloads it
+    /// from its file over the next frames, the copy stays visible meanwhile.
+    /// Textures created from pixels in memory are never evicted. `None` turns
+    /// streaming off and reloads everything evicted so far.
+    pub fn set_texture_budget(&mut self, bytes: Option<u64>) {
+        let Some(bytes) = bytes else {
+            if let Some(res) = &mut self.residency {
+                res.wanted = res
+                    .textures
+                    .iter()
+                    .filter(|(_, s)| !s.resident)
+                    .map(|(&id, _)| id)
+                    .collect();
+            }
+            self.reload_textures(usize::MAX);
+            self.residency = None;
+            return;
+        };
+        match &mut self.residency {
+            Some(res) => res.budget = bytes,
+            None => self.residency = Some(Residency::new(bytes)),
+        }
+    }
+
+    pub fn residency_stats(&self) -> Option<ResidencyStats> {
+        self.residency.as_ref().map(Residency::stats)
+    }
+
+    /// Whether `tex` is drawn at full resolution rather than as its placeholder.
+    pub fn is_resident(&self, tex: TextureId) -> bool {
+        self.residency
+            .as_ref()
+            .and_then(|r| r.textures.get(&tex))
+            .is_none_or(|s| s.resident)
+    }
+
+    fn update_residency(&mut self) {
+        if self.residency.is_none() {
+            return;
+        }
+        self.reload_textures(RELOADS_PER_FRAME);
+        let Some(res) = &mut self.residency else {
+            return;
+        };
+        let mut bytes = res.bytes();
+        while bytes > res.budget {
+            let Some(tex) = res.least_recently_used() else {
+                break;
+            };
+            let s = res.textures.get_mut(&tex).expect("listed texture");
+            let (w, h) = s.placeholder.dimensions();
+            let placeholder =
+                self.backend
+                    .replace_texture(s.slot, w, h, TextureFormat::Rgba8, &s.placeholder);
+            if let Err(e) = placeholder {
+                warn!("Failed to evict {}: {e}", s.path.display());
+                res.textures.remove(&tex);
+                continue;
+            }
+            s.resident = false;
+            bytes -= s.bytes - s.placeholder.as_raw().len() as u64;
+            res.evictions += 1;
+        }
+        res.frame += 1;
+    }
+
+    /// Decodes and uploads up to `max` of the evicted textures drawn since.
+    fn reload_textures(&mut self, max: usize) {
+        let Some(res) = &mut self.residency else {
+            return;
+        };
+        let n = res.wanted.len().min(max);
+        for tex in res.wanted.drain(..n).collect::<Vec<_>>() {
+            let Some(s) = res.textures.get_mut(&tex) else {
+                continue;
+            };
+            let reloaded = open_image(self.import_cache.as_ref(), &s.path)
+                .map_err(|e| e.to_string())
+                .and_then(|img| {
+                    self.backend
+                        .replace_texture(s.slot, img.width, img.height, img.format, &img.data)
+                        .map_err(|e| e.to_string())
+                });
+            match reloaded {
+                Ok(()) => {
+                    s.resident = true;
+                    res.reloads += 1;
+                }
+                Err(e) => {
+                    // keep the placeholder for good rather than retrying every frame
+                    warn!("Failed to reload {}: {e}", s.path.display());
+                    res.textures.remove(&tex);
+                }
+            }
+        }
+    }
+
+    /// Creates a texture from raw RGBA-8 pixels, row-major starting at the top-left texel.
+    pub fn create_texture_from_rgba(
+        &mut self,
+        w: u32,
+        h: u32,
+        pixels: &[u8],
+    ) -> Result<TextureId, B::Error> {
+        let tex_id = TextureId::generated();
+        self.upload_texture(tex_id, w, h, pixels)?;
+        Ok(tex_id)
+    }
+
+    /// Creates a `w` by `h` texture that [`Renderer::set_render_target`] draws
+    /// into, e.g. for a minimap or a character portrait; transparent until then.
+    pub fn create_render_target(&mut self, w: u32, h: u32) -> Result<TextureId, B::Error> {
+        let tex_id = TextureId::generated();
+        self.add_render_target(tex_id, w, h)?;
+        Ok(tex_id)
+    }
+
+    /// Creates a render target under a caller-chosen id, see [`Renderer::create_render_target`].
+    pub fn add_render_target(&mut self, tex_id: TextureId, w: u32, h: u32) -> Result<(), B::Error> {
+        let slot = self.backend.create_render_target(w, h)?;
+        self.lut.insert(tex_id, slot);
+        if slot >= self.metadata.len() {
+            self.metadata.resize(slot + 1, None);
+        }
+        self.metadata[slot] = Some(TextureMeta { w, h });
+        self.targets.insert(tex_id);
+        Ok(())
+    }
+
+    /// Whether `tex` is a render target of this renderer.
+    pub fn is_render_target(&self, tex: TextureId) -> bool {
+        self.targets.contains(&tex)
+    }
+
+    /// Draws into the render target `target` from now on, cleared to
+    /// transparent first, or back into the window with `None`.
+    ///
+    /// Bind a camera after switching: its viewport is a part of the target.
+    /// Switch back before drawing what samples the target; the frame always
+    /// ends drawing into the window. Ids that are not render targets are ignored.
+    ///
+    /// ```ignore
+    /// r.set_render_target(Some(portrait));
+    /// r.bind_camera(&portrait_camera);
+    /// r.draw_sprites(&hero);
+    /// r.set_render_target(None);
+    /// r.bind_camera(&camera);
+    /// r.draw_sprites(&SpriteBatch::new(portrait, vec![frame]));
+    /// ```
+    pub fn set_render_target(&mut self, target: Option<TextureId>) {
+        if target.is_some_and(|t| !self.targets.contains(&t)) || target == self.target {
+            return;
+        }
+        self.target = target;
+        self.bound = None;
+        self.backend
+            .set_render_target(target.and_then(|t| self.lut.get(&t).copied()));
+    }
+
+    /// Creates a texture by evaluating `f(x, y)` for every texel.
+    pub fn create_texture_from_fn<F>(&mut self, w: u32, h: u32, f: F) -> Result<TextureId, B::Error>
+    where
+        F: FnMut(u32, u32) -> Color,
+    {
+        let pixels = rgba_from_fn(w, h, f);
+        self.create_texture_from_rgba(w, h, &pixels)
+    }
+
+    /// Uploads RGBA-8 pixels under a caller-chosen id.
+    ///
+    /// Images larger than [`Backend::max_texture_size`] are split into tiles
+    /// transparently; sprites using them are cut along the tile edges when
+    /// drawn. Scrolling uvs ([`SpriteInstance::uv_anim`](crate::SpriteInstance::uv_anim))
+    /// wrap per tile on such textures, and tiled textures are never streamed.
+    pub fn upload_texture(
+        &mut self,
+        tex_id: TextureId,
+        w: u32,
+        h: u32,
+        pixels: &[u8],
+    ) -> Result<(), B::Error> {
+        self.upload_texture_with_format(tex_id, w, h, TextureFormat::Rgba8, pixels)
+    }
+
+    /// Replaces the RGBA-8 texels of `tex_id`, uploading it if it does not exist yet.
+    ///
+    /// The texture keeps its slot, so batches already referring to it stay
+    /// valid, unless it shared the slot with identical files, which keep it.
+    /// Call between frames; the backend may wait for the GPU to finish first.
+    pub fn update_texture(
+        &mut self,
+        tex_id: TextureId,
+        w: u32,
+        h: u32,
+        pixels: &[u8],
+    ) -> Result<(), B::Error> {
+        // a slot shared with identical files stays theirs, `tex_id` gets a new one
+        if self.aliases.remove(&tex_id).is_some() || self.hand_over(tex_id).is_some() {
+            self.lut.remove(&tex_id);
+        }
+        let Some(&slot) = self.lut.get(&tex_id) else {
+            return self.upload_texture(tex_id, w, h, pixels);
+        };
+        self.backend
+            .replace_texture(slot, w, h, TextureFormat::Rgba8, pixels)?;
+        self.metadata[slot] = Some(TextureMeta { w, h });
+        Ok(())
+    }
+
+    /// Uploads texels in `format` under a caller-chosen id, see [`Renderer::upload_texture`].
+    pub fn upload_texture_with_format(
+        &mut self,
+        tex_id: TextureId,
+        w: u32,
+        h: u32,
+        format: TextureFormat,
+        pixels: &[u8],
+    ) -> Result<(), B::Error> {
+        self.aliases.remove(&tex_id);
+        self.by_content.retain(|_, t| *t != tex_id);
+        let max = self.backend.max_texture_size();
+        if w > max || h > max {
+            let cols = TiledTexture::tiles_along(w, max);
+            let rows = TiledTexture::tiles_along(h, max);
+            let mut tiles = Vec::with_capacity((cols * rows) as usize);
+            for row in 0..rows {
+                for col in 0..cols {
+                    let bpp = format.bytes_per_texel();
+                    let (tw, th, tile) = TiledTexture::cut(pixels, bpp, w, h, max, col, row);
+                    let id = TextureId::generated();
+                    self.upload_texture_with_format(id, tw, th, format, &tile)?;
+                    tiles.push(id);
+                }
+            }
+            let tiled = TiledTexture {
+                meta: TextureMeta { w, h },
+                tile_size: max,
+                cols,
+                tiles,
+            };
+            self.tiled.insert(tex_id, tiled);
+            self.apply_sampler(tex_id);
+            return Ok(());
+        }
         let slot = self
             .backend
-            .create_texture(w, h, &img)
-            .expect("Failed to create texture");
+            .create_texture_with_format(w, h, format, pixels)?;
 
         self.lut.insert(tex_id, slot);
 
@@ -84,10 +1181,24 @@ impl<B: Backend> Renderer<B> {
             self.metadata.resize(slot + 1, None);
         }
         self.metadata[slot] = Some(TextureMeta { w, h });
+        self.apply_sampler(tex_id);
         Ok(())
     }
 }
 
+pub(crate) fn rgba_from_fn<F>(w: u32, h: u32, mut f: F) -> Vec<u8>
+where
+    F: FnMut(u32, u32) -> Color,
+{
+    let mut pixels = Vec::with_capacity(w as usize * h as usize * 4);
+    for y in 0..h {
+        for x in 0..w {
+            pixels.extend_from_slice(&f(x, y).to_rgba8());
+        }
+    }
+    pixels
+}
+
 pub trait Backend: Sized {
     type Error: std::error::Error;
 
@@ -95,9 +1206,60 @@ pub trait Backend: Sized {
 
     fn begin_frame(&mut self);
     fn draw_sprites(&mut self, tex_idx: usize, batch: &SpriteBatch);
+    /// Like `draw_sprites` with colours looked up in a palette texture, see [`crate::Palette`].
+    fn draw_sprites_paletted(&mut self, tex_idx: usize, _palette_idx: usize, batch: &SpriteBatch) {
+        self.draw_sprites(tex_idx, batch)
+    }
     fn end_frame(&mut self);
+    fn draw_panels(&mut self, _panels: &[PanelInstance]) {}
+    /// Draws the triangles of a [`MeshBatch`]; backends without meshes skip them.
+    fn draw_mesh(&mut self, _tex_idx: usize, _batch: &MeshBatch) {}
+    /// Shares the mesh vertex budget, [`MAX_MESH_VERTICES`](constants::MAX_MESH_VERTICES) per frame.
+    fn draw_lines(&mut self, _tex_idx: usize, _vertices: &[MeshVertex]) {}
     fn handle_resize(&mut self, _size: winit::dpi::PhysicalSize<u32>) {}
     fn bind_camera(&mut self, camera: &Camera);
+    fn set_time(&mut self, _seconds: f32) {}
+    fn set_calibration(&mut self, _calibration: DisplayCalibration) {}
+    /// Multiplies the whole final image, e.g. by [`DayNight::tint`](crate::DayNight::tint).
+    fn set_tint(&mut self, _tint: Color) {}
+    /// How frames are presented from the next one on, rebuilding the swapchain if needed.
+    fn set_present_mode(&mut self, _mode: PresentMode) {}
+    /// [`PresentMode::Fifo`] when on, [`PresentMode::Mailbox`] otherwise.
+    fn set_vsync(&mut self, vsync: bool) {
+        self.set_present_mode(match vsync {
+            true => PresentMode::Fifo,
+            false => PresentMode::Mailbox,
+        })
+    }
+    /// Sampling of every texture, [`TextureFilter::Nearest`] until changed.
+    fn set_texture_filter(&mut self, _filter: TextureFilter) {}
+    /// Sampling of the texture in slot `tex_idx`, its filter overriding the
+    /// one of [`Backend::set_texture_filter`] unless `None`.
+    fn set_sampler(&mut self, _tex_idx: usize, _desc: SamplerDesc) {}
+    /// Whether [`SpriteBatch::cutout`] and [`SpriteInstance::depth`](crate::SpriteInstance::depth) are honored.
+    fn supports_depth(&self) -> bool {
+        false
+    }
+    /// Backends that build pipeline variants on first use create them here instead.
+    fn precompile(&mut self, _materials: &[MaterialId], _blend_modes: &[BlendMode]) {}
+    fn adapter_info(&self) -> AdapterInfo {
+        AdapterInfo::default()
+    }
+    fn request_screenshot(&mut self) {}
+    fn take_screenshot(&mut self) -> Option<Screenshot> {
+        None
+    }
+    fn present_stats(&self) -> Option<PresentStats> {
+        None
+    }
+    fn memory_stats(&self) -> MemoryStats {
+        MemoryStats::default()
+    }
+    /// Largest width or height of a single texture; the renderer tiles bigger images.
+    fn max_texture_size(&self) -> u32 {
+        // the minimum every Vulkan device supports
+        4096
+    }
 
     fn create_texture(
         &mut self,
@@ -105,4 +1267,64 @@ pub trait Backend: Sized {
         height: u32,
         pixels: &[u8],
     ) -> Result<usize, Self::Error>;
+    /// Like `create_texture` for texels in `format`; the default converts them to RGBA-8.
+    fn create_texture_with_format(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        pixels: &[u8],
+    ) -> Result<usize, Self::Error> {
+        match format {
+            TextureFormat::Rgba8 => self.create_texture(width, height, pixels),
+            _ => self.create_texture(width, height, &rgba8_from(format, width, height, pixels)),
+        }
+    }
+    /// Swaps the texels of texture slot `idx`, which may change its size and format.
+    ///
+    /// Called between frames only; the slot index stays valid throughout.
+    fn replace_texture(
+        &mut self,
+        idx: usize,
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        pixels: &[u8],
+    ) -> Result<(), Self::Error>;
+    /// Adds a sprite fragment shader from SPIR-V, used by batches whose
+    /// `material` is the returned id; ids count up after [`MaterialId::BUILTIN`].
+    ///
+    /// The shader gets the sprite vertex shader's outputs and bindings:
+    ///
+    /// ```glsl
+    /// layout(set = 0, binding = 0) uniform sampler2D u_tex;
+    /// layout(location = 0) in vec2 vLocal;            // 0..1 across the quad, or tile count when wrapping
+    /// layout(location = 1) flat in vec4 vRect;        // the instance's uv
+    /// layout(location = 2) flat in float vWrap;       // 1 for wrapped sprites
+    /// layout(location = 3) flat in vec4 vCustom;      // Sprite::custom
+    /// layout(push_constant) uniform PC { vec2 screen; vec2 camCenter; vec2 camZoom; float time; } pc;
+    /// // the texel: uv = mix(vRect.xy, vRect.zw, mix(vLocal, fract(vLocal), vWrap)), then uv.y = 1 - uv.y
+    /// ```
+    ///
+    /// A shader that fails, e.g. on bytes that are not SPIR-V, still takes
+    /// its id so later ids stay the same; batches using it draw nothing.
+    fn create_material(&mut self, spirv: &[u8]) -> Result<MaterialId, Self::Error>;
+    /// A texture slot that [`Backend::set_render_target`] can draw into,
+    /// transparent until then; the default is a plain texture drawn into never.
+    fn create_render_target(&mut self, width: u32, height: u32) -> Result<usize, Self::Error> {
+        let pixels = vec![0; width as usize * height as usize * 4];
+        self.create_texture(width, height, &pixels)
+    }
+    /// Draws into render target slot `idx` from now on, cleared to
+    /// transparent first, or back into the frame with `None`.
+    ///
+    /// Only called within a frame; cameras bound afterwards get the target's
+    /// size as their screen, and the frame's target is set back before it ends.
+    fn set_render_target(&mut self, _idx: Option<usize>) {}
+    /// Frees texture slot `idx`, which a later `create_texture`,
+    /// `create_texture_with_format` or `create_render_target` may return
+    /// again; the default keeps it, never reusing the index.
+    ///
+    /// Called between frames only.
+    fn destroy_texture(&mut self, _idx: usize) {}
 }
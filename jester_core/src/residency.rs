@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+
+use hashbrown::HashMap;
+use image::RgbaImage;
+
+use crate::TextureId;
+
+/// Longest side of the low resolution copy shown while an evicted texture reloads.
+pub(crate) const PLACEHOLDER_SIZE: u32 = 16;
+/// Evicted textures decoded again per frame, so a camera cut does not stall on all of them.
+pub(crate) const RELOADS_PER_FRAME: usize = 2;
+
+/// Textures under [`Renderer::set_texture_budget`](crate::Renderer::set_texture_budget), for debug overlays.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResidencyStats {
+    pub streamed: usize,
+    /// Streamed textures currently at full resolution.
+    pub resident: usize,
+    /// Pixel bytes of all streamed textures as they are now, placeholders included.
+    pub bytes: u64,
+    pub budget: u64,
+    pub evictions: u64,
+    pub reloads: u64,
+}
+
+pub(crate) struct Streamed {
+    pub path: PathBuf,
+    pub slot: usize,
+    /// RGBA-8 size at full resolution.
+    pub bytes: u64,
+    pub placeholder: RgbaImage,
+    pub resident: bool,
+    pub last_used: u64,
+}
+
+impl Streamed {
+    pub fn new(path: PathBuf, slot: usize, img: &RgbaImage, frame: u64) -> Self {
+        let (w, h) = img.dimensions();
+        let scale = PLACEHOLDER_SIZE as f32 / w.max(h) as f32;
+        let pw = ((w as f32 * scale).round() as u32).max(1);
+        let ph = ((h as f32 * scale).round() as u32).max(1);
+        Self {
+            path,
+            slot,
+            bytes: w as u64 * h as u64 * 4,
+            placeholder: image::imageops::thumbnail(img, pw, ph),
+            resident: true,
+            last_used: frame,
+        }
+    }
+
+    fn current_bytes(&self) -> u64 {
+        if self.resident {
+            self.bytes
+        } else {
+            self.placeholder.as_raw().len() as u64
+        }
+    }
+}
+
+/// Keeps streamed textures under a byte budget by swapping the least recently
+/// drawn ones for a placeholder, and reloads them from disk once drawn again.
+pub(crate) struct Residency {
+    pub budget: u64,
+    pub frame: u64,
+    pub textures: HashMap<TextureId, Streamed>,
+    /// Evicted textures drawn since, oldest request first.
+    pub wanted: Vec<TextureId>,
+    pub evictions: u64,
+    pub reloads: u64,
+}
+
+impl Residency {
+    pub fn new(budget: u64) -> Self {
+        Self {
+            budget,
+            frame: 0,
+            textures: HashMap::new(),
+            wanted: Vec::new(),
+            evictions: 0,
+            reloads: 0,
+        }
+    }
+
+    /// Marks `tex` as drawn this frame, queueing a reload if it was evicted.
+    pub fn touch(&mut self, tex: TextureId) {
+        let Some(s) = self.textures.get_mut(&tex) else {
+            return;
+        };
+        s.last_used = self.frame;
+        if !s.resident && !self.wanted.contains(&tex) {
+            self.wanted.push(tex);
+        }
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.textures.values().map(Streamed::current_bytes).sum()
+    }
+
+    /// The resident texture drawn longest ago, never one drawn this frame.
+    pub fn least_recently_used(&self) -> Option<TextureId> {
+        self.textures
+            .iter()
+            .filter(|(_, s)| s.resident && s.last_used < self.frame)
+            .min_by_key(|(_, s)| s.last_used)
+            .map(|(&id, _)| id)
+    }
+
+    pub fn stats(&self) -> ResidencyStats {
+        ResidencyStats {
+            streamed: self.textures.len(),
+            resident: self.textures.values().filter(|s| s.resident).count(),
+            bytes: self.bytes(),
+            budget: self.budget,
+            evictions: self.evictions,
+            reloads: self.reloads,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use glam::Vec2;
+
+use crate::{EntityId, EntityPool, Polyline};
+
+/// What an end of a [`Rope`] is held by.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RopeEnd {
+    /// Hangs loose.
+    #[default]
+    Free,
+    /// Pinned to a world position.
+    Point(Vec2),
+    /// Follows a sprite, offset from its translation; loose once the sprite is gone.
+    Entity(EntityId, Vec2),
+}
+
+impl RopeEnd {
+    fn position(&self, pool: &EntityPool) -> Option<Vec2> {
+        match *self {
+            Self::Free => None,
+            Self::Point(p) => Some(p),
+            Self::Entity(id, offset) => pool
+                .entities
+                .get(&id)
+                .map(|s| s.transform.translation + offset),
+        }
+    }
+}
+
+/// Verlet rope: points joined by distance constraints, for ropes, chains,
+/// cables and hair.
+///
+/// Steps at a fixed [`Rope::step`] however long frames take, so it behaves
+/// the same at any frame rate. Call [`Rope::update`] from `Scene::update` and
+/// draw [`Rope::polyline`] from `Scene::render`.
+///
+/// ```ignore
+/// let mut rope = Rope::between(player, lantern, &ctx.pool, 16).with_slack(1.2);
+/// // update
+/// rope.update(ctx.pool, ctx.dt);
+/// // render
+/// let line = rope.polyline(3.0).with_join(LineJoin::Round);
+/// ctx.draw_mesh(MeshBatch::new(white, line.to_mesh()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Rope {
+    /// Rest length between neighboring points.
+    pub segment_length: f32,
+    /// Constraint passes per step; more gives a stiffer rope.
+    pub iterations: u32,
+    /// World pixels per second squared.
+    pub gravity: Vec2,
+    /// Fraction of its velocity a point keeps every step.
+    pub damping: f32,
+    /// Seconds simulated per step.
+    pub step: f32,
+    pub start: RopeEnd,
+    pub end: RopeEnd,
+    points: Vec<Vec2>,
+    previous: Vec<Vec2>,
+    accumulator: f32,
+}
+
+impl Rope {
+    /// Catch-up steps per update, so a long frame does not stall the next ones.
+    const MAX_STEPS: u32 = 8;
+
+    /// A straight rope of `segments` segments from `from` to `to`, both ends free.
+    pub fn new(from: Vec2, to: Vec2, segments: usize) -> Self {
+        let segments = segments.max(1);
+        let points: Vec<Vec2> = (0..=segments)
+            .map(|i| from.lerp(to, i as f32 / segments as f32))
+            .collect();
+        Self {
+            segment_length: from.distance(to) / segments as f32,
+            iterations: 8,
+            gravity: Vec2::new(0.0, -980.0),
+            damping: 0.99,
+            step: 1.0 / 60.0,
+            start: RopeEnd::Free,
+            end: RopeEnd::Free,
+            previous: points.clone(),
+            points,
+            accumulator: 0.0,
+        }
+    }
+
+    /// A rope hanging between the sprites `a` and `b`, as long as they are apart.
+    pub fn between(a: EntityId, b: EntityId, pool: &EntityPool, segments: usize) -> Self {
+        let from = RopeEnd::Entity(a, Vec2::ZERO);
+        let to = RopeEnd::Entity(b, Vec2::ZERO);
+        let mut rope = Self::new(
+            from.position(pool).unwrap_or_default(),
+            to.position(pool).unwrap_or_default(),
+            segments,
+        );
+        rope.start = from;
+        rope.end = to;
+        rope
+    }
+
+    pub fn with_start(mut self, start: RopeEnd) -> Self {
+        self.start = start;
+        self
+    }
+
+    pub fn with_end(mut self, end: RopeEnd) -> Self {
+        self.end = end;
+        self
+    }
+
+    /// Scales the rest length, above 1 for a sagging rope.
+    pub fn with_slack(mut self, slack: f32) -> Self {
+        self.segment_length *= slack;
+        self
+    }
+
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn with_gravity(mut self, gravity: Vec2) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    /// Rest length of the whole rope.
+    pub fn length(&self) -> f32 {
+        self.segment_length * (self.points.len() - 1) as f32
+    }
+
+    /// Pushes every point by `impulse` world pixels per second, e.g. for wind or a hit.
+    pub fn push(&mut self, impulse: Vec2) {
+        for p in &mut self.previous {
+            *p -= impulse * self.step;
+        }
+    }
+
+    /// Advances the simulation by `dt` seconds in fixed steps, following the
+    /// sprites the ends are attached to.
+    pub fn update(&mut self, pool: &EntityPool, dt: f32) {
+        let start = self.start.position(pool);
+        let end = self.end.position(pool);
+        let step = self.step.max(1e-4);
+        self.accumulator = (self.accumulator + dt).min(step * Self::MAX_STEPS as f32);
+        while self.accumulator >= step {
+            self.accumulator -= step;
+            self.simulate(step, start, end);
+        }
+    }
+
+    fn simulate(&mut self, dt: f32, start: Option<Vec2>, end: Option<Vec2>) {
+        let accel = self.gravity * dt * dt;
+        for (p, prev) in self.points.iter_mut().zip(&mut self.previous) {
+            let velocity = (*p - *prev) * self.damping;
+            *prev = *p;
+            *p += velocity + accel;
+        }
+        let last = self.points.len() - 1;
+        if let Some(s) = start {
+            self.points[0] = s;
+        }
+        if let Some(e) = end {
+            self.points[last] = e;
+        }
+        for _ in 0..self.iterations {
+            for i in 0..last {
+                let (a, b) = (self.points[i], self.points[i + 1]);
+                let delta = b - a;
+                let dist = delta.length();
+                if dist <= f32::EPSILON {
+                    continue;
+                }
+                let correction = delta * ((dist - self.segment_length) / dist);
+                // pinned points stay put, their neighbor moves the whole way
+                let (wa, wb) = match (i == 0 && start.is_some(), i + 1 == last && end.is_some()) {
+                    (true, true) => continue,
+                    (true, false) => (0.0, 1.0),
+                    (false, true) => (1.0, 0.0),
+                    (false, false) => (0.5, 0.5),
+                };
+                self.points[i] += correction * wa;
+                self.points[i + 1] -= correction * wb;
+            }
+        }
+    }
+
+    /// The rope as a line of `thickness` through its points.
+    pub fn polyline(&self, thickness: f32) -> Polyline {
+        Polyline::new(self.points.clone(), thickness)
+    }
+}
@@ -4,11 +4,22 @@ use std::{
     hash::{DefaultHasher, Hash, Hasher},
     ops::Deref,
     path::{Path, PathBuf},
-    sync::atomic::{AtomicU32, Ordering},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
 };
 
-use crate::{Camera, InputState, Sprite, TextureId};
-use hashbrown::HashMap;
+use crate::{
+    render::rgba_from_fn, text::rasterize_text, Animation, AsepriteSheet, AsepriteSheets,
+    AssetState, AssetStates, BlendMode, Camera, Color, DebugDraw, Dialogue, DialogueCommand,
+    DialogueRunner, Error, Events, Font, GlobalTransform, Hierarchy, InputDevice, InputDevices,
+    InputState, LoadPriority, MaterialId, MemoryStats, MeshBatch, MsdfAtlas, Panel, PresentMode,
+    SamplerDesc, ScreenFilter, Sprite, SpriteBatch, SpriteStore, Text, TextStyle, TextureId,
+    Tilemap, Transform, Trigger, UiFocus, UiNode, Value, Velocity, WindowPlacement,
+};
+use hashbrown::{HashMap, HashSet};
+use tracing::warn;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct SceneKey(usize);
@@ -35,18 +46,107 @@ impl SceneKey {
 pub trait Scene: Send {
     fn start(&mut self, _ctx: &mut Ctx<'_>) {}
     fn update(&mut self, _ctx: &mut Ctx<'_>) {}
+    /// Called once the window has stopped resizing, with its final size in pixels.
+    fn resized(&mut self, _ctx: &mut Ctx<'_>, _size: Vec2) {}
+    /// Called when GPU memory first exceeds the budget set with `App::set_memory_budget`,
+    /// the place to release streamed assets.
+    fn memory_pressure(&mut self, _ctx: &mut Ctx<'_>, _stats: &MemoryStats) {}
+    /// Submits sprite batches and meshes the scene builds itself, e.g. for
+    /// particle systems or procedural geometry kept outside the [`EntityPool`].
+    ///
+    /// Called every frame after `update`, also while the scene is paused.
+    fn render(&mut self, _ctx: &mut RenderCtx<'_>) {}
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct EntityId(u32);
 
+impl EntityId {
+    /// The raw id, e.g. for passing across an FFI boundary.
+    pub fn to_raw(self) -> u32 {
+        self.0
+    }
+    pub fn from_raw(raw: u32) -> Self {
+        Self(raw)
+    }
+}
+
 pub struct Ctx<'a> {
     pub dt: f32,
     pub resources: &'a mut Resources,
     pub commands: &'a mut Commands,
     pub pool: &'a mut EntityPool,
     pub input: &'a InputState,
+    /// Input of each device and local player, see [`Ctx::assign_device_to_player`].
+    pub devices: &'a InputDevices,
     pub screen_pos: Vec2,
+    /// Cameras as of the start of the frame, in draw order.
+    pub cameras: &'a [Camera],
+    /// Focus and activation in the UI set with [`Ctx::set_ui`], updated before scenes run.
+    pub ui: &'a UiFocus,
+    /// Events sent last frame, see [`Ctx::send_event`].
+    pub events: &'a Events,
+    /// The app's dialogue as of the start of the frame, see [`Ctx::start_dialogue`].
+    pub dialogue: &'a DialogueRunner,
+    /// Textures asked for by file and where they are, as of the start of the frame.
+    pub assets: &'a AssetStates,
+}
+
+/// What [`Scene::render`] draws with.
+///
+/// Batches and meshes are drawn through every camera after the pool's
+/// sprites, in the order they were submitted, and go as they are to the
+/// backend: no sorting, merging or culling. Batches given to
+/// [`RenderCtx::draw_in_layer`] instead sort in with the pool's sprites.
+///
+/// ```ignore
+/// fn render(&mut self, ctx: &mut RenderCtx<'_>) {
+///     let instances = self.particles.iter().map(|p| p.instance()).collect();
+///     ctx.draw(SpriteBatch::new(self.spark, instances));
+/// }
+/// ```
+pub struct RenderCtx<'a> {
+    pub dt: f32,
+    pub resources: &'a Resources,
+    pub pool: &'a EntityPool,
+    pub screen_pos: Vec2,
+    pub cameras: &'a [Camera],
+    /// Submitted so far this frame.
+    pub draws: &'a mut Vec<SceneDraw>,
+    /// Submitted so far this frame with their layer, see [`RenderCtx::draw_in_layer`].
+    pub layered: &'a mut Vec<(i32, SpriteBatch)>,
+}
+
+impl RenderCtx<'_> {
+    pub fn draw(&mut self, batch: SpriteBatch) {
+        self.draws.push(SceneDraw::Sprites(batch));
+    }
+
+    pub fn draw_mesh(&mut self, mesh: MeshBatch) {
+        self.draws.push(SceneDraw::Mesh(mesh));
+    }
+
+    /// Draws `batch` among the pool's sprites on `layer` rather than after
+    /// them all, e.g. smoke between characters and foreground props.
+    ///
+    /// Its instances follow the layer's sprites, or under
+    /// [`SortMode::YSort`](crate::SortMode::YSort) sort in one by one by
+    /// their y, as unkeyed sprites do.
+    ///
+    /// ```ignore
+    /// let instances = self.smoke.iter().map(|p| p.instance()).collect();
+    /// ctx.draw_in_layer(WORLD, SpriteBatch::new(self.puff, instances));
+    /// ```
+    pub fn draw_in_layer(&mut self, layer: i32, batch: SpriteBatch) {
+        self.layered.push((layer, batch));
+    }
+}
+
+/// Something submitted through [`RenderCtx`].
+#[derive(Debug)]
+pub enum SceneDraw {
+    Sprites(SpriteBatch),
+    Mesh(MeshBatch),
 }
 
 impl<'a> Ctx<'a> {
@@ -55,12 +155,226 @@ impl<'a> Ctx<'a> {
         self.commands.sprites_to_spawn.push((id, s));
         id
     }
+    pub fn spawn_panel(&mut self, p: Panel) -> EntityId {
+        let id = EntityId(self.pool.next_id.fetch_add(1, Ordering::Relaxed));
+        self.commands.panels_to_spawn.push((id, p));
+        id
+    }
+    /// Draws `text` with its first line's top-left corner at the world origin, see [`Text`].
+    pub fn spawn_text(&mut self, text: &str, style: TextStyle) -> EntityId {
+        self.spawn_text_at(text, style, Vec2::ZERO)
+    }
+    pub fn spawn_text_at(&mut self, text: &str, style: TextStyle, top_left: Vec2) -> EntityId {
+        let id = EntityId(self.pool.next_id.fetch_add(1, Ordering::Relaxed));
+        let text = Text {
+            text: text.to_owned(),
+            style,
+            top_left,
+            layer: 0,
+        };
+        self.commands.texts_to_spawn.push((id, text));
+        id
+    }
+    /// Adds a [`Trigger`] area; see [`TriggerEvent`](crate::TriggerEvent) for what it reports.
+    pub fn spawn_trigger(&mut self, t: Trigger) -> EntityId {
+        let id = EntityId(self.pool.next_id.fetch_add(1, Ordering::Relaxed));
+        self.commands.triggers_to_spawn.push((id, t));
+        id
+    }
+    /// Lets the sprite `id` set off triggers, e.g. the player or enemies.
+    pub fn watch_triggers(&mut self, id: EntityId) {
+        self.commands.trigger_watchers.push(id);
+    }
+    pub fn despawn(&mut self, id: EntityId) {
+        self.commands.despawn.push(id);
+    }
+    /// Makes sprite `child` follow sprite `parent` at `local`, see [`Hierarchy`];
+    /// false when `parent` is `child` or one of its descendants.
+    pub fn set_parent(&mut self, child: EntityId, parent: EntityId, local: Transform) -> bool {
+        self.pool.hierarchy.set_parent(child, parent, local)
+    }
+    /// Where sprite `id` was last drawn in the world, see [`Hierarchy::global`].
+    pub fn global_transform(&self, id: EntityId) -> Option<GlobalTransform> {
+        self.pool.hierarchy.global(id, &self.pool.entities)
+    }
+    /// Queues an image to load after this frame and returns its id.
+    ///
+    /// An Aseprite `.json` export is read now instead: its sheet image is
+    /// queued and the id returned is that image's, with the frames and
+    /// clips in [`Ctx::aseprite`]. Exports that fail to read are logged and
+    /// load nothing.
     pub fn load_asset(&mut self, p: impl AsRef<Path>) -> TextureId {
         let p = p.as_ref();
+        if p.extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+        {
+            return match self.load_aseprite(p) {
+                Ok(sheet) => {
+                    let tex = sheet.atlas.tex;
+                    if self.resources.get::<AsepriteSheets>().is_none() {
+                        self.resources.insert(AsepriteSheets::default());
+                    }
+                    let sheets = self.resources.get_mut::<AsepriteSheets>();
+                    sheets.expect("inserted above").insert(sheet);
+                    tex
+                }
+                Err(e) => {
+                    warn!("Failed to load {}: {e}", p.display());
+                    TextureId::from_path(p)
+                }
+            };
+        }
         let id = TextureId::from_path(p);
         self.commands.assets_to_load.push((id, p.to_owned()));
         id
     }
+    /// Queues an image to load in the background, a little after each frame,
+    /// before it is needed, e.g. the next level's art; see
+    /// [`Renderer::prefetch_texture`](crate::Renderer::prefetch_texture).
+    ///
+    /// Returns the id [`Ctx::load_asset`] gives the same path, which loads it
+    /// at once if it is still queued by then.
+    pub fn prefetch_asset(&mut self, p: impl AsRef<Path>, priority: LoadPriority) -> TextureId {
+        let p = p.as_ref();
+        let id = TextureId::from_path(p);
+        self.commands
+            .assets_to_prefetch
+            .push((id, p.to_owned(), priority));
+        id
+    }
+    /// Queues an image file embedded in the binary, e.g. with
+    /// `include_bytes!`, so the game ships as one executable without an
+    /// assets folder; see [`Renderer::load_texture_from_bytes`](crate::Renderer::load_texture_from_bytes).
+    ///
+    /// `name` gives the id, the one [`Ctx::load_asset`] gives that path, so
+    /// art can move between files and the binary without its ids changing.
+    ///
+    /// ```ignore
+    /// let hero = ctx.load_asset_bytes("hero.png", include_bytes!("../assets/hero.png"));
+    /// ```
+    pub fn load_asset_bytes(&mut self, name: &str, bytes: &'static [u8]) -> TextureId {
+        let id = TextureId::from_path(name);
+        self.commands
+            .assets_from_bytes
+            .push((id, name.to_owned(), bytes));
+        id
+    }
+    /// Frees a texture after this frame, before the next loads, so its slot
+    /// is reused; see [`Renderer::unload_texture`](crate::Renderer::unload_texture).
+    pub fn unload_asset(&mut self, tex: TextureId) {
+        self.commands.assets_to_unload.push(tex);
+    }
+    /// Like [`Ctx::load_asset`] without stalling the frame: the image is
+    /// decoded on a worker thread and shows once [`Ctx::asset_state`] is
+    /// ready, sprites using it draw nothing until then.
+    pub fn load_asset_async(&mut self, p: impl AsRef<Path>) -> TextureId {
+        self.prefetch_asset(p, LoadPriority::High)
+    }
+    /// Where a texture asked for by file is as of the start of the frame,
+    /// `None` if it never was; see [`AssetStates`].
+    pub fn asset_state(&self, tex: TextureId) -> Option<AssetState> {
+        self.assets.get(tex)
+    }
+    /// Like [`Ctx::load_asset`], sampling the texture with `desc` rather than the defaults.
+    pub fn load_asset_with(&mut self, p: impl AsRef<Path>, desc: SamplerDesc) -> TextureId {
+        let id = TextureId::from_path(p.as_ref());
+        self.commands.samplers.push((id, desc));
+        self.load_asset(p)
+    }
+    /// The sheet of an Aseprite export [`Ctx::load_asset`] loaded as `tex`.
+    pub fn aseprite(&self, tex: TextureId) -> Option<&AsepriteSheet> {
+        self.resources.get::<AsepriteSheets>()?.get(tex)
+    }
+    /// Reads an Aseprite export now and queues its sheet image like
+    /// [`Ctx::load_asset`], returning errors rather than logging them.
+    pub fn load_aseprite(&mut self, p: impl AsRef<Path>) -> Result<AsepriteSheet, Error> {
+        let sheet = AsepriteSheet::load(p)?;
+        self.commands
+            .assets_to_load
+            .push((sheet.atlas.tex, sheet.image.clone()));
+        Ok(sheet)
+    }
+    /// Reads the level `level` of an LDtk project now and queues its tileset
+    /// images like [`Ctx::load_asset`].
+    pub fn load_tilemap(&mut self, p: impl AsRef<Path>, level: &str) -> Result<Tilemap, Error> {
+        let map = Tilemap::load(p, level)?;
+        for (tex, path) in map.tilesets() {
+            self.commands.assets_to_load.push((tex, path.to_owned()));
+        }
+        Ok(map)
+    }
+    /// Queues a texture built from raw RGBA-8 pixels, no file required.
+    pub fn create_texture_from_rgba(&mut self, w: u32, h: u32, pixels: &[u8]) -> TextureId {
+        assert_eq!(
+            pixels.len(),
+            w as usize * h as usize * 4,
+            "pixels buffer must be RGBA-8 per texel"
+        );
+        let id = TextureId::generated();
+        self.commands
+            .textures_to_create
+            .push((id, w, h, pixels.to_vec()));
+        id
+    }
+    /// Queues a texture generated by evaluating `f(x, y)` for every texel.
+    pub fn create_texture_from_fn<F>(&mut self, w: u32, h: u32, f: F) -> TextureId
+    where
+        F: FnMut(u32, u32) -> Color,
+    {
+        let id = TextureId::generated();
+        self.commands
+            .textures_to_create
+            .push((id, w, h, rgba_from_fn(w, h, f)));
+        id
+    }
+    /// Rasterizes `text` once into a standalone texture.
+    ///
+    /// Glyphs are white with coverage in alpha; the result batches like any other sprite.
+    pub fn render_text_to_texture(&mut self, text: &str, font: &Font, size: f32) -> TextureId {
+        let (w, h, pixels) = rasterize_text(font, text, size);
+        let id = TextureId::generated();
+        self.commands.textures_to_create.push((id, w, h, pixels));
+        id
+    }
+    /// Bakes an [`MsdfAtlas`] and queues its texture; one atlas renders `font` at any size.
+    pub fn create_msdf_atlas(&mut self, font: &Font, em_size: f32) -> Result<MsdfAtlas, Error> {
+        let (atlas, pixels) = MsdfAtlas::ascii(font, em_size)?;
+        self.commands
+            .textures_to_create
+            .push((atlas.tex, atlas.width, atlas.height, pixels));
+        Ok(atlas)
+    }
+    /// Queues a `w` by `h` render target, a texture the app can draw the
+    /// world into, see [`Ctx::render_to_texture`].
+    pub fn create_render_target(&mut self, w: u32, h: u32) -> TextureId {
+        let id = TextureId::generated();
+        self.commands.render_targets_to_create.push((id, w, h));
+        id
+    }
+    /// Draws the pool's sprites and the scene's batches through `camera` into
+    /// the render target `target` every frame, before the window's cameras,
+    /// so sprites using `target` show this frame's view; `None` stops it.
+    /// Those sprites are left out of the target itself.
+    ///
+    /// ```ignore
+    /// let portrait = ctx.create_render_target(128, 128);
+    /// let close_up = Camera::new(hero_pos - Vec2::splat(64.0), 1.0);
+    /// ctx.render_to_texture(portrait, Some(close_up));
+    /// ctx.spawn_sprite(Sprite { tex: portrait, ..Default::default() });
+    /// ```
+    pub fn render_to_texture(&mut self, target: TextureId, camera: Option<Camera>) {
+        self.commands.render_views.push((target, camera));
+    }
+    /// Queues [`Renderer::precompile`](crate::Renderer::precompile), e.g. from a loading screen.
+    pub fn precompile(&mut self, materials: &[MaterialId], blend_modes: &[BlendMode]) {
+        self.commands
+            .precompile
+            .push((materials.to_vec(), blend_modes.to_vec()));
+    }
+    /// Stops the app once the current frame is done.
+    pub fn quit(&mut self) {
+        self.commands.quit = true;
+    }
     pub fn goto_scene<S>(&mut self)
     where
         S: Scene + 'static,
@@ -68,31 +382,210 @@ impl<'a> Ctx<'a> {
         self.commands.scene_switch = Some(TypeId::of::<S>());
     }
 
+    /// Moves the window, e.g. onto another monitor; see [`crate::Monitors`] for what is connected.
+    pub fn place_window(&mut self, placement: WindowPlacement) {
+        self.commands.window_placement = Some(placement);
+    }
+
+    /// Changes how frames are presented from the next one on, e.g. from a vsync toggle.
+    pub fn set_present_mode(&mut self, mode: PresentMode) {
+        self.commands.present_mode = Some(mode);
+    }
+
+    /// Replaces the screen-space UI drawn over every camera, `None` removes it.
+    ///
+    /// The tree is laid out again whenever the window is resized.
+    pub fn set_ui(&mut self, ui: Option<UiNode>) {
+        self.commands.ui = Some(ui);
+    }
+
+    /// Queues a typed event, every scene can [read](Events::read) it during the next frame.
+    pub fn send_event<E: Any + Send + Sync>(&mut self, event: E) {
+        self.commands.events.send(event);
+    }
+
+    /// Moves UI focus to the focusable node `name`, e.g. the first entry of a menu just opened.
+    pub fn focus_ui(&mut self, name: &str) {
+        self.commands.ui_focus = Some(name.to_owned());
+    }
+
+    /// Runs `dialogue` from `node`; progress shows in `ctx.dialogue` from the next frame.
+    pub fn start_dialogue(&mut self, dialogue: Arc<Dialogue>, node: &str) {
+        self.commands
+            .dialogue
+            .push(DialogueCommand::Start(dialogue, node.to_owned()));
+    }
+
+    /// Moves past the current dialogue line.
+    pub fn advance_dialogue(&mut self) {
+        self.commands.dialogue.push(DialogueCommand::Advance);
+    }
+
+    /// Picks choice `index` of the ones the dialogue offers.
+    pub fn choose_dialogue(&mut self, index: usize) {
+        self.commands.dialogue.push(DialogueCommand::Choose(index));
+    }
+
+    pub fn stop_dialogue(&mut self) {
+        self.commands.dialogue.push(DialogueCommand::Stop);
+    }
+
+    /// Sets a variable the dialogue can test and show, e.g. the player's gold.
+    pub fn set_dialogue_var(&mut self, name: &str, value: impl Into<Value>) {
+        self.commands
+            .dialogue
+            .push(DialogueCommand::SetVar(name.to_owned(), value.into()));
+    }
+
     pub fn spawn_camera(&mut self, camera: Camera) -> usize {
         self.commands.cameras_to_spawn.push(camera);
-        self.commands.cameras_to_spawn.len() - 1
+        self.cameras.len() + self.commands.cameras_to_spawn.len() - 1
+    }
+
+    /// Switches the post-processing of camera `idx`, e.g. to toggle a CRT look from a menu.
+    pub fn set_camera_filter(&mut self, idx: usize, filter: ScreenFilter) {
+        self.commands.camera_filters.push((idx, filter));
+    }
+
+    /// Eases camera `idx` to `zoom` over `duration` seconds about the middle
+    /// of its view, see [`Camera::zoom_to`].
+    pub fn zoom_camera(&mut self, idx: usize, zoom: f32, duration: f32) {
+        let anchor = self.cameras.get(idx).map(|c| {
+            let (min, size) = c.output_viewport().to_pixels(self.screen_pos);
+            min + size * 0.5
+        });
+        self.commands
+            .camera_zooms
+            .push((idx, zoom, duration, anchor));
+    }
+
+    /// Like [`Ctx::zoom_camera`] keeping the world point under the cursor in place.
+    ///
+    /// ```ignore
+    /// if let Some((idx, _)) = ctx.cursor_world()
+    ///     && ctx.input.scroll() != 0.0
+    /// {
+    ///     let zoom = ctx.cameras[idx].zoom * 1.25f32.powf(ctx.input.scroll());
+    ///     ctx.zoom_camera_at_cursor(idx, zoom, 0.15);
+    /// }
+    /// ```
+    pub fn zoom_camera_at_cursor(&mut self, idx: usize, zoom: f32, duration: f32) {
+        let anchor = Some(self.input.mouse_pos());
+        self.commands
+            .camera_zooms
+            .push((idx, zoom, duration, anchor));
+    }
+
+    /// Routes `device` into [`InputDevices::player`] for `player`, taking it
+    /// from any player it had; applies from the next frame.
+    pub fn assign_device_to_player(&mut self, device: InputDevice, player: usize) {
+        self.commands
+            .device_assignments
+            .push((device, Some(player)));
+    }
+
+    pub fn unassign_device(&mut self, device: InputDevice) {
+        self.commands.device_assignments.push((device, None));
+    }
+
+    /// Shapes drawn over the world this frame, see [`DebugDraw`].
+    pub fn debug_draw(&mut self) -> &mut DebugDraw {
+        &mut self.commands.debug_draw
+    }
+
+    /// Rumbles the gamepads of `player`, every pad when none is assigned, at
+    /// `strength` from 0 to 1 for `duration` seconds; strength 0 stops them.
+    ///
+    /// Pads stop when the window loses focus. Without the `gamepad` feature
+    /// or force-feedback hardware this does nothing.
+    pub fn rumble(&mut self, player: usize, strength: f32, duration: f32) {
+        self.commands.rumbles.push((player, strength, duration));
+    }
+
+    /// Index of the topmost camera whose viewport contains `pos` (window pixels, origin top-left).
+    pub fn camera_at_screen_pos(&self, pos: Vec2) -> Option<usize> {
+        self.cameras
+            .iter()
+            .rposition(|c| c.output_viewport().contains(pos, self.screen_pos))
+    }
+
+    /// Cursor position in the world of the camera under it, with that camera's index.
+    pub fn cursor_world(&self) -> Option<(usize, Vec2)> {
+        let pos = self.input.mouse_pos();
+        let idx = self.camera_at_screen_pos(pos)?;
+        Some((idx, self.cameras[idx].window_to_world(pos, self.screen_pos)))
     }
 }
 
 #[derive(Default)]
 pub struct EntityPool {
     next_id: AtomicU32,
-    pub entities: HashMap<EntityId, Sprite>,
+    pub entities: SpriteStore,
+    pub panels: HashMap<EntityId, Panel>,
+    pub texts: HashMap<EntityId, Text>,
+    pub triggers: HashMap<EntityId, Trigger>,
+    /// Sprites that set off triggers.
+    pub trigger_watchers: HashSet<EntityId>,
+    /// Sprites moved by the app every frame, see [`Velocity`].
+    pub velocities: HashMap<EntityId, Velocity>,
+    /// Flipbooks the app advances every frame, see [`Animation`].
+    pub animations: HashMap<EntityId, Animation>,
+    /// Sprites following other sprites, propagated by the app every frame.
+    pub hierarchy: Hierarchy,
 }
 
 impl EntityPool {
     pub fn sprite_mut(&mut self, id: EntityId) -> Option<&mut Sprite> {
         self.entities.get_mut(&id)
     }
+    pub fn panel_mut(&mut self, id: EntityId) -> Option<&mut Panel> {
+        self.panels.get_mut(&id)
+    }
+    pub fn text_mut(&mut self, id: EntityId) -> Option<&mut Text> {
+        self.texts.get_mut(&id)
+    }
 }
 
 #[derive(Default)]
 pub struct Commands {
     pub sprites_to_spawn: Vec<(EntityId, Sprite)>,
+    pub panels_to_spawn: Vec<(EntityId, Panel)>,
+    pub texts_to_spawn: Vec<(EntityId, Text)>,
+    pub triggers_to_spawn: Vec<(EntityId, Trigger)>,
+    pub trigger_watchers: Vec<EntityId>,
     pub assets_to_load: Vec<(TextureId, PathBuf)>,
+    pub assets_to_prefetch: Vec<(TextureId, PathBuf, LoadPriority)>,
+    /// Unloaded before anything is loaded.
+    pub assets_to_unload: Vec<TextureId>,
+    /// Embedded image files and their names.
+    pub assets_from_bytes: Vec<(TextureId, String, &'static [u8])>,
+    /// Set before `assets_to_load` are loaded.
+    pub samplers: Vec<(TextureId, SamplerDesc)>,
+    pub textures_to_create: Vec<(TextureId, u32, u32, Vec<u8>)>,
+    pub render_targets_to_create: Vec<(TextureId, u32, u32)>,
+    /// Render targets and the camera drawing into them, `None` to stop.
+    pub render_views: Vec<(TextureId, Option<Camera>)>,
     pub despawn: Vec<EntityId>,
     pub scene_switch: Option<TypeId>,
+    pub window_placement: Option<WindowPlacement>,
+    pub present_mode: Option<PresentMode>,
+    pub quit: bool,
     pub cameras_to_spawn: Vec<Camera>,
+    pub camera_filters: Vec<(usize, ScreenFilter)>,
+    /// Camera, target zoom, duration and window point kept in place.
+    pub camera_zooms: Vec<(usize, f32, f32, Option<Vec2>)>,
+    pub device_assignments: Vec<(InputDevice, Option<usize>)>,
+    /// Player, strength and duration.
+    pub rumbles: Vec<(usize, f32, f32)>,
+    pub debug_draw: DebugDraw,
+    pub precompile: Vec<(Vec<MaterialId>, Vec<BlendMode>)>,
+    /// `Some` when [`Ctx::set_ui`] was called.
+    pub ui: Option<Option<UiNode>>,
+    pub ui_focus: Option<String>,
+    pub events: Events,
+    pub dialogue: Vec<DialogueCommand>,
+    /// New translations for existing sprites/panels/texts, e.g. from editor drags.
+    pub moves: Vec<(EntityId, Vec2)>,
 }
 
 #[derive(Default)]
@@ -0,0 +1,232 @@
+use std::{fmt, str::FromStr};
+
+use winit::keyboard::KeyCode;
+
+use crate::{key_from_name, key_name, ButtonState, InputState};
+
+/// Modifier keys held with a [`Chord`]'s key; either side counts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    /// The Windows key, or Command on macOS.
+    pub super_key: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Self = Self {
+        ctrl: false,
+        shift: false,
+        alt: false,
+        super_key: false,
+    };
+
+    /// Modifiers held in `input`, leaving out `key` itself.
+    pub fn held(input: &InputState, key: KeyCode) -> Self {
+        let held = |keys: [KeyCode; 2]| keys.iter().any(|&k| k != key && input.key_pressed(k));
+        Self {
+            ctrl: held([KeyCode::ControlLeft, KeyCode::ControlRight]),
+            shift: held([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+            alt: held([KeyCode::AltLeft, KeyCode::AltRight]),
+            super_key: held([KeyCode::SuperLeft, KeyCode::SuperRight]),
+        }
+    }
+}
+
+/// A key pressed while holding exactly some modifiers, e.g. Ctrl+Shift+S.
+///
+/// Written as the modifiers and a [`key_name`] joined by `+`; letters and
+/// digits may drop their `Key` and `Digit` prefix.
+///
+/// ```ignore
+/// let save = Chord::new(KeyCode::KeyS).cmd();
+/// assert_eq!("Ctrl+Shift+S".parse(), Ok(Chord::new(KeyCode::KeyS).ctrl().shift()));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Chord {
+    pub modifiers: Modifiers,
+    pub key: KeyCode,
+}
+
+impl Chord {
+    /// `key` without modifiers.
+    pub fn new(key: KeyCode) -> Self {
+        Self {
+            modifiers: Modifiers::NONE,
+            key,
+        }
+    }
+
+    pub fn ctrl(mut self) -> Self {
+        self.modifiers.ctrl = true;
+        self
+    }
+
+    pub fn shift(mut self) -> Self {
+        self.modifiers.shift = true;
+        self
+    }
+
+    pub fn alt(mut self) -> Self {
+        self.modifiers.alt = true;
+        self
+    }
+
+    pub fn super_key(mut self) -> Self {
+        self.modifiers.super_key = true;
+        self
+    }
+
+    /// The platform's shortcut modifier: Command on macOS, Ctrl elsewhere.
+    pub fn cmd(self) -> Self {
+        match cfg!(target_os = "macos") {
+            true => self.super_key(),
+            false => self.ctrl(),
+        }
+    }
+
+    /// The key's state while exactly these modifiers are held, released otherwise.
+    pub fn state(&self, input: &InputState) -> ButtonState {
+        match Modifiers::held(input, self.key) == self.modifiers {
+            true => input.key(self.key),
+            false => ButtonState::default(),
+        }
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let m = self.modifiers;
+        for (held, name) in [
+            (m.ctrl, "Ctrl"),
+            (m.shift, "Shift"),
+            (m.alt, "Alt"),
+            (m.super_key, "Super"),
+        ] {
+            if held {
+                write!(f, "{name}+")?;
+            }
+        }
+        let name = key_name(self.key).unwrap_or("?");
+        let short = name
+            .strip_prefix("Key")
+            .or_else(|| name.strip_prefix("Digit"))
+            .filter(|s| s.len() == 1);
+        f.write_str(short.unwrap_or(name))
+    }
+}
+
+impl FromStr for Chord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        let key = parts.pop().unwrap_or_default();
+        let key = match key.chars().next() {
+            Some(c) if key.len() == 1 && c.is_ascii_alphabetic() => {
+                key_from_name(&format!("Key{}", c.to_ascii_uppercase()))
+            }
+            Some(c) if key.len() == 1 && c.is_ascii_digit() => key_from_name(&format!("Digit{c}")),
+            _ => key_from_name(key),
+        }
+        .ok_or_else(|| format!("unknown key `{key}` in `{s}`"))?;
+        let mut chord = Self::new(key);
+        for m in parts {
+            chord = match m.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => chord.ctrl(),
+                "shift" => chord.shift(),
+                "alt" | "option" => chord.alt(),
+                "super" | "cmd" | "command" | "win" | "meta" => chord.super_key(),
+                _ => return Err(format!("unknown modifier `{m}` in `{s}`")),
+            };
+        }
+        Ok(chord)
+    }
+}
+
+/// Why [`Shortcuts::bind`] failed: the chord belongs to another action.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("{chord} is already bound to `{action}`")]
+pub struct ShortcutConflict {
+    pub chord: Chord,
+    /// The action holding the chord.
+    pub action: String,
+}
+
+/// Chords by action name, e.g. the menu shortcuts of an editor scene.
+///
+/// No two actions share a chord; binding one that is taken fails with the
+/// action holding it, so a rebinding menu can ask which to keep.
+///
+/// ```ignore
+/// let mut shortcuts = Shortcuts::new();
+/// shortcuts.bind("save", Chord::new(KeyCode::KeyS).cmd())?;
+/// shortcuts.bind("save_as", "Ctrl+Shift+S".parse().unwrap())?;
+/// // in update
+/// for action in shortcuts.just_pressed(ctx.input) {
+///     run(action);
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Shortcuts {
+    chords: Vec<(String, Chord)>,
+}
+
+impl Shortcuts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds `chord` to `action`, replacing the action's chord, unless another action has it.
+    pub fn bind(&mut self, action: &str, chord: Chord) -> Result<(), ShortcutConflict> {
+        if let Some(other) = self.action(chord).filter(|a| *a != action) {
+            return Err(ShortcutConflict {
+                chord,
+                action: other.to_owned(),
+            });
+        }
+        match self.chords.iter_mut().find(|(a, _)| a == action) {
+            Some((_, c)) => *c = chord,
+            None => self.chords.push((action.to_owned(), chord)),
+        }
+        Ok(())
+    }
+
+    pub fn unbind(&mut self, action: &str) {
+        self.chords.retain(|(a, _)| a != action);
+    }
+
+    pub fn chord(&self, action: &str) -> Option<Chord> {
+        self.chords
+            .iter()
+            .find(|(a, _)| a == action)
+            .map(|(_, c)| *c)
+    }
+
+    /// The action `chord` is bound to.
+    pub fn action(&self, chord: Chord) -> Option<&str> {
+        self.chords
+            .iter()
+            .find(|(_, c)| *c == chord)
+            .map(|(a, _)| a.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Chord)> {
+        self.chords.iter().map(|(a, c)| (a.as_str(), *c))
+    }
+
+    /// State of `action`'s chord, released when it has none.
+    pub fn state(&self, action: &str, input: &InputState) -> ButtonState {
+        self.chord(action)
+            .map_or_else(ButtonState::default, |c| c.state(input))
+    }
+
+    /// Actions whose chord went down this frame.
+    pub fn just_pressed<'a>(&'a self, input: &'a InputState) -> impl Iterator<Item = &'a str> {
+        self.chords
+            .iter()
+            .filter(|(_, c)| c.state(input).just_pressed)
+            .map(|(a, _)| a.as_str())
+    }
+}
@@ -0,0 +1,276 @@
+use glam::Vec2;
+use winit::keyboard::KeyCode;
+
+use crate::{
+    Camera, EntityId, EntityPool, GamepadAxis, GamepadButton, InputDevices, InputState, Viewport,
+};
+
+/// Keys one player moves and acts with when several share a keyboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeySet {
+    pub up: KeyCode,
+    pub down: KeyCode,
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub primary: KeyCode,
+    pub secondary: KeyCode,
+}
+
+impl KeySet {
+    pub const WASD: Self = Self {
+        up: KeyCode::KeyW,
+        down: KeyCode::KeyS,
+        left: KeyCode::KeyA,
+        right: KeyCode::KeyD,
+        primary: KeyCode::Space,
+        secondary: KeyCode::ShiftLeft,
+    };
+
+    pub const ARROWS: Self = Self {
+        up: KeyCode::ArrowUp,
+        down: KeyCode::ArrowDown,
+        left: KeyCode::ArrowLeft,
+        right: KeyCode::ArrowRight,
+        primary: KeyCode::Enter,
+        secondary: KeyCode::ShiftRight,
+    };
+}
+
+/// Where a [`SplitScreen`] player's input comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Controls {
+    Keys(KeySet),
+    /// Left stick or d-pad to move, south and east buttons to act; every
+    /// pad unless the player has devices assigned.
+    Gamepad,
+}
+
+/// One player's input this frame, read through their [`Controls`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PlayerInput {
+    /// Y up, at most 1 long.
+    pub movement: Vec2,
+    pub primary: bool,
+    pub primary_just_pressed: bool,
+    pub secondary: bool,
+    pub secondary_just_pressed: bool,
+}
+
+impl PlayerInput {
+    pub fn read(controls: &Controls, input: &InputState) -> Self {
+        match controls {
+            Controls::Keys(k) => {
+                let axis = |neg, pos| {
+                    input.key_pressed(pos) as u8 as f32 - input.key_pressed(neg) as u8 as f32
+                };
+                Self {
+                    movement: Vec2::new(axis(k.left, k.right), axis(k.down, k.up))
+                        .normalize_or_zero(),
+                    primary: input.key_pressed(k.primary),
+                    primary_just_pressed: input.just_pressed(k.primary),
+                    secondary: input.key_pressed(k.secondary),
+                    secondary_just_pressed: input.just_pressed(k.secondary),
+                }
+            }
+            Controls::Gamepad => {
+                let dpad = |neg, pos| {
+                    input.pad_pressed(pos) as u8 as f32 - input.pad_pressed(neg) as u8 as f32
+                };
+                let stick = Vec2::new(
+                    input.pad_axis(GamepadAxis::LeftStickX),
+                    input.pad_axis(GamepadAxis::LeftStickY),
+                );
+                let pad = Vec2::new(
+                    dpad(GamepadButton::DPadLeft, GamepadButton::DPadRight),
+                    dpad(GamepadButton::DPadDown, GamepadButton::DPadUp),
+                );
+                Self {
+                    movement: (stick + pad).clamp_length_max(1.0),
+                    primary: input.pad_pressed(GamepadButton::South),
+                    primary_just_pressed: input.pad_just_pressed(GamepadButton::South),
+                    secondary: input.pad_pressed(GamepadButton::East),
+                    secondary_just_pressed: input.pad_just_pressed(GamepadButton::East),
+                }
+            }
+        }
+    }
+}
+
+/// How two players share the window; three and four players get quarters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SplitLayout {
+    /// Left and right halves.
+    #[default]
+    SideBySide,
+    /// Top and bottom halves.
+    Stacked,
+}
+
+impl SplitLayout {
+    /// Viewports of `players` players, at most four, in player order.
+    ///
+    /// With three players the first gets the whole top half.
+    pub fn viewports(self, players: usize) -> Vec<Viewport> {
+        match (players, self) {
+            (0, _) => Vec::new(),
+            (1, _) => vec![Viewport::FULL],
+            (2, Self::SideBySide) => vec![
+                Viewport::new(0.0, 0.0, 0.5, 1.0),
+                Viewport::new(0.5, 0.0, 0.5, 1.0),
+            ],
+            (2, Self::Stacked) => vec![
+                Viewport::new(0.0, 0.0, 1.0, 0.5),
+                Viewport::new(0.0, 0.5, 1.0, 0.5),
+            ],
+            (3, _) => vec![
+                Viewport::new(0.0, 0.0, 1.0, 0.5),
+                Viewport::new(0.0, 0.5, 0.5, 0.5),
+                Viewport::new(0.5, 0.5, 0.5, 0.5),
+            ],
+            _ => vec![
+                Viewport::new(0.0, 0.0, 0.5, 0.5),
+                Viewport::new(0.5, 0.0, 0.5, 0.5),
+                Viewport::new(0.0, 0.5, 0.5, 0.5),
+                Viewport::new(0.5, 0.5, 0.5, 0.5),
+            ],
+        }
+    }
+}
+
+/// A local player of a [`SplitScreen`].
+#[derive(Clone, Debug)]
+pub struct SplitPlayer {
+    pub controls: Controls,
+    /// Kept in the middle of the player's view; without it the view stays
+    /// wherever [`Camera::center`] is set.
+    pub follow: Option<EntityId>,
+    /// Zoom, policy, design size and filter of the player's view; its
+    /// viewport and center are set by the split screen.
+    pub camera: Camera,
+    input: PlayerInput,
+}
+
+impl SplitPlayer {
+    /// Input read at the start of the frame.
+    pub fn input(&self) -> PlayerInput {
+        self.input
+    }
+}
+
+/// Local multiplayer on one window: two to four views, each following a
+/// player's sprite, with each player's input read from their own keys or
+/// gamepad.
+///
+/// Add it with `App::add_resource`. While present it owns the app's
+/// cameras: every frame, before scenes update, camera `i` is replaced with
+/// player `i`'s view, laid out by [`SplitScreen::layout`], so
+/// `ctx.cameras` and [`Ctx::cursor_world`](crate::Ctx::cursor_world) work
+/// per player. Cameras spawned with `ctx.spawn_camera` are dropped.
+///
+/// ```ignore
+/// app.add_resource(SplitScreen::new());
+/// // in start
+/// let split = ctx.resources.get_mut::<SplitScreen>().unwrap();
+/// for (controls, hero) in [(Controls::Keys(KeySet::WASD), a), (Controls::Gamepad, b)] {
+///     let p = split.add_player(controls).unwrap();
+///     split.follow(p, hero);
+/// }
+/// // in update
+/// let split = ctx.resources.get::<SplitScreen>().unwrap();
+/// for (p, &hero) in self.heroes.iter().enumerate() {
+///     let step = split.input(p).movement * SPEED * ctx.dt;
+///     ctx.pool.sprite_mut(hero).unwrap().transform.translation += step;
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SplitScreen {
+    pub layout: SplitLayout,
+    players: Vec<SplitPlayer>,
+}
+
+impl SplitScreen {
+    pub const MAX_PLAYERS: usize = 4;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_layout(mut self, layout: SplitLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Adds a player with a default camera, `None` once there are four.
+    pub fn add_player(&mut self, controls: Controls) -> Option<usize> {
+        if self.players.len() == Self::MAX_PLAYERS {
+            return None;
+        }
+        self.players.push(SplitPlayer {
+            controls,
+            follow: None,
+            camera: Camera::default(),
+            input: PlayerInput::default(),
+        });
+        Some(self.players.len() - 1)
+    }
+
+    /// Removes player `idx`, the following ones move up a view.
+    pub fn remove_player(&mut self, idx: usize) -> Option<SplitPlayer> {
+        (idx < self.players.len()).then(|| self.players.remove(idx))
+    }
+
+    pub fn follow(&mut self, idx: usize, id: EntityId) {
+        if let Some(p) = self.players.get_mut(idx) {
+            p.follow = Some(id);
+        }
+    }
+
+    pub fn players(&self) -> &[SplitPlayer] {
+        &self.players
+    }
+
+    pub fn player_mut(&mut self, idx: usize) -> Option<&mut SplitPlayer> {
+        self.players.get_mut(idx)
+    }
+
+    /// Player `idx`'s input this frame, nothing pressed for unknown players.
+    pub fn input(&self, idx: usize) -> PlayerInput {
+        self.players
+            .get(idx)
+            .map_or_else(PlayerInput::default, SplitPlayer::input)
+    }
+
+    /// Reads every player's input and lays out their cameras, called by the
+    /// app before scenes update.
+    ///
+    /// Players with devices assigned through
+    /// [`Ctx::assign_device_to_player`](crate::Ctx::assign_device_to_player)
+    /// read only those, the others read the merged `input`.
+    pub fn update(
+        &mut self,
+        input: &InputState,
+        devices: &InputDevices,
+        pool: &EntityPool,
+        screen: Vec2,
+    ) {
+        let viewports = self.layout.viewports(self.players.len());
+        for (i, (p, viewport)) in self.players.iter_mut().zip(viewports).enumerate() {
+            let input = devices.player(i).unwrap_or(input);
+            p.input = PlayerInput::read(&p.controls, input);
+            p.camera.viewport = viewport;
+            let center = p.camera.center;
+            p.camera.resize(screen);
+            let (_, size) = p.camera.output_viewport().to_pixels(screen);
+            p.camera.center = match p.follow.and_then(|id| pool.entities.get(&id)) {
+                Some(s) => s.transform.translation - size / p.camera.scale() * 0.5,
+                // resizing recenters on the origin, keep where the player was looking
+                None => center,
+            };
+        }
+    }
+
+    /// The players' cameras as laid out by the last [`SplitScreen::update`].
+    pub fn cameras(&self) -> impl Iterator<Item = Camera> + '_ {
+        self.players.iter().map(|p| p.camera)
+    }
+}
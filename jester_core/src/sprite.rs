@@ -1,26 +1,59 @@
 use glam::Vec2;
 
-use crate::Transform;
+use crate::{Aabb, Bounds, Obb, Transform};
 use std::{
     hash::{DefaultHasher, Hash, Hasher},
     ops::Deref,
-    path::Path,
+    path::{Component, Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
 };
 
+static NEXT_GENERATED_TEXTURE: AtomicU64 = AtomicU64::new(1);
+
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct TextureId(pub u64);
 
 impl TextureId {
+    /// Id of the image at `path`, the same for every spelling of it:
+    /// `./a.png`, `a.png` and `b/../a.png` all give one id.
+    ///
+    /// Ids from paths never equal [generated](TextureId::generated) ones; two
+    /// different paths hashing alike are caught when loaded, see
+    /// [`AssetError::IdCollision`](crate::AssetError::IdCollision).
     pub fn from_path<P>(path: P) -> Self
     where
         P: AsRef<Path>,
     {
-        let p = path.as_ref();
         let mut h = DefaultHasher::new();
-        p.hash(&mut h);
-        Self(h.finish())
+        normalize_path(path.as_ref()).hash(&mut h);
+        Self(h.finish() & !GENERATED_BIT)
+    }
+
+    /// Fresh id for textures that are not backed by a file on disk.
+    pub fn generated() -> Self {
+        Self(NEXT_GENERATED_TEXTURE.fetch_add(1, Ordering::Relaxed) | GENERATED_BIT)
+    }
+}
+
+const GENERATED_BIT: u64 = 1 << 63;
+
+/// `path` without `.` and with `..` applied where it follows a directory,
+/// without touching the file system.
+pub(crate) fn normalize_path(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for c in path.components() {
+        match c {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(out.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                out.pop();
+            }
+            c => out.push(c),
+        }
     }
+    out
 }
 
 impl Deref for TextureId {
@@ -34,28 +67,236 @@ impl Deref for TextureId {
 unsafe impl bytemuck::Pod for TextureId {}
 unsafe impl bytemuck::Zeroable for TextureId {}
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 #[repr(C)]
 pub struct SpriteInstance {
     pub pos_size: [f32; 4],
     pub uv: [f32; 4],
+    /// UV offset and scroll speed per second, both in fractions of the `uv` rect.
+    pub uv_anim: [f32; 4],
+    /// Free-form per-sprite parameters for custom shaders, ignored by the default one.
+    pub custom: [f32; 4],
+    /// Row of the batch's palette texture, see [`Palette`].
+    pub palette_row: f32,
+    /// 0 is nearest; only compared between sprites in [`AlphaSorting::TwoPass`].
+    pub depth: f32,
+}
+
+impl Default for SpriteInstance {
+    fn default() -> Self {
+        Self {
+            pos_size: [0.0, 0.0, 1.0, 1.0],
+            uv: [0.0, 0.0, 1.0, 1.0],
+            uv_anim: [0.0; 4],
+            custom: [0.0; 4],
+            palette_row: 0.0,
+            depth: 0.0,
+        }
+    }
 }
 
 unsafe impl bytemuck::Pod for SpriteInstance {}
 unsafe impl bytemuck::Zeroable for SpriteInstance {}
 
+/// How a sprite combines with what is already drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum BlendMode {
+    #[default]
+    Alpha,
+    Additive,
+    /// Darkens by the sprite colour; alpha is ignored, so use white where it should have no effect.
+    Multiply,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 3] = [BlendMode::Alpha, BlendMode::Additive, BlendMode::Multiply];
+}
+
+/// Palette swap: the sprite texture holds colour indices in its red channel
+/// (0, 1, 2, … as 8-bit values) and each row of `tex` is one colour variant.
+///
+/// Index `i` of row `row` is the texel at `(i, row)`, so one small palette
+/// texture recolours any number of sprites without duplicating them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub tex: TextureId,
+    pub row: u32,
+}
+
+/// Shader a sprite batch is drawn with, see [`Renderer::precompile`](crate::Renderer::precompile).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MaterialId(pub u32);
+
+impl MaterialId {
+    /// Plain textured sprites.
+    pub const DEFAULT: Self = Self(0);
+    /// Colours looked up in a [`Palette`].
+    pub const PALETTE: Self = Self(1);
+    /// Alpha-tested with depth writes, see [`SpriteBatch::cutout`].
+    pub const CUTOUT: Self = Self(2);
+    /// Multi-channel distance field text, see [`MsdfAtlas`](crate::MsdfAtlas).
+    pub const MSDF: Self = Self(3);
+    pub const BUILTIN: [Self; 4] = [Self::DEFAULT, Self::PALETTE, Self::CUTOUT, Self::MSDF];
+}
+
 #[derive(Debug)]
 pub struct SpriteBatch {
     pub tex: TextureId,
+    pub blend: BlendMode,
+    pub palette: Option<TextureId>,
+    /// Drawn with depth writes, texels under half alpha are discarded.
+    pub cutout: bool,
+    /// Shader of batches without `palette` or `cutout`.
+    pub material: MaterialId,
+    /// Set on batches of [`Sprite::is_static`] sprites: an id for exactly these
+    /// instances, which a backend may keep in GPU memory and draw again for as
+    /// long as the id stays the same.
+    pub baked: Option<u64>,
+    /// Set on batches drawn every frame from the same sprites, so a backend
+    /// can keep their instances and only upload what changed.
+    pub retained: Option<RetainedBatch>,
     pub instances: Vec<SpriteInstance>,
 }
 
+/// Identifies the instances of a [`SpriteBatch`] across frames.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetainedBatch {
+    /// The same for as long as the batch holds the same sprites, see [`SpriteGroup::id`](crate::SpriteGroup::id).
+    pub id: u64,
+    /// Counts the frames whose batches were built, see [`SpriteStore::generation`](crate::SpriteStore::generation).
+    pub generation: u64,
+    /// Instances added or changed since generation `generation - 1`; the
+    /// rest are as they were then, minus any past the new end.
+    pub dirty: Vec<std::ops::Range<usize>>,
+}
+
+impl SpriteBatch {
+    /// A batch of `instances` drawn with `tex`, alpha blended and with the
+    /// default material, e.g. for [`Scene::render`](crate::Scene::render).
+    pub fn new(tex: TextureId, instances: Vec<SpriteInstance>) -> Self {
+        Self {
+            tex,
+            blend: BlendMode::default(),
+            palette: None,
+            cutout: false,
+            material: MaterialId::DEFAULT,
+            baked: None,
+            retained: None,
+            instances,
+        }
+    }
+
+    /// The shader actually used: a palette or cutout overrides `material`.
+    pub fn effective_material(&self) -> MaterialId {
+        if self.palette.is_some() {
+            MaterialId::PALETTE
+        } else if self.cutout {
+            MaterialId::CUTOUT
+        } else {
+            self.material
+        }
+    }
+}
+
+/// How blended and opaque sprites are ordered across all layers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AlphaSorting {
+    /// Everything back to front by layer, correct for any blending.
+    #[default]
+    Painter,
+    /// [`Sprite::cutout`] sprites first, front to back with depth writes,
+    /// then the rest back to front, hidden where a cutout sprite is in front.
+    ///
+    /// Cutout sprites then batch freely across layers without showing through
+    /// each other. Falls back to `Painter` on backends without depth testing.
+    TwoPass,
+}
+
+/// How sprites within one layer are ordered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortMode {
+    /// Grouped by texture for the fewest draw calls; sprites sharing a
+    /// texture draw in spawn order.
+    #[default]
+    Batched,
+    /// Back to front by `translation.y`: sprites lower on screen draw later,
+    /// as top-down games need for characters walking behind props.
+    YSort,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Sprite {
     pub transform: Transform,
+    /// Size before `transform.scale`; `None` takes the texture's size when spawned.
     pub size: Option<Vec2>,
     pub uv: [f32; 4],
     pub tex: TextureId,
+    /// Lower layers draw first, so UI on a higher layer always covers the
+    /// world; a `sort_key` overrides it.
+    pub layer: i32,
+    pub blend: BlendMode,
+    /// Static texture offset within `uv`, wrapping around its edges.
+    pub uv_offset: Vec2,
+    /// Texture scroll per second of renderer time, e.g. for water or conveyor belts.
+    pub uv_scroll: Vec2,
+    /// Passed to the shader as-is, e.g. a dissolve amount or flash intensity.
+    pub custom: [f32; 4],
+    pub palette: Option<Palette>,
+    /// Texels are either fully opaque or fully transparent, e.g. tiles; see [`AlphaSorting::TwoPass`].
+    pub cutout: bool,
+    /// Shader used when there is no `palette` and `cutout` is off.
+    pub material: MaterialId,
+    /// Never moves, e.g. background tiles: its batch is uploaded to the GPU
+    /// once and drawn from there until it changes, see [`SpriteStore`](crate::SpriteStore).
+    pub is_static: bool,
+    /// Explicit place in the draw order, overriding `layer` and
+    /// [`SortMode::YSort`], for scenes where neither fits, e.g. a hand of
+    /// cards dragged across the board.
+    ///
+    /// Keyed sprites draw after every unkeyed sprite of every layer, lowest
+    /// key first, so sprites that must cover them, e.g. sprite UI, need keys
+    /// too. Equal keys draw in spawn order. Layers with keyed sprites lose
+    /// batch caching.
+    pub sort_key: Option<u64>,
+}
+
+impl Sprite {
+    /// The area the sprite covers in the world: an [`Aabb`] unless it is
+    /// rotated, then an [`Obb`]. Sprites without a size count as one unit.
+    pub fn world_bounds(&self) -> Bounds {
+        let t = &self.transform;
+        let size = self.size.unwrap_or(Vec2::ONE) * t.scale.abs();
+        match t.rotation == 0.0 {
+            true => Bounds::Aabb(Aabb::from_center_size(t.translation, size)),
+            false => Bounds::Obb(Obb::new(t.translation, size, t.rotation)),
+        }
+    }
+
+    /// Instance data the sprite draws with, at depth 0.
+    pub fn instance(&self) -> SpriteInstance {
+        let sz = self
+            .size
+            .map(|size| size * self.transform.scale)
+            .unwrap_or(Vec2::ONE);
+        SpriteInstance {
+            pos_size: [
+                self.transform.translation.x,
+                self.transform.translation.y,
+                sz.x,
+                sz.y,
+            ],
+            uv: self.uv,
+            uv_anim: [
+                self.uv_offset.x,
+                self.uv_offset.y,
+                self.uv_scroll.x,
+                self.uv_scroll.y,
+            ],
+            custom: self.custom,
+            palette_row: self.palette.map_or(0.0, |p| p.row as f32),
+            depth: 0.0,
+        }
+    }
 }
 
 impl Default for Sprite {
@@ -65,6 +306,16 @@ impl Default for Sprite {
             transform: Transform::default(),
             uv: [0.0, 0.0, 1.0, 1.0],
             tex: TextureId(0),
+            layer: 0,
+            blend: BlendMode::Alpha,
+            uv_offset: Vec2::ZERO,
+            uv_scroll: Vec2::ZERO,
+            custom: [0.0; 4],
+            palette: None,
+            cutout: false,
+            material: MaterialId::DEFAULT,
+            is_static: false,
+            sort_key: None,
         }
     }
 }
@@ -0,0 +1,287 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use hashbrown::HashMap;
+
+use crate::{BlendMode, EntityId, MaterialId, Sprite, SpriteInstance};
+
+/// Everything a batch is split on, in draw order within a layer: blend mode
+/// first since switching pipelines costs more than switching textures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct GroupKey {
+    layer: i32,
+    blend: BlendMode,
+    material: MaterialId,
+    palette: Option<u64>,
+    tex: u64,
+    cutout: bool,
+    is_static: bool,
+}
+
+impl GroupKey {
+    fn of(s: &Sprite) -> Self {
+        Self {
+            layer: s.layer,
+            blend: s.blend,
+            material: s.material,
+            palette: s.palette.map(|p| p.tex.0),
+            tex: s.tex.0,
+            cutout: s.cutout && s.palette.is_none(),
+            is_static: s.is_static,
+        }
+    }
+}
+
+/// Sprites that draw in one batch, with their instance data kept up to date
+/// next to them so a batch is one copy of [`SpriteGroup::instances`].
+///
+/// Sprites are kept in spawn order, the order of their [`EntityId`]s, so
+/// overlapping sprites of a group draw the same way whatever was despawned.
+#[derive(Clone, Debug, Default)]
+pub struct SpriteGroup {
+    ids: Vec<EntityId>,
+    sprites: Vec<Sprite>,
+    instances: Vec<SpriteInstance>,
+    /// Per instance, whether it changed since [`SpriteStore::clear_dirty`].
+    dirty: Vec<bool>,
+    id: u64,
+    /// Changes with every change to the group.
+    version: u64,
+}
+
+impl SpriteGroup {
+    pub fn ids(&self) -> &[EntityId] {
+        &self.ids
+    }
+
+    pub fn sprites(&self) -> &[Sprite] {
+        &self.sprites
+    }
+
+    /// One per sprite, in the same order, with a depth of 0.
+    pub fn instances(&self) -> &[SpriteInstance] {
+        &self.instances
+    }
+
+    /// Stays the same for as long as the group has sprites.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Runs of instances added or changed since the last [`SpriteStore::clear_dirty`].
+    pub fn dirty_ranges(&self) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for (i, _) in self.dirty.iter().enumerate().filter(|(_, d)| **d) {
+            match ranges.last_mut() {
+                Some(r) if r.end == i => r.end += 1,
+                _ => ranges.push(i..i + 1),
+            }
+        }
+        ranges
+    }
+
+    /// Any sprite of the group; they share layer, texture, blend mode, palette texture and material.
+    pub fn first(&self) -> &Sprite {
+        &self.sprites[0]
+    }
+
+    /// Whether the group draws with [`SpriteBatch::cutout`](crate::SpriteBatch::cutout) under two-pass sorting.
+    pub fn cutout(&self) -> bool {
+        GroupKey::of(self.first()).cutout
+    }
+
+    /// For [`SpriteBatch::baked`](crate::SpriteBatch::baked): an id of the
+    /// current instances if the group holds static sprites.
+    pub fn baked(&self) -> Option<u64> {
+        self.first().is_static.then_some(self.version)
+    }
+
+    /// Adds `sprite` in spawn order, returning its slot; the sprites after
+    /// it move up one slot.
+    fn insert(&mut self, id: EntityId, sprite: Sprite) -> usize {
+        let slot = self.ids.partition_point(|i| i.to_raw() < id.to_raw());
+        self.ids.insert(slot, id);
+        self.instances.insert(slot, sprite.instance());
+        self.dirty.insert(slot, true);
+        self.dirty[slot..].fill(true);
+        self.sprites.insert(slot, sprite);
+        slot
+    }
+
+    /// Removes slot `slot`, returning its sprite; the sprites after it move
+    /// down one slot, so the rest keep their order.
+    fn remove(&mut self, slot: usize) -> Sprite {
+        self.ids.remove(slot);
+        self.instances.remove(slot);
+        self.dirty.remove(slot);
+        self.dirty[slot..].fill(true);
+        self.sprites.remove(slot)
+    }
+}
+
+/// The sprites of an [`EntityPool`](crate::EntityPool), stored by batch
+/// rather than by id.
+///
+/// Reads like a map from [`EntityId`] to [`Sprite`]. Sprites handed out
+/// through [`SpriteStore::get_mut`] are remembered and their instance data
+/// rebuilt by the next [`SpriteStore::refresh`], moving them to another group
+/// if their texture, layer or material changed; all others are left as they
+/// are, so building the frame's batches costs a copy per group.
+///
+/// Groups of [`Sprite::is_static`] sprites keep their
+/// [`SpriteGroup::baked`] id until one of them is added, removed or changed
+/// through `get_mut`, or [`SpriteStore::invalidate_static`] is called, so
+/// the backend does not upload them again in between.
+#[derive(Debug, Default)]
+pub struct SpriteStore {
+    groups: BTreeMap<GroupKey, SpriteGroup>,
+    slots: HashMap<EntityId, (GroupKey, usize)>,
+    dirty: Vec<EntityId>,
+    next_version: u64,
+    generation: u64,
+}
+
+impl SpriteStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    pub fn contains_key(&self, id: &EntityId) -> bool {
+        self.slots.contains_key(id)
+    }
+
+    pub fn get(&self, id: &EntityId) -> Option<&Sprite> {
+        let (key, slot) = self.slots.get(id)?;
+        Some(&self.groups[key].sprites[*slot])
+    }
+
+    /// The sprite of `id`, whose changes show from the next [`SpriteStore::refresh`] on.
+    pub fn get_mut(&mut self, id: &EntityId) -> Option<&mut Sprite> {
+        let (key, slot) = self.slots.get(id)?;
+        self.dirty.push(*id);
+        Some(&mut self.groups.get_mut(key)?.sprites[*slot])
+    }
+
+    /// Adds or replaces the sprite of `id`, returning the one replaced.
+    pub fn insert(&mut self, id: EntityId, sprite: Sprite) -> Option<Sprite> {
+        let old = self.remove(&id);
+        let key = GroupKey::of(&sprite);
+        let version = self.bump();
+        let group = self.groups.entry(key).or_insert_with(|| SpriteGroup {
+            id: version,
+            ..Default::default()
+        });
+        group.version = version;
+        let slot = group.insert(id, sprite);
+        for (i, id) in group.ids.iter().enumerate().skip(slot) {
+            self.slots.insert(*id, (key, i));
+        }
+        old
+    }
+
+    pub fn remove(&mut self, id: &EntityId) -> Option<Sprite> {
+        let (key, slot) = self.slots.remove(id)?;
+        let version = self.bump();
+        let group = self.groups.get_mut(&key)?;
+        group.version = version;
+        let sprite = group.remove(slot);
+        for (i, id) in group.ids.iter().enumerate().skip(slot) {
+            self.slots.insert(*id, (key, i));
+        }
+        if group.ids.is_empty() {
+            self.groups.remove(&key);
+        }
+        Some(sprite)
+    }
+
+    pub fn clear(&mut self) {
+        self.groups.clear();
+        self.slots.clear();
+        self.dirty.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&EntityId, &Sprite)> {
+        self.groups
+            .values()
+            .flat_map(|g| g.ids.iter().zip(&g.sprites))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &EntityId> {
+        self.groups.values().flat_map(|g| &g.ids)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Sprite> {
+        self.groups.values().flat_map(|g| &g.sprites)
+    }
+
+    /// Every sprite, all of them rebuilt by the next [`SpriteStore::refresh`].
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Sprite> {
+        self.dirty.extend(self.slots.keys().copied());
+        self.groups.values_mut().flat_map(|g| &mut g.sprites)
+    }
+
+    /// Rebuilds the instance data of sprites changed through [`SpriteStore::get_mut`].
+    pub fn refresh(&mut self) {
+        for id in std::mem::take(&mut self.dirty) {
+            let Some(&(key, slot)) = self.slots.get(&id) else {
+                continue;
+            };
+            let version = self.bump();
+            let group = self.groups.get_mut(&key).expect("slot of a live group");
+            let sprite = group.sprites[slot];
+            if GroupKey::of(&sprite) == key {
+                let instance = sprite.instance();
+                if group.instances[slot] != instance {
+                    group.instances[slot] = instance;
+                    group.dirty[slot] = true;
+                    group.version = version;
+                }
+            } else {
+                self.insert(id, sprite);
+            }
+        }
+    }
+
+    /// Gives every static group a new [`SpriteGroup::baked`] id, so the
+    /// backend uploads them again.
+    pub fn invalidate_static(&mut self) {
+        for g in self.groups.values_mut() {
+            self.next_version += 1;
+            g.version = self.next_version;
+        }
+    }
+
+    /// Marks every instance as uploaded, once the frame's batches are built,
+    /// and starts the next [`SpriteStore::generation`].
+    pub fn clear_dirty(&mut self) {
+        for g in self.groups.values_mut() {
+            g.dirty.fill(false);
+        }
+        self.generation += 1;
+    }
+
+    /// Calls to [`SpriteStore::clear_dirty`] so far; [`SpriteGroup::dirty_ranges`]
+    /// are the changes since the previous generation.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn bump(&mut self) -> u64 {
+        self.next_version += 1;
+        self.next_version
+    }
+
+    /// Groups in draw order: by layer, then blend mode, material, palette and texture.
+    ///
+    /// Only reflects changes made through [`SpriteStore::get_mut`] after a [`SpriteStore::refresh`].
+    pub fn groups(&self) -> impl Iterator<Item = &SpriteGroup> {
+        self.groups.values()
+    }
+}
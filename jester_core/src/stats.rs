@@ -0,0 +1,137 @@
+use hashbrown::HashMap;
+
+/// How a [`Modifier`] changes the value of a stat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ModifierKind {
+    /// Added to the base value.
+    Flat,
+    /// Fraction of the flat total added on top, e.g. `0.2` for +20%.
+    Percent,
+}
+
+/// A change to a stat, e.g. from a piece of equipment or a potion.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Modifier {
+    /// What applied it, so everything from one source can be removed together.
+    pub source: String,
+    pub kind: ModifierKind,
+    pub value: f32,
+    /// Seconds left before [`Stats::tick`] removes it, `None` lasts until removed.
+    pub remaining: Option<f32>,
+}
+
+impl Modifier {
+    pub fn flat(source: impl Into<String>, value: f32) -> Self {
+        Self {
+            source: source.into(),
+            kind: ModifierKind::Flat,
+            value,
+            remaining: None,
+        }
+    }
+
+    pub fn percent(source: impl Into<String>, value: f32) -> Self {
+        Self {
+            source: source.into(),
+            kind: ModifierKind::Percent,
+            value,
+            remaining: None,
+        }
+    }
+
+    pub fn with_duration(mut self, secs: f32) -> Self {
+        self.remaining = Some(secs);
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Stat {
+    base: f32,
+    modifiers: Vec<Modifier>,
+}
+
+/// Named numeric stats, each a base value changed by [`Modifier`]s.
+///
+/// A stat is worth `(base + flat) * (1 + percent)`, the sums of its flat and
+/// percent modifiers. Stats never set are worth 0.
+///
+/// ```ignore
+/// let mut stats = Stats::new().with_base("speed", 100.0);
+/// stats.add_modifier("speed", Modifier::percent("boots", 0.25));
+/// stats.add_modifier("speed", Modifier::flat("haste", 20.0).with_duration(5.0));
+/// assert_eq!(stats.get("speed"), 150.0);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+    stats: HashMap<String, Stat>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_base(mut self, stat: &str, base: f32) -> Self {
+        self.set_base(stat, base);
+        self
+    }
+
+    pub fn get(&self, stat: &str) -> f32 {
+        let Some(s) = self.stats.get(stat) else {
+            return 0.0;
+        };
+        let (mut flat, mut percent) = (0.0, 0.0);
+        for m in &s.modifiers {
+            match m.kind {
+                ModifierKind::Flat => flat += m.value,
+                ModifierKind::Percent => percent += m.value,
+            }
+        }
+        (s.base + flat) * (1.0 + percent)
+    }
+
+    pub fn base(&self, stat: &str) -> f32 {
+        self.stats.get(stat).map_or(0.0, |s| s.base)
+    }
+
+    pub fn set_base(&mut self, stat: &str, base: f32) {
+        self.stats.entry_ref(stat).or_default().base = base;
+    }
+
+    pub fn add_modifier(&mut self, stat: &str, modifier: Modifier) {
+        self.stats
+            .entry_ref(stat)
+            .or_default()
+            .modifiers
+            .push(modifier);
+    }
+
+    pub fn modifiers(&self, stat: &str) -> &[Modifier] {
+        self.stats.get(stat).map_or(&[], |s| &s.modifiers)
+    }
+
+    /// Removes every modifier applied by `source`, e.g. when unequipping an item.
+    pub fn remove_source(&mut self, source: &str) {
+        for s in self.stats.values_mut() {
+            s.modifiers.retain(|m| m.source != source);
+        }
+    }
+
+    /// Counts timed modifiers down by `dt` seconds and drops the expired ones.
+    pub fn tick(&mut self, dt: f32) {
+        for s in self.stats.values_mut() {
+            s.modifiers.retain_mut(|m| match &mut m.remaining {
+                Some(left) => {
+                    *left -= dt;
+                    *left > 0.0
+                }
+                None => true,
+            });
+        }
+    }
+}
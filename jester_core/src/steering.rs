@@ -0,0 +1,169 @@
+use glam::Vec2;
+
+/// Seconds [`Steering::arrive`] takes to match the speed it wants, short enough not to overshoot.
+const ARRIVE_TIME: f32 = 0.1;
+
+/// Linear velocity in world units per second.
+///
+/// Inserted into [`EntityPool::velocities`](crate::EntityPool::velocities), the
+/// app moves the sprite by it every frame after the scenes update.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Velocity(pub Vec2);
+
+/// Limits of a steered agent and the classic steering behaviors.
+///
+/// Each behavior returns a force; sum the weighted forces of the behaviors in
+/// play and feed them to [`Steering::apply`] once per frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Steering {
+    pub max_speed: f32,
+    /// Largest change of velocity per second.
+    pub max_force: f32,
+}
+
+impl Steering {
+    pub fn new(max_speed: f32, max_force: f32) -> Self {
+        Self {
+            max_speed,
+            max_force,
+        }
+    }
+
+    /// Full speed towards `target`.
+    pub fn seek(&self, pos: Vec2, vel: Velocity, target: Vec2) -> Vec2 {
+        let desired = (target - pos).normalize_or_zero() * self.max_speed;
+        desired - vel.0
+    }
+
+    /// Full speed away from `threat` while it is closer than `panic_radius`.
+    pub fn flee(&self, pos: Vec2, vel: Velocity, threat: Vec2, panic_radius: f32) -> Vec2 {
+        if pos.distance_squared(threat) > panic_radius * panic_radius {
+            return Vec2::ZERO;
+        }
+        let desired = (pos - threat).normalize_or_zero() * self.max_speed;
+        desired - vel.0
+    }
+
+    /// Like [`Steering::seek`], slowing down within `slow_radius` to stop on `target`.
+    pub fn arrive(&self, pos: Vec2, vel: Velocity, target: Vec2, slow_radius: f32) -> Vec2 {
+        let to = target - pos;
+        let dist = to.length();
+        if dist <= f32::EPSILON {
+            return -vel.0 / ARRIVE_TIME;
+        }
+        let speed = match dist < slow_radius {
+            true => self.max_speed * dist / slow_radius,
+            false => self.max_speed,
+        };
+        (to / dist * speed - vel.0) / ARRIVE_TIME
+    }
+
+    /// Aimless but smooth roaming, see [`Wander`].
+    pub fn wander(&self, pos: Vec2, vel: Velocity, wander: &mut Wander, dt: f32) -> Vec2 {
+        wander.angle += (wander.next_random() * 2.0 - 1.0) * wander.jitter * dt;
+        let heading = vel.0.try_normalize().unwrap_or(Vec2::X);
+        let circle = pos + heading * wander.distance;
+        let target = circle + Vec2::from_angle(wander.angle) * wander.radius;
+        self.seek(pos, vel, target)
+    }
+
+    /// Separation, alignment and cohesion against `neighbors` (positions and
+    /// velocities, the agent itself excluded), see [`Flock`].
+    pub fn flock<I>(&self, pos: Vec2, vel: Velocity, neighbors: I, flock: &Flock) -> Vec2
+    where
+        I: IntoIterator<Item = (Vec2, Velocity)>,
+    {
+        let mut separation = Vec2::ZERO;
+        let mut heading = Vec2::ZERO;
+        let mut center = Vec2::ZERO;
+        let mut count = 0;
+        for (other, other_vel) in neighbors {
+            let away = pos - other;
+            let dist_sq = away.length_squared();
+            if dist_sq > flock.radius * flock.radius || dist_sq <= f32::EPSILON {
+                continue;
+            }
+            if dist_sq < flock.separation_radius * flock.separation_radius {
+                // closer neighbors push harder
+                separation += away / dist_sq;
+            }
+            heading += other_vel.0;
+            center += other;
+            count += 1;
+        }
+        if count == 0 {
+            return Vec2::ZERO;
+        }
+        let n = count as f32;
+        let separate = match separation == Vec2::ZERO {
+            true => Vec2::ZERO,
+            false => separation.normalize() * self.max_speed - vel.0,
+        };
+        let align = (heading / n).normalize_or_zero() * self.max_speed - vel.0;
+        let cohere = self.seek(pos, vel, center / n);
+        separate * flock.separation + align * flock.alignment + cohere * flock.cohesion
+    }
+
+    /// Changes `vel` by `force` for `dt` seconds, within the agent's limits.
+    pub fn apply(&self, vel: &mut Velocity, force: Vec2, dt: f32) {
+        let force = force.clamp_length_max(self.max_force);
+        vel.0 = (vel.0 + force * dt).clamp_length_max(self.max_speed);
+    }
+}
+
+/// State of [`Steering::wander`]: a point jittering on a circle ahead of the agent is sought.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Wander {
+    pub radius: f32,
+    /// How far ahead the circle is.
+    pub distance: f32,
+    /// Largest turn of the target on the circle per second, in radians.
+    pub jitter: f32,
+    angle: f32,
+    rng: u32,
+}
+
+impl Wander {
+    /// `seed` decorrelates agents that would otherwise wander in lockstep.
+    pub fn new(radius: f32, distance: f32, jitter: f32, seed: u32) -> Self {
+        Self {
+            radius,
+            distance,
+            jitter,
+            angle: 0.0,
+            rng: seed.max(1),
+        }
+    }
+
+    // xorshift32, good enough for jitter and free of dependencies
+    fn next_random(&mut self) -> f32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        self.rng as f32 / u32::MAX as f32
+    }
+}
+
+/// Neighborhood and weights of [`Steering::flock`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Flock {
+    /// Neighbors farther away are ignored.
+    pub radius: f32,
+    /// Neighbors closer than this are steered away from.
+    pub separation_radius: f32,
+    pub separation: f32,
+    pub alignment: f32,
+    pub cohesion: f32,
+}
+
+impl Default for Flock {
+    fn default() -> Self {
+        Self {
+            radius: 64.0,
+            separation_radius: 24.0,
+            separation: 1.5,
+            alignment: 1.0,
+            cohesion: 1.0,
+        }
+    }
+}
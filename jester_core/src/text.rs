@@ -0,0 +1,476 @@
+use glam::Vec2;
+use hashbrown::HashMap;
+use std::{path::Path, sync::Arc};
+use tracing::warn;
+
+use crate::{Color, Error, SpriteInstance, TextureId};
+
+/// A loaded TrueType/OpenType font. Cheap to clone, can live in `Resources`.
+#[derive(Clone)]
+pub struct Font {
+    inner: Arc<fontdue::Font>,
+    /// The font file, for outlines, see [`MsdfAtlas`](crate::MsdfAtlas).
+    data: Arc<[u8]>,
+}
+
+impl std::fmt::Debug for Font {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Font").field(&self.inner.name()).finish()
+    }
+}
+
+impl Font {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let font = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default())
+            .map_err(|e| Error::Font(e.to_string()))?;
+        Ok(Self {
+            inner: Arc::new(font),
+            data: bytes.into(),
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn inner(&self) -> &fontdue::Font {
+        &self.inner
+    }
+
+    /// Whether both are clones of one loaded font.
+    pub fn ptr_eq(&self, other: &Font) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    pub(crate) fn face(&self) -> Result<ttf_parser::Face<'_>, Error> {
+        ttf_parser::Face::parse(&self.data, 0).map_err(|e| Error::Font(e.to_string()))
+    }
+}
+
+/// How a [`Text`] looks.
+#[derive(Clone, Debug)]
+pub struct TextStyle {
+    pub font: Font,
+    /// Pixel size the glyphs are rasterized at.
+    pub size: f32,
+    pub color: Color,
+}
+
+/// Text drawn in the world through every camera, spawned with `Ctx::spawn_text`.
+///
+/// The app rasterizes the glyphs into one atlas per font, size and color and
+/// draws each atlas's texts as sprite batches after the pool's sprites.
+#[derive(Clone, Debug)]
+pub struct Text {
+    pub text: String,
+    pub style: TextStyle,
+    /// World position (y up) of the top-left corner of the first line.
+    pub top_left: Vec2,
+    /// Lower layers draw first; texts of one layer draw in spawn order.
+    pub layer: i32,
+}
+
+/// Rasterizes `text` into a white RGBA-8 image whose alpha is the glyph coverage.
+///
+/// Lines are split on `\n`. Returns `(width, height, pixels)`, never smaller than 1x1.
+pub fn rasterize_text(font: &Font, text: &str, size: f32) -> (u32, u32, Vec<u8>) {
+    let font = font.inner();
+    let (ascent, line_height) = match font.horizontal_line_metrics(size) {
+        Some(m) => (m.ascent, m.new_line_size),
+        None => (size, size),
+    };
+
+    // first pass: measure
+    let mut width = 0.0f32;
+    let mut lines = 0;
+    for line in text.split('\n') {
+        width = width.max(line_advance(font, line, size));
+        lines += 1;
+    }
+    let w = (width.ceil() as u32).max(1);
+    let h = ((lines as f32 * line_height).ceil() as u32).max(1);
+
+    // second pass: blit coverage
+    let mut pixels = vec![0u8; w as usize * h as usize * 4];
+    for (row, line) in text.split('\n').enumerate() {
+        let baseline = ascent + row as f32 * line_height;
+        let mut pen = 0.0f32;
+        let mut prev = None;
+        for c in line.chars() {
+            if let Some(p) = prev {
+                pen += font.horizontal_kern(p, c, size).unwrap_or(0.0);
+            }
+            let (m, bitmap) = font.rasterize(c, size);
+            let x0 = (pen + m.xmin as f32).round() as i32;
+            let y0 = (baseline - m.height as f32 - m.ymin as f32).round() as i32;
+            for gy in 0..m.height {
+                for gx in 0..m.width {
+                    let (x, y) = (x0 + gx as i32, y0 + gy as i32);
+                    if x < 0 || y < 0 || x >= w as i32 || y >= h as i32 {
+                        continue;
+                    }
+                    let i = ((y as u32 * w + x as u32) * 4) as usize;
+                    let cov = bitmap[gy * m.width + gx];
+                    pixels[i..i + 3].fill(255);
+                    pixels[i + 3] = pixels[i + 3].max(cov);
+                }
+            }
+            pen += m.advance_width;
+            prev = Some(c);
+        }
+    }
+    (w, h, pixels)
+}
+
+fn line_advance(font: &fontdue::Font, line: &str, size: f32) -> f32 {
+    let mut pen = 0.0;
+    let mut prev = None;
+    for c in line.chars() {
+        if let Some(p) = prev {
+            pen += font.horizontal_kern(p, c, size).unwrap_or(0.0);
+        }
+        pen += font.metrics(c, size).advance_width;
+        prev = Some(c);
+    }
+    pen
+}
+
+/// Placement of one glyph inside a [`GlyphAtlas`].
+#[derive(Clone, Copy, Debug)]
+pub struct Glyph {
+    /// Sprite-space UVs (v up), ready for [`SpriteInstance::uv`].
+    pub uv: [f32; 4],
+    pub size: Vec2,
+    /// Offset of the bitmap's bottom-left corner from the pen position on the baseline.
+    pub offset: Vec2,
+    pub advance: f32,
+}
+
+/// Where a glyph sits in the atlas texture and when it was last drawn.
+#[derive(Debug)]
+struct Entry {
+    glyph: Glyph,
+    /// Texel rect `x, y, w, h` from the top-left of the atlas.
+    rect: [u32; 4],
+    last_used: u64,
+}
+
+/// Pen positions of a laid out line, kerning applied.
+#[derive(Debug)]
+struct ShapedLine {
+    pens: Vec<(char, f32)>,
+    width: f32,
+    last_used: u64,
+}
+
+/// Glyphs rasterized into a single texture at a fixed pixel size as text asks for them.
+///
+/// Call [`GlyphAtlas::prepare`] with every string before laying it out, then
+/// [`GlyphAtlas::finish_frame`] once per frame and re-upload
+/// [`GlyphAtlas::pixels`] when it returns `true`. The texture doubles in height
+/// when full; at its largest, glyphs not drawn this frame are dropped and the
+/// rest packed again.
+#[derive(Debug)]
+pub struct GlyphAtlas {
+    pub tex: TextureId,
+    pub size: f32,
+    pub width: u32,
+    pub height: u32,
+    font: Font,
+    ascent: f32,
+    line_height: f32,
+    glyphs: HashMap<char, Entry>,
+    lines: HashMap<String, ShapedLine>,
+    pixels: Vec<u8>,
+    /// Shelf packer state: next free x, top of the current shelf and its height.
+    cursor: (u32, u32, u32),
+    frame: u64,
+    dirty: bool,
+}
+
+impl GlyphAtlas {
+    const WIDTH: u32 = 512;
+    const INITIAL_HEIGHT: u32 = 64;
+    const MAX_HEIGHT: u32 = 4096;
+    const PADDING: u32 = 1;
+    /// Shaped lines kept around; past this, lines not drawn this frame are forgotten.
+    const MAX_LINES: usize = 256;
+
+    /// An empty atlas, glyphs are added by [`GlyphAtlas::prepare`].
+    pub fn new(font: &Font, size: f32) -> Self {
+        let (ascent, line_height) = match font.inner().horizontal_line_metrics(size) {
+            Some(m) => (m.ascent, m.new_line_size),
+            None => (size, size),
+        };
+        let mut atlas = Self {
+            tex: TextureId::generated(),
+            size,
+            width: Self::WIDTH,
+            height: Self::INITIAL_HEIGHT,
+            font: font.clone(),
+            ascent,
+            line_height,
+            glyphs: HashMap::new(),
+            lines: HashMap::new(),
+            pixels: vec![0; (Self::WIDTH * Self::INITIAL_HEIGHT * 4) as usize],
+            cursor: (Self::PADDING, Self::PADDING, 0),
+            frame: 0,
+            dirty: true,
+        };
+        // the stand-in for characters the font lacks
+        atlas.prepare("?");
+        atlas
+    }
+
+    /// Bakes `' '..='~'` up front. Returns the atlas plus the RGBA-8 pixels to upload under `atlas.tex`.
+    pub fn ascii(font: &Font, size: f32) -> (Self, Vec<u8>) {
+        let mut atlas = Self::new(font, size);
+        let printable: String = (' '..='~').collect();
+        atlas.add_glyphs(&printable);
+        atlas.dirty = false;
+        let pixels = atlas.pixels.clone();
+        (atlas, pixels)
+    }
+
+    /// Rasterizes the glyphs of `text` not in the atlas yet and caches its shaped lines.
+    pub fn prepare(&mut self, text: &str) {
+        let frame = self.frame;
+        for line in text.split('\n') {
+            if let Some(shaped) = self.lines.get_mut(line) {
+                shaped.last_used = frame;
+            } else {
+                if self.lines.len() >= Self::MAX_LINES {
+                    self.lines.retain(|_, l| l.last_used == frame);
+                }
+                let shaped = self.shape(line);
+                self.lines.insert(line.to_owned(), shaped);
+            }
+        }
+        self.add_glyphs(text);
+    }
+
+    /// Ends usage tracking for this frame. Returns whether the texture changed since the last call.
+    pub fn finish_frame(&mut self) -> bool {
+        self.frame += 1;
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// The RGBA-8 texels of the whole atlas, `width` by `height`.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn line_height(&self) -> f32 {
+        self.line_height
+    }
+
+    pub fn glyph(&self, c: char) -> Option<&Glyph> {
+        self.glyphs
+            .get(&c)
+            .or_else(|| self.glyphs.get(&'?'))
+            .map(|e| &e.glyph)
+    }
+
+    /// Width and height of `text` laid out by [`GlyphAtlas::layout`].
+    pub fn measure(&self, text: &str) -> Vec2 {
+        let mut w = 0.0f32;
+        let mut lines = 0;
+        for line in text.split('\n') {
+            w = w.max(match self.lines.get(line) {
+                Some(shaped) => shaped.width,
+                None => self.shape(line).width,
+            });
+            lines += 1;
+        }
+        Vec2::new(w, lines as f32 * self.line_height)
+    }
+
+    /// One instance per visible glyph, `top_left` being the world position (y up) of the first line.
+    ///
+    /// Characters never passed to [`GlyphAtlas::prepare`] are drawn as `?`.
+    pub fn layout(&self, text: &str, top_left: Vec2) -> Vec<SpriteInstance> {
+        let mut out = Vec::with_capacity(text.len());
+        for (row, line) in text.split('\n').enumerate() {
+            let baseline = top_left.y - self.ascent - row as f32 * self.line_height;
+            let uncached;
+            let shaped = match self.lines.get(line) {
+                Some(shaped) => shaped,
+                None => {
+                    uncached = self.shape(line);
+                    &uncached
+                }
+            };
+            for &(c, pen) in &shaped.pens {
+                let Some(g) = self.glyph(c) else { continue };
+                if g.size.x > 0.0 && g.size.y > 0.0 {
+                    let center = Vec2::new(top_left.x + pen, baseline) + g.offset + g.size * 0.5;
+                    out.push(SpriteInstance {
+                        pos_size: [center.x, center.y, g.size.x, g.size.y],
+                        uv: g.uv,
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+        out
+    }
+
+    fn shape(&self, line: &str) -> ShapedLine {
+        let font = self.font.inner();
+        let mut pens = Vec::with_capacity(line.len());
+        let mut pen = 0.0;
+        let mut prev = None;
+        for c in line.chars() {
+            if let Some(p) = prev {
+                pen += font.horizontal_kern(p, c, self.size).unwrap_or(0.0);
+            }
+            pens.push((c, pen));
+            pen += font.metrics(c, self.size).advance_width;
+            prev = Some(c);
+        }
+        ShapedLine {
+            pens,
+            width: pen,
+            last_used: self.frame,
+        }
+    }
+
+    fn add_glyphs(&mut self, text: &str) {
+        // stamp first so a compaction triggered below keeps everything `text` uses
+        for c in text.chars() {
+            if let Some(e) = self.glyphs.get_mut(&c) {
+                e.last_used = self.frame;
+            }
+        }
+        for c in text.chars() {
+            if c == '\n' || self.glyphs.contains_key(&c) {
+                continue;
+            }
+            // missing from the font, `glyph` falls back to '?'
+            if c != '?' && self.font.inner().lookup_glyph_index(c) == 0 {
+                continue;
+            }
+            self.add_glyph(c);
+        }
+    }
+
+    fn add_glyph(&mut self, c: char) {
+        let (m, bitmap) = self.font.inner().rasterize(c, self.size);
+        let (w, h) = (m.width as u32, m.height as u32);
+        let Some((x, y)) = self.allocate(w, h) else {
+            warn!("glyph atlas full, drawing {c:?} as '?'");
+            return;
+        };
+        for row in 0..m.height {
+            for col in 0..m.width {
+                let i = (((y + row as u32) * self.width + x + col as u32) * 4) as usize;
+                self.pixels[i..i + 3].fill(255);
+                self.pixels[i + 3] = bitmap[row * m.width + col];
+            }
+        }
+        let rect = [x, y, w, h];
+        let glyph = Glyph {
+            uv: self.uv(rect),
+            size: Vec2::new(m.width as f32, m.height as f32),
+            offset: Vec2::new(m.xmin as f32, m.ymin as f32),
+            advance: m.advance_width,
+        };
+        let last_used = self.frame;
+        self.glyphs.insert(
+            c,
+            Entry {
+                glyph,
+                rect,
+                last_used,
+            },
+        );
+        self.dirty = true;
+    }
+
+    /// Finds room for a `w` by `h` bitmap, growing or compacting the atlas if needed.
+    fn allocate(&mut self, w: u32, h: u32) -> Option<(u32, u32)> {
+        if w + 2 * Self::PADDING > self.width {
+            return None;
+        }
+        loop {
+            let (mut x, mut y, mut shelf) = self.cursor;
+            // shelf-pack left to right, top to bottom
+            if x + w + Self::PADDING > self.width {
+                x = Self::PADDING;
+                y += shelf + Self::PADDING;
+                shelf = 0;
+            }
+            if y + h + Self::PADDING <= self.height {
+                self.cursor = (x + w + Self::PADDING, y, shelf.max(h));
+                return Some((x, y));
+            }
+            if self.height < Self::MAX_HEIGHT {
+                self.grow();
+            } else if !self.compact() {
+                return None;
+            }
+        }
+    }
+
+    fn grow(&mut self) {
+        self.height *= 2;
+        self.pixels
+            .resize((self.width * self.height * 4) as usize, 0);
+        // rows stay where they are, only v coordinates change
+        let rects: Vec<(char, [u32; 4])> = self.glyphs.iter().map(|(&c, e)| (c, e.rect)).collect();
+        for (c, rect) in rects {
+            let uv = self.uv(rect);
+            self.glyphs.get_mut(&c).expect("collected above").glyph.uv = uv;
+        }
+        self.dirty = true;
+    }
+
+    /// Repacks the glyphs drawn this frame into a cleared atlas. Returns `false` if nothing was freed.
+    fn compact(&mut self) -> bool {
+        let frame = self.frame;
+        let before = self.glyphs.len();
+        self.glyphs.retain(|&c, e| e.last_used == frame || c == '?');
+        if self.glyphs.len() == before {
+            return false;
+        }
+        let old = std::mem::take(&mut self.pixels);
+        self.pixels = vec![0; old.len()];
+        self.cursor = (Self::PADDING, Self::PADDING, 0);
+        // tallest first keeps shelves tight
+        let mut kept: Vec<(char, [u32; 4])> =
+            self.glyphs.iter().map(|(&c, e)| (c, e.rect)).collect();
+        kept.sort_by_key(|&(_, r)| std::cmp::Reverse(r[3]));
+        for (c, [ox, oy, w, h]) in kept {
+            let Some((x, y)) = self.allocate(w, h) else {
+                // packing in a new order can still come out taller
+                self.glyphs.remove(&c);
+                continue;
+            };
+            for row in 0..h {
+                let src = (((oy + row) * self.width + ox) * 4) as usize;
+                let dst = (((y + row) * self.width + x) * 4) as usize;
+                let len = (w * 4) as usize;
+                self.pixels[dst..dst + len].copy_from_slice(&old[src..src + len]);
+            }
+            let rect = [x, y, w, h];
+            let uv = self.uv(rect);
+            let e = self.glyphs.get_mut(&c).expect("kept above");
+            e.rect = rect;
+            e.glyph.uv = uv;
+        }
+        self.dirty = true;
+        true
+    }
+
+    /// Sprite-space UVs (v up) of a texel rect.
+    fn uv(&self, [x, y, w, h]: [u32; 4]) -> [f32; 4] {
+        let (width, height) = (self.width as f32, self.height as f32);
+        [
+            x as f32 / width,
+            1.0 - (y + h) as f32 / height,
+            (x + w) as f32 / width,
+            1.0 - y as f32 / height,
+        ]
+    }
+}
@@ -0,0 +1,257 @@
+use std::path::{Path, PathBuf};
+
+use glam::Vec2;
+use hashbrown::HashMap;
+
+use crate::{json::Json, Aabb, Error, SpriteBatch, SpriteInstance, TextureId, MAX_SPRITES};
+
+/// Tiles per side of a chunk, the unit [`Tilemap::batches`] culls by.
+pub const CHUNK_TILES: f32 = 16.0;
+
+/// One level of an [LDtk](https://ldtk.io) project as tile layers drawn
+/// from their tileset textures, loaded with `Ctx::load_tilemap`.
+///
+/// LDtk's pixels become world units with y flipped to point up, so the
+/// level's top-left corner sits at its world position `(worldX, -worldY)`.
+/// Tile, auto-layer and IntGrid layers with auto rules draw; entity layers
+/// and layer opacity are ignored.
+///
+/// ```ignore
+/// let map = ctx.load_tilemap("assets/world.ldtk", "Level_0")?;
+/// // in Scene::render, only the chunks the camera sees
+/// let view = ctx.cameras[0].view_aabb(ctx.screen_pos);
+/// for batch in map.batches(view) {
+///     ctx.draw(batch);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Tilemap {
+    /// The level's identifier.
+    pub name: String,
+    /// The level's area in world units.
+    pub bounds: Aabb,
+    /// Bottom layer first, the order they draw in.
+    pub layers: Vec<TileLayer>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TileLayer {
+    pub name: String,
+    pub tileset: TextureId,
+    /// The tileset image, found relative to the project file.
+    pub tileset_path: PathBuf,
+    /// Hidden layers are skipped by [`Tilemap::batches`].
+    pub visible: bool,
+    chunks: Vec<TileChunk>,
+}
+
+#[derive(Clone, Debug)]
+struct TileChunk {
+    bounds: Aabb,
+    instances: Vec<SpriteInstance>,
+}
+
+impl TileLayer {
+    pub fn tile_count(&self) -> usize {
+        self.chunks.iter().map(|c| c.instances.len()).sum()
+    }
+}
+
+impl Tilemap {
+    /// Reads the level `level` of the LDtk project at `path`, following
+    /// external level files.
+    pub fn load<P: AsRef<Path>>(path: P, level: &str) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let src = std::fs::read_to_string(path)?;
+        Self::parse(&src, path.parent().unwrap_or(Path::new("")), level).map_err(|e| match e {
+            Error::Tilemap(msg) => Error::Tilemap(format!("{}: {msg}", path.display())),
+            e => e,
+        })
+    }
+
+    /// Parses the level `level` of a project whose tileset and external
+    /// level paths are relative to `dir`; tileset textures are the ids
+    /// `Ctx::load_asset` gives those images.
+    pub fn parse(src: &str, dir: &Path, level: &str) -> Result<Self, Error> {
+        let err = |msg: &str| Error::Tilemap(msg.to_owned());
+        let json = Json::parse(src).map_err(Error::Tilemap)?;
+        let levels = json
+            .get("levels")
+            .and_then(Json::as_array)
+            .ok_or_else(|| err("no `levels`"))?;
+        let found = levels
+            .iter()
+            .find(|l| l.get("identifier").and_then(Json::as_str) == Some(level))
+            .ok_or_else(|| err(&format!("no level `{level}`")))?;
+        // "Save levels to separate files" leaves only a path in the project
+        let external;
+        let found = match found.get("externalRelPath").and_then(Json::as_str) {
+            Some(rel) if !matches!(found.get("layerInstances"), Some(Json::Arr(_))) => {
+                let src = std::fs::read_to_string(dir.join(rel))?;
+                external = Json::parse(&src).map_err(|e| err(&format!("{rel}: {e}")))?;
+                &external
+            }
+            _ => found,
+        };
+
+        let tilesets = json
+            .get("defs")
+            .and_then(|d| d.get("tilesets"))
+            .and_then(Json::as_array)
+            .unwrap_or(&[]);
+        let origin = Vec2::new(num(found, "worldX")?, -num(found, "worldY")?);
+        let size = Vec2::new(num(found, "pxWid")?, num(found, "pxHei")?);
+        let mut map = Self {
+            name: level.to_owned(),
+            bounds: Aabb::new(
+                origin - Vec2::new(0.0, size.y),
+                origin + Vec2::new(size.x, 0.0),
+            ),
+            layers: Vec::new(),
+        };
+        let layers = found
+            .get("layerInstances")
+            .and_then(Json::as_array)
+            .unwrap_or(&[]);
+        // LDtk lists the top layer first
+        for layer in layers.iter().rev() {
+            let Some(uid) = layer.get("__tilesetDefUid").and_then(Json::as_f64) else {
+                continue;
+            };
+            let name = layer
+                .get("__identifier")
+                .and_then(Json::as_str)
+                .unwrap_or_default();
+            let tileset = tilesets
+                .iter()
+                .find(|t| t.get("uid").and_then(Json::as_f64) == Some(uid))
+                .ok_or_else(|| err(&format!("layer `{name}` uses a missing tileset")))?;
+            let rel = tileset
+                .get("relPath")
+                .and_then(Json::as_str)
+                .ok_or_else(|| err(&format!("tileset of layer `{name}` has no image")))?;
+            let tex_size = Vec2::new(num(tileset, "pxWid")?, num(tileset, "pxHei")?).max(Vec2::ONE);
+            let tile = num(tileset, "tileGridSize")?;
+            let offset = Vec2::new(
+                num(layer, "__pxTotalOffsetX")?,
+                num(layer, "__pxTotalOffsetY")?,
+            );
+            let tiles = ["gridTiles", "autoLayerTiles"]
+                .into_iter()
+                .filter_map(|key| layer.get(key).and_then(Json::as_array))
+                .flatten();
+
+            let chunk = CHUNK_TILES * tile.max(1.0);
+            let mut chunks: HashMap<(i32, i32), Vec<SpriteInstance>> = HashMap::new();
+            for t in tiles {
+                let (px, src) = (pair(t, "px")?, pair(t, "src")?);
+                let flip = t.get("f").and_then(Json::as_u32).unwrap_or(0);
+                let [mut u0, mut v0, mut u1, mut v1] = [
+                    src.x / tex_size.x,
+                    1.0 - (src.y + tile) / tex_size.y,
+                    (src.x + tile) / tex_size.x,
+                    1.0 - src.y / tex_size.y,
+                ];
+                if flip & 1 != 0 {
+                    std::mem::swap(&mut u0, &mut u1);
+                }
+                if flip & 2 != 0 {
+                    std::mem::swap(&mut v0, &mut v1);
+                }
+                let p = px + offset;
+                let center = origin + Vec2::new(p.x, -p.y) + Vec2::new(0.5, -0.5) * tile;
+                let key = ((p.x / chunk).floor() as i32, (p.y / chunk).floor() as i32);
+                chunks.entry(key).or_default().push(SpriteInstance {
+                    pos_size: [center.x, center.y, tile, tile],
+                    uv: [u0, v0, u1, v1],
+                    ..Default::default()
+                });
+            }
+            let mut chunks: Vec<_> = chunks.into_iter().collect();
+            chunks.sort_by_key(|(key, _)| (key.1, key.0));
+            map.layers.push(TileLayer {
+                name: name.to_owned(),
+                tileset: TextureId::from_path(dir.join(rel)),
+                tileset_path: dir.join(rel),
+                visible: !matches!(layer.get("visible"), Some(Json::Bool(false))),
+                chunks: chunks
+                    .into_iter()
+                    .map(|(_, instances)| TileChunk {
+                        bounds: instances_bounds(&instances),
+                        instances,
+                    })
+                    .collect(),
+            });
+        }
+        Ok(map)
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&TileLayer> {
+        self.layers.iter().find(|l| l.name == name)
+    }
+
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut TileLayer> {
+        self.layers.iter_mut().find(|l| l.name == name)
+    }
+
+    /// Each tileset texture once with the image it loads from.
+    pub fn tilesets(&self) -> impl Iterator<Item = (TextureId, &Path)> {
+        let mut seen = Vec::new();
+        self.layers
+            .iter()
+            .filter(move |l| {
+                let new = !seen.contains(&l.tileset);
+                seen.push(l.tileset);
+                new
+            })
+            .map(|l| (l.tileset, l.tileset_path.as_path()))
+    }
+
+    /// Batches of the visible layers' tiles in chunks overlapping `view`,
+    /// bottom layer first; none holds more than [`MAX_SPRITES`] instances.
+    pub fn batches(&self, view: Aabb) -> Vec<SpriteBatch> {
+        let mut out = Vec::new();
+        for layer in self.layers.iter().filter(|l| l.visible) {
+            let instances: Vec<SpriteInstance> = layer
+                .chunks
+                .iter()
+                .filter(|c| c.bounds.intersects(&view))
+                .flat_map(|c| c.instances.iter().copied())
+                .collect();
+            out.extend(
+                instances
+                    .chunks(MAX_SPRITES)
+                    .map(|part| SpriteBatch::new(layer.tileset, part.to_vec())),
+            );
+        }
+        out
+    }
+}
+
+fn instances_bounds(instances: &[SpriteInstance]) -> Aabb {
+    let mut bounds = Aabb::new(Vec2::INFINITY, Vec2::NEG_INFINITY);
+    for i in instances {
+        let [x, y, w, h] = i.pos_size;
+        let tile = Aabb::from_center_size(Vec2::new(x, y), Vec2::new(w, h));
+        bounds = Aabb::new(bounds.min.min(tile.min), bounds.max.max(tile.max));
+    }
+    bounds
+}
+
+fn num(obj: &Json, key: &str) -> Result<f32, Error> {
+    obj.get(key)
+        .and_then(Json::as_f64)
+        .map(|n| n as f32)
+        .ok_or_else(|| Error::Tilemap(format!("missing `{key}`")))
+}
+
+fn pair(obj: &Json, key: &str) -> Result<Vec2, Error> {
+    match obj.get(key).and_then(Json::as_array) {
+        Some([x, y]) => x
+            .as_f64()
+            .zip(y.as_f64())
+            .map(|(x, y)| Vec2::new(x as f32, y as f32))
+            .ok_or_else(|| Error::Tilemap(format!("`{key}` is not two numbers"))),
+        _ => Err(Error::Tilemap(format!("missing `{key}`"))),
+    }
+}
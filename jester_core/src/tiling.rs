@@ -0,0 +1,129 @@
+use crate::{
+    render::TextureMeta,
+    sprite::{SpriteBatch, SpriteInstance, TextureId},
+};
+
+/// A texture too large for the backend, stored as a grid of tiles.
+///
+/// Sprites keep referring to the id of the whole image; each drawn instance
+/// is cut along tile edges into one instance per overlapped tile.
+pub(crate) struct TiledTexture {
+    pub meta: TextureMeta,
+    pub tile_size: u32,
+    pub cols: u32,
+    /// Row-major from the top-left tile.
+    pub tiles: Vec<TextureId>,
+}
+
+impl TiledTexture {
+    /// Number of tiles needed along an edge of `len` texels.
+    pub fn tiles_along(len: u32, tile_size: u32) -> u32 {
+        len.div_ceil(tile_size)
+    }
+
+    /// Texels of the tile at `col`, `row` and its size, `bpp` bytes each.
+    pub fn cut(
+        pixels: &[u8],
+        bpp: usize,
+        w: u32,
+        h: u32,
+        tile_size: u32,
+        col: u32,
+        row: u32,
+    ) -> (u32, u32, Vec<u8>) {
+        let (x0, y0) = (col * tile_size, row * tile_size);
+        let tw = tile_size.min(w - x0);
+        let th = tile_size.min(h - y0);
+        let mut out = Vec::with_capacity(tw as usize * th as usize * bpp);
+        for y in y0..y0 + th {
+            let start = (y as usize * w as usize + x0 as usize) * bpp;
+            out.extend_from_slice(&pixels[start..start + tw as usize * bpp]);
+        }
+        (tw, th, out)
+    }
+
+    /// Splits `batch` into one batch per tile, dropping tiles nothing overlaps.
+    pub fn split(&self, batch: &SpriteBatch) -> Vec<SpriteBatch> {
+        let (w, h) = (self.meta.w as f32, self.meta.h as f32);
+        let ts = self.tile_size as f32;
+        let mut out = Vec::new();
+        for (i, &tex) in self.tiles.iter().enumerate() {
+            let (col, row) = (i as u32 % self.cols, i as u32 / self.cols);
+            // tile bounds in sprite uv space, where v grows upwards from the image bottom
+            let u = (col as f32 * ts / w, ((col as f32 + 1.0) * ts).min(w) / w);
+            let v = (
+                1.0 - ((row as f32 + 1.0) * ts).min(h) / h,
+                1.0 - row as f32 * ts / h,
+            );
+            let instances: Vec<SpriteInstance> = batch
+                .instances
+                .iter()
+                .filter_map(|inst| clip(inst, u, v))
+                .collect();
+            if !instances.is_empty() {
+                out.push(SpriteBatch {
+                    tex,
+                    blend: batch.blend,
+                    palette: batch.palette,
+                    cutout: batch.cutout,
+                    material: batch.material,
+                    // clipped anew every frame, nothing to keep
+                    baked: None,
+                    retained: None,
+                    instances,
+                });
+            }
+        }
+        out
+    }
+}
+
+/// The part of `inst` showing the tile spanning `u` and `v`, re-mapped to the tile's own uvs.
+fn clip(inst: &SpriteInstance, u: (f32, f32), v: (f32, f32)) -> Option<SpriteInstance> {
+    let (cx, sx, ux) = clip_axis(
+        inst.pos_size[0],
+        inst.pos_size[2],
+        inst.uv[0],
+        inst.uv[2],
+        u,
+    )?;
+    let (cy, sy, uy) = clip_axis(
+        inst.pos_size[1],
+        inst.pos_size[3],
+        inst.uv[1],
+        inst.uv[3],
+        v,
+    )?;
+    Some(SpriteInstance {
+        pos_size: [cx, cy, sx, sy],
+        uv: [ux.0, uy.0, ux.1, uy.1],
+        ..*inst
+    })
+}
+
+/// Clips one axis of a quad centered at `center` mapping `a..b` onto the tile range `lo..hi`.
+fn clip_axis(
+    center: f32,
+    size: f32,
+    a: f32,
+    b: f32,
+    (lo, hi): (f32, f32),
+) -> Option<(f32, f32, (f32, f32))> {
+    if a == b {
+        // a single texel line, drawn by the tile containing it
+        let local = (a - lo) / (hi - lo);
+        return (lo <= a && a < hi).then_some((center, size, (local, local)));
+    }
+    let t0 = ((lo - a) / (b - a)).clamp(0.0, 1.0);
+    let t1 = ((hi - a) / (b - a)).clamp(0.0, 1.0);
+    let (t0, t1) = (t0.min(t1), t0.max(t1));
+    if t1 <= t0 {
+        return None;
+    }
+    let local = |t: f32| (a + (b - a) * t - lo) / (hi - lo);
+    Some((
+        center + ((t0 + t1) * 0.5 - 0.5) * size,
+        (t1 - t0) * size,
+        (local(t0), local(t1)),
+    ))
+}
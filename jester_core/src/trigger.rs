@@ -0,0 +1,75 @@
+use glam::Vec2;
+use hashbrown::HashSet;
+
+use crate::{Aabb, Bounds, EntityId, EntityPool, Events, Sprite};
+
+/// An area that reports sprites moving in and out of it without blocking them.
+///
+/// Spawn with [`Ctx::spawn_trigger`](crate::Ctx::spawn_trigger); only sprites
+/// registered with [`Ctx::watch_triggers`](crate::Ctx::watch_triggers) set it off.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trigger {
+    pub area: Aabb,
+    /// Free-form data for the scene reacting to it, e.g. `"checkpoint"` or a door id.
+    pub label: String,
+}
+
+impl Trigger {
+    pub fn new(area: Aabb, label: impl Into<String>) -> Self {
+        Self {
+            area,
+            label: label.into(),
+        }
+    }
+}
+
+/// Sent through `ctx.events` when a watched sprite enters or leaves a [`Trigger`].
+///
+/// Despawning either side counts as leaving.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriggerEvent {
+    Enter { trigger: EntityId, other: EntityId },
+    Exit { trigger: EntityId, other: EntityId },
+}
+
+/// Overlaps seen last frame, diffed against the current ones by the app every frame.
+#[derive(Debug, Default)]
+pub struct TriggerTracker {
+    inside: HashSet<(EntityId, EntityId)>,
+}
+
+impl TriggerTracker {
+    pub fn update(&mut self, pool: &EntityPool, events: &mut Events) {
+        let mut inside = HashSet::with_capacity(self.inside.len());
+        for (&trigger, t) in &pool.triggers {
+            for &other in &pool.trigger_watchers {
+                let Some(sprite) = pool.entities.get(&other) else {
+                    continue;
+                };
+                if overlaps(sprite, &t.area) {
+                    inside.insert((trigger, other));
+                }
+            }
+        }
+        for &(trigger, other) in inside.difference(&self.inside) {
+            events.send(TriggerEvent::Enter { trigger, other });
+        }
+        for &(trigger, other) in self.inside.difference(&inside) {
+            events.send(TriggerEvent::Exit { trigger, other });
+        }
+        self.inside = inside;
+    }
+
+    /// Forgets every overlap without reporting exits, e.g. on a scene switch.
+    pub fn clear(&mut self) {
+        self.inside.clear();
+    }
+}
+
+fn overlaps(s: &Sprite, area: &Aabb) -> bool {
+    let size = s.size.unwrap_or_default() * s.transform.scale;
+    if size == Vec2::ZERO {
+        return area.contains(s.transform.translation);
+    }
+    s.world_bounds().intersects(&Bounds::Aabb(*area))
+}
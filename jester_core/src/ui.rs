@@ -0,0 +1,110 @@
+use glam::Vec2;
+
+use crate::{Color, Transform};
+
+/// How the inside of a [`Panel`] is painted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fill {
+    Solid(Color),
+    /// `angle` is in radians, 0 runs left to right.
+    Linear {
+        from: Color,
+        to: Color,
+        angle: f32,
+    },
+    /// From the panel center outwards.
+    Radial {
+        inner: Color,
+        outer: Color,
+    },
+}
+
+impl Default for Fill {
+    fn default() -> Self {
+        Self::Solid(Color::WHITE)
+    }
+}
+
+/// An untextured rounded rectangle drawn with the built-in SDF panel material.
+#[derive(Clone, Copy, Debug)]
+pub struct Panel {
+    pub transform: Transform,
+    pub size: Vec2,
+    pub fill: Fill,
+    pub corner_radius: f32,
+    pub border_width: f32,
+    pub border_color: Color,
+    /// Lower layers draw first; spawned panels of one layer draw in spawn order.
+    pub layer: i32,
+}
+
+impl Default for Panel {
+    fn default() -> Self {
+        Self {
+            transform: Transform::default(),
+            size: Vec2::splat(64.0),
+            fill: Fill::default(),
+            corner_radius: 0.0,
+            border_width: 0.0,
+            border_color: Color::BLACK,
+            layer: 0,
+        }
+    }
+}
+
+impl Panel {
+    pub fn instance(&self) -> PanelInstance {
+        let sz = self.size * self.transform.scale;
+        let (a, b, kind, angle) = match self.fill {
+            Fill::Solid(c) => (c, c, 0.0, 0.0),
+            Fill::Linear { from, to, angle } => (from, to, 1.0, angle),
+            Fill::Radial { inner, outer } => (inner, outer, 2.0, 0.0),
+        };
+        PanelInstance {
+            pos_size: [
+                self.transform.translation.x,
+                self.transform.translation.y,
+                sz.x,
+                sz.y,
+            ],
+            color_a: a.into(),
+            color_b: b.into(),
+            border_color: self.border_color.into(),
+            params: [self.corner_radius, self.border_width, kind, angle],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct PanelInstance {
+    pub pos_size: [f32; 4],
+    pub color_a: [f32; 4],
+    pub color_b: [f32; 4],
+    pub border_color: [f32; 4],
+    /// corner radius, border width, fill kind, gradient angle
+    pub params: [f32; 4],
+}
+
+impl PanelInstance {
+    /// Black or white by brightness with a contrasting border, see
+    /// [`Accessibility::high_contrast`](crate::Accessibility::high_contrast).
+    pub fn high_contrast(mut self) -> Self {
+        let [r, g, b, a] = self.color_a;
+        let light = 0.2126 * r + 0.7152 * g + 0.0722 * b > 0.5;
+        let (fill, border) = match light {
+            true => (1.0, 0.0),
+            false => (0.0, 1.0),
+        };
+        // transparent panels, e.g. focus rings, keep only their border
+        self.color_a = [fill, fill, fill, if a > 0.0 { 1.0 } else { 0.0 }];
+        self.color_b = self.color_a;
+        self.border_color = [border, border, border, 1.0];
+        self.params[1] = self.params[1].max(2.0);
+        self.params[2] = 0.0;
+        self
+    }
+}
+
+unsafe impl bytemuck::Pod for PanelInstance {}
+unsafe impl bytemuck::Zeroable for PanelInstance {}
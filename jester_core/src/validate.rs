@@ -0,0 +1,122 @@
+use std::fmt;
+
+use hashbrown::HashSet;
+
+use crate::{Backend, EntityId, EntityPool, Renderer, SpriteBatch, TextureId, MAX_SPRITES};
+
+/// A frame invariant broken, found by [`FrameValidator`] and sent through
+/// `ctx.events` the first frame it shows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ValidationIssue {
+    /// More sprite instances were drawn across all cameras than the backend
+    /// holds per frame; those past [`MAX_SPRITES`] are dropped.
+    TooManyInstances { count: usize },
+    /// A sprite, or a batch from `Scene::render` when `entity` is `None`,
+    /// draws with a texture the renderer does not have, so it draws nothing.
+    UnknownTexture {
+        tex: TextureId,
+        entity: Option<EntityId>,
+    },
+    /// A sprite's translation, scale or size is NaN or infinite.
+    NonFinite(EntityId),
+    /// A sprite is zero wide or high, so it never shows.
+    ZeroSize(EntityId),
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyInstances { count } => write!(
+                f,
+                "{count} sprite instances this frame, only {MAX_SPRITES} are drawn"
+            ),
+            Self::UnknownTexture {
+                tex,
+                entity: Some(id),
+            } => write!(f, "sprite {id:?} uses unknown texture {tex:?}"),
+            Self::UnknownTexture { tex, entity: None } => {
+                write!(f, "scene batch uses unknown texture {tex:?}")
+            }
+            Self::NonFinite(id) => write!(f, "sprite {id:?} has a NaN or infinite transform"),
+            Self::ZeroSize(id) => write!(f, "sprite {id:?} has zero size"),
+        }
+    }
+}
+
+/// Checks every frame's sprites and batches for mistakes a backend would
+/// otherwise drop silently or draw as garbage, see [`ValidationIssue`].
+///
+/// The app runs one in debug builds, see `App::set_validation`; it warns
+/// and sends an event once per issue until that issue goes away.
+#[derive(Clone, Debug, Default)]
+pub struct FrameValidator {
+    // issues of the last frame, counts zeroed so they compare equal across frames
+    seen: HashSet<ValidationIssue>,
+}
+
+impl FrameValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Issues of this frame not found the frame before, for `pool`'s
+    /// sprites and `batches` drawn through `cameras` cameras.
+    pub fn check<'a, B: Backend>(
+        &mut self,
+        renderer: &Renderer<B>,
+        pool: &EntityPool,
+        batches: impl IntoIterator<Item = &'a SpriteBatch>,
+        cameras: usize,
+    ) -> Vec<ValidationIssue> {
+        let known =
+            |tex: TextureId| renderer.texture_meta(tex).is_some() || renderer.is_retrying(tex);
+        let mut issues = Vec::new();
+        let mut unknown = HashSet::new();
+        for (&id, s) in pool.entities.iter() {
+            let t = &s.transform;
+            let size = s.size.unwrap_or(glam::Vec2::ONE) * t.scale;
+            if !(t.translation.is_finite() && size.is_finite()) {
+                issues.push(ValidationIssue::NonFinite(id));
+            } else if size.x == 0.0 || size.y == 0.0 {
+                issues.push(ValidationIssue::ZeroSize(id));
+            }
+            if !known(s.tex) {
+                unknown.insert(s.tex);
+                issues.push(ValidationIssue::UnknownTexture {
+                    tex: s.tex,
+                    entity: Some(id),
+                });
+            }
+        }
+        let mut count = 0;
+        for b in batches {
+            count += b.instances.len();
+            // textures of sprites were reported with the sprites
+            if !known(b.tex) && unknown.insert(b.tex) {
+                issues.push(ValidationIssue::UnknownTexture {
+                    tex: b.tex,
+                    entity: None,
+                });
+            }
+        }
+        if count * cameras > MAX_SPRITES {
+            issues.push(ValidationIssue::TooManyInstances {
+                count: count * cameras,
+            });
+        }
+
+        let key = |i: &ValidationIssue| match i {
+            ValidationIssue::TooManyInstances { .. } => {
+                ValidationIssue::TooManyInstances { count: 0 }
+            }
+            i => *i,
+        };
+        let new = issues
+            .iter()
+            .filter(|i| !self.seen.contains(&key(i)))
+            .copied()
+            .collect();
+        self.seen = issues.iter().map(key).collect();
+        new
+    }
+}
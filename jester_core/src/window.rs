@@ -0,0 +1,120 @@
+use tracing::warn;
+use winit::{dpi::PhysicalPosition, monitor::MonitorHandle, window::Window};
+
+/// A connected display, as of window creation.
+///
+/// `position`/`size` are the full monitor bounds in physical pixels; winit does not
+/// report the work area (bounds minus taskbars/docks), so placement uses the bounds.
+#[derive(Debug, Clone)]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    pub refresh_rate_hz: Option<f32>,
+    pub scale_factor: f64,
+    pub primary: bool,
+}
+
+impl MonitorInfo {
+    fn new(m: &MonitorHandle, primary: Option<&MonitorHandle>) -> Self {
+        let pos = m.position();
+        let size = m.size();
+        Self {
+            name: m.name(),
+            position: (pos.x, pos.y),
+            size: (size.width, size.height),
+            refresh_rate_hz: m.refresh_rate_millihertz().map(|mhz| mhz as f32 / 1000.0),
+            scale_factor: m.scale_factor(),
+            primary: primary == Some(m),
+        }
+    }
+}
+
+/// Monitors available to the app, kept up to date in `Resources`.
+#[derive(Debug, Clone, Default)]
+pub struct Monitors {
+    pub list: Vec<MonitorInfo>,
+    /// Index into `list` of the monitor the window is on.
+    pub current: Option<usize>,
+}
+
+impl Monitors {
+    pub fn query(win: &Window) -> Self {
+        let primary = win.primary_monitor();
+        let current = win.current_monitor();
+        let handles: Vec<MonitorHandle> = win.available_monitors().collect();
+        Self {
+            list: handles
+                .iter()
+                .map(|m| MonitorInfo::new(m, primary.as_ref()))
+                .collect(),
+            current: handles.iter().position(|m| Some(m) == current.as_ref()),
+        }
+    }
+
+    pub fn primary(&self) -> Option<&MonitorInfo> {
+        self.list.iter().find(|m| m.primary)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum MonitorSelect {
+    #[default]
+    Primary,
+    /// The monitor the window is currently on.
+    Current,
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum WindowPlacement {
+    /// Wherever the platform puts new windows.
+    #[default]
+    Default,
+    Centered(MonitorSelect),
+    /// Outer top-left corner relative to the monitor's top-left, in physical pixels.
+    At(MonitorSelect, i32, i32),
+}
+
+impl WindowPlacement {
+    /// Moves `win` according to the placement; unknown monitors are logged
+    /// and leave the window where it is. Platforms that cannot tell the
+    /// primary or current monitor, e.g. Wayland, use the first one instead.
+    pub fn apply(&self, win: &Window) {
+        let (select, offset) = match self {
+            Self::Default => return,
+            Self::Centered(m) => (m, None),
+            Self::At(m, x, y) => (m, Some((*x, *y))),
+        };
+        let monitor = match select {
+            MonitorSelect::Primary => win
+                .primary_monitor()
+                .or_else(|| win.available_monitors().next()),
+            MonitorSelect::Current => win
+                .current_monitor()
+                .or_else(|| win.available_monitors().next()),
+            MonitorSelect::Index(i) => win.available_monitors().nth(*i),
+            MonitorSelect::Name(n) => win
+                .available_monitors()
+                .find(|m| m.name().as_deref() == Some(n.as_str())),
+        };
+        let Some(monitor) = monitor else {
+            warn!("No monitor matches {select:?}");
+            return;
+        };
+        let origin = monitor.position();
+        let (x, y) = match offset {
+            Some(offset) => offset,
+            None => {
+                let m = monitor.size();
+                let w = win.outer_size();
+                (
+                    (m.width as i32 - w.width as i32) / 2,
+                    (m.height as i32 - w.height as i32) / 2,
+                )
+            }
+        };
+        win.set_outer_position(PhysicalPosition::new(origin.x + x, origin.y + y));
+    }
+}
@@ -0,0 +1,48 @@
+use glam::Vec2;
+use jester_core::{Aabb, Collider, Colliders};
+
+fn ground() -> Aabb {
+    Aabb::new(Vec2::new(-100.0, -10.0), Vec2::new(100.0, 0.0))
+}
+
+// a 2x2 body standing on the ground
+fn body() -> Aabb {
+    Aabb::new(Vec2::new(0.0, 0.0), Vec2::new(2.0, 2.0))
+}
+
+#[test]
+fn sliding_along_a_surface_is_not_blocked() {
+    let mut level = Colliders::new();
+    level.push(Collider::solid(ground()));
+    assert!(level.sweep(body(), Vec2::new(5.0, 0.0)).is_none());
+    assert!(level.sweep(body(), Vec2::new(-5.0, 0.0)).is_none());
+    // nor is jumping off it
+    assert!(level.sweep(body(), Vec2::new(1.0, 3.0)).is_none());
+}
+
+#[test]
+fn resting_on_a_one_way_platform_blocks_falling() {
+    let mut level = Colliders::new();
+    level.push(Collider::one_way(ground()));
+    let (i, hit) = level.sweep(body(), Vec2::new(0.5, -3.0)).unwrap();
+    assert_eq!(i, 0);
+    assert_eq!(hit.t, 0.0);
+    assert_eq!(hit.normal, Vec2::Y);
+}
+
+#[test]
+fn starting_inside_hits_with_a_zero_normal() {
+    let hit = ground().segment_hit(Vec2::new(0.0, -5.0), Vec2::X).unwrap();
+    assert_eq!(hit.t, 0.0);
+    assert_eq!(hit.normal, Vec2::ZERO);
+}
+
+#[test]
+fn entering_a_face_reports_its_normal() {
+    let hit = ground()
+        .segment_hit(Vec2::new(0.0, 5.0), Vec2::new(0.0, -10.0))
+        .unwrap();
+    assert_eq!(hit.t, 0.5);
+    assert_eq!(hit.normal, Vec2::Y);
+    assert_eq!(hit.point, Vec2::ZERO);
+}
@@ -0,0 +1,48 @@
+use jester_core::{EntityId, Sprite, SpriteStore, TextureId};
+
+fn sprite(tex: u64, x: f32) -> Sprite {
+    let mut s = Sprite {
+        tex: TextureId(tex),
+        ..Default::default()
+    };
+    s.transform.translation.x = x;
+    s
+}
+
+fn order(store: &SpriteStore) -> Vec<u32> {
+    store.keys().map(|id| id.to_raw()).collect()
+}
+
+#[test]
+fn despawning_keeps_spawn_order() {
+    let mut store = SpriteStore::new();
+    for i in 0..5 {
+        store.insert(EntityId::from_raw(i), sprite(1, i as f32));
+    }
+    store.remove(&EntityId::from_raw(1));
+    assert_eq!(order(&store), [0, 2, 3, 4]);
+    // the sprites that moved down are still found by id
+    for i in [0, 2, 3, 4] {
+        let s = store.get(&EntityId::from_raw(i)).unwrap();
+        assert_eq!(s.transform.translation.x, i as f32);
+    }
+    let group = store.groups().next().unwrap();
+    let xs: Vec<f32> = group.instances().iter().map(|i| i.pos_size[0]).collect();
+    assert_eq!(xs, [0.0, 2.0, 3.0, 4.0]);
+}
+
+#[test]
+fn sprites_changing_group_rejoin_in_spawn_order() {
+    let mut store = SpriteStore::new();
+    for i in 0..4 {
+        store.insert(EntityId::from_raw(i), sprite(1, i as f32));
+    }
+    store.get_mut(&EntityId::from_raw(1)).unwrap().tex = TextureId(2);
+    store.refresh();
+    store.get_mut(&EntityId::from_raw(1)).unwrap().tex = TextureId(1);
+    store.refresh();
+    assert_eq!(order(&store), [0, 1, 2, 3]);
+    // and replacing a sprite keeps its place
+    store.insert(EntityId::from_raw(0), sprite(1, 10.0));
+    assert_eq!(order(&store), [0, 1, 2, 3]);
+}